@@ -0,0 +1,47 @@
+// Benchmarks the traversal-heavy algorithms on the Enron graph in its original node order
+// and after relabeling the compact indices in BFS and degree order.
+// Usage: cargo run --release --example reorder_benchmark [path to edge list]
+
+use project::distance;
+use project::graph::*;
+use project::stats;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+// Runs `f` and returns how long it took.
+fn time<T>(f: impl FnOnce() -> T) -> Duration {
+    let start = Instant::now();
+    std::hint::black_box(f());
+    start.elapsed()
+}
+
+fn main() {
+    let path = std::env::args().nth(1).unwrap_or_else(|| "email-Enron (1).txt".to_string());
+    let edges = read_file(&path);
+    let original = Graph::from_edges(&edges);
+    let degree: HashMap<usize, usize> = compute_degree(&edges).into_iter().map(|(node, deg)| (node, deg.degree)).collect();
+    let sources: HashSet<usize> = stats::top_k(&degree, 2000).into_iter().map(|(n, _)| n).collect();
+    let pivots: HashSet<usize> = stats::top_k(&degree, 200).into_iter().map(|(n, _)| n).collect();
+
+    println!("{:<10} {:>14} {:>14} {:>14} {:>10}", "Order", "Closeness", "Betweenness", "HyperANF", "Total");
+    let orders = [("original", None), ("BFS", Some(NodeOrder::Bfs)), ("degree", Some(NodeOrder::Degree))];
+    for (name, order) in orders {
+        let mut reorder = Duration::ZERO;
+        let graph = match order {
+            Some(order) => {
+                let start = Instant::now();
+                let graph = original.reordered(order);
+                reorder = start.elapsed();
+                graph
+            }
+            None => Graph::from_edges(&edges),
+        };
+        let closeness = time(|| distance::batched_closeness(&graph, &sources));
+        let betweenness = time(|| brandes_betweenness(&graph, &pivots, BetweennessNormalization::Max));
+        let anf = time(|| distance::hyper_anf(&graph, 8, 50));
+        println!(
+            "{:<10} {:>14.2?} {:>14.2?} {:>14.2?} {:>10.2?}",
+            name, closeness, betweenness, anf, reorder + closeness + betweenness + anf
+        );
+    }
+}
@@ -0,0 +1,154 @@
+// Includes a cache of computed centrality maps on disk, keyed by the graph's content
+// fingerprint, the algorithm and its parameters, so that running the analysis again on the same
+// graph (to regenerate plots or reports, say) reads betweenness and closeness back instead of
+// recomputing them. Scores are stored bit for bit, and a cached map is only used if the graph
+// and parameters recorded in its file match exactly.
+
+use crate::graph::{fnv1a, Graph};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Directory of the cache: `$PROJECT_CACHE_DIR`, or `metric_cache` in the working directory.
+pub fn default_cache_dir() -> PathBuf {
+    std::env::var_os("PROJECT_CACHE_DIR").map_or_else(|| PathBuf::from("metric_cache"), PathBuf::from)
+}
+
+// Parameter hash of a set of node IDs, e.g. the sources of betweenness.
+pub fn node_set_hash<'a>(nodes: impl IntoIterator<Item = &'a usize>) -> u64 {
+    let mut ids: Vec<usize> = nodes.into_iter().copied().collect();
+    ids.sort_unstable();
+    fnv1a(ids.into_iter().map(|id| id as u64))
+}
+
+// Cached metrics of one graph. It can be shared by threads computing different metrics.
+#[derive(Debug)]
+pub struct MetricCache {
+    // `None` when caching is off: every metric is computed
+    dir: Option<PathBuf>,
+    fingerprint: u64,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl MetricCache {
+    // A cache in `dir` for the metrics of `graph`; the directory is created on the first write.
+    pub fn new(dir: &Path, graph: &Graph) -> MetricCache {
+        MetricCache { dir: Some(dir.to_path_buf()), fingerprint: graph.fingerprint(), hits: AtomicUsize::new(0), misses: AtomicUsize::new(0) }
+    }
+
+    // A cache that stores nothing, so every metric is computed.
+    pub fn disabled() -> MetricCache {
+        MetricCache { dir: None, fingerprint: 0, hits: AtomicUsize::new(0), misses: AtomicUsize::new(0) }
+    }
+
+    // File of a metric: the algorithm name and a hash of the graph and parameters.
+    fn path(&self, dir: &Path, algorithm: &str, params: &str) -> PathBuf {
+        let key = fnv1a([self.fingerprint].into_iter().chain(params.bytes().map(u64::from)));
+        dir.join(format!("{}-{:016x}.tsv", algorithm, key))
+    }
+
+    // Returns the cached map of a metric, or computes it and stores it. A cache file that
+    // cannot be read or written is reported on stderr and the metric is computed.
+    // Its inputs are - `algorithm`: name of the metric, e.g. `betweenness` - `params`: every
+    // parameter the result depends on, as text - `compute`: computes the metric on a miss
+    // Its outputs are - `HashMap<usize, f64>`: node ID -> score
+    pub fn get_or_compute(&self, algorithm: &str, params: &str, compute: impl FnOnce() -> HashMap<usize, f64>) -> HashMap<usize, f64> {
+        let Some(dir) = &self.dir else {
+            return compute();
+        };
+        let path = self.path(dir, algorithm, params);
+        if path.is_file() {
+            match self.load(&path, params) {
+                Ok(Some(scores)) => {
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    return scores;
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("Could not read cached {} from {}: {}", algorithm, path.display(), e),
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let scores = compute();
+        if let Err(e) = std::fs::create_dir_all(dir).and_then(|()| self.store(&path, params, &scores)) {
+            eprintln!("Could not cache {} in {}: {}", algorithm, path.display(), e);
+        }
+        scores
+    }
+
+    fn store(&self, path: &Path, params: &str, scores: &HashMap<usize, f64>) -> std::io::Result<()> {
+        let partial = path.with_extension("part");
+        let mut out = BufWriter::new(File::create(&partial)?);
+        writeln!(out, "# graph {:016x}", self.fingerprint)?;
+        writeln!(out, "# params {}", params)?;
+        let mut nodes: Vec<&usize> = scores.keys().collect();
+        nodes.sort();
+        for node in nodes {
+            writeln!(out, "{}\t{:016x}", node, scores[node].to_bits())?;
+        }
+        out.flush()?;
+        drop(out);
+        std::fs::rename(&partial, path)
+    }
+
+    // Reads a cached map, or `None` if the file belongs to another graph or parameters (a hash
+    // collision).
+    fn load(&self, path: &Path, params: &str) -> std::io::Result<Option<HashMap<usize, f64>>> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let graph = lines.next().transpose()?.unwrap_or_default();
+        let stored = lines.next().transpose()?.unwrap_or_default();
+        if graph != format!("# graph {:016x}", self.fingerprint) || stored != format!("# params {}", params) {
+            return Ok(None);
+        }
+        let mut scores = HashMap::new();
+        for line in lines {
+            let line = line?;
+            let parsed = line.split_once('\t').and_then(|(node, bits)| Some((node.parse().ok()?, u64::from_str_radix(bits, 16).ok()?)));
+            let Some((node, bits)) = parsed else {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("bad cache line: {}", line)));
+            };
+            scores.insert(node, f64::from_bits(bits));
+        }
+        Ok(Some(scores))
+    }
+
+    // Directory of the cache, unless it is disabled.
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    // Number of metrics read from the cache and computed so far.
+    pub fn stats(&self) -> (usize, usize) {
+        (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that a metric is computed once and then read back bit for bit, that other
+    // parameters or another graph miss, and that a disabled cache always computes.
+    #[test]
+    fn test_metric_cache() {
+        let dir = std::env::temp_dir().join("project_metric_cache_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let graph = Graph::from_edges(&[(1, 2), (2, 3), (3, 1), (3, 4)]);
+        let scores = HashMap::from([(1, 0.1), (2, 1.0 / 3.0), (3, f64::MIN_POSITIVE), (4, 0.0)]);
+
+        let cache = MetricCache::new(&dir, &graph);
+        assert_eq!(cache.get_or_compute("test", "k=1", || scores.clone()), scores);
+        assert_eq!(cache.get_or_compute("test", "k=1", || panic!("should be cached")), scores);
+        assert!(cache.get_or_compute("test", "k=2", HashMap::new).is_empty());
+        assert_eq!(cache.stats(), (1, 2));
+
+        let other = MetricCache::new(&dir, &Graph::from_edges(&[(1, 2), (2, 3)]));
+        assert!(other.get_or_compute("test", "k=1", HashMap::new).is_empty());
+        let disabled = MetricCache::disabled();
+        assert!(disabled.get_or_compute("test", "k=1", HashMap::new).is_empty());
+        assert_eq!(node_set_hash(&[3, 1, 2]), node_set_hash(&[1, 2, 3]));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,144 @@
+// Includes functions for Guimerà–Amaral functional cartography: given any community partition,
+// every node gets a within-module degree z-score and a participation coefficient,
+// which together classify it as a hub, connector or peripheral node.
+
+use crate::graph::Graph;
+use std::collections::HashMap;
+use std::fmt;
+
+// The seven Guimerà–Amaral roles. Hubs have a within-module z-score of at least 2.5,
+// and the participation coefficient separates the roles within each group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CartographyRole {
+    UltraPeripheral,
+    Peripheral,
+    Connector,
+    Kinless,
+    ProvincialHub,
+    ConnectorHub,
+    KinlessHub,
+}
+
+impl CartographyRole {
+    // Classifies a node from its within-module z-score and participation coefficient.
+    pub fn classify(within_z: f64, participation: f64) -> CartographyRole {
+        if within_z >= 2.5 {
+            if participation <= 0.30 {
+                CartographyRole::ProvincialHub
+            } else if participation <= 0.75 {
+                CartographyRole::ConnectorHub
+            } else {
+                CartographyRole::KinlessHub
+            }
+        } else if participation <= 0.05 {
+            CartographyRole::UltraPeripheral
+        } else if participation <= 0.62 {
+            CartographyRole::Peripheral
+        } else if participation <= 0.80 {
+            CartographyRole::Connector
+        } else {
+            CartographyRole::Kinless
+        }
+    }
+
+    pub fn is_hub(&self) -> bool {
+        matches!(self, CartographyRole::ProvincialHub | CartographyRole::ConnectorHub | CartographyRole::KinlessHub)
+    }
+}
+
+impl fmt::Display for CartographyRole {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CartographyRole::UltraPeripheral => "R1 ultra-peripheral",
+            CartographyRole::Peripheral => "R2 peripheral",
+            CartographyRole::Connector => "R3 connector",
+            CartographyRole::Kinless => "R4 kinless",
+            CartographyRole::ProvincialHub => "R5 provincial hub",
+            CartographyRole::ConnectorHub => "R6 connector hub",
+            CartographyRole::KinlessHub => "R7 kinless hub",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Cartography scores of one node.
+#[derive(Debug, Clone, Copy)]
+pub struct NodeCartography {
+    pub participation: f64,
+    pub within_z: f64,
+    pub role: CartographyRole,
+}
+
+// Computes the participation coefficient P = 1 - sum_s (k_is / k_i)^2 and the within-module
+// degree z-score of every node in the partition. Only edges between two partitioned nodes
+// count, so a partition of a subset (such as the k-means top nodes) is analyzed as a subgraph.
+// Its inputs are - `graph`: the graph - `partition`: node ID -> community ID
+// Its outputs are - `HashMap<usize, NodeCartography>`: node ID -> scores and role
+pub fn functional_cartography(graph: &Graph, partition: &HashMap<usize, usize>) -> HashMap<usize, NodeCartography> {
+    // Links from every node to each community, and the within-module degree
+    let mut links: HashMap<usize, HashMap<usize, usize>> = HashMap::new();
+    for &node in partition.keys() {
+        let counts = links.entry(node).or_default();
+        if let Some(v) = graph.index_of(node) {
+            for &w in graph.neighbors(v) {
+                if let Some(&community) = partition.get(&graph.node_id(w)) {
+                    *counts.entry(community).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+    let within = |node: usize| *links[&node].get(&partition[&node]).unwrap_or(&0) as f64;
+
+    // Mean and standard deviation of the within-module degree per community
+    let mut stats: HashMap<usize, (f64, f64, f64)> = HashMap::new();
+    for (&node, &community) in partition {
+        let k = within(node);
+        let entry = stats.entry(community).or_insert((0.0, 0.0, 0.0));
+        entry.0 += 1.0;
+        entry.1 += k;
+        entry.2 += k * k;
+    }
+
+    partition
+        .iter()
+        .map(|(&node, &community)| {
+            let (count, sum, sum_sq) = stats[&community];
+            let mean = sum / count;
+            let std = (sum_sq / count - mean * mean).max(0.0).sqrt();
+            let within_z = if std > 0.0 { (within(node) - mean) / std } else { 0.0 };
+
+            let degree: usize = links[&node].values().sum();
+            let participation = if degree > 0 {
+                1.0 - links[&node].values().map(|&k| (k as f64 / degree as f64).powi(2)).sum::<f64>()
+            } else {
+                0.0
+            };
+
+            let role = CartographyRole::classify(within_z, participation);
+            (node, NodeCartography { participation, within_z, role })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests participation coefficients and roles on two communities joined by one node.
+    // Node 5 has two links into community 0 and two into community 1.
+    #[test]
+    fn test_functional_cartography() {
+        let edges = vec![(1, 2), (2, 3), (3, 1), (5, 1), (5, 2), (5, 6), (5, 7), (6, 7), (7, 8), (8, 6)];
+        let graph = Graph::from_edges(&edges);
+        let partition: HashMap<usize, usize> =
+            vec![(1, 0), (2, 0), (3, 0), (5, 1), (6, 1), (7, 1), (8, 1)].into_iter().collect();
+        let result = functional_cartography(&graph, &partition);
+
+        assert!((result[&5].participation - 0.5).abs() < 1e-9);
+        assert_eq!(result[&5].role, CartographyRole::Peripheral);
+        assert_eq!(result[&3].participation, 0.0);
+        assert_eq!(result[&3].role, CartographyRole::UltraPeripheral);
+        assert_eq!(CartographyRole::classify(3.0, 0.5), CartographyRole::ConnectorHub);
+        assert!(CartographyRole::classify(3.0, 0.9).is_hub());
+    }
+}
@@ -0,0 +1,137 @@
+// Includes functions for a load-based cascading failure model (Motter–Lai). Every node carries
+// the shortest-path traffic through it as its load and can handle (1 + tolerance) times its
+// initial load. When a node fails its traffic reroutes, and any node pushed over capacity fails next.
+
+use crate::graph::Graph;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+// Outcome of the cascade started by one node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CascadeResult {
+    // Node ID removed to start the cascade
+    pub trigger: usize,
+    // Node IDs that failed afterwards, in the round they failed
+    pub failed: Vec<usize>,
+    // Number of redistribution rounds until no node was overloaded
+    pub rounds: usize,
+}
+
+// Computes the shortest-path load of every alive node with Brandes' dependency accumulation
+// from the alive sources. Loads are scaled by alive nodes / sources, so a sample of sources
+// estimates the load from all of them.
+fn shortest_path_load(graph: &Graph, alive: &[bool], sources: &[usize]) -> Vec<f64> {
+    let n = graph.node_count();
+    let mut load = vec![0.0; n];
+    let mut dist = vec![usize::MAX; n];
+    let mut paths = vec![0.0; n];
+    let mut delta = vec![0.0; n];
+    let mut order = Vec::new();
+    let alive_sources: Vec<usize> = sources.iter().copied().filter(|&s| alive[s]).collect();
+
+    for &s in &alive_sources {
+        dist[s] = 0;
+        paths[s] = 1.0;
+        order.push(s);
+        let mut head = 0;
+        while head < order.len() {
+            let u = order[head];
+            head += 1;
+            for &v in graph.neighbors(u) {
+                if !alive[v] {
+                    continue;
+                }
+                if dist[v] == usize::MAX {
+                    dist[v] = dist[u] + 1;
+                    order.push(v);
+                }
+                if dist[v] == dist[u] + 1 {
+                    paths[v] += paths[u];
+                }
+            }
+        }
+        // Walk back from the farthest nodes, passing dependencies to predecessors
+        for &w in order.iter().rev() {
+            for &v in graph.neighbors(w) {
+                if alive[v] && dist[v] != usize::MAX && dist[v] + 1 == dist[w] {
+                    delta[v] += paths[v] / paths[w] * (1.0 + delta[w]);
+                }
+            }
+            if w != s {
+                load[w] += delta[w];
+            }
+        }
+        for &v in &order {
+            dist[v] = usize::MAX;
+            paths[v] = 0.0;
+            delta[v] = 0.0;
+        }
+        order.clear();
+    }
+
+    let alive_count = alive.iter().filter(|&&a| a).count();
+    let scale = alive_count as f64 / alive_sources.len().max(1) as f64;
+    load.iter_mut().for_each(|x| *x *= scale);
+    load
+}
+
+// Simulates the cascade triggered by removing each of the given nodes. Loads are estimated
+// from `samples` random source nodes (all nodes when `samples` is at least the node count),
+// the same sources being used before and after every failure.
+// Its inputs are - `graph`: the graph - `triggers`: node IDs to remove, one cascade each
+// - `tolerance`: spare capacity as a fraction of the initial load - `samples`: BFS sources - `seed`: RNG seed
+// Its outputs are - `Vec<CascadeResult>`: one result per trigger found in the graph
+pub fn cascading_failures(graph: &Graph, triggers: &[usize], tolerance: f64, samples: usize, seed: u64) -> Vec<CascadeResult> {
+    let n = graph.node_count();
+    let mut sources: Vec<usize> = (0..n).collect();
+    sources.shuffle(&mut StdRng::seed_from_u64(seed));
+    sources.truncate(samples);
+
+    let initial = shortest_path_load(graph, &vec![true; n], &sources);
+    // Small slack so that loads equal to the capacity do not fail through rounding
+    let capacity: Vec<f64> = initial.iter().map(|&l| (1.0 + tolerance) * l + 1e-9).collect();
+
+    triggers
+        .iter()
+        .filter_map(|&id| graph.index_of(id).map(|v| (id, v)))
+        .map(|(trigger, v)| {
+            let mut alive = vec![true; n];
+            alive[v] = false;
+            let mut failed = Vec::new();
+            let mut rounds = 0;
+            loop {
+                let load = shortest_path_load(graph, &alive, &sources);
+                let overloaded: Vec<usize> = (0..n).filter(|&u| alive[u] && load[u] > capacity[u]).collect();
+                if overloaded.is_empty() {
+                    break;
+                }
+                rounds += 1;
+                for u in overloaded {
+                    alive[u] = false;
+                    failed.push(graph.node_id(u));
+                }
+            }
+            CascadeResult { trigger, failed, rounds }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests the cascade on a 4-cycle 1-2-3-4, where each node carries half of the traffic
+    // between its two neighbors. Removing node 1 sends all 2-4 traffic through node 3.
+    #[test]
+    fn test_cascading_failures() {
+        let edges = vec![(1, 2), (2, 3), (3, 4), (4, 1)];
+        let graph = Graph::from_edges(&edges);
+
+        let tight = cascading_failures(&graph, &[1], 0.0, 10, 0);
+        assert_eq!(tight, vec![CascadeResult { trigger: 1, failed: vec![3], rounds: 1 }]);
+
+        let spare = cascading_failures(&graph, &[1, 99], 1.5, 10, 0);
+        assert_eq!(spare, vec![CascadeResult { trigger: 1, failed: vec![], rounds: 0 }]);
+    }
+}
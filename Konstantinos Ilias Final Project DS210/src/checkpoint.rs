@@ -0,0 +1,220 @@
+// Includes checkpointing for long betweenness runs: while Brandes' algorithm works through the
+// sources, the partially accumulated scores and the number of sources done are saved to a file
+// every so often, so a run over all sources that is interrupted after hours can resume from the
+// last checkpoint instead of starting over. Sources are processed in node ID order and scores are
+// saved bit for bit, so a resumed run gives exactly the scores of an uninterrupted one.
+
+use crate::graph::{accumulate_betweenness, fnv1a, normalize_betweenness, BetweennessNormalization, Graph};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+// Where and how often to checkpoint.
+#[derive(Debug, Clone)]
+pub struct CheckpointConfig {
+    pub path: PathBuf,
+    // Least time between two checkpoints
+    pub interval: Duration,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        CheckpointConfig { path: PathBuf::from("betweenness.checkpoint"), interval: Duration::from_secs(60) }
+    }
+}
+
+// Saved progress of a betweenness run.
+#[derive(Debug, Clone, PartialEq)]
+struct Checkpoint {
+    // Fingerprint of the graph the scores belong to
+    graph: u64,
+    // Hash of the sorted source IDs, and their number
+    sources_hash: u64,
+    sources: usize,
+    // Sources done, in node ID order
+    done: usize,
+    // Node ID -> raw score, for every node reached so far
+    scores: HashMap<usize, f64>,
+}
+
+impl Checkpoint {
+    // Writes the checkpoint next to its final path, then renames it over the old one, so that
+    // an interruption while writing leaves the previous checkpoint intact.
+    fn write(&self, path: &Path) -> std::io::Result<()> {
+        let partial = path.with_extension("part");
+        let mut out = BufWriter::new(File::create(&partial)?);
+        writeln!(out, "# betweenness checkpoint: node ID and raw score (f64 bits) per reached node")?;
+        writeln!(out, "graph {:016x}", self.graph)?;
+        writeln!(out, "sources {} {:016x}", self.sources, self.sources_hash)?;
+        writeln!(out, "done {}", self.done)?;
+        let mut nodes: Vec<&usize> = self.scores.keys().collect();
+        nodes.sort();
+        for node in nodes {
+            writeln!(out, "{} {:016x}", node, self.scores[node].to_bits())?;
+        }
+        out.into_inner()?.sync_all()?;
+        std::fs::rename(&partial, path)
+    }
+
+    fn read(path: &Path) -> std::io::Result<Checkpoint> {
+        let invalid = |line: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("bad checkpoint line: {}", line));
+        let hex = |x: &str| u64::from_str_radix(x, 16).ok();
+        let mut checkpoint = Checkpoint { graph: 0, sources_hash: 0, sources: 0, done: 0, scores: HashMap::new() };
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                [] => {}
+                [first, ..] if first.starts_with('#') => {}
+                ["graph", fingerprint] => checkpoint.graph = hex(fingerprint).ok_or_else(|| invalid(&line))?,
+                ["sources", count, hash] => {
+                    checkpoint.sources = count.parse().map_err(|_| invalid(&line))?;
+                    checkpoint.sources_hash = hex(hash).ok_or_else(|| invalid(&line))?;
+                }
+                ["done", done] => checkpoint.done = done.parse().map_err(|_| invalid(&line))?,
+                [node, bits] => {
+                    let node = node.parse().map_err(|_| invalid(&line))?;
+                    let bits = hex(bits).ok_or_else(|| invalid(&line))?;
+                    checkpoint.scores.insert(node, f64::from_bits(bits));
+                }
+                _ => return Err(invalid(&line)),
+            }
+        }
+        Ok(checkpoint)
+    }
+}
+
+// Result of a checkpointed betweenness run.
+#[derive(Debug, Clone)]
+pub struct ResumableBetweenness {
+    // Node ID -> betweenness score
+    pub centrality: HashMap<usize, f64>,
+    // Number of sources
+    pub sources: usize,
+    // Sources taken from a checkpoint rather than searched in this run
+    pub resumed: usize,
+    // Whether a checkpoint was found but belonged to another graph or source set, and was ignored
+    pub discarded: bool,
+}
+
+// Computes the same betweenness as `brandes_betweenness`, saving progress to a checkpoint file
+// and resuming from it if it matches the graph and sources. The file is removed once the run
+// completes.
+// Its inputs are - `graph`: the graph - `nodes`: node IDs to use as sources - `normalization`:
+// how the raw scores are scaled - `config`: checkpoint file and interval - `progress`: called
+// after each checkpoint with (sources done, sources)
+// Its outputs are - `ResumableBetweenness`: the scores and how much was resumed, or the error
+// of reading or writing the checkpoint
+pub fn resumable_betweenness(
+    graph: &Graph,
+    nodes: &HashSet<usize>,
+    normalization: BetweennessNormalization,
+    config: &CheckpointConfig,
+    mut progress: impl FnMut(usize, usize),
+) -> std::io::Result<ResumableBetweenness> {
+    let mut ids: Vec<usize> = nodes.iter().copied().filter(|&id| graph.index_of(id).is_some()).collect();
+    ids.sort_unstable();
+    let sources: Vec<usize> = ids.iter().filter_map(|&id| graph.index_of(id)).collect();
+    let (fingerprint, sources_hash) = (graph.fingerprint(), fnv1a(ids.iter().map(|&id| id as u64)));
+
+    let n = graph.node_count();
+    let mut scores = vec![0.0; n];
+    let mut touched = vec![false; n];
+    let (mut resumed, mut discarded) = (0, false);
+    if config.path.exists() {
+        let saved = Checkpoint::read(&config.path)?;
+        if saved.graph == fingerprint && saved.sources_hash == sources_hash && saved.sources == sources.len() && saved.done <= sources.len() {
+            for (id, score) in saved.scores {
+                let v = graph.index_of(id).ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "checkpoint node not in the graph"))?;
+                scores[v] = score;
+                touched[v] = true;
+            }
+            resumed = saved.done;
+        } else {
+            discarded = true;
+        }
+    }
+
+    let mut last = Instant::now();
+    let mut failure = None;
+    accumulate_betweenness(graph, &sources[resumed..], &mut scores, &mut touched, |done, scores, touched| {
+        let done = resumed + done;
+        if failure.is_some() || done == sources.len() || last.elapsed() < config.interval {
+            return;
+        }
+        let checkpoint = Checkpoint {
+            graph: fingerprint,
+            sources_hash,
+            sources: sources.len(),
+            done,
+            scores: (0..n).filter(|&v| touched[v]).map(|v| (graph.node_id(v), scores[v])).collect(),
+        };
+        match checkpoint.write(&config.path) {
+            Ok(()) => progress(done, sources.len()),
+            Err(e) => failure = Some(e),
+        }
+        last = Instant::now();
+    });
+    if let Some(e) = failure {
+        return Err(e);
+    }
+    match std::fs::remove_file(&config.path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+
+    let centrality = normalize_betweenness(graph, &scores, &touched, sources.len(), normalization);
+    Ok(ResumableBetweenness { centrality, sources: sources.len(), resumed, discarded })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::brandes_betweenness;
+
+    // Tests that a run resumed from a checkpoint written halfway (as an interrupted run leaves
+    // it) gives exactly the scores of an uninterrupted run, that the checkpoint is removed at the
+    // end, and that a checkpoint of another graph is ignored.
+    #[test]
+    fn test_resumable_betweenness() {
+        let edges: Vec<(usize, usize)> = (0..120usize).flat_map(|v| [(v, (v + 1) % 120), (v, (v * 17 + 5) % 120)]).collect();
+        let graph = Graph::from_edges(&edges);
+        let nodes: HashSet<usize> = (0..120).collect();
+        let config = CheckpointConfig { path: std::env::temp_dir().join("project_betweenness_test.checkpoint"), interval: Duration::ZERO };
+        let _ = std::fs::remove_file(&config.path);
+        let norm = BetweennessNormalization::None;
+
+        let mut saves = 0;
+        let full = resumable_betweenness(&graph, &nodes, norm, &config, |_, _| saves += 1).unwrap();
+        assert_eq!((full.sources, full.resumed, full.discarded, saves), (120, 0, false, 119));
+        assert!(!config.path.exists());
+        let reference = brandes_betweenness(&graph, &nodes, norm);
+        assert!(full.centrality.iter().all(|(id, score)| (score - reference[id]).abs() < 1e-9));
+
+        // The checkpoint an interrupted run leaves after 50 sources
+        let mut scores = vec![0.0; graph.node_count()];
+        let mut touched = vec![false; graph.node_count()];
+        let sources: Vec<usize> = (0..120).map(|id| graph.index_of(id).unwrap()).collect();
+        accumulate_betweenness(&graph, &sources[..50], &mut scores, &mut touched, |_, _, _| {});
+        let mut checkpoint = Checkpoint {
+            graph: graph.fingerprint(),
+            sources_hash: fnv1a(0..120),
+            sources: 120,
+            done: 50,
+            scores: (0..graph.node_count()).filter(|&v| touched[v]).map(|v| (graph.node_id(v), scores[v])).collect(),
+        };
+        checkpoint.write(&config.path).unwrap();
+        assert_eq!(Checkpoint::read(&config.path).unwrap(), checkpoint);
+        let resumed = resumable_betweenness(&graph, &nodes, norm, &config, |_, _| {}).unwrap();
+        assert_eq!((resumed.resumed, resumed.discarded), (50, false));
+        assert_eq!(resumed.centrality, full.centrality);
+
+        checkpoint.graph ^= 1;
+        checkpoint.write(&config.path).unwrap();
+        let restarted = resumable_betweenness(&graph, &nodes, norm, &config, |_, _| {}).unwrap();
+        assert_eq!((restarted.resumed, restarted.discarded), (0, true));
+        assert_eq!(restarted.centrality, full.centrality);
+    }
+}
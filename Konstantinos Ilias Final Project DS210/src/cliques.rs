@@ -0,0 +1,145 @@
+// Includes functions to enumerate maximal cliques (groups where everyone emailed everyone),
+// using the Bron–Kerbosch algorithm with pivoting and a degeneracy ordering of the nodes.
+
+use crate::graph::Graph;
+
+// Returns the degeneracy ordering of the graph: repeatedly removing a node of minimum
+// remaining degree, using buckets indexed by degree.
+// Its inputs are - `graph`: the graph
+// Its outputs are - `Vec<usize>`: compact indices in removal order
+pub fn degeneracy_order(graph: &Graph) -> Vec<usize> {
+    let n = graph.node_count();
+    let mut degree: Vec<usize> = (0..n).map(|v| graph.degree(v)).collect();
+    let max_degree = degree.iter().copied().max().unwrap_or(0);
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); max_degree + 1];
+    for v in 0..n {
+        buckets[degree[v]].push(v);
+    }
+
+    let mut removed = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    let mut d = 0;
+    while order.len() < n {
+        // Buckets hold stale entries; skip nodes already removed or whose degree has dropped
+        let Some(v) = buckets[d].pop() else {
+            d += 1;
+            continue;
+        };
+        if removed[v] || degree[v] != d {
+            continue;
+        }
+        removed[v] = true;
+        order.push(v);
+        for &w in graph.neighbors(v) {
+            if !removed[w] {
+                degree[w] -= 1;
+                buckets[degree[w]].push(w);
+                d = d.min(degree[w]);
+            }
+        }
+    }
+    order
+}
+
+// Intersection of two ascending lists.
+fn intersect(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let (mut i, mut j) = (0, 0);
+    let mut result = Vec::new();
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                result.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    result
+}
+
+// Recursive Bron–Kerbosch step with Tomita pivoting. `r` is the growing clique,
+// `p` the candidates that extend it and `x` the nodes already explored; both kept ascending.
+fn expand(
+    graph: &Graph,
+    r: &mut Vec<usize>,
+    mut p: Vec<usize>,
+    mut x: Vec<usize>,
+    min_size: usize,
+    cliques: &mut Vec<Vec<usize>>,
+) {
+    if p.is_empty() {
+        if x.is_empty() && r.len() >= min_size {
+            let mut clique: Vec<usize> = r.iter().map(|&v| graph.node_id(v)).collect();
+            clique.sort_unstable();
+            cliques.push(clique);
+        }
+        return;
+    }
+    // Even taking every candidate cannot reach the size threshold
+    if r.len() + p.len() < min_size {
+        return;
+    }
+
+    // Pivot on the node covering the most candidates, so only its non-neighbors are branched on
+    let pivot = p
+        .iter()
+        .chain(x.iter())
+        .copied()
+        .max_by_key(|&u| intersect(&p, graph.neighbors(u)).len())
+        .unwrap();
+    let branches: Vec<usize> = p.iter().copied().filter(|v| graph.neighbors(pivot).binary_search(v).is_err()).collect();
+
+    for v in branches {
+        r.push(v);
+        expand(graph, r, intersect(&p, graph.neighbors(v)), intersect(&x, graph.neighbors(v)), min_size, cliques);
+        r.pop();
+        p.retain(|&w| w != v);
+        let pos = x.binary_search(&v).unwrap_or_else(|e| e);
+        x.insert(pos, v);
+    }
+}
+
+// Lists all maximal cliques with at least `min_size` nodes.
+// Each node v starts one search over its neighbors later in the degeneracy ordering,
+// which keeps every candidate set no larger than the graph's degeneracy.
+// Its inputs are - `graph`: the graph - `min_size`: smallest clique size to report
+// Its outputs are - `Vec<Vec<usize>>`: cliques as ascending node IDs, largest cliques first
+pub fn maximal_cliques(graph: &Graph, min_size: usize) -> Vec<Vec<usize>> {
+    let order = degeneracy_order(graph);
+    let mut position = vec![0; graph.node_count()];
+    for (i, &v) in order.iter().enumerate() {
+        position[v] = i;
+    }
+
+    let mut cliques = Vec::new();
+    for (i, &v) in order.iter().enumerate() {
+        let (mut p, mut x): (Vec<usize>, Vec<usize>) = graph.neighbors(v).iter().partition(|&&w| position[w] > i);
+        p.sort_unstable();
+        x.sort_unstable();
+        expand(graph, &mut vec![v], p, x, min_size, &mut cliques);
+    }
+
+    cliques.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    cliques
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that maximal cliques are found and that the size threshold is applied.
+    // Nodes 1-4 form a 4-clique, 4-5-6 a triangle, and 6-7 a single edge.
+    #[test]
+    fn test_maximal_cliques() {
+        let edges = vec![(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4), (4, 5), (5, 6), (6, 4), (6, 7)];
+        let graph = Graph::from_edges(&edges);
+
+        let all = maximal_cliques(&graph, 2);
+        assert_eq!(all, vec![vec![1, 2, 3, 4], vec![4, 5, 6], vec![6, 7]]);
+
+        let large = maximal_cliques(&graph, 3);
+        assert_eq!(large, vec![vec![1, 2, 3, 4], vec![4, 5, 6]]);
+    }
+}
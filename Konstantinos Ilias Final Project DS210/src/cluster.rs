@@ -1,11 +1,13 @@
 // Includes functions to find connected components (clusters),
 // normalize features for machine learning, and finally apply k-means clustering.
+// It also groups structurally equivalent nodes into a blockmodel.
 
 use std::collections::{HashMap, HashSet, VecDeque};
 
 // Finds connected components (clusters) in the graph.
 // Its inputs are - `edges`: list of (u, v) graph edges
-// Its outputs are - `Vec<HashSet<usize>>`: list of clusters, each a set of node IDs
+// Its outputs are - `Vec<HashSet<usize>>`: list of clusters, each a set of node IDs, in the
+// order of their smallest node ID
 pub fn find_clusters(edges: &[(usize, usize)]) -> Vec<HashSet<usize>> {
     let mut graph: HashMap<_, Vec<_>> = HashMap::new();
     for &(u, v) in edges {
@@ -15,8 +17,10 @@ pub fn find_clusters(edges: &[(usize, usize)]) -> Vec<HashSet<usize>> {
 
     let mut visited = HashSet::new();
     let mut clusters = Vec::new();
+    let mut nodes: Vec<usize> = graph.keys().copied().collect();
+    nodes.sort_unstable();
 
-    for &node in graph.keys() {
+    for node in nodes {
         if visited.contains(&node) {
             continue;
         }
@@ -43,8 +47,52 @@ pub fn find_clusters(edges: &[(usize, usize)]) -> Vec<HashSet<usize>> {
     clusters
 }
 
+use crate::distance;
+use crate::coarsen::CoarseGraph;
+use crate::graph::Graph;
+
+// Size, density and internal diameter of one connected component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentSummary {
+    pub size: usize,
+    pub edges: usize,
+    pub density: f64,
+    pub diameter: usize,
+}
+
+// Extracts one component found by `find_clusters` as an independent graph.
+// Its inputs are - `graph`: the whole graph - `cluster`: node IDs of the component
+// Its outputs are - `Graph`: the component, with its own compact indices
+pub fn extract_component(graph: &Graph, cluster: &HashSet<usize>) -> Graph {
+    let mut nodes: Vec<usize> = cluster.iter().filter_map(|&id| graph.index_of(id)).collect();
+    nodes.sort_unstable();
+    graph.subgraph(&nodes)
+}
+
+// Companion to `find_clusters`: summarizes every component on its own. The diameter is
+// exact, using the bounded eccentricity search instead of a BFS from every node.
+// Its inputs are - `graph`: the whole graph - `clusters`: components from `find_clusters`
+// Its outputs are - `Vec<ComponentSummary>`: one summary per component, in the same order
+pub fn component_summary(graph: &Graph, clusters: &[HashSet<usize>]) -> Vec<ComponentSummary> {
+    clusters
+        .iter()
+        .map(|cluster| {
+            let component = extract_component(graph, cluster);
+            let summary = component.summary();
+            let nodes: Vec<usize> = (0..component.node_count()).collect();
+            ComponentSummary {
+                size: summary.nodes,
+                edges: summary.edges,
+                density: summary.density,
+                diameter: distance::eccentricity(&component, &nodes).diameter,
+            }
+        })
+        .collect()
+}
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::SeedableRng;
+use rayon::prelude::*;
 
 pub type Features = (f64, f64, f64);
 
@@ -59,62 +107,164 @@ pub fn kmeans(
     k: usize,
     max_iters: usize,
 ) -> std::collections::HashMap<usize, usize> {
-    let mut rng = thread_rng();
-    let node_ids: Vec<_> = features.keys().cloned().collect();
+    let vectors = features
+        .iter()
+        .map(|(&node, &(d, c, b))| (node, vec![d, c, b]))
+        .collect();
+    kmeans_vectors(&vectors, k, max_iters)
+}
+
+// Runs k-means clustering on feature vectors of any (equal) length, so extra features
+// such as graphlet degree vectors can be appended to the centrality features.
+// Its inputs are:
+// - `features`: map from node ID to feature vector
+// - `k`: number of clusters
+// - `max_iters`: maximum number of iterations
+// Its outputs are `HashMap<usize, usize>`: node ID -> assigned cluster ID
+pub fn kmeans_vectors(
+    features: &HashMap<usize, Vec<f64>>,
+    k: usize,
+    max_iters: usize,
+) -> HashMap<usize, usize> {
+    let mut assignments = HashMap::new();
+    run_kmeans(features, k, max_iters, |step, _| assignments = step.clone());
+    assignments
+}
+
+// Assignments and centroids after one k-means iteration.
+#[derive(Debug, Clone)]
+pub struct KMeansStep {
+    pub assignments: HashMap<usize, usize>,
+    pub centroids: Vec<Vec<f64>>,
+}
+
+// Runs k-means like `kmeans_vectors`, but records every iteration, e.g. for animating how the
+// clusters converge.
+// Its inputs are - `features`: node ID -> feature vector - `k`: number of clusters
+// - `max_iters`: maximum number of iterations
+// Its outputs are - `Vec<KMeansStep>`: one step per iteration that changed the assignments; the
+// last holds the final clustering. Fewer than `max_iters` steps means k-means converged
+pub fn kmeans_steps(features: &HashMap<usize, Vec<f64>>, k: usize, max_iters: usize) -> Vec<KMeansStep> {
+    let mut steps: Vec<KMeansStep> = Vec::new();
+    run_kmeans(features, k, max_iters, |assignments, centroids| {
+        steps.push(KMeansStep { assignments: assignments.clone(), centroids: centroids.to_vec() });
+    });
+    steps
+}
+
+// Lloyd's algorithm from k randomly chosen nodes, calling `on_step` with the assignments
+// and updated centroids after every iteration. It stops as soon as an iteration leaves every
+// assignment unchanged (that iteration is not reported), or after `max_iters` iterations.
+// The initial nodes are drawn with a fixed seed and nodes are visited in ID order, so the
+// same features always give the same clusters. With k = 0 there is nothing to assign the nodes
+// to, so no iteration is run.
+fn run_kmeans(
+    features: &HashMap<usize, Vec<f64>>,
+    k: usize,
+    max_iters: usize,
+    mut on_step: impl FnMut(&HashMap<usize, usize>, &[Vec<f64>]),
+) {
+    if k == 0 {
+        return;
+    }
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut node_ids: Vec<usize> = features.keys().copied().collect();
+    node_ids.sort_unstable();
+    let dims = features.values().next().map_or(0, |f| f.len());
 
     // Randomly selecting k initial centroids
-    let mut centroids: Vec<Features> = node_ids
+    let mut centroids: Vec<Vec<f64>> = node_ids
         .choose_multiple(&mut rng, k)
-        .map(|id| features[id])
+        .map(|id| features[id].clone())
         .collect();
 
-    let mut assignments = std::collections::HashMap::new();
+    let mut assignments = HashMap::new();
 
     for _ in 0..max_iters {
         // Assigning nodes to closest centroid
-        for (&node, &feat) in features {
+        let mut changed = false;
+        for &node in &node_ids {
+            let feat = &features[&node];
             let mut best = 0;
-            let mut best_dist = euclidean_distance(feat, centroids[0]);
-            for (i, &centroid) in centroids.iter().enumerate().skip(1) {
+            let mut best_dist = euclidean_distance(feat, &centroids[0]);
+            for (i, centroid) in centroids.iter().enumerate().skip(1) {
                 let dist = euclidean_distance(feat, centroid);
                 if dist < best_dist {
                     best = i;
                     best_dist = dist;
                 }
             }
-            assignments.insert(node, best);
+            changed |= assignments.insert(node, best) != Some(best);
+        }
+        if !changed {
+            break;
         }
 
         // Updating centroids based on current assignments
         let mut counts = vec![0; k];
-        let mut sums = vec![(0.0, 0.0, 0.0); k];
+        let mut sums = vec![vec![0.0; dims]; k];
 
-        for (&node, &cluster) in &assignments {
-            let (d, c, b) = features[&node];
-            sums[cluster].0 += d;
-            sums[cluster].1 += c;
-            sums[cluster].2 += b;
+        for &node in &node_ids {
+            let cluster = assignments[&node];
+            for (sum, value) in sums[cluster].iter_mut().zip(&features[&node]) {
+                *sum += value;
+            }
             counts[cluster] += 1;
         }
 
-        for i in 0..k {
+        for i in 0..centroids.len() {
             if counts[i] > 0 {
-                centroids[i] = (
-                    sums[i].0 / counts[i] as f64,
-                    sums[i].1 / counts[i] as f64,
-                    sums[i].2 / counts[i] as f64,
-                );
+                centroids[i] = sums[i].iter().map(|s| s / counts[i] as f64).collect();
             }
         }
+        on_step(&assignments, &centroids);
     }
+}
 
-    assignments
+// Computes the mean feature vector and size of every cluster, so clusters can be described
+// by their typical member.
+// Its inputs are - `features`: node ID -> (degree, closeness, betweenness) - `assignments`: node ID -> cluster ID
+// Its outputs are - `Vec<(Features, usize)>`: (centroid, number of nodes) indexed by cluster ID
+pub fn cluster_centroids(features: &HashMap<usize, Features>, assignments: &HashMap<usize, usize>) -> Vec<(Features, usize)> {
+    let k = assignments.values().max().map_or(0, |&c| c + 1);
+    let mut centroids = vec![((0.0, 0.0, 0.0), 0); k];
+    for (&node, &cluster) in assignments {
+        if let Some(&(d, c, b)) = features.get(&node) {
+            let (sum, count) = &mut centroids[cluster];
+            sum.0 += d;
+            sum.1 += c;
+            sum.2 += b;
+            *count += 1;
+        }
+    }
+    for (sum, count) in centroids.iter_mut() {
+        if *count > 0 {
+            let n = *count as f64;
+            *sum = (sum.0 / n, sum.1 / n, sum.2 / n);
+        }
+    }
+    centroids
 }
 
 // Computes Euclidean distance between two vectors.
-fn euclidean_distance(a: Features, b: Features) -> f64 {
-    let (dx, dy, dz) = (a.0 - b.0, a.1 - b.1, a.2 - b.2);
-    (dx * dx + dy * dy + dz * dz).sqrt()
+fn euclidean_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f64>().sqrt()
+}
+
+// Normalizes each dimension of variable-length feature vectors to [0, 1] range.
+pub fn normalize_vectors(features: &mut HashMap<usize, Vec<f64>>) {
+    let dims = features.values().next().map_or(0, |f| f.len());
+    let mut max = vec![0.0; dims];
+    for vector in features.values() {
+        for (m, &v) in max.iter_mut().zip(vector) {
+            if v > *m { *m = v; }
+        }
+    }
+    for vector in features.values_mut() {
+        for (v, &m) in vector.iter_mut().zip(&max) {
+            if m > 0.0 { *v /= m; }
+        }
+    }
 }
 
 // Normalizes each feature dimension to [0, 1] range.
@@ -137,6 +287,264 @@ pub fn normalize_features(features: &mut std::collections::HashMap<usize, Featur
     }
 }
 
+// Result of blockmodeling: which block each node belongs to, and the image matrix
+// where `image[i][j]` is the density of edges between blocks i and j.
+#[derive(Debug, Clone)]
+pub struct Blockmodel {
+    pub blocks: HashMap<usize, usize>,
+    pub sizes: Vec<usize>,
+    pub image: Vec<Vec<f64>>,
+}
+
+// Jaccard similarity of two ascending neighbor lists.
+fn jaccard(a: &[usize], b: &[usize]) -> f64 {
+    let (mut i, mut j, mut common) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                common += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    let union = a.len() + b.len() - common;
+    if union == 0 { 0.0 } else { common as f64 / union as f64 }
+}
+
+// Most nodes `blockmodel` groups: it keeps every pairwise distance, n² values (128 MB here).
+pub const BLOCKMODEL_NODES: usize = 4_000;
+
+// Groups nodes into k blocks of structurally equivalent nodes, i.e. nodes with similar
+// neighborhoods, using k-medoids on the Jaccard distance between adjacency rows.
+// Medoids start from the highest-degree node and then the farthest remaining nodes,
+// so the result is deterministic.
+// Its inputs are:
+// - `graph`: the graph
+// - `nodes`: node IDs to group, at most `BLOCKMODEL_NODES` of them
+// - `k`: number of blocks
+// - `max_iters`: maximum number of k-medoids iterations
+// Its outputs are `Result<Blockmodel, String>`: node ID -> block, block sizes and the block density
+// image matrix, or an error if there are too many nodes or no blocks
+pub fn blockmodel(graph: &Graph, nodes: &HashSet<usize>, k: usize, max_iters: usize) -> Result<Blockmodel, String> {
+    if k == 0 {
+        return Err("blockmodeling needs at least one block (k = 0)".to_string());
+    }
+    let mut members: Vec<usize> = nodes.iter().filter_map(|&id| graph.index_of(id)).collect();
+    members.sort_unstable();
+    let n = members.len();
+    if n > BLOCKMODEL_NODES {
+        return Err(format!("blockmodeling {} nodes needs their {}² pairwise distances; at most {} nodes are supported", n, n, BLOCKMODEL_NODES));
+    }
+    let k = k.min(n);
+
+    // Pairwise Jaccard distances between adjacency rows
+    let distance: Vec<Vec<f64>> = members
+        .par_iter()
+        .map(|&a| members.iter().map(|&b| 1.0 - jaccard(graph.neighbors(a), graph.neighbors(b))).collect())
+        .collect();
+
+    let mut medoids: Vec<usize> = Vec::new();
+    if let Some(first) = (0..n).max_by_key(|&i| (graph.degree(members[i]), std::cmp::Reverse(i))) {
+        medoids.push(first);
+    }
+    while medoids.len() < k {
+        let next = (0..n)
+            .filter(|i| !medoids.contains(i))
+            .max_by(|&a, &b| {
+                let da = medoids.iter().map(|&m| distance[a][m]).fold(f64::MAX, f64::min);
+                let db = medoids.iter().map(|&m| distance[b][m]).fold(f64::MAX, f64::min);
+                da.partial_cmp(&db).unwrap().then(b.cmp(&a))
+            })
+            .unwrap();
+        medoids.push(next);
+    }
+
+    let nearest = |i: usize, medoids: &[usize]| {
+        (0..medoids.len())
+            .min_by(|&a, &b| distance[i][medoids[a]].partial_cmp(&distance[i][medoids[b]]).unwrap())
+            .unwrap_or(0)
+    };
+    let mut assignment: Vec<usize> = (0..n).map(|i| nearest(i, &medoids)).collect();
+
+    for _ in 0..max_iters {
+        // Each block's new medoid is the member closest to all other members
+        let mut changed = false;
+        for (b, medoid) in medoids.iter_mut().enumerate() {
+            let block: Vec<usize> = (0..n).filter(|&i| assignment[i] == b).collect();
+            let cost = |c: usize| block.iter().map(|&j| distance[c][j]).sum::<f64>();
+            if let Some(&best) = block.iter().min_by(|&&a, &&c| cost(a).partial_cmp(&cost(c)).unwrap()) {
+                if best != *medoid {
+                    *medoid = best;
+                    changed = true;
+                }
+            }
+        }
+        assignment = (0..n).map(|i| nearest(i, &medoids)).collect();
+        if !changed {
+            break;
+        }
+    }
+
+    // Image matrix: observed edges between blocks divided by possible node pairs
+    let mut sizes = vec![0usize; k];
+    for &b in &assignment {
+        sizes[b] += 1;
+    }
+    let position: HashMap<usize, usize> = members.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+    let mut edge_counts = vec![vec![0.0; k]; k];
+    for (i, &v) in members.iter().enumerate() {
+        for w in graph.neighbors(v) {
+            if let Some(&j) = position.get(w) {
+                edge_counts[assignment[i]][assignment[j]] += 1.0;
+            }
+        }
+    }
+    let image = (0..k)
+        .map(|a| {
+            (0..k)
+                .map(|b| {
+                    // Within a block every edge was seen from both ends
+                    let pairs = if a == b {
+                        (sizes[a] * sizes[a].saturating_sub(1)) as f64
+                    } else {
+                        (sizes[a] * sizes[b]) as f64
+                    };
+                    if pairs > 0.0 { edge_counts[a][b] / pairs } else { 0.0 }
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(Blockmodel {
+        blocks: members.iter().zip(&assignment).map(|(&v, &b)| (graph.node_id(v), b)).collect(),
+        sizes,
+        image,
+    })
+}
+
+// Finds communities by asynchronous label propagation (Raghavan, Albert & Kumara): every node
+// starts with its own label and repeatedly, in random order, adopts the label most common
+// among its neighbors (ties broken at random) until no label changes. Communities are then
+// numbered by decreasing size, ties by their smallest compact index, so 0 is the largest.
+// Its inputs are - `graph`: the graph - `max_iters`: maximum number of sweeps - `seed`: RNG seed
+// Its outputs are - `HashMap<usize, usize>`: node ID -> community
+pub fn label_propagation(graph: &Graph, max_iters: usize, seed: u64) -> HashMap<usize, usize> {
+    let n = graph.node_count();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut label: Vec<usize> = (0..n).collect();
+    let mut order: Vec<usize> = (0..n).collect();
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for _ in 0..max_iters {
+        order.shuffle(&mut rng);
+        let mut changed = false;
+        for &v in &order {
+            counts.clear();
+            for &w in graph.neighbors(v) {
+                *counts.entry(label[w]).or_insert(0) += 1;
+            }
+            let Some(&best) = counts.values().max() else { continue };
+            // Keep the current label when it is among the most common, so sweeps can settle
+            if counts.get(&label[v]) == Some(&best) {
+                continue;
+            }
+            let mut candidates: Vec<usize> = counts.iter().filter(|&(_, &c)| c == best).map(|(&l, _)| l).collect();
+            candidates.sort_unstable();
+            label[v] = *candidates.choose(&mut rng).unwrap();
+            changed = true;
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut sizes: HashMap<usize, (usize, usize)> = HashMap::new();
+    for (v, &l) in label.iter().enumerate() {
+        let entry = sizes.entry(l).or_insert((0, v));
+        entry.0 += 1;
+    }
+    let mut ranked: Vec<(usize, (usize, usize))> = sizes.into_iter().collect();
+    ranked.sort_by_key(|&(_, (size, first))| (std::cmp::Reverse(size), first));
+    let number: HashMap<usize, usize> = ranked.iter().enumerate().map(|(i, &(l, _))| (l, i)).collect();
+    (0..n).map(|v| (graph.node_id(v), number[&label[v]])).collect()
+}
+
+// Finds communities by the Louvain method (Blondel et al.): nodes are moved, in random order,
+// to the neighboring community with the largest modularity gain until no move helps; then
+// every community is coarsened into a super-node and the same is repeated on the summary
+// graph, until a level changes nothing. Edge weights are used if the graph has them.
+// Communities are numbered by decreasing size, ties by their smallest compact index.
+// Its inputs are - `graph`: the graph - `seed`: RNG seed for the node order
+// Its outputs are - `HashMap<usize, usize>`: node ID -> community
+pub fn louvain(graph: &Graph, seed: u64) -> HashMap<usize, usize> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut level = CoarseGraph::from_graph(graph);
+    let two_m = 2.0 * level.total_weight();
+    loop {
+        // A graph without edges has nothing to gain from moving nodes
+        if two_m == 0.0 {
+            break;
+        }
+        let g = &level.graph;
+        let n = g.node_count();
+        let strength: Vec<f64> = (0..n).map(|v| level.strength(v)).collect();
+        let mut community: Vec<usize> = (0..n).collect();
+        let mut total = strength.clone();
+        let mut order: Vec<usize> = (0..n).collect();
+        let mut improved = false;
+        let mut links: HashMap<usize, f64> = HashMap::new();
+        loop {
+            order.shuffle(&mut rng);
+            let mut moved = false;
+            for &v in &order {
+                let own = community[v];
+                links.clear();
+                links.insert(own, 0.0);
+                for (w, weight) in g.weighted_neighbors(v) {
+                    *links.entry(community[w]).or_insert(0.0) += weight;
+                }
+                total[own] -= strength[v];
+                // Gain of joining c, up to terms that are the same for every c
+                let gain = |c: usize, link: f64| link - total[c] * strength[v] / two_m;
+                let mut best = (own, gain(own, links[&own]));
+                let mut candidates: Vec<(usize, f64)> = links.iter().map(|(&c, &l)| (c, l)).collect();
+                candidates.sort_unstable_by_key(|&(c, _)| c);
+                for (c, link) in candidates {
+                    let g = gain(c, link);
+                    if g > best.1 + 1e-12 {
+                        best = (c, g);
+                    }
+                }
+                total[best.0] += strength[v];
+                if best.0 != own {
+                    community[v] = best.0;
+                    moved = true;
+                    improved = true;
+                }
+            }
+            if !moved {
+                break;
+            }
+        }
+        if !improved {
+            break;
+        }
+        let assignment: HashMap<usize, usize> = (0..n).map(|v| (g.node_id(v), community[v])).collect();
+        level = level.coarsen(&assignment);
+    }
+
+    let mut ranked: Vec<(usize, usize, usize)> = level
+        .members
+        .iter()
+        .map(|(&c, members)| (c, members.len(), members.iter().filter_map(|&id| graph.index_of(id)).min().unwrap_or(usize::MAX)))
+        .collect();
+    ranked.sort_by_key(|&(_, size, first)| (std::cmp::Reverse(size), first));
+    let number: HashMap<usize, usize> = ranked.iter().enumerate().map(|(i, &(c, _, _))| (c, i)).collect();
+    level.assignment().into_iter().map(|(node, c)| (node, number[&c])).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,15 +554,32 @@ mod tests {
     #[test]
     fn test_find_clusters() {
         let edges = vec![
-            (1, 2), (2, 3),
             (10, 11),
+            (1, 2), (2, 3),
         ];
         let clusters = find_clusters(&edges);
 
         assert_eq!(clusters.len(), 2); 
         let sizes: Vec<_> = clusters.iter().map(|c| c.len()).collect();
-        assert!(sizes.contains(&3)); 
-        assert!(sizes.contains(&2)); 
+        // Clusters come in the order of their smallest node
+        assert_eq!(sizes, vec![3, 2]);
+    }
+
+    // Tests the summary of a 4-node path next to a triangle, and that extracted components
+    // keep only their own edges.
+    #[test]
+    fn test_component_summary() {
+        let edges = vec![(1, 2), (2, 3), (3, 4), (10, 11), (11, 12), (12, 10)];
+        let graph = Graph::from_edges(&edges);
+        let mut clusters = find_clusters(&edges);
+        clusters.sort_by_key(|c| *c.iter().min().unwrap());
+        let summaries = component_summary(&graph, &clusters);
+
+        assert_eq!(summaries[0], ComponentSummary { size: 4, edges: 3, density: 0.5, diameter: 3 });
+        assert_eq!(summaries[1], ComponentSummary { size: 3, edges: 3, density: 1.0, diameter: 1 });
+        let triangle = extract_component(&graph, &clusters[1]);
+        assert_eq!(triangle.node_count(), 3);
+        assert!(triangle.index_of(1).is_none());
     }
 
     // Tests that features are correctly normalized to range [0,1].
@@ -201,5 +626,140 @@ mod tests {
 
         // Different clusters between groups
         assert_ne!(cluster_of_node1, cluster_of_node3);
+
+        // The same features give the same cluster IDs every time
+        assert_eq!(kmeans(&features, 2, 100), assignments);
+
+        // Centroid of the first group is the mean of nodes 1 and 2
+        let centroids = cluster_centroids(&features, &assignments);
+        let (centroid, count) = centroids[cluster_of_node1];
+        assert_eq!(count, 2);
+        assert!((centroid.0 - 0.05).abs() < 1e-12 && centroid.1 == 0.0 && (centroid.2 - 0.05).abs() < 1e-12);
+    }
+
+    // Tests cluster centroids on edge cases: no assignments give no clusters, a cluster ID with
+    // no members gets a zero centroid of size 0, and assigned nodes without features are not
+    // counted.
+    #[test]
+    fn test_cluster_centroids_edge_cases() {
+        assert!(cluster_centroids(&HashMap::new(), &HashMap::new()).is_empty());
+        let features = HashMap::from([(1, (1.0, 0.5, 0.0)), (2, (0.0, 0.5, 1.0))]);
+        let assignments = HashMap::from([(1, 2), (2, 2), (3, 0)]);
+        let centroids = cluster_centroids(&features, &assignments);
+        assert_eq!(centroids.len(), 3);
+        assert_eq!(centroids[0], ((0.0, 0.0, 0.0), 0));
+        assert_eq!(centroids[1], ((0.0, 0.0, 0.0), 0));
+        assert_eq!(centroids[2], ((0.5, 0.5, 0.5), 2));
+    }
+
+    // Tests that k-means stops as soon as the assignments stop changing: on well-separated
+    // groups the trace is short, every recorded step changes some assignment, and the result
+    // matches `kmeans_vectors`; a limit of one iteration records exactly one step, and zero
+    // clusters or no nodes record none.
+    #[test]
+    fn test_kmeans_steps() {
+        let features: HashMap<usize, Vec<f64>> =
+            vec![(1, vec![0.0, 0.0]), (2, vec![0.1, 0.0]), (3, vec![1.0, 1.0]), (4, vec![0.9, 1.0])].into_iter().collect();
+        let steps = kmeans_steps(&features, 2, 50);
+
+        assert!(!steps.is_empty() && steps.len() < 50);
+        assert!(steps.windows(2).all(|pair| pair[0].assignments != pair[1].assignments));
+        let last = steps.last().unwrap();
+        assert_eq!(last.centroids.len(), 2);
+        assert_eq!(last.assignments[&1], last.assignments[&2]);
+        assert_ne!(last.assignments[&1], last.assignments[&3]);
+        assert_eq!(last.assignments, kmeans_vectors(&features, 2, 50));
+
+        // Three groups of 40 points far apart
+        let separated: HashMap<usize, Vec<f64>> =
+            (0..120).map(|i| (i, vec![(i % 3) as f64 * 100.0 + (i % 7) as f64 * 0.1, (i % 5) as f64 * 0.1])).collect();
+        let steps = kmeans_steps(&separated, 3, 100);
+        assert!(steps.len() < 10, "{} iterations", steps.len());
+        let last = &steps.last().unwrap().assignments;
+        assert!((0..120).all(|i| last[&i] == last[&(i % 3)]));
+        assert_eq!(kmeans_steps(&separated, 3, 1).len(), 1);
+        assert!(kmeans_steps(&separated, 0, 100).is_empty() && kmeans_vectors(&separated, 0, 100).is_empty());
+        assert!(kmeans_steps(&HashMap::new(), 3, 100).is_empty());
+    }
+
+    // Tests that blockmodeling groups nodes with identical neighborhoods.
+    // Nodes 1 and 2 both email 10, 11, 12; nodes 3 and 4 both email 20, 21, 22. Too many nodes
+    // and k = 0 are errors.
+    #[test]
+    fn test_blockmodel() {
+        let mut edges = Vec::new();
+        for &(a, b) in &[(1, 2), (3, 4)] {
+            let targets = if a == 1 { [10, 11, 12] } else { [20, 21, 22] };
+            for t in targets {
+                edges.push((a, t));
+                edges.push((b, t));
+            }
+        }
+        let graph = Graph::from_edges(&edges);
+        let nodes: HashSet<usize> = vec![1, 2, 3, 4, 10, 11, 12, 20, 21, 22].into_iter().collect();
+        let model = blockmodel(&graph, &nodes, 4, 10).unwrap();
+
+        assert_eq!(model.blocks[&1], model.blocks[&2]);
+        assert_eq!(model.blocks[&3], model.blocks[&4]);
+        assert_eq!(model.blocks[&10], model.blocks[&12]);
+        assert_ne!(model.blocks[&1], model.blocks[&3]);
+        // Senders and their recipients are completely connected, with no edges inside either block
+        let (s, r) = (model.blocks[&1], model.blocks[&10]);
+        assert_eq!(model.image[s][r], 1.0);
+        assert_eq!(model.image[s][s], 0.0);
+        assert_eq!(model.sizes.iter().sum::<usize>(), 10);
+
+        // Too many nodes for the distance matrix is an error, not an out-of-memory crash
+        let path: Vec<(usize, usize)> = (0..BLOCKMODEL_NODES).map(|v| (v, v + 1)).collect();
+        let all: HashSet<usize> = (0..=BLOCKMODEL_NODES).collect();
+        assert!(blockmodel(&Graph::from_edges(&path), &all, 4, 10).is_err());
+        // So are no blocks at all, with or without nodes to group
+        assert!(blockmodel(&graph, &nodes, 0, 10).is_err());
+        assert!(blockmodel(&graph, &HashSet::new(), 0, 10).is_err());
+    }
+
+    // Tests that label propagation separates two 5-cliques joined by a single edge, with the
+    // larger community numbered 0.
+    #[test]
+    fn test_label_propagation() {
+        let mut edges = Vec::new();
+        for base in [0, 10] {
+            for a in 0..5 {
+                for b in a + 1..5 {
+                    edges.push((base + a, base + b));
+                }
+            }
+        }
+        edges.push((4, 10));
+        edges.push((20, 10));
+        let graph = Graph::from_edges(&edges);
+        let community = label_propagation(&graph, 100, 7);
+        assert!((0..5).all(|v| community[&v] == community[&0]));
+        assert!((10..15).all(|v| community[&v] == community[&11]));
+        assert_ne!(community[&0], community[&11]);
+        assert_eq!(community[&11], 0);
+    }
+
+    // Tests that Louvain separates two 5-cliques joined by a single edge, keeps an isolated
+    // pair apart, and finds a higher modularity than putting every node in one community.
+    #[test]
+    fn test_louvain() {
+        let mut edges = Vec::new();
+        for base in [0, 10] {
+            for a in 0..5 {
+                for b in a + 1..5 {
+                    edges.push((base + a, base + b));
+                }
+            }
+        }
+        edges.extend([(4, 10), (20, 21)]);
+        let graph = Graph::from_edges(&edges);
+        let community = louvain(&graph, 7);
+        assert!((0..5).all(|v| community[&v] == community[&0]));
+        assert!((10..15).all(|v| community[&v] == community[&10]));
+        assert_ne!(community[&0], community[&10]);
+        assert_eq!((community[&0], community[&20]), (0, 2));
+        let one: HashMap<usize, usize> = community.keys().map(|&v| (v, 0)).collect();
+        assert!(crate::coarsen::modularity(&graph, &community) > crate::coarsen::modularity(&graph, &one) + 0.3);
     }
 }// Final commit: updated cluster.rs
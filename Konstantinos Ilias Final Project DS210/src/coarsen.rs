@@ -0,0 +1,149 @@
+// Includes graph coarsening: the nodes of each community (from Louvain, label propagation or
+// connected components) are merged into one super-node, and the edges between two communities
+// into one edge carrying their total weight. The summary graph is small enough to lay out and
+// plot in full, is a `Graph` like any other so every metric runs on it, and can be coarsened
+// again for a hierarchy of summaries.
+
+use crate::graph::Graph;
+use std::collections::HashMap;
+
+// A graph of super-nodes, with the original nodes each one stands for.
+pub struct CoarseGraph {
+    // Super-nodes and the total weight of the edges between them (1 per edge of an unweighted graph)
+    pub graph: Graph,
+    // Super-node ID -> original node IDs merged into it, sorted
+    pub members: HashMap<usize, Vec<usize>>,
+    // Super-node ID -> total weight of the original edges inside it
+    pub internal: HashMap<usize, f64>,
+}
+
+impl CoarseGraph {
+    // The trivial summary of a graph: every node is its own super-node, keeping its ID.
+    pub fn from_graph(graph: &Graph) -> CoarseGraph {
+        let ids: Vec<usize> = (0..graph.node_count()).map(|v| graph.node_id(v)).collect();
+        CoarseGraph {
+            graph: graph.subgraph(&(0..graph.node_count()).collect::<Vec<_>>()),
+            members: ids.iter().map(|&id| (id, vec![id])).collect(),
+            internal: ids.iter().map(|&id| (id, 0.0)).collect(),
+        }
+    }
+
+    // Merges super-nodes into communities. Super-nodes missing from the assignment become
+    // communities of their own, numbered after the largest community given.
+    // Its inputs are - `assignment`: super-node ID -> community
+    // Its outputs are - `CoarseGraph`: one super-node per community, with the community as its ID
+    pub fn coarsen(&self, assignment: &HashMap<usize, usize>) -> CoarseGraph {
+        let graph = &self.graph;
+        let mut next = assignment.values().max().map_or(0, |&c| c + 1);
+        let community: Vec<usize> = (0..graph.node_count())
+            .map(|v| {
+                assignment.get(&graph.node_id(v)).copied().unwrap_or_else(|| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect();
+
+        let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut internal: HashMap<usize, f64> = HashMap::new();
+        let mut between: HashMap<(usize, usize), f64> = HashMap::new();
+        for (v, &c) in community.iter().enumerate() {
+            let id = graph.node_id(v);
+            members.entry(c).or_default().extend(&self.members[&id]);
+            *internal.entry(c).or_insert(0.0) += self.internal[&id];
+            for (w, weight) in graph.weighted_neighbors(v).filter(|&(w, _)| w > v) {
+                let d = community[w];
+                if c == d {
+                    *internal.entry(c).or_insert(0.0) += weight;
+                } else {
+                    *between.entry((c.min(d), c.max(d))).or_insert(0.0) += weight;
+                }
+            }
+        }
+        for list in members.values_mut() {
+            list.sort_unstable();
+        }
+
+        let mut edges: Vec<(usize, usize, f64)> = between.into_iter().map(|((c, d), w)| (c, d, w)).collect();
+        edges.sort_unstable_by_key(|&(c, d, _)| (c, d));
+        let mut coarse = Graph::from_weighted_edges(&edges);
+        // Communities without outside edges are still super-nodes
+        let mut ids: Vec<usize> = members.keys().copied().collect();
+        ids.sort_unstable();
+        for c in ids {
+            coarse.add_node(c);
+        }
+        CoarseGraph { graph: coarse, members, internal }
+    }
+
+    // Super-node of every original node.
+    // Its outputs are - `HashMap<usize, usize>`: original node ID -> super-node ID
+    pub fn assignment(&self) -> HashMap<usize, usize> {
+        self.members.iter().flat_map(|(&c, list)| list.iter().map(move |&node| (node, c))).collect()
+    }
+
+    // Total edge weight of the original graph: inside and between super-nodes.
+    pub fn total_weight(&self) -> f64 {
+        let between: f64 = (0..self.graph.node_count()).flat_map(|v| self.graph.weighted_neighbors(v).map(|(_, w)| w)).sum::<f64>() / 2.0;
+        self.internal.values().sum::<f64>() + between
+    }
+
+    // Total weight of the original edges at each super-node, counting edges inside it twice
+    // (the sum of the weighted degrees of its members).
+    pub fn strength(&self, v: usize) -> f64 {
+        self.graph.weighted_neighbors(v).map(|(_, w)| w).sum::<f64>() + 2.0 * self.internal[&self.graph.node_id(v)]
+    }
+}
+
+// Coarsens a graph by merging each community into a super-node.
+// Its inputs are - `graph`: the graph - `assignment`: node ID -> community
+// Its outputs are - `CoarseGraph`: the summary graph, with community numbers as node IDs
+pub fn coarsen(graph: &Graph, assignment: &HashMap<usize, usize>) -> CoarseGraph {
+    CoarseGraph::from_graph(graph).coarsen(assignment)
+}
+
+// Weighted modularity Q = sum over communities c of [in_c / m - (tot_c / 2m)^2], where in_c is
+// the weight inside c, tot_c the total strength of its members and m the total weight: the
+// share of the weight inside communities beyond what random rewiring would put there.
+// Its inputs are - `graph`: the graph - `assignment`: node ID -> community
+// Its outputs are - `f64`: modularity, between -1/2 and 1 (0 for a graph without edges)
+pub fn modularity(graph: &Graph, assignment: &HashMap<usize, usize>) -> f64 {
+    let coarse = coarsen(graph, assignment);
+    let m = coarse.total_weight();
+    if m == 0.0 {
+        return 0.0;
+    }
+    (0..coarse.graph.node_count())
+        .map(|v| coarse.internal[&coarse.graph.node_id(v)] / m - (coarse.strength(v) / (2.0 * m)).powi(2))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests coarsening two triangles joined by one edge, plus an isolated pair: each triangle
+    // becomes a super-node with internal weight 3, the bridge a weight-1 edge, the pair a
+    // super-node without edges; coarsening again merges everything, and the modularity of the
+    // split matches the formula.
+    #[test]
+    fn test_coarsen() {
+        let graph = Graph::from_edges(&[(1, 2), (2, 3), (3, 1), (4, 5), (5, 6), (6, 4), (3, 4), (7, 8)]);
+        let assignment: HashMap<usize, usize> = [(1, 0), (2, 0), (3, 0), (4, 1), (5, 1), (6, 1), (7, 2), (8, 2)].into_iter().collect();
+        let coarse = coarsen(&graph, &assignment);
+        assert_eq!(coarse.graph.node_count(), 3);
+        assert_eq!(coarse.graph.edges(), vec![(0, 1)]);
+        assert_eq!((coarse.internal[&0], coarse.internal[&1], coarse.internal[&2]), (3.0, 3.0, 1.0));
+        assert_eq!(coarse.members[&1], vec![4, 5, 6]);
+        assert_eq!(coarse.total_weight(), 8.0);
+        assert_eq!(coarse.assignment()[&8], 2);
+
+        let top = coarse.coarsen(&HashMap::from([(0, 0), (1, 0), (2, 0)]));
+        assert_eq!((top.graph.node_count(), top.internal[&0], top.members[&0].len()), (1, 8.0, 8));
+
+        // Q = 3/8 - (7/16)^2 + 3/8 - (7/16)^2 + 1/8 - (2/16)^2
+        let expected = 7.0 / 8.0 - 2.0 * (7.0f64 / 16.0).powi(2) - (2.0f64 / 16.0).powi(2);
+        assert!((modularity(&graph, &assignment) - expected).abs() < 1e-12);
+        assert!(modularity(&graph, &HashMap::new()) < 0.0);
+    }
+}
@@ -0,0 +1,249 @@
+// Includes a compressed adjacency encoding for holding much larger SNAP graphs in memory than
+// `Graph` can. Each sorted neighbor list is stored as its length followed by the gaps between
+// consecutive neighbors, every number as a variable-length integer (7 bits per byte), so a
+// typical edge costs one or two bytes instead of eight. Node IDs are numbered in ascending order,
+// which makes the ID lookup a binary search instead of a hash map. The graph implements
+// `Adjacency`, so BFS, closeness and PageRank run on it unchanged, decoding lists on the fly.
+
+use crate::graph::{Adjacency, Graph};
+
+// Appends `x` as an unsigned LEB128 varint: 7 bits per byte, high bit set on all but the last.
+fn write_varint(data: &mut Vec<u8>, mut x: u64) {
+    while x >= 0x80 {
+        data.push((x as u8) | 0x80);
+        x >>= 7;
+    }
+    data.push(x as u8);
+}
+
+// Reads the varint at `data[*pos..]` and moves `pos` past it.
+fn read_varint(data: &[u8], pos: &mut usize) -> u64 {
+    let mut x = 0;
+    let mut shift = 0;
+    loop {
+        let byte = data[*pos];
+        *pos += 1;
+        x |= u64::from(byte & 0x7f) << shift;
+        if byte < 0x80 {
+            return x;
+        }
+        shift += 7;
+    }
+}
+
+// Maps a signed difference to an unsigned one, small magnitudes to small numbers (0, -1, 1, -2, ...).
+fn zigzag(x: i64) -> u64 {
+    ((x << 1) ^ (x >> 63)) as u64
+}
+
+fn unzigzag(x: u64) -> i64 {
+    (x >> 1) as i64 ^ -((x & 1) as i64)
+}
+
+// Undirected simple graph with delta-varint encoded neighbor lists.
+// The list of compact index `v` starts at `data[offsets[v]]` with the degree; the first
+// neighbor is stored as a zigzag difference from `v` (neighbors tend to have nearby indices),
+// the rest as the gap to the previous neighbor minus one.
+pub struct CompressedGraph {
+    // Compact index -> original node ID, ascending
+    ids: Vec<usize>,
+    offsets: Vec<usize>,
+    data: Vec<u8>,
+    edges: usize,
+}
+
+impl CompressedGraph {
+    // Encodes sorted, deduplicated neighbor lists given by compact index.
+    fn encode(mut ids: Vec<usize>, mut lists: impl FnMut(usize, &mut Vec<usize>)) -> CompressedGraph {
+        let mut offsets = Vec::with_capacity(ids.len() + 1);
+        let mut data = Vec::new();
+        let mut nbrs = Vec::new();
+        let mut edges = 0;
+        for v in 0..ids.len() {
+            offsets.push(data.len());
+            nbrs.clear();
+            lists(v, &mut nbrs);
+            write_varint(&mut data, nbrs.len() as u64);
+            let mut prev = v;
+            for (k, &w) in nbrs.iter().enumerate() {
+                if k == 0 {
+                    write_varint(&mut data, zigzag(w as i64 - v as i64));
+                } else {
+                    write_varint(&mut data, (w - prev - 1) as u64);
+                }
+                prev = w;
+            }
+            edges += nbrs.len();
+        }
+        offsets.push(data.len());
+        ids.shrink_to_fit();
+        data.shrink_to_fit();
+        CompressedGraph { ids, offsets, data, edges: edges / 2 }
+    }
+
+    // Builds a compressed graph from an edge list, dropping self loops and duplicate edges,
+    // without building a `Graph` first: only the list of index pairs is held uncompressed.
+    // Its inputs are - `edges`: list of (u, v) graph edges
+    // Its outputs are - `CompressedGraph`: the undirected graph
+    pub fn from_edges(edges: &[(usize, usize)]) -> CompressedGraph {
+        let mut ids: Vec<usize> = edges.iter().filter(|&&(u, v)| u != v).flat_map(|&(u, v)| [u, v]).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        let index = |id: usize| ids.binary_search(&id).expect("endpoint was collected");
+        let mut pairs: Vec<(usize, usize)> = edges
+            .iter()
+            .filter(|&&(u, v)| u != v)
+            .flat_map(|&(u, v)| {
+                let (a, b) = (index(u), index(v));
+                [(a, b), (b, a)]
+            })
+            .collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+
+        let mut start = 0;
+        CompressedGraph::encode(ids, |v, nbrs| {
+            while start < pairs.len() && pairs[start].0 == v {
+                nbrs.push(pairs[start].1);
+                start += 1;
+            }
+        })
+    }
+
+    // Compresses a graph, dropping edge weights.
+    // Its inputs are - `graph`: the graph
+    // Its outputs are - `CompressedGraph`: the same nodes and edges, numbered by ascending node ID
+    pub fn from_graph(graph: &Graph) -> CompressedGraph {
+        let mut order: Vec<usize> = (0..graph.node_count()).collect();
+        order.sort_unstable_by_key(|&v| graph.node_id(v));
+        let mut rank = vec![0; order.len()];
+        for (k, &v) in order.iter().enumerate() {
+            rank[v] = k;
+        }
+        let ids = order.iter().map(|&v| graph.node_id(v)).collect();
+        CompressedGraph::encode(ids, |k, nbrs| {
+            nbrs.extend(graph.neighbors(order[k]).iter().map(|&w| rank[w]));
+            nbrs.sort_unstable();
+        })
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    // Heap memory held by the graph, in bytes.
+    pub fn heap_bytes(&self) -> usize {
+        self.ids.capacity() * std::mem::size_of::<usize>() + self.offsets.capacity() * std::mem::size_of::<usize>() + self.data.capacity()
+    }
+
+    // Bytes of the encoded neighbor lists alone.
+    pub fn encoded_bytes(&self) -> usize {
+        self.data.len()
+    }
+}
+
+// Decodes one neighbor list as it is iterated.
+pub struct CompressedNeighbors<'a> {
+    data: &'a [u8],
+    pos: usize,
+    remaining: usize,
+    // Previous neighbor, or `None` before the first one
+    prev: Option<usize>,
+    node: usize,
+}
+
+impl Iterator for CompressedNeighbors<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let x = read_varint(self.data, &mut self.pos);
+        let w = match self.prev {
+            None => (self.node as i64 + unzigzag(x)) as usize,
+            Some(prev) => prev + x as usize + 1,
+        };
+        self.prev = Some(w);
+        Some(w)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for CompressedNeighbors<'_> {}
+
+impl Adjacency for CompressedGraph {
+    type Neighbors<'a> = CompressedNeighbors<'a>;
+
+    fn node_count(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn node_id(&self, i: usize) -> usize {
+        self.ids[i]
+    }
+
+    fn index_of(&self, id: usize) -> Option<usize> {
+        self.ids.binary_search(&id).ok()
+    }
+
+    fn degree(&self, i: usize) -> usize {
+        let mut pos = self.offsets[i];
+        read_varint(&self.data, &mut pos) as usize
+    }
+
+    fn neighbor_iter(&self, i: usize) -> CompressedNeighbors<'_> {
+        let mut pos = self.offsets[i];
+        let remaining = read_varint(&self.data, &mut pos) as usize;
+        CompressedNeighbors { data: &self.data, pos, remaining, prev: None, node: i }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::batched_closeness;
+    use crate::pagerank::pagerank;
+    use std::collections::HashSet;
+
+    // Tests varints at byte boundaries, and that a compressed graph (with large, sparse IDs,
+    // duplicates and a self loop) has the same neighbors as the `Graph` of the same edges and
+    // gives the same closeness and PageRank.
+    #[test]
+    fn test_compressed_graph() {
+        let mut data = Vec::new();
+        let values = [0, 127, 128, 16383, 16384, u64::MAX];
+        for &x in &values {
+            write_varint(&mut data, x);
+        }
+        let mut pos = 0;
+        assert_eq!(values.map(|_| read_varint(&data, &mut pos)), values);
+        assert_eq!([-3, -1, 0, 2].map(|x| unzigzag(zigzag(x))), [-3, -1, 0, 2]);
+
+        let mut edges = vec![(999_999, 999_999), (1_000_000, 3), (3, 1_000_000), (7, 2)];
+        for v in 0..300usize {
+            edges.push((v * 11, (v * 37 + 1) % 997));
+        }
+        let graph = Graph::from_edges(&edges);
+        for compressed in [CompressedGraph::from_edges(&edges), CompressedGraph::from_graph(&graph)] {
+            assert_eq!((compressed.node_count(), compressed.edge_count()), (graph.node_count(), graph.edge_count()));
+            assert_eq!(compressed.index_of(999_999), None);
+            for v in 0..graph.node_count() {
+                let c = compressed.index_of(graph.node_id(v)).unwrap();
+                let mut expected: Vec<usize> = graph.neighbors(v).iter().map(|&w| graph.node_id(w)).collect();
+                expected.sort_unstable();
+                let found: Vec<usize> = compressed.neighbor_iter(c).map(|w| compressed.node_id(w)).collect();
+                assert_eq!(found, expected);
+                assert_eq!(compressed.degree(c), graph.degree(v));
+            }
+            let nodes: HashSet<usize> = [3, 7, 1_000_000].into_iter().collect();
+            assert_eq!(batched_closeness(&compressed, &nodes), batched_closeness(&graph, &nodes));
+            let (a, b) = (pagerank(&compressed, 0.85, 1e-12, 200), pagerank(&graph, 0.85, 1e-12, 200));
+            assert!(a.iter().all(|(id, r)| (r - b[id]).abs() < 1e-9));
+        }
+    }
+}
@@ -0,0 +1,209 @@
+// Includes functions about how robustly the network is connected, such as articulation points
+// and biconnected components, both found with Tarjan's depth-first search low-link values.
+
+use crate::graph::Graph;
+use std::collections::HashMap;
+
+const UNVISITED: usize = usize::MAX;
+
+// Everything the low-link DFS learns about the graph, in compact indices.
+struct LowLink {
+    // (root, nodes visited from it) for every DFS tree, i.e. every connected component
+    trees: Vec<(usize, Vec<usize>)>,
+    // Sizes of the DFS subtrees that get separated from each node
+    pieces: Vec<Vec<usize>>,
+    // Node sets of the biconnected components
+    blocks: Vec<Vec<usize>>,
+}
+
+// Runs an iterative version of Tarjan's DFS (the graph is too deep for recursion).
+// When a child c of v finishes with low[c] >= disc[v], the subtree of c is separated from the
+// rest at v, and the edges pushed since the tree edge (v, c) form one biconnected component.
+fn low_link(graph: &Graph) -> LowLink {
+    let n = graph.node_count();
+    let mut disc = vec![UNVISITED; n];
+    let mut low = vec![0; n];
+    let mut size = vec![1; n];
+    let mut parent = vec![UNVISITED; n];
+    let mut pieces: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut blocks = Vec::new();
+    let mut trees = Vec::new();
+    let mut edge_stack: Vec<(usize, usize)> = Vec::new();
+    let mut time = 0;
+
+    for root in 0..n {
+        if disc[root] != UNVISITED {
+            continue;
+        }
+        disc[root] = time;
+        low[root] = time;
+        time += 1;
+        let mut stack = vec![(root, 0)];
+        let mut visited = vec![root];
+
+        while let Some(&mut (v, ref mut next)) = stack.last_mut() {
+            if let Some(&w) = graph.neighbors(v).get(*next) {
+                *next += 1;
+                if disc[w] == UNVISITED {
+                    parent[w] = v;
+                    disc[w] = time;
+                    low[w] = time;
+                    time += 1;
+                    edge_stack.push((v, w));
+                    stack.push((w, 0));
+                    visited.push(w);
+                } else if w != parent[v] && disc[w] < disc[v] {
+                    low[v] = low[v].min(disc[w]);
+                    edge_stack.push((v, w));
+                }
+            } else {
+                stack.pop();
+                let p = parent[v];
+                if p == UNVISITED {
+                    continue;
+                }
+                low[p] = low[p].min(low[v]);
+                size[p] += size[v];
+                if low[v] >= disc[p] {
+                    pieces[p].push(size[v]);
+                    let mut block = Vec::new();
+                    while let Some((a, b)) = edge_stack.pop() {
+                        block.push(a);
+                        block.push(b);
+                        if (a, b) == (p, v) {
+                            break;
+                        }
+                    }
+                    block.sort_unstable();
+                    block.dedup();
+                    blocks.push(block);
+                }
+            }
+        }
+        trees.push((root, visited));
+    }
+
+    LowLink { trees, pieces, blocks }
+}
+
+// Finds articulation points: nodes whose removal splits their connected component.
+// A non-root node is a cut node if one of its DFS subtrees is separated at it,
+// and a DFS root is one if it has two or more children.
+// Its inputs are - `graph`: the graph
+// Its outputs are - `HashMap<usize, usize>`: articulation point ID -> number of nodes cut off
+// from the largest remaining piece of its component when it is removed
+pub fn articulation_points(graph: &Graph) -> HashMap<usize, usize> {
+    let LowLink { trees, mut pieces, .. } = low_link(graph);
+    let mut result = HashMap::new();
+
+    for (root, visited) in trees {
+        let component_size = visited.len();
+        for v in visited {
+            let mut parts = std::mem::take(&mut pieces[v]);
+            if v == root {
+                if parts.len() < 2 {
+                    continue;
+                }
+            } else {
+                if parts.is_empty() {
+                    continue;
+                }
+                let rest = component_size - 1 - parts.iter().sum::<usize>();
+                parts.push(rest);
+            }
+            let largest = *parts.iter().max().unwrap();
+            let cut_off = parts.iter().sum::<usize>() - largest;
+            result.insert(graph.node_id(v), cut_off);
+        }
+    }
+
+    result
+}
+
+// Block-cut tree of the graph: biconnected components (blocks) joined through the
+// articulation points they share. It is a forest with one tree per connected component.
+#[derive(Debug, Clone)]
+pub struct BlockCutTree {
+    // Node IDs of each block, sorted ascending
+    pub blocks: Vec<Vec<usize>>,
+    // Node IDs of the articulation points, sorted ascending
+    pub cut_nodes: Vec<usize>,
+    // Tree edges as (block index, articulation point ID)
+    pub edges: Vec<(usize, usize)>,
+}
+
+// Decomposes the graph into biconnected components, i.e. maximal pieces that stay
+// connected after removing any single node, and links them into a block-cut tree.
+// Its inputs are - `graph`: the graph
+// Its outputs are - `BlockCutTree`: the blocks, cut nodes and tree edges
+pub fn biconnected_components(graph: &Graph) -> BlockCutTree {
+    let LowLink { blocks, .. } = low_link(graph);
+
+    // A node that appears in more than one block is an articulation point
+    let mut membership = vec![0; graph.node_count()];
+    for block in &blocks {
+        for &v in block {
+            membership[v] += 1;
+        }
+    }
+
+    let mut edges = Vec::new();
+    for (i, block) in blocks.iter().enumerate() {
+        for &v in block.iter().filter(|&&v| membership[v] > 1) {
+            edges.push((i, graph.node_id(v)));
+        }
+    }
+    let mut cut_nodes: Vec<usize> = (0..graph.node_count())
+        .filter(|&v| membership[v] > 1)
+        .map(|v| graph.node_id(v))
+        .collect();
+    cut_nodes.sort_unstable();
+
+    let blocks = blocks
+        .into_iter()
+        .map(|block| {
+            let mut ids: Vec<usize> = block.into_iter().map(|v| graph.node_id(v)).collect();
+            ids.sort_unstable();
+            ids
+        })
+        .collect();
+
+    BlockCutTree { blocks, cut_nodes, edges }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that articulation points are detected with the right number of cut-off nodes.
+    // Triangle 1-2-3 hangs off node 3, which connects to path 3-4-5; node 6 is a leaf on 5.
+    #[test]
+    fn test_articulation_points() {
+        let edges = vec![(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 6), (10, 11)];
+        let graph = Graph::from_edges(&edges);
+        let points = articulation_points(&graph);
+
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[&3], 2);
+        assert_eq!(points[&4], 2);
+        assert_eq!(points[&5], 1);
+        assert!(!points.contains_key(&1));
+        assert!(!points.contains_key(&10));
+    }
+
+    // Tests the biconnected components and block-cut tree of the same graph.
+    // Expected blocks: the triangle, the three bridges 3-4, 4-5, 5-6 and the separate edge 10-11.
+    #[test]
+    fn test_biconnected_components() {
+        let edges = vec![(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 6), (10, 11)];
+        let graph = Graph::from_edges(&edges);
+        let tree = biconnected_components(&graph);
+
+        let mut blocks = tree.blocks.clone();
+        blocks.sort();
+        assert_eq!(blocks, vec![vec![1, 2, 3], vec![3, 4], vec![4, 5], vec![5, 6], vec![10, 11]]);
+        assert_eq!(tree.cut_nodes, vec![3, 4, 5]);
+        // Each cut node joins exactly two blocks here
+        assert_eq!(tree.edges.len(), 6);
+    }
+}
@@ -0,0 +1,288 @@
+// Includes functions that compare centrality measures with each other: Pearson correlation
+// of the raw values, Spearman and Kendall correlation of their rankings, and a correlation
+// matrix over any number of centrality maps, computed on the nodes they all cover.
+
+use crate::inequality::CentralityValue;
+use crate::stats::top_k;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+// How two measures are correlated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorrelationMethod {
+    // Linear correlation of the values
+    Pearson,
+    // Pearson correlation of the ranks, so any monotonic relationship scores 1
+    Spearman,
+    // Kendall's tau-b: concordant minus discordant pairs, corrected for ties
+    Kendall,
+}
+
+impl fmt::Display for CorrelationMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CorrelationMethod::Pearson => "Pearson",
+            CorrelationMethod::Spearman => "Spearman",
+            CorrelationMethod::Kendall => "Kendall",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Computes the Pearson correlation coefficient of two equally long samples.
+// Its inputs are - `x`, `y`: paired samples
+// Its outputs are - `f64`: correlation in [-1, 1] (0 when either sample is constant)
+pub fn pearson(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len().min(y.len());
+    if n == 0 {
+        return 0.0;
+    }
+    let mean_x = x[..n].iter().sum::<f64>() / n as f64;
+    let mean_y = y[..n].iter().sum::<f64>() / n as f64;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (a, b) in x.iter().zip(y) {
+        cov += (a - mean_x) * (b - mean_y);
+        var_x += (a - mean_x) * (a - mean_x);
+        var_y += (b - mean_y) * (b - mean_y);
+    }
+    if var_x > 0.0 && var_y > 0.0 { cov / (var_x * var_y).sqrt() } else { 0.0 }
+}
+
+// Ranks values from 1 (smallest) up, giving tied values the average of their ranks.
+pub(crate) fn average_ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap());
+    let mut ranks = vec![0.0; values.len()];
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i;
+        while j + 1 < order.len() && values[order[j + 1]] == values[order[i]] {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        for &k in &order[i..=j] {
+            ranks[k] = rank;
+        }
+        i = j + 1;
+    }
+    ranks
+}
+
+// Computes the Spearman rank correlation of two equally long samples, with tied values
+// sharing their average rank.
+// Its inputs are - `x`, `y`: paired samples
+// Its outputs are - `f64`: rank correlation in [-1, 1]
+pub fn spearman(x: &[f64], y: &[f64]) -> f64 {
+    pearson(&average_ranks(x), &average_ranks(y))
+}
+
+// Computes Kendall's tau-b of two equally long samples with Knight's O(n log n) algorithm:
+// pairs are sorted by x, and the discordant pairs are the swaps a merge sort needs to
+// sort the y values that follow.
+// Its inputs are - `x`, `y`: paired samples
+// Its outputs are - `f64`: tau-b in [-1, 1] (0 when either sample is constant)
+pub fn kendall_tau(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len().min(y.len());
+    let mut pairs: Vec<(f64, f64)> = x.iter().copied().zip(y.iter().copied()).take(n).collect();
+    pairs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap().then(a.1.partial_cmp(&b.1).unwrap()));
+
+    // Pairs tied in x, tied in both x and y, and (after sorting) tied in y
+    let x_ties = tied_pairs(n, |i| pairs[i].0 == pairs[i - 1].0);
+    let joint_ties = tied_pairs(n, |i| pairs[i] == pairs[i - 1]);
+    let mut ys: Vec<f64> = pairs.iter().map(|p| p.1).collect();
+    let swaps = merge_sort_swaps(&mut ys);
+    let y_ties = tied_pairs(n, |i| ys[i] == ys[i - 1]);
+
+    let total = (n * n.saturating_sub(1) / 2) as u64;
+    if n < 2 || x_ties == total || y_ties == total {
+        return 0.0;
+    }
+    let concordant_minus_discordant = total as f64 - x_ties as f64 - y_ties as f64 + joint_ties as f64 - 2.0 * swaps as f64;
+    concordant_minus_discordant / (((total - x_ties) as f64) * ((total - y_ties) as f64)).sqrt()
+}
+
+// Counts the pairs within runs of equal neighbors in a sorted sequence of length `n`,
+// where `same(i)` tells whether element i equals element i - 1.
+fn tied_pairs(n: usize, same: impl Fn(usize) -> bool) -> u64 {
+    let mut pairs = 0;
+    let mut run = 0;
+    for i in 1..n {
+        if same(i) {
+            run += 1;
+            pairs += run;
+        } else {
+            run = 0;
+        }
+    }
+    pairs
+}
+
+// Sorts the values ascending and returns how many adjacent swaps that takes (the number of
+// strictly inverted pairs).
+fn merge_sort_swaps(values: &mut [f64]) -> u64 {
+    let n = values.len();
+    if n < 2 {
+        return 0;
+    }
+    let mid = n / 2;
+    let mut swaps = merge_sort_swaps(&mut values[..mid]) + merge_sort_swaps(&mut values[mid..]);
+    let mut merged = Vec::with_capacity(n);
+    let (mut i, mut j) = (0, mid);
+    while i < mid && j < n {
+        if values[j] < values[i] {
+            merged.push(values[j]);
+            swaps += (mid - i) as u64;
+            j += 1;
+        } else {
+            merged.push(values[i]);
+            i += 1;
+        }
+    }
+    merged.extend_from_slice(&values[i..mid]);
+    merged.extend_from_slice(&values[j..]);
+    values.copy_from_slice(&merged);
+    swaps
+}
+
+// How consistently two centrality measures rank the same nodes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankAgreement {
+    pub spearman: f64,
+    pub kendall: f64,
+    // Number of nodes scored by both measures
+    pub common: usize,
+}
+
+// Compares the rankings of two centrality maps on the nodes they have in common.
+// Its inputs are - `a`, `b`: node ID -> centrality value
+// Its outputs are - `RankAgreement`: Spearman's rho, Kendall's tau-b and the number of common nodes
+pub fn rank_agreement<A: CentralityValue, B: CentralityValue>(a: &HashMap<usize, A>, b: &HashMap<usize, B>) -> RankAgreement {
+    let mut nodes: Vec<usize> = a.keys().copied().filter(|n| b.contains_key(n)).collect();
+    nodes.sort_unstable();
+    let x: Vec<f64> = nodes.iter().map(|n| a[n].as_f64()).collect();
+    let y: Vec<f64> = nodes.iter().map(|n| b[n].as_f64()).collect();
+    RankAgreement { spearman: spearman(&x, &y), kendall: kendall_tau(&x, &y), common: nodes.len() }
+}
+
+// The `k` highest-scoring nodes of a centrality map, with ties broken by smaller node ID.
+fn top_k_set<V: CentralityValue>(centrality: &HashMap<usize, V>, k: usize) -> HashSet<usize> {
+    let values: HashMap<usize, f64> = centrality.iter().map(|(&node, v)| (node, v.as_f64())).collect();
+    top_k(&values, k).into_iter().map(|(node, _)| node).collect()
+}
+
+// Computes the Jaccard overlap |A ∩ B| / |A ∪ B| of the top-k node sets of two centrality
+// maps, i.e. how far two measures agree on who the key players are.
+// Its inputs are - `a`, `b`: node ID -> centrality value - `k`: size of each top set
+// Its outputs are - `f64`: overlap between 0 (disjoint) and 1 (same set)
+pub fn top_k_jaccard<A: CentralityValue, B: CentralityValue>(a: &HashMap<usize, A>, b: &HashMap<usize, B>, k: usize) -> f64 {
+    let top_a = top_k_set(a, k);
+    let top_b = top_k_set(b, k);
+    let union = top_a.union(&top_b).count();
+    if union == 0 { 0.0 } else { top_a.intersection(&top_b).count() as f64 / union as f64 }
+}
+
+// Computes the top-k Jaccard overlap of every pair of measures for each k.
+// Its inputs are - `measures`: (name, node ID -> value) per measure - `ks`: top set sizes
+// Its outputs are - `Vec<(String, String, Vec<f64>)>`: (first, second, overlap per k) per pair
+pub fn top_k_overlaps(measures: &[(&str, HashMap<usize, f64>)], ks: &[usize]) -> Vec<(String, String, Vec<f64>)> {
+    let mut overlaps = Vec::new();
+    for i in 0..measures.len() {
+        for j in (i + 1)..measures.len() {
+            let values = ks.iter().map(|&k| top_k_jaccard(&measures[i].1, &measures[j].1, k)).collect();
+            overlaps.push((measures[i].0.to_string(), measures[j].0.to_string(), values));
+        }
+    }
+    overlaps
+}
+
+// Computes the correlation between every pair of centrality maps, using the nodes present in
+// all of them (e.g. the top nodes when closeness and betweenness are only computed for those).
+// Its inputs are - `measures`: (name, node ID -> value) per measure - `method`: Pearson, Spearman or Kendall
+// Its outputs are - `Vec<Vec<f64>>`: symmetric matrix with 1 on the diagonal, in the order of `measures`
+pub fn correlation_matrix(measures: &[(&str, HashMap<usize, f64>)], method: CorrelationMethod) -> Vec<Vec<f64>> {
+    let k = measures.len();
+    let nodes: Vec<usize> = match measures.first() {
+        Some((_, first)) => {
+            let mut nodes: Vec<usize> = first.keys().copied().filter(|n| measures.iter().all(|(_, m)| m.contains_key(n))).collect();
+            nodes.sort_unstable();
+            nodes
+        }
+        None => Vec::new(),
+    };
+    let columns: Vec<Vec<f64>> = measures.iter().map(|(_, m)| nodes.iter().map(|n| m[n]).collect()).collect();
+
+    let mut matrix = vec![vec![1.0; k]; k];
+    for i in 0..k {
+        for j in (i + 1)..k {
+            let r = match method {
+                CorrelationMethod::Pearson => pearson(&columns[i], &columns[j]),
+                CorrelationMethod::Spearman => spearman(&columns[i], &columns[j]),
+                CorrelationMethod::Kendall => kendall_tau(&columns[i], &columns[j]),
+            };
+            matrix[i][j] = r;
+            matrix[j][i] = r;
+        }
+    }
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that a monotonic but nonlinear relationship has Spearman correlation 1 and a
+    // Pearson correlation below 1, and that tied values share their average rank.
+    #[test]
+    fn test_pearson_and_spearman() {
+        let x = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let y: Vec<f64> = x.iter().map(|v: &f64| v.powi(4)).collect();
+        assert!((spearman(&x, &y) - 1.0).abs() < 1e-12);
+        assert!(pearson(&x, &y) < 0.95);
+        assert!((pearson(&x, &[5.0, 4.0, 3.0, 2.0, 1.0]) + 1.0).abs() < 1e-12);
+        assert_eq!(average_ranks(&[10.0, 20.0, 20.0, 5.0]), vec![2.0, 3.5, 3.5, 1.0]);
+
+        let a: HashMap<usize, f64> = vec![(1, 1.0), (2, 2.0), (3, 3.0), (4, 9.0)].into_iter().collect();
+        let b: HashMap<usize, f64> = vec![(1, 3.0), (2, 2.0), (3, 1.0)].into_iter().collect();
+        let matrix = correlation_matrix(&[("a", a), ("b", b)], CorrelationMethod::Spearman);
+        assert!((matrix[0][1] + 1.0).abs() < 1e-12);
+        assert_eq!(matrix[1][1], 1.0);
+    }
+
+    // Tests Kendall's tau-b against a brute-force count of concordant and discordant pairs,
+    // including ties in both samples.
+    #[test]
+    fn test_kendall_tau() {
+        let x = vec![1.0, 2.0, 2.0, 3.0, 4.0, 5.0, 5.0, 6.0];
+        let y = vec![2.0, 1.0, 3.0, 3.0, 6.0, 4.0, 5.0, 5.0];
+        let (mut concordant, mut discordant, mut x_ties, mut y_ties) = (0.0, 0.0, 0.0, 0.0);
+        for i in 0..x.len() {
+            for j in (i + 1)..x.len() {
+                let s = (x[i] - x[j]) * (y[i] - y[j]);
+                if s > 0.0 { concordant += 1.0 } else if s < 0.0 { discordant += 1.0 }
+                if x[i] == x[j] { x_ties += 1.0 }
+                if y[i] == y[j] { y_ties += 1.0 }
+            }
+        }
+        let total: f64 = 28.0;
+        let expected = (concordant - discordant) / ((total - x_ties) * (total - y_ties)).sqrt();
+        assert!((kendall_tau(&x, &y) - expected).abs() < 1e-12);
+        assert!((kendall_tau(&x, &x) - 1.0).abs() < 1e-12);
+
+        let a: HashMap<usize, usize> = vec![(1, 1), (2, 2), (3, 3), (4, 4)].into_iter().collect();
+        let b: HashMap<usize, f64> = vec![(1, 0.4), (2, 0.3), (3, 0.2), (5, 0.1)].into_iter().collect();
+        let agreement = rank_agreement(&a, &b);
+        assert_eq!(agreement.common, 3);
+        assert!((agreement.kendall + 1.0).abs() < 1e-12 && (agreement.spearman + 1.0).abs() < 1e-12);
+    }
+
+    // Tests top-k Jaccard overlap: the top 2 are {4, 3} and {1, 2} (disjoint), while the
+    // top 3 share nodes 2 and 3 out of four distinct nodes.
+    #[test]
+    fn test_top_k_jaccard() {
+        let a: HashMap<usize, usize> = vec![(1, 1), (2, 2), (3, 3), (4, 4)].into_iter().collect();
+        let b: HashMap<usize, f64> = vec![(1, 0.4), (2, 0.3), (3, 0.2), (4, 0.1)].into_iter().collect();
+        assert_eq!(top_k_jaccard(&a, &b, 2), 0.0);
+        assert!((top_k_jaccard(&a, &b, 3) - 0.5).abs() < 1e-12);
+        assert_eq!(top_k_jaccard(&a, &a, 3), 1.0);
+    }
+}
@@ -0,0 +1,164 @@
+// Includes a registry of well-known SNAP datasets, with where to download each one and how its
+// edge list is laid out (comment lines, separators, direction), and a downloader that unpacks
+// them into a local cache. The download is done by the `curl` and `gzip` programs so the crate
+// needs no HTTP or compression dependencies.
+
+use crate::graph::drop_invalid_weights;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+// Layout of a whitespace-separated edge list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdgeListFormat {
+    // Lines starting with this prefix are comments
+    pub comment: &'static str,
+    // Whether (u, v) means u -> v; undirected SNAP files list both directions
+    pub directed: bool,
+    // Whether a third column holds the edge weight (1 otherwise)
+    pub weighted: bool,
+}
+
+// A dataset of the registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dataset {
+    // Name used on the command line, e.g. `fetch email-Enron`
+    pub name: &'static str,
+    pub description: &'static str,
+    // Address of the gzipped edge list
+    pub url: &'static str,
+    // Name of the unpacked edge list in the cache
+    pub file: &'static str,
+    pub format: EdgeListFormat,
+    // Node ID -> email mapping CSV that matches the node IDs, if there is one
+    pub mapping: Option<&'static str>,
+}
+
+impl Dataset {
+    // Path of the unpacked edge list in a cache directory.
+    pub fn path(&self, cache: &Path) -> PathBuf {
+        cache.join(self.file)
+    }
+
+    pub fn is_cached(&self, cache: &Path) -> bool {
+        self.path(cache).is_file()
+    }
+}
+
+// The known datasets.
+pub const DATASETS: [Dataset; 3] = [
+    Dataset {
+        name: "email-Enron",
+        description: "Enron email communication network (36,692 nodes, undirected)",
+        url: "https://snap.stanford.edu/data/email-Enron.txt.gz",
+        file: "email-Enron.txt",
+        format: EdgeListFormat { comment: "#", directed: false, weighted: false },
+        mapping: Some("email_to_node.csv"),
+    },
+    Dataset {
+        name: "email-Eu-core",
+        description: "Emails within a European research institution (1,005 nodes, directed)",
+        url: "https://snap.stanford.edu/data/email-Eu-core.txt.gz",
+        file: "email-Eu-core.txt",
+        format: EdgeListFormat { comment: "#", directed: true, weighted: false },
+        mapping: None,
+    },
+    Dataset {
+        name: "wiki-Vote",
+        description: "Wikipedia adminship votes (7,115 nodes, directed)",
+        url: "https://snap.stanford.edu/data/wiki-Vote.txt.gz",
+        file: "wiki-Vote.txt",
+        format: EdgeListFormat { comment: "#", directed: true, weighted: false },
+        mapping: None,
+    },
+];
+
+// Looks up a dataset of the registry by name, ignoring case.
+pub fn find_dataset(name: &str) -> Option<&'static Dataset> {
+    DATASETS.iter().find(|d| d.name.eq_ignore_ascii_case(name.trim()))
+}
+
+// Cache directory for downloaded datasets: `$PROJECT_DATA_DIR`, or `data` in the working directory.
+pub fn cache_dir() -> PathBuf {
+    std::env::var_os("PROJECT_DATA_DIR").map_or_else(|| PathBuf::from("data"), PathBuf::from)
+}
+
+// Runs an external program, turning a failure to start it or a non-zero exit into an error.
+fn run(command: &mut Command, what: &str) -> std::io::Result<()> {
+    let status = command.status().map_err(|e| std::io::Error::other(format!("could not run {}: {}", what, e)))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!("{} failed ({})", what, status)))
+    }
+}
+
+// Downloads and unpacks a dataset into the cache, unless it is already there. The file is
+// written under a temporary name and renamed at the end, so an interrupted download never
+// leaves a truncated edge list behind.
+// Its inputs are - `dataset`: the dataset to fetch - `cache`: the cache directory
+// - `force`: download again even if the dataset is cached
+// Its outputs are - `std::io::Result<PathBuf>`: path of the unpacked edge list
+pub fn fetch(dataset: &Dataset, cache: &Path, force: bool) -> std::io::Result<PathBuf> {
+    let path = dataset.path(cache);
+    if !force && path.is_file() {
+        return Ok(path);
+    }
+    std::fs::create_dir_all(cache)?;
+    let gz = cache.join(format!("{}.gz.part", dataset.file));
+    let partial = cache.join(format!("{}.part", dataset.file));
+    let result = run(Command::new("curl").args(["--fail", "--location", "--silent", "--show-error", "--output"]).arg(&gz).arg(dataset.url), "curl")
+        .and_then(|()| run(Command::new("gzip").arg("-dc").arg(&gz).stdout(Stdio::from(File::create(&partial)?)), "gzip"))
+        .and_then(|()| std::fs::rename(&partial, &path));
+    let _ = std::fs::remove_file(&gz);
+    let _ = std::fs::remove_file(&partial);
+    result.map(|()| path)
+}
+
+// Reads an edge list in the given format. Fields may be separated by tabs or spaces, and
+// malformed lines are skipped, as are edges whose weight is not a finite positive number
+// (with a warning).
+// Its inputs are - `path`: path to the edge list - `format`: its layout
+// Its outputs are - `std::io::Result<Vec<(usize, usize, f64)>>`: (u, v, weight) edges
+pub fn read_edge_list(path: &Path, format: &EdgeListFormat) -> std::io::Result<Vec<(usize, usize, f64)>> {
+    let mut edges = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(format.comment) {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let weight = match (format.weighted, fields.get(2)) {
+            (true, Some(w)) => w.parse().ok(),
+            _ => Some(1.0),
+        };
+        if let (Some(Ok(u)), Some(Ok(v)), Some(w)) = (fields.first().map(|x| x.parse()), fields.get(1).map(|x| x.parse()), weight) {
+            edges.push((u, v, w));
+        }
+    }
+    Ok(drop_invalid_weights(edges, &path.display().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests registry lookups and reading edge lists separated by tabs or spaces, with
+    // comments, blank and malformed lines, and an optional weight column.
+    #[test]
+    fn test_read_edge_list() {
+        assert_eq!(find_dataset(" WIKI-vote").map(|d| d.file), Some("wiki-Vote.txt"));
+        assert!(find_dataset("email-Enron").is_some_and(|d| !d.format.directed && d.mapping.is_some()));
+        assert!(find_dataset("karate").is_none());
+
+        let path = std::env::temp_dir().join("project_datasets_test.txt");
+        std::fs::write(&path, "# Nodes: 3 Edges: 3\n# FromNodeId\tToNodeId\n0\t1\n1 2 2.5\n\nx 3\n2   0\n0 2 0\n1 0 NaN\n").unwrap();
+        let plain = EdgeListFormat { comment: "#", directed: true, weighted: false };
+        let weighted = EdgeListFormat { weighted: true, ..plain };
+        assert_eq!(read_edge_list(&path, &plain).unwrap(), vec![(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0), (0, 2, 1.0), (1, 0, 1.0)]);
+        assert_eq!(read_edge_list(&path, &weighted).unwrap(), vec![(0, 1, 1.0), (1, 2, 2.5), (2, 0, 1.0)]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
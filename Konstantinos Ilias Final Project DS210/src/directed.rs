@@ -0,0 +1,69 @@
+// Includes measures that only make sense when edge direction is kept,
+// such as reciprocity (how often an email from u to v is answered by one from v to u).
+
+use std::collections::{HashMap, HashSet};
+
+// Reciprocity of a directed graph, overall and per node.
+#[derive(Debug, Clone)]
+pub struct Reciprocity {
+    // Fraction of directed edges whose reverse edge also exists
+    pub overall: f64,
+    // Number of node pairs connected in both directions
+    pub mutual: usize,
+    // Number of node pairs connected in one direction only
+    pub one_way: usize,
+    // Node ID -> fraction of its outgoing edges that are reciprocated
+    pub per_node: HashMap<usize, f64>,
+}
+
+// Computes the reciprocity of a directed edge list. Duplicate edges and self loops are ignored.
+// Its inputs are - `edges`: list of directed (from, to) edges
+// Its outputs are - `Reciprocity`: overall fraction, mutual/one-way pair counts and per-node fractions
+pub fn reciprocity(edges: &[(usize, usize)]) -> Reciprocity {
+    let edge_set: HashSet<(usize, usize)> = edges.iter().copied().filter(|&(u, v)| u != v).collect();
+
+    let mut out_total: HashMap<usize, usize> = HashMap::new();
+    let mut out_mutual: HashMap<usize, usize> = HashMap::new();
+    let mut reciprocated = 0;
+    for &(u, v) in &edge_set {
+        *out_total.entry(u).or_insert(0) += 1;
+        if edge_set.contains(&(v, u)) {
+            reciprocated += 1;
+            *out_mutual.entry(u).or_insert(0) += 1;
+        }
+    }
+
+    let overall = if edge_set.is_empty() { 0.0 } else { reciprocated as f64 / edge_set.len() as f64 };
+    let per_node = out_total
+        .iter()
+        .map(|(&node, &total)| (node, *out_mutual.get(&node).unwrap_or(&0) as f64 / total as f64))
+        .collect();
+
+    Reciprocity {
+        overall,
+        mutual: reciprocated / 2,
+        one_way: edge_set.len() - reciprocated,
+        per_node,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests reciprocity on a small directed graph.
+    // 1<->2 is mutual, 1->3 and 3->4 are one-way, and the duplicate 1->2 is ignored.
+    #[test]
+    fn test_reciprocity() {
+        let edges = vec![(1, 2), (2, 1), (1, 2), (1, 3), (3, 4)];
+        let result = reciprocity(&edges);
+
+        assert!((result.overall - 0.5).abs() < 1e-9);
+        assert_eq!(result.mutual, 1);
+        assert_eq!(result.one_way, 2);
+        assert!((result.per_node[&1] - 0.5).abs() < 1e-9);
+        assert_eq!(result.per_node[&2], 1.0);
+        assert_eq!(result.per_node[&3], 0.0);
+        assert!(!result.per_node.contains_key(&4));
+    }
+}
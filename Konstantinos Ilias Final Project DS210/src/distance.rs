@@ -0,0 +1,912 @@
+// Includes functions that measure distances in the network, such as the diameter
+// and the average shortest path length, using breadth-first search over the compact graph.
+// For graphs too large for exact all-pairs BFS, HyperANF approximates the neighborhood function.
+
+use crate::graph::{Adjacency, Graph};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+// Marks nodes that have not been reached by a BFS.
+pub const UNREACHED: usize = usize::MAX;
+
+// Diameter and mean geodesic distance of the analyzed nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceStats {
+    pub diameter: usize,
+    pub avg_path_length: f64,
+}
+
+// Runs a BFS from `source`, writing hop distances into `dist` and the visit order into `order`.
+// `dist` must be filled with `UNREACHED` before the call; use `reset` to restore it afterwards
+// so the same buffers can be reused for many sources.
+// Its inputs are - `graph`: the graph - `source`: compact index of the start node
+pub fn bfs_from<G: Adjacency>(graph: &G, source: usize, dist: &mut [usize], order: &mut Vec<usize>) {
+    order.clear();
+    dist[source] = 0;
+    order.push(source);
+    let mut head = 0;
+    while head < order.len() {
+        let v = order[head];
+        head += 1;
+        for w in graph.neighbor_iter(v) {
+            if dist[w] == UNREACHED {
+                dist[w] = dist[v] + 1;
+                order.push(w);
+            }
+        }
+    }
+}
+
+// Restores the distance buffer after `bfs_from` by clearing only the visited entries.
+pub fn reset(dist: &mut [usize], order: &[usize]) {
+    for &v in order {
+        dist[v] = UNREACHED;
+    }
+}
+
+// Distance totals of one source in a batched BFS.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SourceTotals {
+    pub distance_sum: u64,
+    // Nodes reached, including the source itself
+    pub reached: usize,
+    pub eccentricity: usize,
+}
+
+// Result of `multi_source_bfs`: totals per source, in the order of the sources, and
+// `pairs_at[d]`, the number of (source, node) pairs at distance exactly d.
+#[derive(Debug, Clone, Default)]
+pub struct MultiSourceBfs {
+    pub totals: Vec<SourceTotals>,
+    pub pairs_at: Vec<u64>,
+}
+
+// Runs one BFS level by level for up to 64 sources at once: bit i of `seen[v]` marks that
+// source i has reached v, so a level costs one pass over the edges for all sources together.
+fn bfs_batch<G: Adjacency>(graph: &G, batch: &[usize]) -> MultiSourceBfs {
+    let n = graph.node_count();
+    let mut seen = vec![0u64; n];
+    let mut frontier = vec![0u64; n];
+    for (i, &s) in batch.iter().enumerate() {
+        seen[s] |= 1 << i;
+        frontier[s] |= 1 << i;
+    }
+    let mut totals = vec![SourceTotals { distance_sum: 0, reached: 1, eccentricity: 0 }; batch.len()];
+    let mut pairs_at = vec![batch.len() as u64];
+    let mut next = vec![0u64; n];
+
+    for d in 1.. {
+        let mut pairs = 0;
+        for v in 0..n {
+            let reached = graph.neighbor_iter(v).fold(0, |bits, w| bits | frontier[w]) & !seen[v];
+            next[v] = reached;
+            if reached == 0 {
+                continue;
+            }
+            seen[v] |= reached;
+            pairs += reached.count_ones() as u64;
+            let mut bits = reached;
+            while bits != 0 {
+                let t = &mut totals[bits.trailing_zeros() as usize];
+                t.distance_sum += d as u64;
+                t.reached += 1;
+                t.eccentricity = d;
+                bits &= bits - 1;
+            }
+        }
+        if pairs == 0 {
+            break;
+        }
+        pairs_at.push(pairs);
+        std::mem::swap(&mut frontier, &mut next);
+    }
+    MultiSourceBfs { totals, pairs_at }
+}
+
+// Runs a BFS from every source with bit-parallel frontiers, 64 sources per pass and the
+// batches in parallel. For many sources this is much faster than one BFS per source.
+// Its inputs are - `graph`: the graph - `sources`: compact indices of the start nodes
+// Its outputs are - `MultiSourceBfs`: distance totals per source and pair counts per distance
+pub fn multi_source_bfs<G: Adjacency>(graph: &G, sources: &[usize]) -> MultiSourceBfs {
+    let batches: Vec<MultiSourceBfs> = sources.par_chunks(64).map(|batch| bfs_batch(graph, batch)).collect();
+    let mut result = MultiSourceBfs::default();
+    for batch in batches {
+        result.totals.extend(batch.totals);
+        if result.pairs_at.len() < batch.pairs_at.len() {
+            result.pairs_at.resize(batch.pairs_at.len(), 0);
+        }
+        for (total, pairs) in result.pairs_at.iter_mut().zip(batch.pairs_at) {
+            *total += pairs;
+        }
+    }
+    result
+}
+
+// Computes the same closeness as `compute_closeness`, (reached nodes - 1) / sum of distances,
+// with batched bit-parallel BFS over the compact graph.
+// Its inputs are - `graph`: the graph - `nodes`: node IDs to compute closeness for
+// Its outputs are - `HashMap<usize, f64>`: node ID -> closeness (0 for isolated nodes)
+pub fn batched_closeness<G: Adjacency>(graph: &G, nodes: &HashSet<usize>) -> HashMap<usize, f64> {
+    let mut sources: Vec<usize> = nodes.iter().filter_map(|&id| graph.index_of(id)).collect();
+    sources.sort_unstable();
+    let bfs = multi_source_bfs(graph, &sources);
+    sources
+        .iter()
+        .zip(&bfs.totals)
+        .map(|(&v, t)| {
+            let score = if t.distance_sum > 0 { (t.reached - 1) as f64 / t.distance_sum as f64 } else { 0.0 };
+            (graph.node_id(v), score)
+        })
+        .collect()
+}
+
+// Below this many nodes a delta-stepping phase is relaxed on the current thread, since
+// splitting it into rayon tasks costs more than the work.
+const PARALLEL_PHASE: usize = 512;
+
+// Picks the bucket width of delta-stepping: the mean edge length (1 / weight). Wider buckets
+// give each phase more parallel work but relax more edges more than once.
+// Its inputs are - `graph`: the graph
+// Its outputs are - `f64`: bucket width (1 for graphs without edges)
+pub fn default_delta(graph: &Graph) -> f64 {
+    let (total, count) = (0..graph.node_count())
+        .flat_map(|v| graph.weighted_neighbors(v))
+        .fold((0.0, 0usize), |(total, count), (_, weight)| (total + 1.0 / weight, count + 1));
+    if count > 0 { total / count as f64 } else { 1.0 }
+}
+
+// Tentative distances offered by relaxing the light (length <= delta) or heavy edges of a node.
+fn relaxations<'a>(graph: &'a Graph, dist: &'a [f64], v: usize, delta: f64, light: bool) -> impl Iterator<Item = (usize, f64)> + 'a {
+    graph
+        .weighted_neighbors(v)
+        .map(|(w, weight)| (w, 1.0 / weight))
+        .filter(move |&(_, length)| (length <= delta) == light)
+        .map(move |(w, length)| (w, dist[v] + length))
+        .filter(|&(w, alt)| alt < dist[w])
+}
+
+// Computes weighted shortest-path distances from one source by delta-stepping (Meyer &
+// Sanders), with edge length 1 / weight. Nodes are kept in buckets of width `delta` by
+// tentative distance; the lowest bucket is settled by relaxing the light edges of all its
+// nodes at once, in parallel with rayon, until it stays empty, and then their heavy edges.
+// With unit weights and delta 1 this is a level-synchronous BFS.
+// Its inputs are - `graph`: the graph - `source`: compact index of the start node
+// - `delta`: bucket width, e.g. `default_delta(graph)`
+// Its outputs are - `Vec<f64>`: distance of every compact index (infinity if unreachable)
+pub fn delta_stepping(graph: &Graph, source: usize, delta: f64) -> Vec<f64> {
+    let mut dist = vec![f64::INFINITY; graph.node_count()];
+    let bucket_of = |d: f64| (d / delta) as usize;
+    let mut buckets: Vec<Vec<usize>> = vec![vec![source]];
+    dist[source] = 0.0;
+
+    let relax = |frontier: &[usize], dist: &[f64], light: bool| -> Vec<(usize, f64)> {
+        if frontier.len() >= PARALLEL_PHASE {
+            frontier.par_iter().flat_map_iter(|&v| relaxations(graph, dist, v, delta, light)).collect()
+        } else {
+            frontier.iter().flat_map(|&v| relaxations(graph, dist, v, delta, light)).collect()
+        }
+    };
+    let apply = |requests: Vec<(usize, f64)>, dist: &mut [f64], buckets: &mut Vec<Vec<usize>>| {
+        for (w, alt) in requests {
+            if alt < dist[w] {
+                dist[w] = alt;
+                let b = bucket_of(alt);
+                if b >= buckets.len() {
+                    buckets.resize(b + 1, Vec::new());
+                }
+                buckets[b].push(w);
+            }
+        }
+    };
+
+    let mut i = 0;
+    while i < buckets.len() {
+        let mut settled = Vec::new();
+        loop {
+            // Entries whose distance has since dropped into a lower bucket are stale
+            let mut frontier: Vec<usize> = std::mem::take(&mut buckets[i]).into_iter().filter(|&v| bucket_of(dist[v]) == i).collect();
+            frontier.sort_unstable();
+            frontier.dedup();
+            if frontier.is_empty() {
+                break;
+            }
+            let requests = relax(&frontier, &dist, true);
+            settled.extend(frontier);
+            apply(requests, &mut dist, &mut buckets);
+        }
+        settled.sort_unstable();
+        settled.dedup();
+        let requests = relax(&settled, &dist, false);
+        apply(requests, &mut dist, &mut buckets);
+        i += 1;
+    }
+    dist
+}
+
+// Computes weighted closeness, (reached nodes - 1) / sum of weighted distances, with
+// delta-stepping from every requested node. On an unweighted graph it equals `batched_closeness`.
+// Its inputs are - `graph`: the graph - `nodes`: node IDs to compute closeness for
+// - `delta`: bucket width of delta-stepping
+// Its outputs are - `HashMap<usize, f64>`: node ID -> closeness (0 for isolated nodes)
+pub fn weighted_closeness(graph: &Graph, nodes: &HashSet<usize>, delta: f64) -> HashMap<usize, f64> {
+    let sources: Vec<usize> = nodes.iter().filter_map(|&id| graph.index_of(id)).collect();
+    sources
+        .par_iter()
+        .map(|&v| {
+            let dist = delta_stepping(graph, v, delta);
+            let (total, reached) = dist.iter().filter(|d| d.is_finite()).fold((0.0, 0usize), |(t, r), &d| (t + d, r + 1));
+            let score = if total > 0.0 { (reached - 1) as f64 / total } else { 0.0 };
+            (graph.node_id(v), score)
+        })
+        .collect()
+}
+
+// Number of pivots Eppstein–Wang needs for an additive error of `epsilon` times the
+// diameter with high probability: ln(n) / epsilon^2.
+pub fn pivots_for_error(n: usize, epsilon: f64) -> usize {
+    ((n.max(2) as f64).ln() / (epsilon * epsilon)).ceil() as usize
+}
+
+// Estimates the closeness of every node from BFS runs out of a random sample of pivots
+// (Eppstein & Wang): a node's mean distance to the pivots in its component, which are
+// a uniform sample of that component, estimates its mean distance to all reachable nodes.
+// With `pivots_for_error(n, epsilon)` pivots the mean distance is within epsilon times
+// the diameter of the exact value with high probability. Nodes that no pivot reaches
+// (small components without a pivot) are computed exactly, which is cheap for them.
+// Its inputs are - `graph`: the graph - `pivots`: number of pivots - `seed`: RNG seed
+// Its outputs are - `HashMap<usize, f64>`: node ID -> estimated closeness (1 / mean distance)
+pub fn approximate_closeness(graph: &Graph, pivots: usize, seed: u64) -> HashMap<usize, f64> {
+    let n = graph.node_count();
+    let mut sample: Vec<usize> = (0..n).collect();
+    sample.shuffle(&mut StdRng::seed_from_u64(seed));
+    sample.truncate(pivots);
+
+    // For every node: sum of distances to the pivots it reaches, and how many it reaches
+    let (sums, hits) = sample
+        .par_iter()
+        .fold(
+            || (vec![0u64; n], vec![0u32; n], vec![UNREACHED; n], Vec::new()),
+            |(mut sums, mut hits, mut dist, mut order), &p| {
+                bfs_from(graph, p, &mut dist, &mut order);
+                for &v in &order[1..] {
+                    sums[v] += dist[v] as u64;
+                    hits[v] += 1;
+                }
+                reset(&mut dist, &order);
+                (sums, hits, dist, order)
+            },
+        )
+        .map(|(sums, hits, _, _)| (sums, hits))
+        .reduce(
+            || (vec![0u64; n], vec![0u32; n]),
+            |(mut sums, mut hits), (other_sums, other_hits)| {
+                sums.iter_mut().zip(other_sums).for_each(|(a, b)| *a += b);
+                hits.iter_mut().zip(other_hits).for_each(|(a, b)| *a += b);
+                (sums, hits)
+            },
+        );
+
+    let mut dist = vec![UNREACHED; n];
+    let mut order = Vec::new();
+    (0..n)
+        .map(|v| {
+            let (sum, count) = if hits[v] > 0 {
+                (sums[v], hits[v] as u64)
+            } else {
+                bfs_from(graph, v, &mut dist, &mut order);
+                let exact: usize = order.iter().map(|&w| dist[w]).sum();
+                reset(&mut dist, &order);
+                (exact as u64, order.len() as u64 - 1)
+            };
+            let score = if sum > 0 { count as f64 / sum as f64 } else { 0.0 };
+            (graph.node_id(v), score)
+        })
+        .collect()
+}
+
+// Computes the neighborhood function exactly with batched BFS from every node: the
+// reference HyperANF approximates, affordable on graphs of tens of thousands of nodes.
+// Its inputs are - `graph`: the graph
+// Its outputs are - `NeighborhoodFunction`: exact pair counts per distance
+pub fn exact_neighborhood_function<G: Adjacency>(graph: &G) -> NeighborhoodFunction {
+    let sources: Vec<usize> = (0..graph.node_count()).collect();
+    let mut running = 0.0;
+    let cumulative = multi_source_bfs(graph, &sources)
+        .pairs_at
+        .iter()
+        .map(|&pairs| {
+            running += pairs as f64;
+            running
+        })
+        .collect();
+    NeighborhoodFunction { cumulative }
+}
+
+// Computes the exact diameter and average shortest path length over all connected pairs.
+// BFS is run in parallel from every node except degree-1 leaves: a leaf's distances are
+// its neighbor's distances plus one, so its totals are derived instead of searched.
+// Its inputs are - `graph`: the graph - `largest_only`: restrict to the giant component
+// Its outputs are - `DistanceStats`: diameter and average path length
+pub fn distance_stats(graph: &Graph, largest_only: bool) -> DistanceStats {
+    let mut components = graph.components();
+    if largest_only {
+        components.sort_by_key(|c| std::cmp::Reverse(c.len()));
+        components.truncate(1);
+    }
+
+    let n = graph.node_count();
+    let mut diameter = 0;
+    let mut total: u64 = 0;
+    let mut pairs: u64 = 0;
+
+    for component in components.iter().filter(|c| c.len() > 1) {
+        let size = component.len();
+        let sources: Vec<usize> = component
+            .iter()
+            .copied()
+            .filter(|&v| graph.degree(v) > 1 || size == 2)
+            .collect();
+
+        // (source, sum of distances, eccentricity) for every searched node
+        let searched: Vec<(usize, u64, usize)> = sources
+            .par_iter()
+            .map_init(
+                || (vec![UNREACHED; n], Vec::new()),
+                |(dist, order), &s| {
+                    bfs_from(graph, s, dist, order);
+                    let sum = order.iter().map(|&v| dist[v] as u64).sum();
+                    let ecc = dist[*order.last().unwrap()];
+                    reset(dist, order);
+                    (s, sum, ecc)
+                },
+            )
+            .collect();
+
+        let mut by_source = vec![(0, 0); n];
+        for &(s, sum, ecc) in &searched {
+            by_source[s] = (sum, ecc);
+            total += sum;
+            diameter = diameter.max(ecc);
+        }
+
+        // Leaves reuse the BFS of their only neighbor
+        for &v in component.iter().filter(|&&v| graph.degree(v) == 1 && size > 2) {
+            let u = graph.neighbors(v)[0];
+            let (sum, ecc) = by_source[u];
+            total += sum + size as u64 - 2;
+            let leaf_ecc = if ecc >= 2 { ecc + 1 } else { 2 };
+            diameter = diameter.max(leaf_ecc);
+        }
+
+        pairs += (size * (size - 1)) as u64;
+    }
+
+    let avg_path_length = if pairs > 0 { total as f64 / pairs as f64 } else { 0.0 };
+    DistanceStats { diameter, avg_path_length }
+}
+
+// Estimates the average shortest path length of one component from BFS runs out of
+// `samples` random source nodes (every node when the component is smaller).
+// Its inputs are - `graph`: the graph - `component`: compact indices of a connected component
+// - `samples`: number of BFS sources - `seed`: RNG seed for choosing the sources
+// Its outputs are - `f64`: mean distance from the sources to the other nodes (0 for a single node)
+pub fn sampled_path_length(graph: &Graph, component: &[usize], samples: usize, seed: u64) -> f64 {
+    let mut sources = component.to_vec();
+    sources.shuffle(&mut StdRng::seed_from_u64(seed));
+    sources.truncate(samples);
+
+    let mut dist = vec![UNREACHED; graph.node_count()];
+    let mut visited = Vec::new();
+    let (mut sum, mut pairs) = (0usize, 0usize);
+    for source in sources {
+        bfs_from(graph, source, &mut dist, &mut visited);
+        sum += visited.iter().map(|&v| dist[v]).sum::<usize>();
+        pairs += visited.len() - 1;
+        reset(&mut dist, &visited);
+    }
+    if pairs > 0 { sum as f64 / pairs as f64 } else { 0.0 }
+}
+
+// Eccentricity of every node in one component, with the derived radius, diameter,
+// center (nodes with eccentricity = radius) and periphery (eccentricity = diameter).
+// All node sets are given as original node IDs, sorted ascending.
+#[derive(Debug, Clone)]
+pub struct Eccentricity {
+    pub ecc: HashMap<usize, usize>,
+    pub radius: usize,
+    pub diameter: usize,
+    pub center: Vec<usize>,
+    pub periphery: Vec<usize>,
+}
+
+// Computes the exact eccentricity of every node in `component`.
+// Instead of one BFS per node, it keeps lower/upper eccentricity bounds for every node
+// (Takes & Kosters' bounding approach): a BFS from v with eccentricity e gives every w
+// max(e - d(v,w), d(v,w)) <= ecc(w) <= e + d(v,w). Sources alternate between the node with
+// the largest upper bound and the one with the smallest lower bound until all bounds meet.
+// Its inputs are - `graph`: the graph - `component`: compact indices of one connected component
+// Its outputs are - `Eccentricity`: per-node eccentricity, radius, diameter, center and periphery
+pub fn eccentricity(graph: &Graph, component: &[usize]) -> Eccentricity {
+    let n = graph.node_count();
+    let mut lower = vec![0; n];
+    let mut upper = vec![usize::MAX; n];
+    let mut ecc = vec![0; n];
+    let mut candidates: Vec<usize> = component.to_vec();
+    let mut dist = vec![UNREACHED; n];
+    let mut order = Vec::new();
+    let mut pick_high = true;
+
+    while !candidates.is_empty() {
+        // Ties are broken by highest degree, which tends to tighten bounds fastest
+        let &v = if pick_high {
+            candidates.iter().max_by_key(|&&w| (upper[w], graph.degree(w))).unwrap()
+        } else {
+            candidates.iter().min_by_key(|&&w| (lower[w], std::cmp::Reverse(graph.degree(w)))).unwrap()
+        };
+        pick_high = !pick_high;
+
+        bfs_from(graph, v, &mut dist, &mut order);
+        let ecc_v = dist[*order.last().unwrap()];
+
+        candidates.retain(|&w| {
+            let d = dist[w];
+            lower[w] = lower[w].max(ecc_v.saturating_sub(d).max(d));
+            upper[w] = upper[w].min(ecc_v + d);
+            if lower[w] == upper[w] {
+                ecc[w] = lower[w];
+                false
+            } else {
+                true
+            }
+        });
+        reset(&mut dist, &order);
+    }
+
+    let radius = component.iter().map(|&v| ecc[v]).min().unwrap_or(0);
+    let diameter = component.iter().map(|&v| ecc[v]).max().unwrap_or(0);
+    let mut center: Vec<usize> = component.iter().filter(|&&v| ecc[v] == radius).map(|&v| graph.node_id(v)).collect();
+    let mut periphery: Vec<usize> = component.iter().filter(|&&v| ecc[v] == diameter).map(|&v| graph.node_id(v)).collect();
+    center.sort_unstable();
+    periphery.sort_unstable();
+
+    Eccentricity {
+        ecc: component.iter().map(|&v| (graph.node_id(v), ecc[v])).collect(),
+        radius,
+        diameter,
+        center,
+        periphery,
+    }
+}
+
+// Bounds on the distance between two nodes from a landmark index. The true distance lies
+// in [lower, upper]; the upper bound is exact when a landmark lies on a shortest path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DistanceEstimate {
+    pub lower: usize,
+    pub upper: usize,
+}
+
+// Landmark distance oracle: exact BFS distances from a few high-degree landmarks, from
+// which the distance between any pair is bounded by the triangle inequality,
+// |d(u, l) - d(l, v)| <= d(u, v) <= d(u, l) + d(l, v), without a BFS per query.
+pub struct LandmarkIndex {
+    // Compact indices of the landmarks
+    landmarks: Vec<usize>,
+    // `dist[i][v]`: distance from landmark i to compact index v (`UNREACHED` if disconnected)
+    dist: Vec<Vec<usize>>,
+}
+
+impl LandmarkIndex {
+    // Builds the index from the `count` highest-degree nodes (ties broken by compact index),
+    // running their BFS in parallel. Memory is `count` distances per node.
+    // Its inputs are - `graph`: the graph - `count`: number of landmarks
+    // Its outputs are - `LandmarkIndex`: the distance oracle
+    pub fn build(graph: &Graph, count: usize) -> LandmarkIndex {
+        let mut landmarks: Vec<usize> = (0..graph.node_count()).collect();
+        landmarks.sort_by_key(|&v| (std::cmp::Reverse(graph.degree(v)), v));
+        landmarks.truncate(count);
+        let dist = landmarks
+            .par_iter()
+            .map(|&l| {
+                let mut dist = vec![UNREACHED; graph.node_count()];
+                bfs_from(graph, l, &mut dist, &mut Vec::new());
+                dist
+            })
+            .collect();
+        LandmarkIndex { landmarks, dist }
+    }
+
+    // Original node IDs of the landmarks.
+    pub fn landmarks(&self, graph: &Graph) -> Vec<usize> {
+        self.landmarks.iter().map(|&l| graph.node_id(l)).collect()
+    }
+
+    // Bounds the distance between two nodes using only the stored landmark distances.
+    // Its inputs are - `graph`: the graph the index was built on - `from`, `to`: node IDs
+    // Its outputs are - `Option<DistanceEstimate>`: lower and upper bounds, or None if a node is
+    // missing, the nodes are disconnected, or no landmark reaches their component
+    pub fn estimate(&self, graph: &Graph, from: usize, to: usize) -> Option<DistanceEstimate> {
+        let (u, v) = (graph.index_of(from)?, graph.index_of(to)?);
+        if u == v {
+            return Some(DistanceEstimate { lower: 0, upper: 0 });
+        }
+        let (mut lower, mut upper) = (1, UNREACHED);
+        for dist in &self.dist {
+            match (dist[u], dist[v]) {
+                (UNREACHED, UNREACHED) => continue,
+                // One end reached and the other not: they are in different components
+                (UNREACHED, _) | (_, UNREACHED) => return None,
+                (a, b) => {
+                    lower = lower.max(a.abs_diff(b));
+                    upper = upper.min(a + b);
+                }
+            }
+        }
+        (upper != UNREACHED).then_some(DistanceEstimate { lower, upper })
+    }
+}
+
+// Marks unreachable pairs in a `DistanceMatrix`.
+const FAR: u16 = u16::MAX;
+
+// Exact all-pairs hop distances of a small graph (e.g. the subgraph of the top-N nodes) in a
+// flat row-major u16 matrix, 2 bytes per pair, so metrics needed repeatedly (eccentricity,
+// closeness, group closeness) are table lookups instead of new BFS runs.
+pub struct DistanceMatrix {
+    n: usize,
+    dist: Vec<u16>,
+}
+
+impl DistanceMatrix {
+    // Runs one BFS per node, in parallel, filling a row of the matrix each. Graphs larger than
+    // `max_nodes` are refused, since memory grows with the square of the node count.
+    // Its inputs are - `graph`: the graph - `max_nodes`: largest node count accepted
+    // Its outputs are - `Option<DistanceMatrix>`: the matrix, or None if the graph is too large
+    pub fn build(graph: &Graph, max_nodes: usize) -> Option<DistanceMatrix> {
+        let n = graph.node_count();
+        if n > max_nodes {
+            return None;
+        }
+        let mut dist = vec![FAR; n * n];
+        dist.par_chunks_mut(n.max(1)).enumerate().for_each_init(
+            || (vec![UNREACHED; n], Vec::new()),
+            |(hops, order), (source, row)| {
+                bfs_from(graph, source, hops, order);
+                for &v in order.iter() {
+                    // Paths of 65535 hops or more cannot occur below 65536 nodes
+                    row[v] = hops[v].min(FAR as usize - 1) as u16;
+                }
+                reset(hops, order);
+            },
+        );
+        Some(DistanceMatrix { n, dist })
+    }
+
+    // Distance between two compact indices, or None if they are disconnected.
+    pub fn distance(&self, u: usize, v: usize) -> Option<usize> {
+        let d = self.dist[u * self.n + v];
+        (d != FAR).then_some(d as usize)
+    }
+
+    // Reachable distances from compact index `u`, excluding itself.
+    fn row(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
+        self.dist[u * self.n..(u + 1) * self.n].iter().filter(|&&d| d != FAR && d > 0).map(|&d| d as usize)
+    }
+
+    // Closeness of every node, (reached nodes - 1) / sum of distances, as in `batched_closeness`.
+    // Its inputs are - `graph`: the graph the matrix was built on
+    // Its outputs are - `HashMap<usize, f64>`: node ID -> closeness
+    pub fn closeness(&self, graph: &Graph) -> HashMap<usize, f64> {
+        (0..self.n)
+            .map(|u| {
+                let (sum, reached) = self.row(u).fold((0, 0), |(s, r), d| (s + d, r + 1));
+                (graph.node_id(u), if sum > 0 { reached as f64 / sum as f64 } else { 0.0 })
+            })
+            .collect()
+    }
+
+    // Eccentricity of every node within its own component.
+    // Its inputs are - `graph`: the graph the matrix was built on
+    // Its outputs are - `HashMap<usize, usize>`: node ID -> eccentricity
+    pub fn eccentricity(&self, graph: &Graph) -> HashMap<usize, usize> {
+        (0..self.n).map(|u| (graph.node_id(u), self.row(u).max().unwrap_or(0))).collect()
+    }
+
+    // Group closeness of a set of nodes, as in `group::group_centrality`: reachable
+    // non-members divided by the sum of their distances to the nearest member.
+    // Its inputs are - `graph`: the graph the matrix was built on - `members`: node IDs of the group
+    // Its outputs are - `f64`: group closeness (0 if no outsider is reachable)
+    pub fn group_closeness(&self, graph: &Graph, members: &HashSet<usize>) -> f64 {
+        let group: Vec<usize> = members.iter().filter_map(|&id| graph.index_of(id)).collect();
+        let (mut sum, mut reached) = (0, 0);
+        for t in 0..self.n {
+            let nearest = group.iter().map(|&m| self.dist[m * self.n + t]).min().unwrap_or(FAR);
+            if nearest != FAR && nearest > 0 {
+                sum += nearest as usize;
+                reached += 1;
+            }
+        }
+        if sum > 0 { reached as f64 / sum as f64 } else { 0.0 }
+    }
+}
+
+// Approximate neighborhood function: `cumulative[t]` estimates the number of
+// ordered node pairs (including each node with itself) at distance at most `t`.
+#[derive(Debug, Clone)]
+pub struct NeighborhoodFunction {
+    pub cumulative: Vec<f64>,
+}
+
+impl NeighborhoodFunction {
+    // Estimated number of pairs at exactly distance `t`, for t = 1, 2, ...
+    pub fn distance_distribution(&self) -> Vec<f64> {
+        self.cumulative.windows(2).map(|w| (w[1] - w[0]).max(0.0)).collect()
+    }
+
+    // Smallest (interpolated) distance within which a `quantile` fraction of connected pairs lie.
+    // With `quantile` = 0.9 this is the usual 90% effective diameter.
+    pub fn effective_diameter(&self, quantile: f64) -> f64 {
+        let total = *self.cumulative.last().unwrap_or(&0.0);
+        let target = quantile * total;
+        for t in 1..self.cumulative.len() {
+            let (prev, curr) = (self.cumulative[t - 1], self.cumulative[t]);
+            if curr >= target {
+                let step = curr - prev;
+                let frac = if step > 0.0 { (target - prev) / step } else { 0.0 };
+                return (t - 1) as f64 + frac.clamp(0.0, 1.0);
+            }
+        }
+        (self.cumulative.len().saturating_sub(1)) as f64
+    }
+}
+
+// Mixes a node index into a well-distributed 64-bit hash (SplitMix64 finalizer).
+pub(crate) fn hash_node(v: usize) -> u64 {
+    let mut z = (v as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// Estimates the cardinality of one HyperLogLog counter.
+fn hll_estimate(registers: &[u8]) -> f64 {
+    let m = registers.len() as f64;
+    let alpha = 0.7213 / (1.0 + 1.079 / m);
+    let sum: f64 = registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+    let raw = alpha * m * m / sum;
+    let zeros = registers.iter().filter(|&&r| r == 0).count();
+    // Linear counting is more accurate while many registers are still empty
+    if raw <= 2.5 * m && zeros > 0 {
+        m * (m / zeros as f64).ln()
+    } else {
+        raw
+    }
+}
+
+// Approximates the neighborhood function with HyperANF: every node keeps a HyperLogLog
+// counter of the nodes within distance t, and each pass unions in the neighbors' counters.
+// Iteration stops once no counter changes (or after `max_iters` passes).
+// Its inputs are - `graph`: the graph - `log2m`: log2 of registers per counter (4..=16)
+// - `max_iters`: maximum number of passes
+// Its outputs are - `NeighborhoodFunction`: estimated pair counts per distance
+pub fn hyper_anf(graph: &Graph, log2m: u32, max_iters: usize) -> NeighborhoodFunction {
+    let log2m = log2m.clamp(4, 16);
+    let m = 1usize << log2m;
+    let n = graph.node_count();
+
+    // Every counter starts out containing only its own node
+    let mut counters = vec![0u8; n * m];
+    for v in 0..n {
+        let h = hash_node(v);
+        let register = (h >> (64 - log2m)) as usize;
+        let rank = ((h << log2m) | (1 << (log2m - 1))).leading_zeros() as u8 + 1;
+        counters[v * m + register] = rank;
+    }
+
+    let total = |counters: &[u8]| -> f64 { counters.par_chunks(m).map(hll_estimate).sum() };
+    let mut cumulative = vec![total(&counters)];
+
+    for _ in 0..max_iters {
+        let next: Vec<u8> = (0..n)
+            .into_par_iter()
+            .flat_map_iter(|v| {
+                let mut own = counters[v * m..(v + 1) * m].to_vec();
+                for &w in graph.neighbors(v) {
+                    for (r, &other) in own.iter_mut().zip(&counters[w * m..(w + 1) * m]) {
+                        *r = (*r).max(other);
+                    }
+                }
+                own
+            })
+            .collect();
+
+        if next == counters {
+            break;
+        }
+        counters = next;
+        cumulative.push(total(&counters));
+    }
+
+    NeighborhoodFunction { cumulative }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests diameter and average path length on a path with a pendant leaf.
+    // Path 1-2-3-4 plus leaf 5 on node 2, and a separate edge 10-11.
+    #[test]
+    fn test_distance_stats() {
+        let edges = vec![(1, 2), (2, 3), (3, 4), (2, 5), (10, 11)];
+        let graph = Graph::from_edges(&edges);
+
+        let giant = distance_stats(&graph, true);
+        assert_eq!(giant.diameter, 3);
+        // Pair distances: 1-2:1 1-3:2 1-4:3 1-5:2 2-3:1 2-4:2 2-5:1 3-4:1 3-5:2 4-5:3 -> 18 / 10
+        assert!((giant.avg_path_length - 1.8).abs() < 1e-9);
+
+        let all = distance_stats(&graph, false);
+        assert_eq!(all.diameter, 3);
+        assert!((all.avg_path_length - (36.0 + 2.0) / 22.0).abs() < 1e-9);
+    }
+
+    // Tests landmark bounds on the path 1-2-3-4-5 with a chord 2-4, where node 2 is the
+    // landmark (degree 3, tied with node 4 but interned first), and on disconnected pairs.
+    #[test]
+    fn test_landmark_index() {
+        let graph = Graph::from_edges(&[(1, 2), (2, 3), (3, 4), (4, 5), (2, 4), (8, 9)]);
+        let index = LandmarkIndex::build(&graph, 1);
+        assert_eq!(index.landmarks(&graph), vec![2]);
+        // 1 and 5 are 3 apart and the landmark 2 is on a shortest path, so only the upper bound is tight
+        assert_eq!(index.estimate(&graph, 1, 5), Some(DistanceEstimate { lower: 1, upper: 3 }));
+        // From the landmark itself both bounds are exact
+        assert_eq!(index.estimate(&graph, 2, 5), Some(DistanceEstimate { lower: 2, upper: 2 }));
+        // 3 and 5 are 2 apart, at distances 1 and 2 from the landmark
+        assert_eq!(index.estimate(&graph, 3, 5), Some(DistanceEstimate { lower: 1, upper: 3 }));
+        assert_eq!(index.estimate(&graph, 1, 9), None);
+        assert_eq!(index.estimate(&graph, 8, 9), None);
+        assert_eq!(index.estimate(&graph, 3, 3), Some(DistanceEstimate { lower: 0, upper: 0 }));
+    }
+
+    // Tests delta-stepping on a weighted square with a tail, 1-2-3-4-1 plus 3-5, against
+    // Dijkstra and, with unit weights, against BFS closeness, for narrow and wide buckets.
+    #[test]
+    fn test_delta_stepping() {
+        let edges = vec![(1, 2, 4.0), (2, 3, 4.0), (3, 4, 1.0), (4, 1, 1.0), (3, 5, 0.5)];
+        let graph = Graph::from_weighted_edges(&edges);
+        let mut dag = crate::graph::WeightedShortestPathDag::new(graph.node_count());
+        for delta in [0.1, default_delta(&graph), 10.0] {
+            for s in 0..graph.node_count() {
+                dag.search(&graph, s);
+                let dist = delta_stepping(&graph, s, delta);
+                assert!((0..graph.node_count()).all(|v| (dist[v] - dag.dist[v]).abs() < 1e-12));
+            }
+        }
+        let dist = delta_stepping(&graph, graph.index_of(1).unwrap(), 0.3);
+        assert_eq!(dist[graph.index_of(5).unwrap()], 2.5);
+
+        let unit: Vec<(usize, usize)> = (0..30).map(|i| (i, (i + 1) % 30)).chain([(40, 41)]).collect();
+        let graph = Graph::from_edges(&unit);
+        let nodes: HashSet<usize> = (0..30).chain([40]).collect();
+        assert_eq!(weighted_closeness(&graph, &nodes, 1.0), batched_closeness(&graph, &nodes));
+    }
+
+    // Tests the all-pairs matrix on a 6-cycle plus a separate edge: closeness and group
+    // closeness agree with the BFS-based versions, and too large graphs are refused.
+    #[test]
+    fn test_distance_matrix() {
+        let edges = vec![(1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (6, 1), (8, 9)];
+        let graph = Graph::from_edges(&edges);
+        assert!(DistanceMatrix::build(&graph, 7).is_none());
+        let matrix = DistanceMatrix::build(&graph, 8).unwrap();
+        let (a, b) = (graph.index_of(1).unwrap(), graph.index_of(4).unwrap());
+        assert_eq!((matrix.distance(a, b), matrix.distance(a, graph.index_of(9).unwrap())), (Some(3), None));
+
+        let all: HashSet<usize> = (1..=6).chain([8, 9]).collect();
+        assert_eq!(matrix.closeness(&graph), batched_closeness(&graph, &all));
+        assert_eq!(matrix.eccentricity(&graph)[&2], 3);
+        assert_eq!(matrix.eccentricity(&graph)[&8], 1);
+        let group: HashSet<usize> = [1, 4].into_iter().collect();
+        let expected = crate::group::group_centrality(&graph, &group, 0, 1).closeness;
+        assert_eq!(matrix.group_closeness(&graph, &group), expected);
+    }
+
+    // Tests that batched BFS matches one BFS per source, across more than 64 sources
+    // (a 100-node cycle plus a separate edge), and that closeness matches `compute_closeness`.
+    #[test]
+    fn test_multi_source_bfs() {
+        let mut edges: Vec<(usize, usize)> = (0..100).map(|i| (i, (i + 1) % 100)).collect();
+        edges.push((200, 201));
+        let graph = Graph::from_edges(&edges);
+        let sources: Vec<usize> = (0..graph.node_count()).collect();
+        let bfs = multi_source_bfs(&graph, &sources);
+
+        let mut dist = vec![UNREACHED; graph.node_count()];
+        let mut order = Vec::new();
+        for &s in &sources {
+            bfs_from(&graph, s, &mut dist, &mut order);
+            let sum: usize = order.iter().map(|&v| dist[v]).sum();
+            assert_eq!(bfs.totals[s].distance_sum, sum as u64);
+            assert_eq!(bfs.totals[s].reached, order.len());
+            reset(&mut dist, &order);
+        }
+        // Every cycle node has two nodes at each distance 1..49 and one at 50
+        assert_eq!(bfs.pairs_at[1], 2 * 100 + 2);
+        assert_eq!(bfs.pairs_at[50], 100);
+        assert_eq!(exact_neighborhood_function(&graph).cumulative.last(), Some(&(100.0 * 100.0 + 4.0)));
+
+        let nodes: HashSet<usize> = [0, 7, 50, 200].into_iter().collect();
+        let expected = crate::graph::compute_closeness(&edges, &nodes, crate::graph::ClosenessMode::Standard);
+        for (node, score) in batched_closeness(&graph, &nodes) {
+            assert!((score - expected[&node]).abs() < 1e-12);
+        }
+    }
+
+    // Tests that sampled closeness is exact when every node is a pivot, and close on a 20x20
+    // grid with a quarter of the nodes as pivots. A separate edge is exact whether or not
+    // one of its ends is sampled, through the fallback BFS.
+    #[test]
+    fn test_approximate_closeness() {
+        let mut edges = Vec::new();
+        for r in 0..20 {
+            for c in 0..20 {
+                if c < 19 {
+                    edges.push((r * 20 + c, r * 20 + c + 1));
+                }
+                if r < 19 {
+                    edges.push((r * 20 + c, (r + 1) * 20 + c));
+                }
+            }
+        }
+        edges.push((1000, 1001));
+        let graph = Graph::from_edges(&edges);
+        let nodes: HashSet<usize> = (0..graph.node_count()).map(|v| graph.node_id(v)).collect();
+        let exact = batched_closeness(&graph, &nodes);
+
+        let all = approximate_closeness(&graph, graph.node_count(), 1);
+        assert!(exact.iter().all(|(node, score)| (score - all[node]).abs() < 1e-12));
+        let sampled = approximate_closeness(&graph, 100, 1);
+        assert!(exact.iter().all(|(node, score)| ((score - sampled[node]) / score).abs() < 0.25));
+        assert_eq!(sampled[&1000], 1.0);
+    }
+
+    // Tests eccentricity, radius, center and periphery on a path with a pendant leaf.
+    // Path 1-2-3-4 plus leaf 5 on node 2: nodes 2 and 3 form the center, the three leaves the periphery.
+    #[test]
+    fn test_eccentricity() {
+        let edges = vec![(1, 2), (2, 3), (3, 4), (2, 5)];
+        let graph = Graph::from_edges(&edges);
+        let component = graph.components().remove(0);
+        let result = eccentricity(&graph, &component);
+
+        assert_eq!(result.ecc[&1], 3);
+        assert_eq!(result.ecc[&2], 2);
+        assert_eq!(result.ecc[&3], 2);
+        assert_eq!(result.ecc[&4], 3);
+        assert_eq!(result.ecc[&5], 3);
+        assert_eq!(result.radius, 2);
+        assert_eq!(result.diameter, 3);
+        assert_eq!(result.center, vec![2, 3]);
+        assert_eq!(result.periphery, vec![1, 4, 5]);
+    }
+
+    // Tests that HyperANF approximates the neighborhood function of a small path.
+    // A path of 6 nodes has 36 ordered pairs (self pairs included) within its diameter of 5.
+    #[test]
+    fn test_hyper_anf() {
+        let edges = vec![(1, 2), (2, 3), (3, 4), (4, 5), (5, 6)];
+        let graph = Graph::from_edges(&edges);
+        let nf = hyper_anf(&graph, 10, 20);
+
+        assert!(nf.cumulative.len() <= 6);
+        assert!(nf.cumulative.windows(2).all(|w| w[1] >= w[0]));
+        let total = *nf.cumulative.last().unwrap();
+        assert!((total - 36.0).abs() < 4.0);
+        let eff = nf.effective_diameter(0.9);
+        assert!(eff > 2.0 && eff <= 5.0);
+    }
+}
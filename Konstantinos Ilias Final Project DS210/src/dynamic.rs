@@ -0,0 +1,269 @@
+// Includes a graph whose metrics are kept up to date while edges are added and removed, so a
+// stream of changes does not require recomputing everything after each one. Degrees change
+// locally, components are tracked by a union-find (which cannot undo a merge, so it is rebuilt
+// after removals), and betweenness can be estimated from sampled sources whose shortest-path
+// DAGs are recomputed only when a change actually touches them.
+
+use crate::distance::UNREACHED;
+use crate::graph::{Graph, ShortestPathDag};
+use crate::unionfind::UnionFind;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::HashMap;
+
+// Settings of the incremental metrics.
+#[derive(Debug, Clone)]
+pub struct DynamicConfig {
+    // Number of edge removals after which the components are rebuilt from scratch; 1 keeps
+    // them exact, larger values batch the rebuilds and may report split components as one
+    pub rebuild_after: usize,
+    // Number of sampled betweenness sources (0 turns betweenness off)
+    pub betweenness_sources: usize,
+    pub seed: u64,
+}
+
+impl Default for DynamicConfig {
+    fn default() -> Self {
+        DynamicConfig { rebuild_after: 1, betweenness_sources: 0, seed: 42 }
+    }
+}
+
+// Sampled-source betweenness (hop counts) with each source's distances and dependencies kept,
+// so a change is only propagated to the sources whose shortest-path DAG it alters.
+struct IncrementalBetweenness {
+    sources: Vec<usize>,
+    dist: Vec<Vec<usize>>,
+    contribution: Vec<Vec<f64>>,
+    scores: Vec<f64>,
+}
+
+impl IncrementalBetweenness {
+    fn new(graph: &Graph, sources: usize, seed: u64) -> IncrementalBetweenness {
+        let n = graph.node_count();
+        let mut pool: Vec<usize> = (0..n).collect();
+        pool.shuffle(&mut StdRng::seed_from_u64(seed));
+        pool.truncate(sources);
+        let mut state = IncrementalBetweenness {
+            dist: vec![Vec::new(); pool.len()],
+            contribution: vec![Vec::new(); pool.len()],
+            sources: pool,
+            scores: vec![0.0; n],
+        };
+        for i in 0..state.sources.len() {
+            state.recompute(graph, i);
+        }
+        state
+    }
+
+    // Replaces the contribution of source `i` by a fresh BFS and dependency accumulation.
+    fn recompute(&mut self, graph: &Graph, i: usize) {
+        let n = graph.node_count();
+        let mut dag = ShortestPathDag::new(n);
+        dag.search(graph, self.sources[i]);
+        let mut delta = vec![0.0; n];
+        dag.dependencies(graph, &mut delta);
+        delta[self.sources[i]] = 0.0;
+
+        self.scores.resize(n, 0.0);
+        for (score, old) in self.scores.iter_mut().zip(&self.contribution[i]) {
+            *score -= old;
+        }
+        for (score, new) in self.scores.iter_mut().zip(&delta) {
+            *score += new;
+        }
+        self.dist[i] = dag.dist;
+        self.contribution[i] = delta;
+    }
+
+    // Updates the sources affected by adding or removing the edge between compact indices
+    // `a` and `b`. In an unweighted graph the edge lies on a shortest path from s exactly when
+    // its endpoints are at different distances from s, so only those sources are recomputed.
+    // Its outputs are - `usize`: number of sources recomputed
+    fn edge_changed(&mut self, graph: &Graph, a: usize, b: usize) -> usize {
+        let mut recomputed = 0;
+        for i in 0..self.sources.len() {
+            let d = |v: usize| self.dist[i].get(v).copied().unwrap_or(UNREACHED);
+            if d(a) != d(b) {
+                self.recompute(graph, i);
+                recomputed += 1;
+            }
+        }
+        recomputed
+    }
+}
+
+// A graph with degrees, components and (optionally) approximate betweenness maintained
+// under edge insertions and removals.
+pub struct DynamicGraph {
+    graph: Graph,
+    config: DynamicConfig,
+    degree: HashMap<usize, usize>,
+    components: UnionFind,
+    // Removals since the components were last rebuilt
+    pending_removals: usize,
+    betweenness: Option<IncrementalBetweenness>,
+    // Betweenness sources recomputed so far, compared to all sources per change
+    recomputed: usize,
+}
+
+impl DynamicGraph {
+    // Starts tracking a graph, computing the initial metrics once.
+    // Its inputs are - `graph`: the starting graph - `config`: rebuild and betweenness settings
+    // Its outputs are - `DynamicGraph`: the graph with its metrics
+    pub fn new(graph: Graph, config: DynamicConfig) -> DynamicGraph {
+        let degree = (0..graph.node_count()).map(|v| (graph.node_id(v), graph.degree(v))).collect();
+        let components = UnionFind::from_graph(&graph);
+        let betweenness =
+            (config.betweenness_sources > 0).then(|| IncrementalBetweenness::new(&graph, config.betweenness_sources, config.seed));
+        DynamicGraph { graph, config, degree, components, pending_removals: 0, betweenness, recomputed: 0 }
+    }
+
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    // Node ID -> current degree, as `compute_degree` counts it for the current edges.
+    pub fn degree(&self) -> &HashMap<usize, usize> {
+        &self.degree
+    }
+
+    // Adds an edge between two node IDs and updates the metrics.
+    // Its inputs are - `u`, `v`: node IDs of the endpoints
+    // Its outputs are - `bool`: false if the edge already existed or is a self loop
+    pub fn add_edge(&mut self, u: usize, v: usize) -> bool {
+        if !self.graph.add_edge(u, v) {
+            return false;
+        }
+        while self.components.len() < self.graph.node_count() {
+            self.components.push();
+        }
+        let (a, b) = (self.graph.index_of(u).unwrap(), self.graph.index_of(v).unwrap());
+        *self.degree.entry(u).or_insert(0) += 1;
+        *self.degree.entry(v).or_insert(0) += 1;
+        self.components.union(a, b);
+        if let Some(betweenness) = self.betweenness.as_mut() {
+            self.recomputed += betweenness.edge_changed(&self.graph, a, b);
+        }
+        true
+    }
+
+    // Removes an edge between two node IDs and updates the metrics. The components are
+    // rebuilt once `rebuild_after` removals have accumulated.
+    // Its inputs are - `u`, `v`: node IDs of the endpoints
+    // Its outputs are - `bool`: false if there was no such edge
+    pub fn remove_edge(&mut self, u: usize, v: usize) -> bool {
+        if !self.graph.remove_edge(u, v) {
+            return false;
+        }
+        let (a, b) = (self.graph.index_of(u).unwrap(), self.graph.index_of(v).unwrap());
+        for id in [u, v] {
+            if let Some(d) = self.degree.get_mut(&id) {
+                *d -= 1;
+            }
+        }
+        self.pending_removals += 1;
+        if self.pending_removals >= self.config.rebuild_after {
+            self.rebuild_components();
+        }
+        if let Some(betweenness) = self.betweenness.as_mut() {
+            self.recomputed += betweenness.edge_changed(&self.graph, a, b);
+        }
+        true
+    }
+
+    // Recomputes the components from the current edges, making them exact again.
+    pub fn rebuild_components(&mut self) {
+        self.components = UnionFind::from_graph(&self.graph);
+        self.pending_removals = 0;
+    }
+
+    // Whether the components reflect every removal so far (always true after insertions only).
+    pub fn components_exact(&self) -> bool {
+        self.pending_removals == 0
+    }
+
+    // Number of connected components, isolated nodes included.
+    pub fn component_count(&self) -> usize {
+        self.components.set_count()
+    }
+
+    // Representative node ID of the component containing a node, if the node is in the graph.
+    pub fn component_of(&mut self, id: usize) -> Option<usize> {
+        let v = self.graph.index_of(id)?;
+        let root = self.components.find(v);
+        Some(self.graph.node_id(root))
+    }
+
+    // Whether two node IDs are in the same component, in O(α(n)) amortized time. Until the
+    // components are rebuilt after a removal, nodes it disconnected may still be reported together.
+    pub fn same_component(&mut self, u: usize, v: usize) -> bool {
+        match (self.graph.index_of(u), self.graph.index_of(v)) {
+            (Some(a), Some(b)) => self.components.same_set(a, b),
+            _ => false,
+        }
+    }
+
+    // Number of nodes in the component containing a node ID, if the node is in the graph.
+    pub fn component_size(&mut self, id: usize) -> Option<usize> {
+        let v = self.graph.index_of(id)?;
+        Some(self.components.set_size(v))
+    }
+
+    // Current betweenness estimate, normalized by pairs as in `BetweennessNormalization::Pairs`.
+    // Sources are sampled among the starting nodes; nodes added later are only targets.
+    // Its outputs are - `Option<HashMap<usize, f64>>`: node ID -> betweenness, None if turned off
+    pub fn betweenness(&self) -> Option<HashMap<usize, f64>> {
+        let betweenness = self.betweenness.as_ref()?;
+        let n = self.graph.node_count();
+        let k = betweenness.sources.len();
+        let scale = if n > 2 && k > 0 { n as f64 / k as f64 / ((n - 1) * (n - 2)) as f64 } else { 1.0 };
+        Some((0..n).map(|v| (self.graph.node_id(v), betweenness.scores.get(v).copied().unwrap_or(0.0) * scale)).collect())
+    }
+
+    // Total number of betweenness sources recomputed by the changes so far; a full
+    // recomputation would redo every source for every change.
+    pub fn recomputed_sources(&self) -> usize {
+        self.recomputed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{brandes_betweenness, compute_degree, BetweennessNormalization};
+    use std::collections::HashSet;
+
+    // Tests that after a series of insertions and removals on two triangles, the maintained
+    // degrees, components and betweenness (with every node a source) match a recomputation
+    // on the final edges, and that a removal between two distance-equal endpoints is free.
+    #[test]
+    fn test_dynamic_graph() {
+        let graph = Graph::from_edges(&[(1, 2), (2, 3), (3, 1), (4, 5), (5, 6), (6, 4)]);
+        let config = DynamicConfig { betweenness_sources: 6, ..DynamicConfig::default() };
+        let mut dynamic = DynamicGraph::new(graph, config);
+        assert_eq!(dynamic.component_count(), 2);
+
+        assert!(dynamic.add_edge(3, 4) && !dynamic.add_edge(4, 3) && !dynamic.add_edge(2, 2));
+        assert_eq!(dynamic.component_count(), 1);
+        assert_eq!(dynamic.component_of(1), dynamic.component_of(6));
+        assert!(dynamic.same_component(2, 5) && dynamic.component_size(5) == Some(6));
+        assert!(dynamic.add_edge(6, 7));
+        let before = dynamic.recomputed_sources();
+        assert!(dynamic.remove_edge(1, 2));
+        assert_eq!(dynamic.recomputed_sources() - before, 2);
+        assert!(dynamic.remove_edge(3, 4) && !dynamic.remove_edge(3, 4));
+        assert_eq!(dynamic.component_count(), 2);
+        assert!(!dynamic.same_component(1, 6) && dynamic.component_size(7) == Some(4));
+
+        let edges = dynamic.graph().edges();
+        let degree: HashMap<usize, usize> = compute_degree(&edges).into_iter().map(|(node, deg)| (node, deg.degree)).collect();
+        assert_eq!(dynamic.degree(), &degree);
+        let all: HashSet<usize> = (1..=6).collect();
+        let expected = brandes_betweenness(&Graph::from_edges(&edges), &all, BetweennessNormalization::Pairs);
+        let actual = dynamic.betweenness().unwrap();
+        for (node, score) in expected {
+            assert!((actual[&node] - score).abs() < 1e-12);
+        }
+    }
+}
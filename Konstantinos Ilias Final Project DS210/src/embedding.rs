@@ -0,0 +1,296 @@
+// Includes functions to learn node embeddings with node2vec/DeepWalk: biased random walks
+// are treated as sentences and a skip-gram model with negative sampling turns every node
+// into a d-dimensional vector, which can be clustered or exported to CSV.
+// It also computes Laplacian eigenmaps, a spectral alternative for clustering and 2D plots.
+
+use crate::graph::Graph;
+use crate::walks::{generate_walks, WalkBias};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+// Parameters of node2vec. With p = q = 1 the walks are uniform and this is DeepWalk.
+#[derive(Debug, Clone)]
+pub struct Node2VecConfig {
+    pub dimensions: usize,
+    pub walk_length: usize,
+    pub walks_per_node: usize,
+    pub window: usize,
+    // Return parameter: high values make walks less likely to step straight back
+    pub p: f64,
+    // In-out parameter: low values push walks outward (DFS-like), high values keep them local
+    pub q: f64,
+    pub negative: usize,
+    pub epochs: usize,
+    pub learning_rate: f64,
+    pub seed: u64,
+}
+
+impl Default for Node2VecConfig {
+    fn default() -> Self {
+        Node2VecConfig {
+            dimensions: 32,
+            walk_length: 40,
+            walks_per_node: 5,
+            window: 5,
+            p: 1.0,
+            q: 1.0,
+            negative: 5,
+            epochs: 1,
+            learning_rate: 0.025,
+            seed: 42,
+        }
+    }
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+// Learns node2vec embeddings: random walks from every node, then skip-gram training with
+// negative samples drawn from the degree^0.75 distribution and a linearly decaying learning rate.
+// Its inputs are - `graph`: the graph - `config`: walk and training parameters
+// Its outputs are - `HashMap<usize, Vec<f64>>`: node ID -> embedding vector
+pub fn node2vec(graph: &Graph, config: &Node2VecConfig) -> HashMap<usize, Vec<f64>> {
+    let n = graph.node_count();
+    let d = config.dimensions;
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let bias = if config.p == 1.0 && config.q == 1.0 {
+        WalkBias::Uniform
+    } else {
+        WalkBias::Node2Vec { p: config.p, q: config.q }
+    };
+    let walks = generate_walks(graph, None, config.walks_per_node, config.walk_length, bias, config.seed);
+
+    // Cumulative degree^0.75 distribution for negative sampling
+    let mut cumulative = Vec::with_capacity(n);
+    let mut total = 0.0;
+    for v in 0..n {
+        total += (graph.degree(v) as f64).powf(0.75);
+        cumulative.push(total);
+    }
+    let sample_negative = |rng: &mut StdRng| {
+        let x = rng.gen::<f64>() * total;
+        cumulative.partition_point(|&c| c < x).min(n - 1)
+    };
+
+    // Input and output vectors are stored row-major in flat buffers for cache locality
+    let mut emb: Vec<f64> = (0..n * d).map(|_| (rng.gen::<f64>() - 0.5) / d as f64).collect();
+    let mut ctx: Vec<f64> = vec![0.0; n * d];
+    let steps = (config.epochs * walks.len()).max(1) as f64;
+    let mut step = 0.0;
+    let mut grad = vec![0.0; d];
+
+    for _ in 0..config.epochs {
+        for walk in &walks {
+            let lr = (config.learning_rate * (1.0 - step / steps)).max(config.learning_rate * 1e-4);
+            step += 1.0;
+            for (i, &center) in walk.iter().enumerate() {
+                let lo = i.saturating_sub(config.window);
+                let hi = (i + config.window + 1).min(walk.len());
+                for (j, &context) in walk.iter().enumerate().take(hi).skip(lo) {
+                    if i == j {
+                        continue;
+                    }
+                    grad.iter_mut().for_each(|g| *g = 0.0);
+                    let center_vec = &emb[center * d..(center + 1) * d];
+                    for k in 0..=config.negative {
+                        let (target, label) = if k == 0 { (context, 1.0) } else { (sample_negative(&mut rng), 0.0) };
+                        if k > 0 && target == context {
+                            continue;
+                        }
+                        let target_vec = &mut ctx[target * d..(target + 1) * d];
+                        let dot: f64 = center_vec.iter().zip(target_vec.iter()).map(|(a, b)| a * b).sum();
+                        let g = lr * (label - sigmoid(dot));
+                        for ((gr, t), c) in grad.iter_mut().zip(target_vec.iter_mut()).zip(center_vec) {
+                            *gr += g * *t;
+                            *t += g * c;
+                        }
+                    }
+                    for (e, g) in emb[center * d..(center + 1) * d].iter_mut().zip(&grad) {
+                        *e += g;
+                    }
+                }
+            }
+        }
+    }
+
+    (0..n).map(|v| (graph.node_id(v), emb[v * d..(v + 1) * d].to_vec())).collect()
+}
+
+// Writes embeddings to a CSV file with a `node,e0,e1,...` header, one row per node in ID order.
+// Its inputs are - `path`: output file - `embeddings`: node ID -> vector
+pub fn write_embeddings_csv(path: impl AsRef<std::path::Path>, embeddings: &HashMap<usize, Vec<f64>>) -> std::io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let dims = embeddings.values().next().map_or(0, |v| v.len());
+    let header: Vec<String> = (0..dims).map(|i| format!("e{}", i)).collect();
+    writeln!(out, "node,{}", header.join(","))?;
+
+    let mut nodes: Vec<&usize> = embeddings.keys().collect();
+    nodes.sort();
+    for node in nodes {
+        let values: Vec<String> = embeddings[node].iter().map(|x| format!("{:.6}", x)).collect();
+        writeln!(out, "{},{}", node, values.join(","))?;
+    }
+    Ok(())
+}
+
+// Result of a Laplacian eigenmap.
+#[derive(Debug, Clone)]
+pub struct SpectralEmbedding {
+    // Node ID -> coordinates, one per nontrivial eigenvector
+    pub coords: HashMap<usize, Vec<f64>>,
+    // Matching eigenvalues of the normalized Laplacian, ascending
+    pub eigenvalues: Vec<f64>,
+}
+
+// Normalizes a vector to unit length after removing its projections onto `basis`
+// (Gram–Schmidt against orthonormal vectors).
+fn orthonormalize(v: &mut [f64], basis: &[Vec<f64>]) {
+    for b in basis {
+        let dot: f64 = v.iter().zip(b).map(|(x, y)| x * y).sum();
+        for (x, y) in v.iter_mut().zip(b) {
+            *x -= dot * y;
+        }
+    }
+    let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+// Computes a Laplacian eigenmap of one connected component: the eigenvectors of the normalized
+// Laplacian L = I - D^-1/2 A D^-1/2 with the smallest nonzero eigenvalues, found by subspace
+// iteration on (I + D^-1/2 A D^-1/2) / 2 with the trivial eigenvector projected out.
+// Coordinates are rescaled by D^-1/2, so they solve the generalized problem L y = lambda D y.
+// Its inputs are - `graph`: the graph - `component`: compact indices of a connected component
+// - `dims`: number of eigenvectors - `iters`: iterations - `seed`: RNG seed for the start vectors
+// Its outputs are - `SpectralEmbedding`: node coordinates and eigenvalues
+pub fn laplacian_eigenmap(graph: &Graph, component: &[usize], dims: usize, iters: usize, seed: u64) -> SpectralEmbedding {
+    let n = component.len();
+    let mut local = HashMap::new();
+    for (i, &v) in component.iter().enumerate() {
+        local.insert(v, i);
+    }
+    let inv_sqrt: Vec<f64> = component.iter().map(|&v| 1.0 / (graph.degree(v).max(1) as f64).sqrt()).collect();
+    let neighbors: Vec<Vec<usize>> = component
+        .iter()
+        .map(|&v| graph.neighbors(v).iter().filter_map(|w| local.get(w).copied()).collect())
+        .collect();
+    // y = (x + D^-1/2 A D^-1/2 x) / 2
+    let apply = |x: &[f64]| -> Vec<f64> {
+        (0..n)
+            .map(|i| {
+                let sum: f64 = neighbors[i].iter().map(|&j| x[j] * inv_sqrt[j]).sum();
+                (x[i] + sum * inv_sqrt[i]) / 2.0
+            })
+            .collect()
+    };
+
+    // The trivial eigenvector is proportional to sqrt(degree)
+    let mut trivial: Vec<f64> = inv_sqrt.iter().map(|x| 1.0 / x).collect();
+    orthonormalize(&mut trivial, &[]);
+
+    let dims = dims.min(n.saturating_sub(1));
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut basis: Vec<Vec<f64>> = Vec::with_capacity(dims);
+    for _ in 0..dims {
+        let mut v: Vec<f64> = (0..n).map(|_| rng.gen::<f64>() - 0.5).collect();
+        let mut against = vec![trivial.clone()];
+        against.extend(basis.iter().cloned());
+        orthonormalize(&mut v, &against);
+        basis.push(v);
+    }
+    for _ in 0..iters {
+        let mut next: Vec<Vec<f64>> = Vec::with_capacity(dims);
+        for v in &basis {
+            let mut y = apply(v);
+            let mut against = vec![trivial.clone()];
+            against.extend(next.iter().cloned());
+            orthonormalize(&mut y, &against);
+            next.push(y);
+        }
+        basis = next;
+    }
+
+    // A Rayleigh quotient s of the shifted operator gives the Laplacian eigenvalue 2 (1 - s)
+    let eigenvalues: Vec<f64> = basis
+        .iter()
+        .map(|v| {
+            let s: f64 = apply(v).iter().zip(v).map(|(a, b)| a * b).sum();
+            2.0 * (1.0 - s)
+        })
+        .collect();
+    let coords = component
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| (graph.node_id(v), basis.iter().map(|b| b[i] * inv_sqrt[i]).collect()))
+        .collect();
+    SpectralEmbedding { coords, eigenvalues }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cosine(a: &[f64], b: &[f64]) -> f64 {
+        let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        let norm = |v: &[f64]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        dot / (norm(a) * norm(b))
+    }
+
+    // Tests that nodes in the same dense group get more similar embeddings than nodes in different groups.
+    // Two 6-cliques are joined by a single edge between nodes 1 and 11.
+    #[test]
+    fn test_node2vec() {
+        let mut edges = Vec::new();
+        for base in [0, 10] {
+            for a in 1..=6 {
+                for b in a + 1..=6 {
+                    edges.push((base + a, base + b));
+                }
+            }
+        }
+        edges.push((1, 11));
+        let graph = Graph::from_edges(&edges);
+        let config = Node2VecConfig { dimensions: 8, walks_per_node: 20, walk_length: 20, epochs: 3, ..Default::default() };
+        let emb = node2vec(&graph, &config);
+
+        assert_eq!(emb.len(), 12);
+        assert!(emb.values().all(|v| v.len() == 8));
+        assert!(cosine(&emb[&2], &emb[&3]) > cosine(&emb[&2], &emb[&13]));
+        assert!(cosine(&emb[&12], &emb[&14]) > cosine(&emb[&12], &emb[&4]));
+    }
+
+    // Tests Laplacian eigenmap eigenvalues on a cycle and the Fiedler split of two joined cliques.
+    // The normalized Laplacian of an 8-cycle has eigenvalues 1 - cos(2 pi k / 8).
+    #[test]
+    fn test_laplacian_eigenmap() {
+        let cycle: Vec<(usize, usize)> = (0..8).map(|i| (i, (i + 1) % 8)).collect();
+        let graph = Graph::from_edges(&cycle);
+        let all: Vec<usize> = (0..graph.node_count()).collect();
+        let spectral = laplacian_eigenmap(&graph, &all, 3, 500, 1);
+        let expected = [1.0 - (std::f64::consts::PI / 4.0).cos(), 1.0 - (std::f64::consts::PI / 4.0).cos(), 1.0];
+        for (got, want) in spectral.eigenvalues.iter().zip(expected) {
+            assert!((got - want).abs() < 1e-6);
+        }
+
+        let mut edges = Vec::new();
+        for base in [0, 10] {
+            for a in 1..=4 {
+                for b in a + 1..=4 {
+                    edges.push((base + a, base + b));
+                }
+            }
+        }
+        edges.push((1, 11));
+        let graph = Graph::from_edges(&edges);
+        let all: Vec<usize> = (0..graph.node_count()).collect();
+        let fiedler = laplacian_eigenmap(&graph, &all, 1, 500, 1).coords;
+        let side = |id: usize| fiedler[&id][0] > 0.0;
+        assert!((1..=4).all(|id| side(id) == side(1)));
+        assert!((11..=14).all(|id| side(id) != side(1)));
+    }
+}
@@ -0,0 +1,181 @@
+// Includes random graph generators (Erdős–Rényi, Barabási–Albert and Watts–Strogatz).
+// They return edge lists over node IDs 0..n, like `read_file`, so the synthetic graphs go
+// through the same analysis as the Enron network and serve as baselines and test topologies.
+// It also randomizes a graph while keeping every node's degree, for null-model comparisons.
+
+use crate::graph::Graph;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+
+// Generates a uniform random graph with n nodes and exactly m edges (G(n, m)).
+// m is capped at the number of possible edges.
+// Its inputs are - `n`: number of nodes - `m`: number of edges - `seed`: RNG seed
+// Its outputs are - `Vec<(usize, usize)>`: edges (u, v) with u < v
+pub fn erdos_renyi(n: usize, m: usize, seed: u64) -> Vec<(usize, usize)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let m = m.min(n * n.saturating_sub(1) / 2);
+    let mut seen = HashSet::with_capacity(m);
+    let mut edges = Vec::with_capacity(m);
+    while edges.len() < m {
+        let (a, b) = (rng.gen_range(0..n), rng.gen_range(0..n));
+        if a != b && seen.insert((a.min(b), a.max(b))) {
+            edges.push((a.min(b), a.max(b)));
+        }
+    }
+    edges
+}
+
+// Generates a Barabási–Albert preferential attachment graph. It starts from a clique of
+// m + 1 nodes; every new node links to m distinct existing nodes chosen with probability
+// proportional to their degree.
+// Its inputs are - `n`: number of nodes - `m`: edges added per new node - `seed`: RNG seed
+// Its outputs are - `Vec<(usize, usize)>`: edges (u, v) with u < v
+pub fn barabasi_albert(n: usize, m: usize, seed: u64) -> Vec<(usize, usize)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let start = (m + 1).min(n);
+    let mut edges = Vec::new();
+    // Every edge endpoint appears once here, so a uniform pick is a degree-proportional pick
+    let mut endpoints = Vec::new();
+    for a in 0..start {
+        for b in a + 1..start {
+            edges.push((a, b));
+            endpoints.push(a);
+            endpoints.push(b);
+        }
+    }
+    for v in start..n {
+        let mut targets = HashSet::new();
+        while targets.len() < m {
+            targets.insert(*endpoints.choose(&mut rng).unwrap());
+        }
+        let mut targets: Vec<usize> = targets.into_iter().collect();
+        targets.sort_unstable();
+        for u in targets {
+            edges.push((u, v));
+            endpoints.push(u);
+            endpoints.push(v);
+        }
+    }
+    edges
+}
+
+// Generates a Watts–Strogatz small-world graph: a ring where every node links to its k
+// nearest neighbors (k / 2 on each side), after which each edge's far end is rewired to a
+// uniformly random node with probability beta, avoiding self loops and duplicate edges.
+// Its inputs are - `n`: number of nodes - `k`: even ring degree - `beta`: rewiring probability - `seed`: RNG seed
+// Its outputs are - `Vec<(usize, usize)>`: edges (u, v) with u < v
+pub fn watts_strogatz(n: usize, k: usize, beta: f64, seed: u64) -> Vec<(usize, usize)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let half = (k / 2).min(n.saturating_sub(1) / 2);
+    let mut lattice = Vec::with_capacity(n * half);
+    for v in 0..n {
+        for offset in 1..=half {
+            lattice.push((v, (v + offset) % n));
+        }
+    }
+    let mut present: HashSet<(usize, usize)> = lattice.iter().map(|&(a, b)| (a.min(b), a.max(b))).collect();
+    let mut degree = vec![2 * half; n];
+
+    let mut edges = Vec::with_capacity(lattice.len());
+    for (u, v) in lattice {
+        let mut target = v;
+        // A node already linked to everyone keeps its lattice edge
+        if rng.gen::<f64>() < beta && degree[u] < n - 1 {
+            loop {
+                let w = rng.gen_range(0..n);
+                if w != u && present.insert((u.min(w), u.max(w))) {
+                    present.remove(&(u.min(v), u.max(v)));
+                    degree[v] -= 1;
+                    degree[w] += 1;
+                    target = w;
+                    break;
+                }
+            }
+        }
+        edges.push((u.min(target), u.max(target)));
+    }
+    edges
+}
+
+// Randomizes the graph with double edge swaps: two edges (a, b) and (c, d) are replaced by
+// (a, d) and (c, b), which keeps every node's degree. Swaps creating a self loop or a duplicate
+// edge are rejected, and `swaps_per_edge * edges` swaps are attempted in total.
+// Its inputs are - `graph`: the graph - `swaps_per_edge`: attempts per edge (10 mixes well) - `seed`: RNG seed
+// Its outputs are - `Vec<(usize, usize)>`: edges (u, v) of the randomized graph as node IDs, with u < v
+pub fn degree_preserving_rewire(graph: &Graph, swaps_per_edge: usize, seed: u64) -> Vec<(usize, usize)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut edges: Vec<(usize, usize)> = Vec::with_capacity(graph.edge_count());
+    for u in 0..graph.node_count() {
+        for &v in graph.neighbors(u) {
+            if u < v {
+                edges.push((u, v));
+            }
+        }
+    }
+    let mut present: HashSet<(usize, usize)> = edges.iter().copied().collect();
+    let key = |a: usize, b: usize| (a.min(b), a.max(b));
+
+    if edges.len() >= 2 {
+        for _ in 0..swaps_per_edge * edges.len() {
+            let (i, j) = (rng.gen_range(0..edges.len()), rng.gen_range(0..edges.len()));
+            let (a, b) = edges[i];
+            // Flipping the second edge half the time reaches both possible swaps
+            let (c, d) = if rng.gen::<bool>() { edges[j] } else { (edges[j].1, edges[j].0) };
+            if i == j || a == d || c == b || present.contains(&key(a, d)) || present.contains(&key(c, b)) {
+                continue;
+            }
+            present.remove(&key(a, b));
+            present.remove(&key(c, d));
+            present.insert(key(a, d));
+            present.insert(key(c, b));
+            edges[i] = key(a, d);
+            edges[j] = key(c, b);
+        }
+    }
+    edges.into_iter().map(|(u, v)| key(graph.node_id(u), graph.node_id(v))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests edge counts and basic structure of the three generators.
+    #[test]
+    fn test_generators() {
+        let er = Graph::from_edges(&erdos_renyi(100, 300, 1));
+        assert_eq!(er.edge_count(), 300);
+
+        // A clique of 4 nodes (6 edges) plus 3 edges for each of the 96 other nodes
+        let ba_edges = barabasi_albert(100, 3, 1);
+        assert_eq!(ba_edges.len(), 6 + 96 * 3);
+        let ba = Graph::from_edges(&ba_edges);
+        assert_eq!(ba.edge_count(), ba_edges.len());
+        assert_eq!(ba.components().len(), 1);
+        assert!((0..ba.node_count()).all(|v| ba.degree(v) >= 3));
+
+        // Without rewiring every node keeps exactly its 4 ring neighbors
+        let ring = Graph::from_edges(&watts_strogatz(20, 4, 0.0, 1));
+        assert!((0..ring.node_count()).all(|v| ring.degree(v) == 4));
+        let rewired = Graph::from_edges(&watts_strogatz(20, 4, 0.5, 1));
+        assert_eq!(rewired.edge_count(), 40);
+        assert!((0..rewired.node_count()).any(|v| rewired.degree(v) != 4));
+    }
+    // Tests that double edge swaps keep every degree and the edge count but change the edges.
+    #[test]
+    fn test_degree_preserving_rewire() {
+        let graph = Graph::from_edges(&barabasi_albert(60, 2, 3));
+        let rewired_edges = degree_preserving_rewire(&graph, 10, 3);
+        let rewired = Graph::from_edges(&rewired_edges);
+
+        assert_eq!(rewired.edge_count(), graph.edge_count());
+        assert!(rewired_edges.iter().all(|&(u, v)| u != v));
+        for v in 0..graph.node_count() {
+            let id = graph.node_id(v);
+            assert_eq!(rewired.degree(rewired.index_of(id).unwrap()), graph.degree(v));
+        }
+        let before: HashSet<(usize, usize)> = barabasi_albert(60, 2, 3).into_iter().collect();
+        assert!(rewired_edges.iter().filter(|e| !before.contains(e)).count() > graph.edge_count() / 2);
+    }
+}
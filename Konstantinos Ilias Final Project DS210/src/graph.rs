@@ -4,12 +4,28 @@
 
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::collections::{HashMap, HashSet, VecDeque};
+use crate::distance::{bfs_from, default_delta, delta_stepping, reset, UNREACHED};
+use crate::mapping::EmailIndex;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
 // Reads an edge list file and returns a vector of (node1, node2) tuples.
 // Its inputs are - `path`: path to the edge list file
 // Its outputs are - `Vec<(usize, usize)>`: list of undirected edges
 pub fn read_file(path: &str) -> Vec<(usize, usize)> {
+    read_weighted_file(path).into_iter().map(|(u, v, _)| (u, v)).collect()
+}
+
+// Reads an edge list whose lines may carry a third column with the edge weight (e.g. the
+// number of emails exchanged); edges without one get weight 1, and edges whose weight is not a
+// finite positive number are skipped with a warning.
+// Its inputs are - `path`: path to the edge list file
+// Its outputs are - `Vec<(usize, usize, f64)>`: list of (node1, node2, weight) edges
+pub fn read_weighted_file(path: &str) -> Vec<(usize, usize, f64)> {
     let mut result = Vec::new();
     let file = File::open(path).expect("Could not open file");
     let buf_reader = BufReader::new(file).lines();
@@ -17,13 +33,33 @@ pub fn read_file(path: &str) -> Vec<(usize, usize)> {
     for line in buf_reader.skip(4) {
         let line_str = line.expect("Error reading");
         let v: Vec<&str> = line_str.trim().split('\t').collect();
-        if v.len() == 2 {
-            if let (Ok(x), Ok(y)) = (v[0].parse(), v[1].parse()) {
-                result.push((x, y));
+        if v.len() == 2 || v.len() == 3 {
+            let weight = if v.len() == 3 { v[2].parse().ok() } else { Some(1.0) };
+            if let (Ok(x), Ok(y), Some(w)) = (v[0].parse(), v[1].parse(), weight) {
+                result.push((x, y, w));
             }
         }
     }
-    result
+    drop_invalid_weights(result, path)
+}
+
+// Whether an edge weight can be used. A path's length is the sum of 1 / weight, so a zero
+// weight gives an infinite distance, a negative one breaks the shortest paths and NaN poisons
+// every sum: only finite positive weights are valid.
+pub fn is_valid_weight(weight: f64) -> bool {
+    weight.is_finite() && weight > 0.0
+}
+
+// Drops the edges whose weight is not valid (see `is_valid_weight`), warning how many there were.
+// Its inputs are - `edges`: list of (u, v, weight) edges - `origin`: where they came from, for the warning
+// Its outputs are - `Vec<(usize, usize, f64)>`: the edges with a valid weight, in order
+pub fn drop_invalid_weights(mut edges: Vec<(usize, usize, f64)>, origin: &str) -> Vec<(usize, usize, f64)> {
+    let before = edges.len();
+    edges.retain(|&(_, _, w)| is_valid_weight(w));
+    if edges.len() < before {
+        eprintln!("Skipped {} edges of {} whose weight is not a finite positive number", before - edges.len(), origin);
+    }
+    edges
 }
 
 // Loads a CSV file mapping node IDs to email addresses and folders.
@@ -46,127 +82,1107 @@ pub fn load_email_mapping(path: &str) -> HashMap<usize, (String, String)> {
     map
 }
 
-/// Computes the degree centrality (number of connections) for each node.
+// Finds the node ID of an email address, ignoring case, by scanning the mapping; for
+// repeated lookups build an `EmailIndex` instead.
+// Its inputs are - `email_map`: node ID -> (email, folder) - `email`: address to look up
+// Its outputs are - `Option<usize>`: node ID, if the address is in the mapping
+pub fn find_node_by_email(email_map: &HashMap<usize, (String, String)>, email: &str) -> Option<usize> {
+    email_map
+        .iter()
+        .find(|(_, (address, _))| address.eq_ignore_ascii_case(email.trim()))
+        .map(|(&id, _)| id)
+}
+
+// Computes the strength centrality (sum of the weights of incident edges) for each node.
+// With unit weights and each edge listed once, it equals the degree of `compute_degree`.
+// Its inputs are - `edges`: list of weighted graph edges
+// Its outputs are - `HashMap<usize, f64>`: node ID -> strength
+pub fn compute_strength(edges: &[(usize, usize, f64)]) -> HashMap<usize, f64> {
+    let mut strengths = HashMap::new();
+    for &(u, v, w) in edges {
+        *strengths.entry(u).or_insert(0.0) += w;
+        *strengths.entry(v).or_insert(0.0) += w;
+    }
+    strengths
+}
+
+// How the activity of a node is measured in the degree report and the clustering features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DegreeMeasure {
+    // Number of incident edges
+    Degree,
+    // Sum of incident edge weights
+    Strength,
+}
+
+impl std::fmt::Display for DegreeMeasure {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DegreeMeasure::Degree => write!(f, "degree"),
+            DegreeMeasure::Strength => write!(f, "strength"),
+        }
+    }
+}
+
+// Number of incoming and outgoing edges of a node in a directed graph.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InOutDegree {
+    pub in_deg: usize,
+    pub out_deg: usize,
+    // Undirected degree: the other nodes linked either way, each counted once
+    pub degree: usize,
+}
+
+/// Computes the degree centrality (number of connections) for each node, split into in-degree
+// (emails received from) and out-degree (emails sent to) by treating every (u, v) edge as directed
+// from u to v. Like `Graph::from_edges`, it counts a repeated edge once and skips self loops, so
+// an edge the SNAP file lists in both directions adds 1 to the degree, not 2.
 // Its inputs are - `edges`: list of graph edges
-// Its outputs are - `HashMap<usize, usize>`: node ID -> degree
-pub fn compute_degree(edges: &[(usize, usize)]) -> HashMap<usize, usize> {
-    let mut degrees = HashMap::new();
+// Its outputs are - `HashMap<usize, InOutDegree>`: node ID -> in/out and undirected degree
+pub fn compute_degree(edges: &[(usize, usize)]) -> HashMap<usize, InOutDegree> {
+    let mut degrees: HashMap<usize, InOutDegree> = HashMap::new();
+    let mut arcs = HashSet::new();
+    let mut links = HashSet::new();
     for &(u, v) in edges {
-        *degrees.entry(u).or_insert(0) += 1;
-        *degrees.entry(v).or_insert(0) += 1;
+        if u == v {
+            continue;
+        }
+        if arcs.insert((u, v)) {
+            degrees.entry(u).or_default().out_deg += 1;
+            degrees.entry(v).or_default().in_deg += 1;
+        }
+        if links.insert((u.min(v), u.max(v))) {
+            degrees.entry(u).or_default().degree += 1;
+            degrees.entry(v).or_default().degree += 1;
+        }
     }
     degrees
 }
 
-// Computes closeness centrality for a set of nodes.
+// How closeness is defined on a disconnected graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClosenessMode {
+    // (reached nodes - 1) / sum of distances, within the node's own component, so a member of
+    // a tiny component can outscore a hub of the giant one
+    Standard,
+    // Wasserman & Faust: the standard score times the fraction of the other nodes reached,
+    // (reached - 1) / (n - 1), which makes scores comparable across components
+    WassermanFaust,
+}
+
+impl std::fmt::Display for ClosenessMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ClosenessMode::Standard => write!(f, "standard"),
+            ClosenessMode::WassermanFaust => write!(f, "Wasserman-Faust"),
+        }
+    }
+}
+
+// Computes closeness centrality for a set of nodes, in parallel on all cores.
 // Its Inputs are - `edges`: list of edges - `nodes`: node IDs to compute closeness for each of them
+// - `mode`: standard or component-adjusted (Wasserman-Faust) closeness
 // Its outputs are - `HashMap<usize, f64>`: node ID -> closeness centrality
-pub fn compute_closeness(edges: &[(usize, usize)], nodes: &HashSet<usize>) -> HashMap<usize, f64> {
-    let mut graph: HashMap<usize, Vec<usize>> = HashMap::new();
-    for &(u, v) in edges {
-        graph.entry(u).or_default().push(v);
-        graph.entry(v).or_default().push(u);
+pub fn compute_closeness(edges: &[(usize, usize)], nodes: &HashSet<usize>, mode: ClosenessMode) -> HashMap<usize, f64> {
+    let graph = Graph::from_edges(edges).reordered(NodeOrder::Bfs);
+    let mut closeness = parallel_closeness(&graph, nodes, 0);
+    adjust_closeness(&graph, &mut closeness, mode);
+    closeness
+}
+
+// Converts standard closeness scores, from any of the closeness functions, to the chosen
+// mode. Every node reaches exactly its own component, so the Wasserman-Faust factor only
+// needs the component sizes.
+// Its inputs are - `graph`: the graph the scores were computed on - `closeness`: node ID ->
+// standard closeness, scaled in place - `mode`: the closeness mode wanted
+pub fn adjust_closeness(graph: &Graph, closeness: &mut HashMap<usize, f64>, mode: ClosenessMode) {
+    let n = graph.node_count();
+    if mode == ClosenessMode::Standard || n < 2 {
+        return;
+    }
+    let mut reached = vec![0; n];
+    for component in graph.components() {
+        for &v in &component {
+            reached[v] = component.len();
+        }
+    }
+    for (node, score) in closeness.iter_mut() {
+        let size = graph.index_of(*node).map_or(1, |v| reached[v]);
+        *score *= (size - 1) as f64 / (n - 1) as f64;
+    }
+}
+
+// Computes closeness centrality with one independent BFS per source, spread over a rayon pool.
+// Each worker reuses a dense distance vector that is reset after every source, so computing
+// closeness for every node of the Enron graph takes seconds rather than minutes.
+// Its Inputs are - `graph`: the graph - `nodes`: node IDs to compute closeness for
+// - `threads`: worker threads (0 uses all cores)
+// Its outputs are - `HashMap<usize, f64>`: node ID -> closeness centrality (0 for nodes that reach nobody)
+pub fn parallel_closeness(graph: &Graph, nodes: &HashSet<usize>, threads: usize) -> HashMap<usize, f64> {
+    let sources: Vec<usize> = nodes.iter().copied().collect();
+    let run = || {
+        sources
+            .par_iter()
+            .map_init(
+                || (vec![UNREACHED; graph.node_count()], Vec::new()),
+                |(dist, visited), &start| {
+                    // Nodes without edges reach nobody
+                    let Some(source) = graph.index_of(start) else {
+                        return (start, 0.0);
+                    };
+
+                    // BFS to calculate shortest paths from start node
+                    bfs_from(graph, source, dist, visited);
+
+                    // Closeness = (number of reachable nodes) / (sum of distances)
+                    let total_distance: usize = visited.iter().map(|&v| dist[v]).sum();
+                    let score = if total_distance > 0 {
+                        (visited.len() - 1) as f64 / total_distance as f64
+                    } else {
+                        0.0
+                    };
+                    reset(dist, visited);
+                    (start, score)
+                },
+            )
+            .collect()
+    };
+    match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+        Ok(pool) => pool.install(run),
+        Err(_) => run(),
     }
+}
+
+// Shortest-path DAG from one source, as built by the BFS phase of Brandes' algorithm, in
+// dense vectors over compact indices. `order` lists the reached nodes by nondecreasing
+// distance and `sigma[v]` counts the shortest paths from the source to v. Predecessors are
+// not stored: they are the neighbors one step closer to the source. The buffers are reused
+// across sources, clearing only the entries the previous search touched.
+#[derive(Debug, Clone)]
+pub struct ShortestPathDag {
+    pub order: Vec<usize>,
+    pub dist: Vec<usize>,
+    pub sigma: Vec<f64>,
+}
 
-    let mut closeness = HashMap::new();
+impl ShortestPathDag {
+    // Creates empty buffers for a graph with `n` nodes.
+    pub fn new(n: usize) -> ShortestPathDag {
+        ShortestPathDag { order: Vec::new(), dist: vec![UNREACHED; n], sigma: vec![0.0; n] }
+    }
 
-    for &start in nodes {
-        let mut visited = HashMap::new();
-        let mut queue = VecDeque::new();
-        visited.insert(start, 0);
-        queue.push_back(start);
+    // Builds the DAG from a source by BFS, counting shortest paths.
+    // Its inputs are - `graph`: the graph - `source`: compact index of the start node
+    pub fn search(&mut self, graph: &Graph, source: usize) {
+        for &v in &self.order {
+            self.dist[v] = UNREACHED;
+            self.sigma[v] = 0.0;
+        }
+        self.order.clear();
+        self.dist[source] = 0;
+        self.sigma[source] = 1.0;
+        self.order.push(source);
 
-        // BFS to calculate shortest paths from start node
-        while let Some(node) = queue.pop_front() {
-            let dist = visited[&node];
-            for &nbr in graph.get(&node).unwrap_or(&vec![]) {
-                if !visited.contains_key(&nbr) {
-                    visited.insert(nbr, dist + 1);
-                    queue.push_back(nbr);
+        let mut head = 0;
+        while head < self.order.len() {
+            let v = self.order[head];
+            head += 1;
+            let d = self.dist[v];
+            for &w in graph.neighbors(v) {
+                if self.dist[w] == UNREACHED {
+                    self.dist[w] = d + 1;
+                    self.order.push(w);
+                }
+                if self.dist[w] == d + 1 {
+                    self.sigma[w] += self.sigma[v];
                 }
             }
         }
+    }
 
-        // Closeness = (number of reachable nodes) / (sum of distances)
-        let total_distance: usize = visited.values().sum();
-        let score = if total_distance > 0 {
-            (visited.len() - 1) as f64 / total_distance as f64
-        } else {
-            0.0
-        };
-        closeness.insert(start, score);
+    // Predecessors of a reached node on its shortest paths from the source.
+    pub fn predecessors<'a>(&'a self, graph: &'a Graph, v: usize) -> impl Iterator<Item = usize> + 'a {
+        graph.neighbors(v).iter().copied().filter(move |&w| self.dist[w] != UNREACHED && self.dist[w] + 1 == self.dist[v])
     }
 
-    closeness
+    // Accumulates the dependency of the source on every reached node (Brandes' second phase):
+    // delta[v] = sum over successors w of sigma[v] / sigma[w] * (1 + delta[w]).
+    // Its inputs are - `graph`: the graph searched - `delta`: buffer of length n; entries of
+    // reached nodes are overwritten, the others are left untouched
+    pub fn dependencies(&self, graph: &Graph, delta: &mut [f64]) {
+        for &v in &self.order {
+            delta[v] = 0.0;
+        }
+        for &v in self.order.iter().rev() {
+            let coeff = (1.0 + delta[v]) / self.sigma[v];
+            for p in self.predecessors(graph, v) {
+                delta[p] += self.sigma[p] * coeff;
+            }
+        }
+    }
+}
+
+// Weighted graphs with at least this many nodes find shortest paths by delta-stepping, whose
+// parallel phases only pay off once the frontiers are large.
+pub const DELTA_STEPPING_NODES: usize = 10_000;
+
+// Relative tolerance under which two weighted path lengths count as equal, so paths whose
+// lengths differ only by floating-point rounding are all counted as shortest.
+const LENGTH_TOLERANCE: f64 = 1e-9;
+
+fn same_length(a: f64, b: f64) -> bool {
+    (a - b).abs() <= LENGTH_TOLERANCE * a.abs().max(b.abs()).max(1.0)
+}
+
+// Weighted counterpart of `ShortestPathDag`, built by Dijkstra's algorithm with a binary
+// heap. An edge of weight w has length 1 / w, so heavier (more intense) ties are shorter.
+// `order` lists the reached nodes by nondecreasing distance.
+#[derive(Debug, Clone)]
+pub struct WeightedShortestPathDag {
+    pub order: Vec<usize>,
+    pub dist: Vec<f64>,
+    pub sigma: Vec<f64>,
+    settled: Vec<bool>,
+}
+
+impl WeightedShortestPathDag {
+    // Creates empty buffers for a graph with `n` nodes.
+    pub fn new(n: usize) -> WeightedShortestPathDag {
+        WeightedShortestPathDag { order: Vec::new(), dist: vec![f64::INFINITY; n], sigma: vec![0.0; n], settled: vec![false; n] }
+    }
+
+    // Clears the entries touched by the previous search.
+    fn clear(&mut self) {
+        for &v in &self.order {
+            self.dist[v] = f64::INFINITY;
+            self.sigma[v] = 0.0;
+            self.settled[v] = false;
+        }
+        self.order.clear();
+    }
+
+    // Builds the DAG from a source by Dijkstra's algorithm, counting shortest paths.
+    // Its inputs are - `graph`: the graph - `source`: compact index of the start node
+    pub fn search(&mut self, graph: &Graph, source: usize) {
+        self.clear();
+        self.dist[source] = 0.0;
+        self.sigma[source] = 1.0;
+
+        // Distances are non-negative, so their bit patterns sort like the values
+        let mut heap = BinaryHeap::from([Reverse((0f64.to_bits(), source))]);
+        while let Some(Reverse((bits, v))) = heap.pop() {
+            if self.settled[v] || f64::from_bits(bits) > self.dist[v] {
+                continue;
+            }
+            self.settled[v] = true;
+            self.order.push(v);
+            let d = self.dist[v];
+            for (w, weight) in graph.weighted_neighbors(v) {
+                if self.settled[w] {
+                    continue;
+                }
+                let alt = d + 1.0 / weight;
+                if self.dist[w].is_finite() && same_length(alt, self.dist[w]) {
+                    self.sigma[w] += self.sigma[v];
+                } else if alt < self.dist[w] {
+                    self.dist[w] = alt;
+                    self.sigma[w] = self.sigma[v];
+                    heap.push(Reverse((alt.to_bits(), w)));
+                }
+            }
+        }
+    }
+
+    // Builds the same DAG from distances found by parallel delta-stepping, then counts the
+    // shortest paths in order of distance from the predecessors of each node.
+    // Its inputs are - `graph`: the graph - `source`: compact index of the start node
+    // - `delta`: bucket width of delta-stepping
+    pub fn search_delta_stepping(&mut self, graph: &Graph, source: usize, delta: f64) {
+        self.clear();
+        let dist = delta_stepping(graph, source, delta);
+        self.order.extend((0..dist.len()).filter(|&v| dist[v].is_finite()));
+        self.order.sort_by(|&a, &b| dist[a].total_cmp(&dist[b]).then(a.cmp(&b)));
+        for &v in &self.order {
+            self.dist[v] = dist[v];
+            self.settled[v] = true;
+        }
+        self.sigma[source] = 1.0;
+        for k in 1..self.order.len() {
+            let v = self.order[k];
+            self.sigma[v] = self.predecessors(graph, v).map(|p| self.sigma[p]).sum();
+        }
+    }
+
+    // Predecessors of a reached node on its shortest paths from the source.
+    pub fn predecessors<'a>(&'a self, graph: &'a Graph, v: usize) -> impl Iterator<Item = usize> + 'a {
+        graph
+            .weighted_neighbors(v)
+            .filter(move |&(w, weight)| w != v && self.settled[w] && self.dist[w] < self.dist[v] && same_length(self.dist[w] + 1.0 / weight, self.dist[v]))
+            .map(|(w, _)| w)
+    }
+
+    // Accumulates the dependency of the source on every reached node, as in
+    // `ShortestPathDag::dependencies`.
+    pub fn dependencies(&self, graph: &Graph, delta: &mut [f64]) {
+        for &v in &self.order {
+            delta[v] = 0.0;
+        }
+        for &v in self.order.iter().rev() {
+            let coeff = (1.0 + delta[v]) / self.sigma[v];
+            for p in self.predecessors(graph, v) {
+                delta[p] += self.sigma[p] * coeff;
+            }
+        }
+    }
+}
+
+// How raw betweenness (summed pair dependencies) is scaled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BetweennessNormalization {
+    // Raw sums of pair dependencies over the sources
+    None,
+    // Divided by the largest score, so the top node has 1 (not comparable across graphs)
+    Max,
+    // Fraction of pairs of other nodes whose shortest paths run through the node,
+    // 2 / ((n - 1)(n - 2)) per unordered pair, extrapolated from the sources to all n nodes
+    Pairs,
+    // Min-max rescaled to [0, 1]
+    Rescaled,
+}
+
+impl std::fmt::Display for BetweennessNormalization {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BetweennessNormalization::None => write!(f, "none"),
+            BetweennessNormalization::Max => write!(f, "max"),
+            BetweennessNormalization::Pairs => write!(f, "pairs"),
+            BetweennessNormalization::Rescaled => write!(f, "rescaled"),
+        }
+    }
 }
 
 // Computes betweenness centrality for a set of nodes using Brandes' algorithm.
+// Scores are kept in dense vectors over compact indices; every node reached from at least one
+// source gets an entry. The graph is relabeled in BFS order first, which roughly halves the
+// time on the Enron graph (see `examples/reorder_benchmark.rs`).
 // Its inputs are - `edges`: list of edges - `nodes`: node IDs to compute betweenness for
 // Its outputs are - `HashMap<usize, f64>`: node ID -> normalized betweenness score
 pub fn compute_betweenness(edges: &[(usize, usize)], nodes: &HashSet<usize>) -> HashMap<usize, f64> {
-    let mut graph: HashMap<usize, Vec<usize>> = HashMap::new();
-    for &(u, v) in edges {
-        graph.entry(u).or_default().push(v);
-        graph.entry(v).or_default().push(u);
-    }
-
-    let mut centrality = HashMap::new();
-    for &s in nodes {
-        let mut stack = Vec::new();
-        let mut pred: HashMap<usize, Vec<usize>> = HashMap::new();
-        let mut sigma = HashMap::new();
-        let mut dist = HashMap::new();
-        let mut queue = VecDeque::new();
-
-        for &v in graph.keys() {
-            pred.insert(v, Vec::new());
-            sigma.insert(v, 0.0);
-        }
-
-        // Initializing
-        sigma.insert(s, 1.0);
-        dist.insert(s, 0);
-        queue.push_back(s);
-
-        // BFS to find shortest paths
-        while let Some(v) = queue.pop_front() {
-            stack.push(v);
-            let d = dist[&v];
-            for &w in &graph[&v] {
-                if !dist.contains_key(&w) {
-                    dist.insert(w, d + 1);
-                    queue.push_back(w);
+    brandes_betweenness(&Graph::from_edges(edges).reordered(NodeOrder::Bfs), nodes, BetweennessNormalization::Max)
+}
+
+// Computes the same betweenness as `compute_betweenness` on an already built graph, so the
+// graph can be preprocessed first (for example reordered for locality), with a choice of scaling.
+// On a weighted graph, shortest paths are found by Dijkstra with edge lengths 1 / weight, so
+// betweenness follows the most intense channels of communication rather than the fewest hops;
+// graphs of at least `DELTA_STEPPING_NODES` nodes use parallel delta-stepping instead.
+// Sources are searched in node ID order, so the scores are summed in the same order (and come
+// out bit for bit the same) however the set was built.
+// Its inputs are - `graph`: the graph - `nodes`: node IDs to use as sources
+// - `normalization`: how the raw scores are scaled
+// Its outputs are - `HashMap<usize, f64>`: node ID -> betweenness score
+pub fn brandes_betweenness(graph: &Graph, nodes: &HashSet<usize>, normalization: BetweennessNormalization) -> HashMap<usize, f64> {
+    let n = graph.node_count();
+    let mut scores = vec![0.0; n];
+    let mut touched = vec![false; n];
+    let mut ids: Vec<usize> = nodes.iter().copied().collect();
+    ids.sort_unstable();
+    let sources: Vec<usize> = ids.into_iter().filter_map(|id| graph.index_of(id)).collect();
+    accumulate_betweenness(graph, &sources, &mut scores, &mut touched, |_, _, _| {});
+    normalize_betweenness(graph, &scores, &touched, sources.len(), normalization)
+}
+
+// Runs Brandes' searches from the sources in order, adding each source's dependencies to the
+// raw scores, and calls `after_source` with the number of sources done so far, so that a caller
+// can report progress or save the partial scores and later continue from the next source.
+// Its inputs are - `graph`: the graph - `sources`: compact indices of the sources
+// - `scores`: raw score per compact index, added to - `touched`: set for every node reached
+// - `after_source`: called after each source with (sources done, scores, touched)
+pub fn accumulate_betweenness(
+    graph: &Graph,
+    sources: &[usize],
+    scores: &mut [f64],
+    touched: &mut [bool],
+    mut after_source: impl FnMut(usize, &[f64], &[bool]),
+) {
+    let n = graph.node_count();
+    let mut delta = vec![0.0; n];
+    let accumulate = |order: &[usize], delta: &[f64], scores: &mut [f64], touched: &mut [bool]| {
+        for &v in &order[1..] {
+            scores[v] += delta[v];
+            touched[v] = true;
+        }
+    };
+    if graph.is_weighted() {
+        let mut dag = WeightedShortestPathDag::new(n);
+        let width = default_delta(graph);
+        for (done, &s) in sources.iter().enumerate() {
+            if n >= DELTA_STEPPING_NODES {
+                dag.search_delta_stepping(graph, s, width);
+            } else {
+                dag.search(graph, s);
+            }
+            dag.dependencies(graph, &mut delta);
+            accumulate(&dag.order, &delta, scores, touched);
+            after_source(done + 1, scores, touched);
+        }
+    } else {
+        let mut dag = ShortestPathDag::new(n);
+        for (done, &s) in sources.iter().enumerate() {
+            // BFS to find shortest paths
+            dag.search(graph, s);
+
+            // Accumulating the dependencies
+            dag.dependencies(graph, &mut delta);
+            accumulate(&dag.order, &delta, scores, touched);
+            after_source(done + 1, scores, touched);
+        }
+    }
+}
+
+// Scales raw betweenness scores from `accumulate_betweenness`.
+// Its inputs are - `graph`: the graph - `scores`, `touched`: raw scores and reached nodes per
+// compact index - `sources`: number of sources searched - `normalization`: how to scale
+// Its outputs are - `HashMap<usize, f64>`: node ID -> betweenness score, for every reached node
+pub fn normalize_betweenness(
+    graph: &Graph,
+    scores: &[f64],
+    touched: &[bool],
+    sources: usize,
+    normalization: BetweennessNormalization,
+) -> HashMap<usize, f64> {
+    let n = graph.node_count();
+    let mut centrality: HashMap<usize, f64> =
+        (0..n).filter(|&v| touched[v]).map(|v| (graph.node_id(v), scores[v])).collect();
+
+    // Normalizing betweenness scores
+    let max_val = centrality.values().cloned().fold(0.0, f64::max);
+    let min_val = centrality.values().cloned().fold(f64::INFINITY, f64::min);
+    let (offset, scale) = match normalization {
+        BetweennessNormalization::None => (0.0, 1.0),
+        BetweennessNormalization::Max => (0.0, if max_val > 0.0 { 1.0 / max_val } else { 1.0 }),
+        // Every unordered pair is counted once from each end when all nodes are sources
+        BetweennessNormalization::Pairs if n > 2 && sources > 0 => {
+            (0.0, n as f64 / sources as f64 / ((n - 1) * (n - 2)) as f64)
+        }
+        BetweennessNormalization::Pairs => (0.0, 1.0),
+        BetweennessNormalization::Rescaled if max_val > min_val => (min_val, 1.0 / (max_val - min_val)),
+        BetweennessNormalization::Rescaled => (min_val, 1.0),
+    };
+    for val in centrality.values_mut() {
+        *val = (*val - offset) * scale;
+    }
+
+    centrality
+}
+
+// Settings of the progressive top-k betweenness search.
+#[derive(Debug, Clone)]
+pub struct TopKBetweennessConfig {
+    // Number of top nodes to identify
+    pub k: usize,
+    // Sources in the first round; each later round doubles the total
+    pub initial_samples: usize,
+    // Stop after this many sources even if the top k are not yet separated
+    pub max_samples: usize,
+    // Width of the confidence intervals in standard errors (2.58 for about 99%)
+    pub z: f64,
+    // Accepted overlap between the top k and the rest, relative to the k-th score. Near-ties
+    // at the cut-off cannot be separated by sampling, so 0 often means searching from every node.
+    pub tolerance: f64,
+    pub seed: u64,
+}
+
+impl Default for TopKBetweennessConfig {
+    fn default() -> Self {
+        TopKBetweennessConfig { k: 10, initial_samples: 64, max_samples: usize::MAX, z: 2.58, tolerance: 0.2, seed: 42 }
+    }
+}
+
+// Result of `top_k_betweenness`.
+#[derive(Debug, Clone)]
+pub struct TopKBetweenness {
+    // (node ID, estimated raw betweenness), highest first
+    pub top: Vec<(usize, f64)>,
+    // Number of sources searched
+    pub samples: usize,
+    // Whether the confidence intervals of the top k cleared those of every other node (within the tolerance)
+    pub separated: bool,
+}
+
+// Finds the k nodes with the highest betweenness without searching from every source.
+// Sources are drawn at random without replacement in rounds of doubling size; each node's
+// betweenness is estimated as n times its mean dependency, with a confidence interval of
+// z standard errors (with the finite-population correction, so intervals shrink to zero
+// once every node has been a source). Sampling stops as soon as the lowest lower bound in
+// the top k, plus the tolerance, exceeds the highest upper bound outside it: then no
+// excluded node can beat a returned one by more than `tolerance` times the k-th score.
+// On the Enron graph the default finds the top 10 from about a tenth of the sources.
+// Its inputs are - `graph`: the graph - `config`: k, sample sizes, interval width and seed
+// Its outputs are - `TopKBetweenness`: the top k with estimates, samples used and whether they separated
+pub fn top_k_betweenness(graph: &Graph, config: &TopKBetweennessConfig) -> TopKBetweenness {
+    let n = graph.node_count();
+    let k = config.k.min(n);
+    if k == 0 {
+        return TopKBetweenness { top: Vec::new(), samples: 0, separated: true };
+    }
+    let mut sources: Vec<usize> = (0..n).collect();
+    sources.shuffle(&mut StdRng::seed_from_u64(config.seed));
+    // At least one source, so every estimate is a mean over some searches
+    let limit = config.max_samples.clamp(1, n);
+
+    // Weighted graphs are searched by Dijkstra (or delta-stepping), as in `brandes_betweenness`
+    let mut dag = ShortestPathDag::new(n);
+    let mut weighted_dag = graph.is_weighted().then(|| WeightedShortestPathDag::new(n));
+    let width = default_delta(graph);
+    let mut delta = vec![0.0; n];
+    let mut sums = vec![0.0; n];
+    let mut squares = vec![0.0; n];
+    let mut samples = 0;
+    let mut target = config.initial_samples.max(1).min(limit);
+
+    loop {
+        for &s in &sources[samples..target] {
+            let order = match weighted_dag.as_mut() {
+                Some(weighted) => {
+                    if n >= DELTA_STEPPING_NODES {
+                        weighted.search_delta_stepping(graph, s, width);
+                    } else {
+                        weighted.search(graph, s);
+                    }
+                    weighted.dependencies(graph, &mut delta);
+                    &weighted.order
                 }
-                if dist[&w] == d + 1 {
-                    sigma.insert(w, sigma[&w] + sigma[&v]);
-                    pred.get_mut(&w).unwrap().push(v);
+                None => {
+                    dag.search(graph, s);
+                    dag.dependencies(graph, &mut delta);
+                    &dag.order
                 }
+            };
+            for &v in &order[1..] {
+                sums[v] += delta[v];
+                squares[v] += delta[v] * delta[v];
             }
         }
+        samples = target;
+
+        // Mean dependency per source and its confidence interval, for every node
+        let t = samples as f64;
+        let correction = if n > 1 { ((n - samples) as f64 / (n - 1) as f64).sqrt() } else { 0.0 };
+        let mut ranked: Vec<(usize, f64, f64)> = (0..n)
+            .map(|v| {
+                let mean = sums[v] / t;
+                let variance = (squares[v] / t - mean * mean).max(0.0);
+                (v, mean, config.z * (variance / t).sqrt() * correction)
+            })
+            .collect();
+        // Ties go to the lower node ID, so they do not depend on how the graph was relabeled
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(graph.node_id(a.0).cmp(&graph.node_id(b.0))));
+        let lowest_top = ranked[..k].iter().map(|&(_, mean, half)| mean - half).fold(f64::INFINITY, f64::min);
+        let highest_rest = ranked[k..].iter().map(|&(_, mean, half)| mean + half).fold(f64::NEG_INFINITY, f64::max);
+        let separated = lowest_top + config.tolerance * ranked[k - 1].1 > highest_rest;
+
+        if separated || samples >= limit {
+            let top = ranked[..k].iter().map(|&(v, mean, _)| (graph.node_id(v), mean * n as f64)).collect();
+            return TopKBetweenness { top, samples, separated };
+        }
+        target = (2 * samples).min(limit);
+    }
+}
+
+// Read-only view of an unweighted graph over compact indices 0..n. Traversals written against
+// it run unchanged on a `Graph` or on a `CompressedGraph`.
+pub trait Adjacency: Sync {
+    type Neighbors<'a>: Iterator<Item = usize>
+    where
+        Self: 'a;
+
+    fn node_count(&self) -> usize;
+
+    // Original node ID of compact index `i`.
+    fn node_id(&self, i: usize) -> usize;
+
+    // Compact index of an original node ID, if the node is in the graph.
+    fn index_of(&self, id: usize) -> Option<usize>;
+
+    fn degree(&self, i: usize) -> usize;
+
+    // Compact indices of the neighbors of compact index `i`, ascending.
+    fn neighbor_iter(&self, i: usize) -> Self::Neighbors<'_>;
+}
+
+// Undirected simple graph built from an edge list.
+// Nodes are stored under compact indices 0..n so algorithms can use dense vectors,
+// `ids` maps a compact index back to the original node ID and `index` does the reverse.
+// `weights[i][k]` is the weight of the edge to `adj[i][k]`; it is empty for unweighted graphs.
+pub struct Graph {
+    ids: Vec<usize>,
+    index: HashMap<usize, usize>,
+    adj: Vec<Vec<usize>>,
+    weights: Vec<Vec<f64>>,
+}
+
+// Order in which `Graph::reordered` relabels the compact indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeOrder {
+    // Breadth-first from the highest-degree node of each component, so neighbors get nearby indices
+    Bfs,
+    // By decreasing degree, so the hubs most traversals touch share cache lines
+    Degree,
+}
 
-        // Accumulating the dependencies
-        let mut delta = HashMap::new();
-        for &v in stack.iter().rev() {
-            let coeff = (1.0 + *delta.get(&v).unwrap_or(&0.0)) / sigma[&v];
-            for &p in &pred[&v] {
-                let contrib = sigma[&p] * coeff;
-                *delta.entry(p).or_insert(0.0) += contrib;
+impl std::fmt::Display for NodeOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            NodeOrder::Bfs => write!(f, "BFS"),
+            NodeOrder::Degree => write!(f, "degree"),
+        }
+    }
+}
+
+// Basic statistics describing the whole network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphSummary {
+    pub nodes: usize,
+    pub edges: usize,
+    pub density: f64,
+    pub avg_degree: f64,
+    pub max_degree: usize,
+    pub components: usize,
+    pub giant_component: usize,
+}
+
+impl Graph {
+    // Builds a graph from an edge list, dropping self loops and duplicate edges
+    // (the SNAP file stores every edge in both directions).
+    // Its inputs are - `edges`: list of (u, v) graph edges
+    // Its outputs are - `Graph`: the undirected graph
+    pub fn from_edges(edges: &[(usize, usize)]) -> Graph {
+        let mut graph = Graph { ids: Vec::new(), index: HashMap::new(), adj: Vec::new(), weights: Vec::new() };
+        for &(u, v) in edges {
+            if u == v {
+                continue;
             }
-            if v != s {
-                *centrality.entry(v).or_insert(0.0) += delta.get(&v).unwrap_or(&0.0);
+            let a = graph.intern(u);
+            let b = graph.intern(v);
+            graph.adj[a].push(b);
+            graph.adj[b].push(a);
+        }
+        for nbrs in graph.adj.iter_mut() {
+            nbrs.sort_unstable();
+            nbrs.dedup();
+        }
+        graph
+    }
+
+    // Builds a weighted graph from an edge list, adding up the weights of duplicate edges
+    // (e.g. the two directions of a pair). If every weight is 1 the graph is left unweighted.
+    // Weights must be positive: they measure tie strength, and a path's length is the sum of
+    // 1 / weight over its edges. Edges with an invalid weight (see `is_valid_weight`) are left
+    // out with a warning.
+    // Its inputs are - `edges`: list of (u, v, weight) graph edges
+    // Its outputs are - `Graph`: the undirected graph, weighted unless all weights are 1
+    pub fn from_weighted_edges(edges: &[(usize, usize, f64)]) -> Graph {
+        if !edges.iter().all(|&(_, _, w)| is_valid_weight(w)) {
+            return Graph::from_weighted_edges(&drop_invalid_weights(edges.to_vec(), "the weighted edge list"));
+        }
+        let pairs: Vec<(usize, usize)> = edges.iter().map(|&(u, v, _)| (u, v)).collect();
+        let mut graph = Graph::from_edges(&pairs);
+        if edges.iter().all(|&(_, _, w)| w == 1.0) {
+            return graph;
+        }
+        let mut total: HashMap<(usize, usize), f64> = HashMap::new();
+        for &(u, v, w) in edges.iter().filter(|&&(u, v, _)| u != v) {
+            let (a, b) = (graph.index[&u], graph.index[&v]);
+            *total.entry((a.min(b), a.max(b))).or_insert(0.0) += w;
+        }
+        graph.weights = graph
+            .adj
+            .iter()
+            .enumerate()
+            .map(|(a, nbrs)| nbrs.iter().map(|&b| total[&(a.min(b), a.max(b))]).collect())
+            .collect();
+        graph
+    }
+
+    // Returns the compact index of a node ID, adding the node if it is new.
+    fn intern(&mut self, id: usize) -> usize {
+        if let Some(&i) = self.index.get(&id) {
+            return i;
+        }
+        let i = self.ids.len();
+        self.ids.push(id);
+        self.index.insert(id, i);
+        self.adj.push(Vec::new());
+        if self.is_weighted() {
+            self.weights.push(Vec::new());
+        }
+        i
+    }
+
+    // Adds a node without edges, if it is new.
+    // Its inputs are - `id`: node ID
+    // Its outputs are - `usize`: compact index of the node
+    pub fn add_node(&mut self, id: usize) -> usize {
+        self.intern(id)
+    }
+
+    // Adds the edge between two node IDs, adding nodes that are new. In a weighted graph the
+    // new edge gets weight 1. Neighbor lists stay sorted, so this takes O(degree) time.
+    // Its inputs are - `u`, `v`: node IDs of the endpoints
+    // Its outputs are - `bool`: false if the edge already existed or is a self loop
+    pub fn add_edge(&mut self, u: usize, v: usize) -> bool {
+        if u == v || self.index_of(u).zip(self.index_of(v)).is_some_and(|(a, b)| self.adj[a].binary_search(&b).is_ok()) {
+            return false;
+        }
+        let (a, b) = (self.intern(u), self.intern(v));
+        for (x, y) in [(a, b), (b, a)] {
+            let k = self.adj[x].binary_search(&y).unwrap_err();
+            self.adj[x].insert(k, y);
+            if let Some(ws) = self.weights.get_mut(x) {
+                ws.insert(k, 1.0);
             }
         }
+        true
     }
 
-    // Normalizing betweenness scores
-    let max_val = centrality.values().cloned().fold(0.0, f64::max);
-    if max_val > 0.0 {
-        for val in centrality.values_mut() {
-            *val /= max_val;
+    // Removes the edge between two node IDs. Both nodes stay in the graph, even if isolated,
+    // so compact indices remain valid.
+    // Its inputs are - `u`, `v`: node IDs of the endpoints
+    // Its outputs are - `bool`: false if there was no such edge
+    pub fn remove_edge(&mut self, u: usize, v: usize) -> bool {
+        let Some((a, b)) = self.index_of(u).zip(self.index_of(v)) else {
+            return false;
+        };
+        let Ok(k) = self.adj[a].binary_search(&b) else {
+            return false;
+        };
+        for (x, k) in [(a, k), (b, self.adj[b].binary_search(&a).unwrap())] {
+            self.adj[x].remove(k);
+            if let Some(ws) = self.weights.get_mut(x) {
+                ws.remove(k);
+            }
         }
+        true
     }
 
-    centrality
+    pub fn node_count(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adj.iter().map(|n| n.len()).sum::<usize>() / 2
+    }
+
+    // Fingerprint of the graph's contents, independent of how its compact indices are ordered:
+    // the FNV-1a hash of every node ID with its degree and neighbor IDs (and edge weights), in
+    // ID order. Saved results carry it to tell whether they belong to the graph.
+    pub fn fingerprint(&self) -> u64 {
+        let mut order: Vec<usize> = (0..self.node_count()).collect();
+        order.sort_unstable_by_key(|&v| self.ids[v]);
+        fnv1a(order.into_iter().flat_map(|v| {
+            let mut nbrs: Vec<(usize, u64)> = self.weighted_neighbors(v).map(|(w, weight)| (self.ids[w], weight.to_bits())).collect();
+            nbrs.sort_unstable();
+            [self.ids[v] as u64, nbrs.len() as u64].into_iter().chain(nbrs.into_iter().flat_map(|(id, bits)| [id as u64, bits]))
+        }))
+    }
+
+    // Heap memory held by the graph, in bytes: the ID table, the ID index (estimated from its
+    // capacity) and the neighbor and weight lists.
+    pub fn heap_bytes(&self) -> usize {
+        fn lists<T>(lists: &Vec<Vec<T>>) -> usize {
+            lists.capacity() * std::mem::size_of::<Vec<T>>() + lists.iter().map(|l| l.capacity() * std::mem::size_of::<T>()).sum::<usize>()
+        }
+        let word = std::mem::size_of::<usize>();
+        self.ids.capacity() * word + self.index.capacity() * (2 * word + 1) * 8 / 7 + lists(&self.adj) + lists(&self.weights)
+    }
+
+    // Original node ID of compact index `i`.
+    pub fn node_id(&self, i: usize) -> usize {
+        self.ids[i]
+    }
+
+    // Compact index of an original node ID, if the node is in the graph.
+    pub fn index_of(&self, id: usize) -> Option<usize> {
+        self.index.get(&id).copied()
+    }
+
+    // Compact indices of the neighbors of compact index `i`, sorted ascending.
+    pub fn neighbors(&self, i: usize) -> &[usize] {
+        &self.adj[i]
+    }
+
+    pub fn degree(&self, i: usize) -> usize {
+        self.adj[i].len()
+    }
+
+    // Sum of the weights of the edges of compact index `i` (its degree if unweighted).
+    pub fn strength(&self, i: usize) -> f64 {
+        self.weighted_neighbors(i).map(|(_, weight)| weight).sum()
+    }
+
+    // Whether the edges carry weights (see `from_weighted_edges`).
+    pub fn is_weighted(&self) -> bool {
+        !self.weights.is_empty()
+    }
+
+    // Neighbors of compact index `i` with the weights of the edges to them (1 if unweighted).
+    pub fn weighted_neighbors(&self, i: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let weights = self.weights.get(i);
+        self.adj[i].iter().enumerate().map(move |(k, &w)| (w, weights.map_or(1.0, |ws| ws[k])))
+    }
+
+    // Lists every edge once, in original node IDs, as (u, v) with u's compact index below v's.
+    // Its outputs are - `Vec<(usize, usize)>`: list of undirected edges
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        (0..self.node_count())
+            .flat_map(|u| self.adj[u].iter().filter(move |&&v| v > u).map(move |&v| (self.ids[u], self.ids[v])))
+            .collect()
+    }
+
+    // Finds every node within `radius` hops of a source by a BFS that stops at that depth.
+    // Its inputs are - `source`: compact index of the start node - `radius`: maximum number of hops
+    // Its outputs are - `Vec<(usize, usize)>`: (compact index, distance) in BFS order, starting with (source, 0)
+    pub fn within_hops(&self, source: usize, radius: usize) -> Vec<(usize, usize)> {
+        let mut dist: HashMap<usize, usize> = HashMap::from([(source, 0)]);
+        let mut order = vec![(source, 0)];
+        let mut head = 0;
+        while head < order.len() {
+            let (curr, d) = order[head];
+            head += 1;
+            if d == radius {
+                continue;
+            }
+            for &nbr in &self.adj[curr] {
+                if let std::collections::hash_map::Entry::Vacant(e) = dist.entry(nbr) {
+                    e.insert(d + 1);
+                    order.push((nbr, d + 1));
+                }
+            }
+        }
+        order
+    }
+
+    // Computes a relabeling of the compact indices that improves memory locality.
+    // Its inputs are - `order`: BFS or degree order
+    // Its outputs are - `Vec<usize>`: old compact indices in their new order (ties by old index)
+    pub fn node_order(&self, order: NodeOrder) -> Vec<usize> {
+        let mut by_degree: Vec<usize> = (0..self.node_count()).collect();
+        by_degree.sort_by_key(|&v| (std::cmp::Reverse(self.degree(v)), v));
+        if order == NodeOrder::Degree {
+            return by_degree;
+        }
+        let mut visited = vec![false; self.node_count()];
+        let mut sequence = Vec::with_capacity(self.node_count());
+        for start in by_degree {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            let mut head = sequence.len();
+            sequence.push(start);
+            while head < sequence.len() {
+                let v = sequence[head];
+                head += 1;
+                for &w in &self.adj[v] {
+                    if !visited[w] {
+                        visited[w] = true;
+                        sequence.push(w);
+                    }
+                }
+            }
+        }
+        sequence
+    }
+
+    // Relabels the compact indices in BFS or degree order. Original node IDs are kept, so
+    // results keyed by node ID are unchanged; only traversals get faster.
+    // Its inputs are - `order`: BFS or degree order
+    // Its outputs are - `Graph`: the same graph with relabeled compact indices
+    pub fn reordered(&self, order: NodeOrder) -> Graph {
+        self.subgraph(&self.node_order(order))
+    }
+
+    // Extracts the subgraph induced by a set of nodes, keeping nodes that end up isolated.
+    // Its inputs are - `nodes`: compact indices of the nodes to keep
+    // Its outputs are - `Graph`: a new graph with its own compact indices, in the order of `nodes`
+    pub fn subgraph(&self, nodes: &[usize]) -> Graph {
+        let mut sub = Graph { ids: Vec::new(), index: HashMap::new(), adj: Vec::new(), weights: Vec::new() };
+        for &v in nodes {
+            sub.intern(self.ids[v]);
+        }
+        for (a, &v) in nodes.iter().enumerate() {
+            let mut nbrs: Vec<(usize, f64)> =
+                self.weighted_neighbors(v).filter_map(|(w, weight)| Some((sub.index_of(self.ids[w])?, weight))).collect();
+            nbrs.sort_unstable_by_key(|&(w, _)| w);
+            sub.adj[a] = nbrs.iter().map(|&(w, _)| w).collect();
+            if self.is_weighted() {
+                sub.weights.push(nbrs.iter().map(|&(_, weight)| weight).collect());
+            }
+        }
+        sub
+    }
+
+    // Finds connected components using BFS over compact indices.
+    // Its outputs are - `Vec<Vec<usize>>`: list of components, each a list of compact indices
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.node_count()];
+        let mut components = Vec::new();
+        for start in 0..self.node_count() {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            let mut component = vec![start];
+            let mut head = 0;
+            while head < component.len() {
+                let curr = component[head];
+                head += 1;
+                for &nbr in &self.adj[curr] {
+                    if !visited[nbr] {
+                        visited[nbr] = true;
+                        component.push(nbr);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    // Computes node count, edge count, density, average/max degree,
+    // number of connected components, and the size of the giant component.
+    // Its outputs are - `GraphSummary`: the collected statistics
+    pub fn summary(&self) -> GraphSummary {
+        let n = self.node_count();
+        let m = self.edge_count();
+        let density = if n > 1 { 2.0 * m as f64 / (n as f64 * (n - 1) as f64) } else { 0.0 };
+        let avg_degree = if n > 0 { 2.0 * m as f64 / n as f64 } else { 0.0 };
+        let max_degree = (0..n).map(|i| self.degree(i)).max().unwrap_or(0);
+        let components = self.components();
+        let giant_component = components.iter().map(|c| c.len()).max().unwrap_or(0);
+
+        GraphSummary {
+            nodes: n,
+            edges: m,
+            density,
+            avg_degree,
+            max_degree,
+            components: components.len(),
+            giant_component,
+        }
+    }
+
+    // Computes the global clustering coefficient: 3 * triangles / connected triples.
+    // Each triangle is counted once, from its lowest node, by intersecting ascending neighbor lists.
+    // Its outputs are - `f64`: transitivity between 0 and 1
+    pub fn transitivity(&self) -> f64 {
+        let mut triangles = 0usize;
+        let mut triples = 0usize;
+        for u in 0..self.node_count() {
+            let d = self.degree(u);
+            triples += d * d.saturating_sub(1) / 2;
+            for &v in self.adj[u].iter().filter(|&&v| v > u) {
+                triangles += self.adj[v].iter().filter(|&&w| w > v && self.adj[u].binary_search(&w).is_ok()).count();
+            }
+        }
+        if triples > 0 { 3.0 * triangles as f64 / triples as f64 } else { 0.0 }
+    }
+
+    // Computes Newman's degree assortativity: the Pearson correlation between the degrees
+    // at the two ends of every edge. Positive values mean hubs mail other hubs.
+    // Its outputs are - `f64`: assortativity between -1 and 1 (0 if all degrees are equal)
+    pub fn degree_assortativity(&self) -> f64 {
+        let (mut product, mut sum, mut squares, mut m) = (0.0, 0.0, 0.0, 0.0);
+        for u in 0..self.node_count() {
+            for &v in self.adj[u].iter().filter(|&&v| v > u) {
+                let (j, k) = (self.degree(u) as f64, self.degree(v) as f64);
+                product += j * k;
+                sum += (j + k) / 2.0;
+                squares += (j * j + k * k) / 2.0;
+                m += 1.0;
+            }
+        }
+        if m == 0.0 {
+            return 0.0;
+        }
+        let mean_sq = (sum / m).powi(2);
+        let variance = squares / m - mean_sq;
+        if variance > 1e-12 { (product / m - mean_sq) / variance } else { 0.0 }
+    }
+}
+
+impl Adjacency for Graph {
+    type Neighbors<'a> = std::iter::Copied<std::slice::Iter<'a, usize>>;
+
+    fn node_count(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn node_id(&self, i: usize) -> usize {
+        self.ids[i]
+    }
+
+    fn index_of(&self, id: usize) -> Option<usize> {
+        self.index.get(&id).copied()
+    }
+
+    fn degree(&self, i: usize) -> usize {
+        self.adj[i].len()
+    }
+
+    fn neighbor_iter(&self, i: usize) -> Self::Neighbors<'_> {
+        self.adj[i].iter().copied()
+    }
+}
+
+// 64-bit FNV-1a hash of a sequence of numbers (each fed as its 8 little-endian bytes): not
+// cryptographic, but stable across runs and platforms, unlike the std hasher.
+pub fn fnv1a(values: impl IntoIterator<Item = u64>) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for value in values {
+        for byte in value.to_le_bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100_0000_01b3);
+        }
+    }
+    hash
+}
+
+// Extracts the largest connected component (the giant component) as its own graph, so
+// distance-based measures are not distorted by tiny isolated components.
+// Its inputs are - `graph`: the graph
+// Its outputs are - `Graph`: the giant component (empty if the graph is empty)
+pub fn largest_component(graph: &Graph) -> Graph {
+    let giant = graph.components().into_iter().max_by_key(|c| c.len()).unwrap_or_default();
+    graph.subgraph(&giant)
+}
+
+// Extracts the ego network of a person: everyone within `radius` hops of their address,
+// with all edges among them, so the analysis can be run on that neighborhood alone.
+// Its inputs are - `graph`: the graph - `emails`: the email index
+// - `email`: address of the ego - `radius`: number of hops
+// Its outputs are - `Option<Graph>`: the ego network, or None if the address is unknown or not in the graph
+pub fn ego_network(graph: &Graph, emails: &EmailIndex, email: &str, radius: usize) -> Option<Graph> {
+    let ego = graph.index_of(emails.node(email)?)?;
+    let nodes: Vec<usize> = graph.within_hops(ego, radius).into_iter().map(|(v, _)| v).collect();
+    Some(graph.subgraph(&nodes))
+}
+
+// Extracts the subgraph induced by the people whose address and mailbox folder satisfy a
+// predicate, such as only @enron.com addresses or only some employees' folders, keeping all
+// edges among them. Nodes missing from the mapping are tested with an empty address and folder.
+// Its inputs are - `graph`: the graph - `email_map`: node ID -> (email, folder)
+// - `keep`: called with (email, folder), true for nodes to keep
+// Its outputs are - `Graph`: the induced subgraph, in the original index order
+pub fn filter_nodes(graph: &Graph, email_map: &HashMap<usize, (String, String)>, keep: impl Fn(&str, &str) -> bool) -> Graph {
+    let nodes: Vec<usize> = (0..graph.node_count())
+        .filter(|&v| {
+            let (email, folder) = email_map.get(&graph.node_id(v)).map_or(("", ""), |(e, f)| (e.as_str(), f.as_str()));
+            keep(email, folder)
+        })
+        .collect();
+    graph.subgraph(&nodes)
 }
 
 #[cfg(test)]
@@ -182,10 +1198,126 @@ mod tests {
         let degree = compute_degree(&edges);
 
         assert_eq!(degree.len(), 4); 
-        assert_eq!(degree[&1], 1);
-        assert_eq!(degree[&2], 2);
-        assert_eq!(degree[&3], 2);
-        assert_eq!(degree[&4], 1);
+        assert_eq!(degree[&1].degree, 1);
+        assert_eq!(degree[&2].degree, 2);
+        assert_eq!(degree[&3].degree, 2);
+        assert_eq!(degree[&4].degree, 1);
+    }
+
+    // Tests that strength sums the weights of incident edges, and a weighted file with a
+    // missing weight column falls back to weight 1 while zero, negative, infinite and NaN
+    // weights are skipped, both when reading and when building the graph.
+    #[test]
+    fn test_compute_strength() {
+        let strength = compute_strength(&[(1, 2, 2.0), (2, 3, 0.5), (3, 4, 1.0)]);
+        assert_eq!((strength[&1], strength[&2], strength[&3], strength[&4]), (2.0, 2.5, 1.5, 1.0));
+
+        let path = std::env::temp_dir().join("project_weighted_edges.txt");
+        std::fs::write(&path, "#\n#\n#\n#\n1\t2\t3.5\n2\t3\n3\t4\tx\n4\t5\t0\n5\t6\t-2\n6\t7\tNaN\n7\t8\tinf\n").unwrap();
+        let edges = read_weighted_file(path.to_str().unwrap());
+        assert_eq!(edges, vec![(1, 2, 3.5), (2, 3, 1.0)]);
+        assert_eq!(read_file(path.to_str().unwrap()), vec![(1, 2), (2, 3)]);
+        std::fs::remove_file(path).unwrap();
+
+        let graph = Graph::from_weighted_edges(&[(1, 2, 2.0), (2, 3, 0.0), (3, 4, f64::NAN), (1, 3, -1.0), (4, 5, 1.0)]);
+        assert_eq!(graph.edges(), vec![(1, 2), (4, 5)]);
+        assert!((0..graph.node_count()).all(|v| graph.strength(v).is_finite() && graph.strength(v) > 0.0));
+    }
+
+    // Tests that the giant component of a triangle plus a separate edge is the triangle,
+    // with its edges listed once each.
+    #[test]
+    fn test_largest_component() {
+        let graph = Graph::from_edges(&[(1, 2), (2, 3), (3, 1), (4, 5), (5, 4)]);
+        let giant = largest_component(&graph);
+        assert_eq!(giant.node_count(), 3);
+        assert_eq!(giant.edge_count(), 3);
+        assert!(giant.index_of(4).is_none());
+        let mut edges: Vec<(usize, usize)> = giant.edges().into_iter().map(|(u, v)| (u.min(v), u.max(v))).collect();
+        edges.sort_unstable();
+        assert_eq!(edges, vec![(1, 2), (1, 3), (2, 3)]);
+    }
+
+    // Tests that the radius-1 ego network of node 2 on the path 1-2-3-4 is 1-2-3, and that
+    // unknown addresses give None.
+    #[test]
+    fn test_ego_network() {
+        let graph = Graph::from_edges(&[(1, 2), (2, 3), (3, 4)]);
+        let emails = EmailIndex::new((1..=4).map(|i| (i, (format!("user{}@enron.com", i), "inbox".to_string()))).collect());
+        let ego = ego_network(&graph, &emails, "USER2@enron.com", 1).unwrap();
+        assert_eq!(ego.node_count(), 3);
+        assert_eq!(ego.edge_count(), 2);
+        assert!(ego.index_of(4).is_none());
+        assert_eq!(graph.within_hops(0, 2).last(), Some(&(graph.index_of(3).unwrap(), 2)));
+        assert!(ego_network(&graph, &emails, "nobody@enron.com", 1).is_none());
+    }
+
+    // Tests filtering by domain and by folder: edges to removed nodes disappear, and nodes
+    // missing from the mapping only pass predicates that accept an empty address.
+    #[test]
+    fn test_filter_nodes() {
+        let graph = Graph::from_edges(&[(1, 2), (2, 3), (3, 4), (4, 5)]);
+        let email_map: HashMap<usize, (String, String)> = HashMap::from([
+            (1, ("a@enron.com".to_string(), "lay-k".to_string())),
+            (2, ("b@ENRON.com".to_string(), "lay-k".to_string())),
+            (3, ("c@aol.com".to_string(), "lay-k".to_string())),
+            (4, ("d@enron.com".to_string(), "skilling-j".to_string())),
+        ]);
+        let enron = filter_nodes(&graph, &email_map, |email, _| email.to_lowercase().ends_with("@enron.com"));
+        assert_eq!((enron.node_count(), enron.edge_count()), (3, 1));
+        assert!(enron.index_of(3).is_none() && enron.index_of(5).is_none());
+
+        let lay = filter_nodes(&graph, &email_map, |_, folder| folder == "lay-k");
+        assert_eq!(lay.edges(), vec![(1, 2), (2, 3)]);
+    }
+
+    // Tests that reordering puts the hub first and keeps the same node IDs and edges.
+    #[test]
+    fn test_reordered() {
+        let graph = Graph::from_edges(&[(5, 6), (1, 2), (2, 3), (2, 4), (7, 7)]);
+        for order in [NodeOrder::Bfs, NodeOrder::Degree] {
+            let relabeled = graph.reordered(order);
+            assert_eq!(relabeled.node_id(0), 2);
+            assert_eq!(relabeled.node_count(), graph.node_count());
+            let sorted = |g: &Graph| {
+                let mut edges: Vec<(usize, usize)> = g.edges().into_iter().map(|(u, v)| (u.min(v), u.max(v))).collect();
+                edges.sort_unstable();
+                edges
+            };
+            assert_eq!(sorted(&relabeled), sorted(&graph));
+        }
+        // BFS keeps the star together before the separate edge
+        let bfs: Vec<usize> = graph.node_order(NodeOrder::Bfs).into_iter().map(|v| graph.node_id(v)).collect();
+        assert_eq!(bfs, vec![2, 1, 3, 4, 5, 6]);
+    }
+
+    // Tests transitivity and degree assortativity on a triangle with a pendant node.
+    // Node 3 (degree 3) links to nodes of degree 2, 2 and 1, so the graph is disassortative.
+    #[test]
+    fn test_transitivity_and_assortativity() {
+        let graph = Graph::from_edges(&[(1, 2), (2, 3), (3, 1), (3, 4)]);
+        // One triangle, and triples 1 + 1 + 3 centered at nodes 1, 2 and 3
+        assert!((graph.transitivity() - 3.0 / 5.0).abs() < 1e-12);
+        assert!((graph.degree_assortativity() - (-5.0 / 7.0)).abs() < 1e-12);
+
+        let star = Graph::from_edges(&[(1, 2), (1, 3), (1, 4)]);
+        assert_eq!(star.transitivity(), 0.0);
+        assert!((star.degree_assortativity() + 1.0).abs() < 1e-12);
+    }
+
+    // Tests that in-degree and out-degree are counted separately for directed edges, with
+    // repeated edges and self loops left out and the undirected degree matching the graph.
+    #[test]
+    fn test_compute_degree_directed() {
+        let edges = vec![(1, 2), (1, 3), (3, 1), (2, 3), (1, 2), (2, 2)];
+        let degree = compute_degree(&edges);
+
+        assert_eq!(degree[&1], InOutDegree { in_deg: 1, out_deg: 2, degree: 2 });
+        assert_eq!(degree[&2], InOutDegree { in_deg: 1, out_deg: 1, degree: 2 });
+        assert_eq!(degree[&3], InOutDegree { in_deg: 2, out_deg: 1, degree: 2 });
+        // The degree agrees with the graph, where (1, 3) and (3, 1) are one edge
+        let graph = Graph::from_edges(&edges);
+        assert!((0..graph.node_count()).all(|v| graph.degree(v) == degree[&graph.node_id(v)].degree));
     }
 
     // Tests that closeness centrality finds more central nodes correctly.
@@ -194,13 +1326,118 @@ mod tests {
     fn test_compute_closeness() {
         let edges = vec![(1, 2), (2, 3), (3, 4)];
         let nodes: HashSet<_> = vec![1, 2, 3, 4].into_iter().collect();
-        let closeness = compute_closeness(&edges, &nodes);
+        let closeness = compute_closeness(&edges, &nodes, ClosenessMode::Standard);
 
         assert_eq!(closeness.len(), 4); 
         assert!(closeness[&2] > closeness[&1]);
         assert!(closeness[&3] > closeness[&4]);
     }
 
+    // Tests that Wasserman-Faust closeness ranks the middle of a 4-node path above the nodes of
+    // a separate edge, which standard closeness scores a perfect 1.
+    #[test]
+    fn test_wasserman_faust_closeness() {
+        let edges = vec![(1, 2), (2, 3), (3, 4), (8, 9)];
+        let nodes: HashSet<usize> = [2, 8].into_iter().collect();
+        let standard = compute_closeness(&edges, &nodes, ClosenessMode::Standard);
+        assert_eq!((standard[&2], standard[&8]), (0.75, 1.0));
+        let adjusted = compute_closeness(&edges, &nodes, ClosenessMode::WassermanFaust);
+        assert!((adjusted[&2] - 0.75 * 3.0 / 5.0).abs() < 1e-12);
+        assert!((adjusted[&8] - 1.0 / 5.0).abs() < 1e-12);
+    }
+
+    // Tests that the parallel closeness gives the same scores with one thread and with several.
+    #[test]
+    fn test_parallel_closeness() {
+        let edges: Vec<(usize, usize)> = (0..50).map(|i| (i, (i * 7 + 3) % 50)).collect();
+        let graph = Graph::from_edges(&edges);
+        let nodes: HashSet<usize> = (0..50).collect();
+        let single = parallel_closeness(&graph, &nodes, 1);
+        let multi = parallel_closeness(&graph, &nodes, 4);
+        assert_eq!(single.len(), 50);
+        assert_eq!(single, multi);
+    }
+
+    // Tests weighted betweenness on the square 1-2-3-4-1 where the edges through 2 are heavy:
+    // the short route from 1 to 3 runs through 2 only, while 2 and 4 stay tied between 1 and 3.
+    // Unit weights leave the graph unweighted, and subgraphs keep the weights.
+    #[test]
+    fn test_weighted_betweenness() {
+        let edges = vec![(1, 2, 4.0), (2, 3, 4.0), (3, 4, 1.0), (4, 1, 1.0)];
+        let graph = Graph::from_weighted_edges(&edges);
+        assert!(graph.is_weighted());
+        let nodes: HashSet<usize> = (1..=4).collect();
+        let betweenness = brandes_betweenness(&graph, &nodes, BetweennessNormalization::None);
+        assert_eq!((betweenness[&1], betweenness[&2], betweenness[&3], betweenness[&4]), (1.0, 2.0, 1.0, 0.0));
+        let reordered = brandes_betweenness(&graph.reordered(NodeOrder::Degree), &nodes, BetweennessNormalization::None);
+        assert_eq!(reordered, betweenness);
+
+        let unit: Vec<(usize, usize, f64)> = edges.iter().map(|&(u, v, _)| (u, v, 1.0)).collect();
+        let unweighted = Graph::from_weighted_edges(&unit);
+        assert!(!unweighted.is_weighted());
+        assert_eq!(brandes_betweenness(&unweighted, &nodes, BetweennessNormalization::None)[&2], 1.0);
+    }
+
+    // Tests the normalization schemes on the star with center 1 and leaves 2..=5, using all
+    // nodes as sources: the center lies on all 6 leaf pairs, counted from both ends.
+    #[test]
+    fn test_betweenness_normalization() {
+        let graph = Graph::from_edges(&[(1, 2), (1, 3), (1, 4), (1, 5)]);
+        let nodes: HashSet<usize> = (1..=5).collect();
+        let score = |norm| brandes_betweenness(&graph, &nodes, norm)[&1];
+        assert_eq!(score(BetweennessNormalization::None), 12.0);
+        assert_eq!(score(BetweennessNormalization::Max), 1.0);
+        assert_eq!(score(BetweennessNormalization::Pairs), 1.0);
+        let rescaled = brandes_betweenness(&graph, &nodes, BetweennessNormalization::Rescaled);
+        assert_eq!((rescaled[&1], rescaled[&2]), (1.0, 0.0));
+    }
+
+    // Tests the progressive top-k search on two stars joined through a middle node:
+    // the two centers and the middle node stand far above the leaves and separate early,
+    // while sampling every source reproduces the exact raw betweenness.
+    #[test]
+    fn test_top_k_betweenness() {
+        let mut edges = vec![(0, 100), (100, 200)];
+        edges.extend((1..=30).map(|i| (0, i)));
+        edges.extend((201..=230).map(|i| (200, i)));
+        let graph = Graph::from_edges(&edges);
+
+        let config = TopKBetweennessConfig { k: 3, initial_samples: 8, tolerance: 0.0, ..TopKBetweennessConfig::default() };
+        let result = top_k_betweenness(&graph, &config);
+        let mut top: Vec<usize> = result.top.iter().map(|&(node, _)| node).collect();
+        top.sort_unstable();
+        assert_eq!(top, vec![0, 100, 200]);
+        assert!(result.separated && result.samples < graph.node_count());
+
+        let all = TopKBetweennessConfig { k: 1, initial_samples: graph.node_count(), ..TopKBetweennessConfig::default() };
+        let exact = brandes_betweenness(&graph, &(0..=230).collect(), BetweennessNormalization::None);
+        let result = top_k_betweenness(&graph, &all);
+        // Each center lies on 435 + 30 * 32 pairs, more than the 31 * 31 through the middle node
+        assert_eq!(result.top[0].0, 0);
+        assert!((result.top[0].1 - exact[&0]).abs() < 1e-6);
+        assert_eq!(exact[&0], 2.0 * 1395.0);
+
+        // No samples asked for still searches one source; ties among the leaves go to the
+        // lowest node IDs however the graph is relabeled
+        let none = TopKBetweennessConfig { max_samples: 0, ..config };
+        assert_eq!(top_k_betweenness(&graph, &none).samples, 1);
+        let ties = TopKBetweennessConfig { k: 5, ..all };
+        let expected = vec![0, 200, 100, 1, 2];
+        for order in [NodeOrder::Bfs, NodeOrder::Degree] {
+            let result = top_k_betweenness(&graph.reordered(order), &ties);
+            assert_eq!(result.top.iter().map(|&(node, _)| node).collect::<Vec<_>>(), expected);
+        }
+
+        // On a weighted square the heavy path through 2 carries the 1 - 3 pair alone
+        let square = Graph::from_weighted_edges(&[(1, 2, 10.0), (2, 3, 10.0), (3, 4, 1.0), (4, 1, 1.0)]);
+        let exact = brandes_betweenness(&square, &(1..=4).collect(), BetweennessNormalization::None);
+        let result = top_k_betweenness(&square, &TopKBetweennessConfig { k: 4, initial_samples: 4, ..TopKBetweennessConfig::default() });
+        assert_eq!(result.top[0].0, 2);
+        for (node, score) in result.top {
+            assert!((score - exact[&node]).abs() < 1e-9);
+        }
+    }
+
     // Tests that betweenness centrality correctly identifies bridge nodes.
     // Nodes in the middle (2, 3) should have higher betweenness than nodes in the edges (1, 4).
     #[test]
@@ -213,6 +1450,71 @@ mod tests {
         assert!(betweenness[&2] > betweenness[&1]);
         assert!(betweenness[&3] > betweenness[&4]);
     }
+
+    // Tests the BFS-based closeness and betweenness on edge cases: no edges or no nodes give
+    // empty results, a node missing from the graph reaches nobody, scores on a graph of two
+    // components stay within each component, and a search DAG reused across components
+    // forgets the previous one.
+    #[test]
+    fn test_bfs_edge_cases() {
+        let none: HashSet<usize> = HashSet::new();
+        assert!(compute_closeness(&[], &none, ClosenessMode::Standard).is_empty());
+        assert_eq!(compute_closeness(&[], &HashSet::from([5]), ClosenessMode::Standard), HashMap::from([(5, 0.0)]));
+        assert!(compute_betweenness(&[], &none).is_empty());
+        assert!(compute_betweenness(&[(1, 2)], &none).values().all(|&b| b == 0.0));
+
+        let edges = vec![(1, 2), (2, 3), (7, 8)];
+        let nodes: HashSet<usize> = [1, 2, 3, 7, 8, 9].into_iter().collect();
+        let closeness = compute_closeness(&edges, &nodes, ClosenessMode::Standard);
+        assert_eq!((closeness[&1], closeness[&2], closeness[&7], closeness[&9]), (2.0 / 3.0, 1.0, 1.0, 0.0));
+        let graph = Graph::from_edges(&edges);
+        let betweenness = brandes_betweenness(&graph, &nodes, BetweennessNormalization::None);
+        assert_eq!(betweenness[&2], 2.0);
+        assert!([1, 3, 7, 8].iter().all(|node| betweenness.get(node).copied().unwrap_or(0.0) == 0.0));
+
+        let mut dag = ShortestPathDag::new(graph.node_count());
+        dag.search(&graph, graph.index_of(1).unwrap());
+        assert_eq!(dag.order.len(), 3);
+        dag.search(&graph, graph.index_of(7).unwrap());
+        assert_eq!(dag.order.len(), 2);
+        for node in [1, 2, 3] {
+            let v = graph.index_of(node).unwrap();
+            assert_eq!((dag.dist[v], dag.sigma[v]), (UNREACHED, 0.0));
+        }
+        assert_eq!(dag.dist[graph.index_of(8).unwrap()], 1);
+    }
+
+    // Tests that betweenness does not depend on how the source set was built: two sets of the
+    // same nodes, filled in opposite orders, give bit-for-bit equal scores, weighted or not.
+    #[test]
+    fn test_betweenness_deterministic() {
+        let edges: Vec<(usize, usize, f64)> = (0..300usize).flat_map(|v| [(v, (v + 1) % 300, 1.0 + (v % 3) as f64), (v, (v * 37 + 11) % 300, 2.0)]).collect();
+        let forward: HashSet<usize> = (0..300).collect();
+        let mut backward = HashSet::with_capacity(4096);
+        backward.extend((0..300).rev());
+        for graph in [Graph::from_weighted_edges(&edges), Graph::from_edges(&edges.iter().map(|&(u, v, _)| (u, v)).collect::<Vec<_>>())] {
+            let a = brandes_betweenness(&graph, &forward, BetweennessNormalization::None);
+            let b = brandes_betweenness(&graph, &backward, BetweennessNormalization::None);
+            assert_eq!(a.len(), 300);
+            assert!(a.iter().all(|(node, score)| score.to_bits() == b[node].to_bits()));
+        }
+    }
+
+    // Tests that the graph summary counts each undirected edge once.
+    // Two components (a triangle and a single edge), with one edge listed in both directions.
+    #[test]
+    fn test_graph_summary() {
+        let edges = vec![(1, 2), (2, 1), (2, 3), (3, 1), (10, 11)];
+        let summary = Graph::from_edges(&edges).summary();
+
+        assert_eq!(summary.nodes, 5);
+        assert_eq!(summary.edges, 4);
+        assert_eq!(summary.max_degree, 2);
+        assert_eq!(summary.components, 2);
+        assert_eq!(summary.giant_component, 3);
+        assert!((summary.density - 0.4).abs() < 1e-9);
+        assert!((summary.avg_degree - 1.6).abs() < 1e-9);
+    }
 }
 // Intermediate commit: updated graph.rs
 // Intermediate commit: modified graph.rs
@@ -0,0 +1,154 @@
+// Includes group centralities (Everett & Borgatti), which measure how central a set of
+// nodes is when treated as a single unit: how many outsiders it touches, how close it is
+// to everyone else, and how much of the traffic between outsiders passes through it.
+
+use crate::distance::{reset, UNREACHED};
+use crate::graph::Graph;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::{HashMap, HashSet};
+
+// Centrality of one group of nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupCentrality {
+    // Group members present in the graph
+    pub size: usize,
+    // Non-members adjacent to at least one member
+    pub degree: usize,
+    // Reachable non-members divided by the sum of their distances to the nearest member
+    pub closeness: f64,
+    // Share of the shortest paths between pairs of non-members that pass through a member,
+    // estimated from the sampled sources
+    pub betweenness: f64,
+}
+
+// Groups the nodes by the mailbox folder they were found in, as group definitions.
+// Its inputs are - `email_map`: node ID -> (email, folder)
+// Its outputs are - `HashMap<String, HashSet<usize>>`: folder -> node IDs
+pub fn groups_by_folder(email_map: &HashMap<usize, (String, String)>) -> HashMap<String, HashSet<usize>> {
+    let mut groups: HashMap<String, HashSet<usize>> = HashMap::new();
+    for (&node, (_, folder)) in email_map {
+        groups.entry(folder.clone()).or_default().insert(node);
+    }
+    groups
+}
+
+// Counts shortest paths from `source` by BFS, optionally never entering the masked nodes.
+fn count_paths(graph: &Graph, source: usize, blocked: Option<&[bool]>, dist: &mut [usize], sigma: &mut [f64], order: &mut Vec<usize>) {
+    for &v in order.iter() {
+        dist[v] = UNREACHED;
+        sigma[v] = 0.0;
+    }
+    order.clear();
+    dist[source] = 0;
+    sigma[source] = 1.0;
+    order.push(source);
+    let mut head = 0;
+    while head < order.len() {
+        let v = order[head];
+        head += 1;
+        for &w in graph.neighbors(v) {
+            if blocked.is_some_and(|b| b[w]) {
+                continue;
+            }
+            if dist[w] == UNREACHED {
+                dist[w] = dist[v] + 1;
+                order.push(w);
+            }
+            if dist[w] == dist[v] + 1 {
+                sigma[w] += sigma[v];
+            }
+        }
+    }
+}
+
+// Computes group degree, closeness and betweenness of a set of nodes. Group closeness
+// uses one BFS started from all members at once. Group betweenness compares, from each
+// sampled non-member source, the number of shortest paths to every other non-member with
+// the number that avoid the group: the difference is the share running through it.
+// Its inputs are - `graph`: the graph - `members`: node IDs of the group
+// - `sources`: number of non-member sources sampled for betweenness - `seed`: RNG seed
+// Its outputs are - `GroupCentrality`: size, degree, closeness and betweenness of the group
+pub fn group_centrality(graph: &Graph, members: &HashSet<usize>, sources: usize, seed: u64) -> GroupCentrality {
+    let n = graph.node_count();
+    let mut in_group = vec![false; n];
+    let group: Vec<usize> = members.iter().filter_map(|&id| graph.index_of(id)).collect();
+    for &v in &group {
+        in_group[v] = true;
+    }
+
+    // Group degree: distinct outside neighbors
+    let mut touched = vec![false; n];
+    let mut degree = 0;
+    for &v in &group {
+        for &w in graph.neighbors(v) {
+            if !in_group[w] && !touched[w] {
+                touched[w] = true;
+                degree += 1;
+            }
+        }
+    }
+
+    // Group closeness: a BFS seeded with every member gives each outsider's distance to the group
+    let mut dist = vec![UNREACHED; n];
+    let mut order = group.clone();
+    for &v in &group {
+        dist[v] = 0;
+    }
+    let mut head = 0;
+    while head < order.len() {
+        let v = order[head];
+        head += 1;
+        for &w in graph.neighbors(v) {
+            if dist[w] == UNREACHED {
+                dist[w] = dist[v] + 1;
+                order.push(w);
+            }
+        }
+    }
+    let (reached, total) = (order.len() - group.len(), order.iter().map(|&v| dist[v]).sum::<usize>());
+    let closeness = if total > 0 { reached as f64 / total as f64 } else { 0.0 };
+    reset(&mut dist, &order);
+
+    // Group betweenness over sampled non-member sources
+    let mut pool: Vec<usize> = (0..n).filter(|&v| !in_group[v]).collect();
+    pool.shuffle(&mut StdRng::seed_from_u64(seed));
+    pool.truncate(sources);
+    let (mut sigma, mut dist_avoid, mut sigma_avoid) = (vec![0.0; n], vec![UNREACHED; n], vec![0.0; n]);
+    let (mut order_avoid, mut through, mut pairs) = (Vec::new(), 0.0, 0usize);
+    order.clear();
+    for &s in &pool {
+        count_paths(graph, s, None, &mut dist, &mut sigma, &mut order);
+        count_paths(graph, s, Some(&in_group), &mut dist_avoid, &mut sigma_avoid, &mut order_avoid);
+        for &t in order.iter().filter(|&&t| t != s && !in_group[t]) {
+            let avoiding = if dist_avoid[t] == dist[t] { sigma_avoid[t] / sigma[t] } else { 0.0 };
+            through += 1.0 - avoiding;
+            pairs += 1;
+        }
+    }
+    let betweenness = if pairs > 0 { through / pairs as f64 } else { 0.0 };
+
+    GroupCentrality { size: group.len(), degree, closeness, betweenness }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests the group {2, 4} on the path 1-2-3-4-5: it touches 1, 3 and 5, every outsider
+    // is one step away, and paths 1-3, 1-5 and 3-5 all pass through a member.
+    #[test]
+    fn test_group_centrality() {
+        let graph = Graph::from_edges(&[(1, 2), (2, 3), (3, 4), (4, 5)]);
+        let group: HashSet<usize> = [2, 4].into_iter().collect();
+        let result = group_centrality(&graph, &group, 10, 1);
+        assert_eq!(result, GroupCentrality { size: 2, degree: 3, closeness: 1.0, betweenness: 1.0 });
+
+        // The end node 1 lies on no path between the others
+        let end: HashSet<usize> = [1].into_iter().collect();
+        let result = group_centrality(&graph, &end, 10, 1);
+        assert_eq!((result.degree, result.betweenness), (1, 0.0));
+        assert!((result.closeness - 4.0 / 10.0).abs() < 1e-12);
+    }
+}
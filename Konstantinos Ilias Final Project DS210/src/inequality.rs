@@ -0,0 +1,97 @@
+// Includes functions that measure how unequally a centrality is spread over the nodes:
+// the Lorenz curve (share of the total held by the bottom fraction of nodes) and the
+// Gini coefficient (0 when every node is equal, close to 1 when one node holds everything).
+
+use std::collections::HashMap;
+
+// Centrality values that can be summarized as numbers (degree counts or scores).
+pub trait CentralityValue: Copy {
+    fn as_f64(self) -> f64;
+}
+
+impl CentralityValue for usize {
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl CentralityValue for u64 {
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl CentralityValue for f64 {
+    fn as_f64(self) -> f64 {
+        self
+    }
+}
+
+// The finite values, negative ones raised to 0, sorted ascending. NaN and infinite scores say
+// nothing about how the total is shared, so they are left out.
+fn sorted_values<V: CentralityValue>(centrality: &HashMap<usize, V>) -> Vec<f64> {
+    let mut values: Vec<f64> = centrality.values().map(|v| v.as_f64()).filter(|x| x.is_finite()).map(|x| x.max(0.0)).collect();
+    values.sort_by(f64::total_cmp);
+    values
+}
+
+// Computes the Gini coefficient of a centrality map, from the values sorted ascending as
+// G = sum_i (2i - n - 1) x_i / (n sum x). Negative values are treated as 0 and non-finite ones
+// are left out.
+// Its inputs are - `centrality`: node ID -> centrality value
+// Its outputs are - `f64`: Gini coefficient between 0 and 1 (0 for an empty or all-zero map)
+pub fn gini<V: CentralityValue>(centrality: &HashMap<usize, V>) -> f64 {
+    let values = sorted_values(centrality);
+    let n = values.len() as f64;
+    let total: f64 = values.iter().sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+    let weighted: f64 = values.iter().enumerate().map(|(i, x)| (2.0 * (i as f64 + 1.0) - n - 1.0) * x).sum();
+    weighted / (n * total)
+}
+
+// Computes the Lorenz curve of a centrality map: after the k lowest nodes, the point
+// (k / n, their share of the total). The curve starts at (0, 0) and ends at (1, 1).
+// Its inputs are - `centrality`: node ID -> centrality value
+// Its outputs are - `Vec<(f64, f64)>`: (fraction of nodes, fraction of total centrality)
+pub fn lorenz_curve<V: CentralityValue>(centrality: &HashMap<usize, V>) -> Vec<(f64, f64)> {
+    let values = sorted_values(centrality);
+    let n = values.len() as f64;
+    let total: f64 = values.iter().sum();
+    let mut points = vec![(0.0, 0.0)];
+    let mut running = 0.0;
+    for (i, x) in values.iter().enumerate() {
+        running += x;
+        let share = if total > 0.0 { running / total } else { (i + 1) as f64 / n };
+        points.push(((i + 1) as f64 / n, share));
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests the Gini coefficient and Lorenz curve for equal and concentrated values.
+    #[test]
+    fn test_gini_and_lorenz() {
+        let equal: HashMap<usize, usize> = (0..4).map(|i| (i, 5)).collect();
+        assert!(gini(&equal).abs() < 1e-12);
+
+        // One node holds everything: G = (n - 1) / n
+        let concentrated: HashMap<usize, f64> = vec![(0, 0.0), (1, 0.0), (2, 0.0), (3, 8.0)].into_iter().collect();
+        assert!((gini(&concentrated) - 0.75).abs() < 1e-12);
+        let curve = lorenz_curve(&concentrated);
+        assert_eq!(curve, vec![(0.0, 0.0), (0.25, 0.0), (0.5, 0.0), (0.75, 0.0), (1.0, 1.0)]);
+    }
+
+    // Tests that NaN and infinite scores are left out instead of panicking or poisoning the sums.
+    #[test]
+    fn test_gini_non_finite() {
+        let scores: HashMap<usize, f64> = vec![(0, 0.0), (1, f64::NAN), (2, 0.0), (3, f64::INFINITY), (4, 0.0), (5, 8.0)].into_iter().collect();
+        assert!((gini(&scores) - 0.75).abs() < 1e-12);
+        assert_eq!(lorenz_curve(&scores).len(), 5);
+        assert_eq!(gini(&HashMap::from([(0, f64::NAN)])), 0.0);
+    }
+}
@@ -0,0 +1,115 @@
+// Includes a force-directed layout for drawing part of the network: following
+// Fruchterman–Reingold, edges pull their endpoints together, every pair of nodes pushes
+// apart, and the step size cools each iteration until the positions settle.
+
+use crate::graph::Graph;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+
+// Strength of the pull toward the center, relative to the total repulsion
+const GRAVITY: f64 = 0.3;
+
+// Lays out the subgraph induced by `nodes`, rescaled to the unit square. Repulsion is computed between
+// all pairs, so this is meant for hundreds to a few thousand nodes rather than the full graph.
+// Its inputs are - `graph`: the graph - `nodes`: node IDs to lay out
+// - `iterations`: number of cooling steps - `seed`: RNG seed for the starting positions
+// Its outputs are - `HashMap<usize, (f64, f64)>`: node ID -> position in [0, 1] x [0, 1]
+pub fn force_directed_layout(graph: &Graph, nodes: &HashSet<usize>, iterations: usize, seed: u64) -> HashMap<usize, (f64, f64)> {
+    let mut ids: Vec<usize> = nodes.iter().copied().filter(|&id| graph.index_of(id).is_some()).collect();
+    ids.sort_unstable();
+    let n = ids.len();
+    if n == 0 {
+        return HashMap::new();
+    }
+    let local: HashMap<usize, usize> = ids.iter().enumerate().map(|(i, &id)| (graph.index_of(id).unwrap(), i)).collect();
+
+    // Edges of the induced subgraph, in local indices
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    for (i, &id) in ids.iter().enumerate() {
+        for w in graph.neighbors(graph.index_of(id).unwrap()) {
+            if let Some(&j) = local.get(w) {
+                if i < j {
+                    edges.push((i, j));
+                }
+            }
+        }
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut pos: Vec<(f64, f64)> = (0..n).map(|_| (rng.gen::<f64>(), rng.gen::<f64>())).collect();
+    // Ideal edge length for n nodes in the unit square
+    let k = (1.0 / n as f64).sqrt();
+    let start_temperature = 0.1;
+
+    for iter in 0..iterations {
+        let temperature = start_temperature * (1.0 - iter as f64 / iterations as f64);
+        let mut shift = vec![(0.0, 0.0); n];
+
+        // Repulsion k^2 / d between every pair
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let (dx, dy) = (pos[i].0 - pos[j].0, pos[i].1 - pos[j].1);
+                let d = (dx * dx + dy * dy).sqrt().max(1e-9);
+                let force = k * k / d;
+                shift[i].0 += dx / d * force;
+                shift[i].1 += dy / d * force;
+                shift[j].0 -= dx / d * force;
+                shift[j].1 -= dy / d * force;
+            }
+        }
+        // Attraction d^2 / k along every edge
+        for &(i, j) in &edges {
+            let (dx, dy) = (pos[i].0 - pos[j].0, pos[i].1 - pos[j].1);
+            let d = (dx * dx + dy * dy).sqrt().max(1e-9);
+            let force = d * d / k;
+            shift[i].0 -= dx / d * force;
+            shift[i].1 -= dy / d * force;
+            shift[j].0 += dx / d * force;
+            shift[j].1 += dy / d * force;
+        }
+
+        // Gravity toward the center keeps disconnected parts from drifting away; each node
+        // then moves at most `temperature`
+        for (p, s) in pos.iter_mut().zip(shift.iter_mut()) {
+            s.0 -= (p.0 - 0.5) * GRAVITY * n as f64 * k;
+            s.1 -= (p.1 - 0.5) * GRAVITY * n as f64 * k;
+            let length = (s.0 * s.0 + s.1 * s.1).sqrt();
+            if length > 0.0 {
+                let step = length.min(temperature);
+                p.0 += s.0 / length * step;
+                p.1 += s.1 / length * step;
+            }
+        }
+    }
+
+    // Rescale so the drawing fills the square
+    let (min_x, max_x) = pos.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| (lo.min(p.0), hi.max(p.0)));
+    let (min_y, max_y) = pos.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| (lo.min(p.1), hi.max(p.1)));
+    let scale = |x: f64, lo: f64, hi: f64| if hi > lo { (x - lo) / (hi - lo) } else { 0.5 };
+    ids.iter()
+        .zip(&pos)
+        .map(|(&id, &(x, y))| (id, (scale(x, min_x, max_x), scale(y, min_y, max_y))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that two triangles joined by one edge are laid out as two separate groups:
+    // nodes within a triangle end up closer than nodes of different triangles.
+    #[test]
+    fn test_force_directed_layout() {
+        let edges = vec![(1, 2), (2, 3), (3, 1), (4, 5), (5, 6), (6, 4), (3, 4)];
+        let graph = Graph::from_edges(&edges);
+        let nodes: HashSet<usize> = (1..=6).collect();
+        let pos = force_directed_layout(&graph, &nodes, 200, 3);
+
+        assert_eq!(pos.len(), 6);
+        assert!(pos.values().all(|&(x, y)| (0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y)));
+        let dist = |a: usize, b: usize| ((pos[&a].0 - pos[&b].0).powi(2) + (pos[&a].1 - pos[&b].1).powi(2)).sqrt();
+        assert!(dist(1, 2) < dist(1, 5));
+        assert!(dist(5, 6) < dist(2, 6));
+    }
+}
@@ -0,0 +1,56 @@
+// Library side of the Enron network analysis, so the centrality, distance, clustering
+// and plotting functions can be reused outside of the main program.
+
+pub mod graph;
+pub mod cluster;
+pub mod plot;
+pub mod distance;
+pub mod connectivity;
+pub mod directed;
+pub mod cliques;
+pub mod motifs;
+pub mod roles;
+pub mod cartography;
+pub mod walks;
+pub mod embedding;
+pub mod pagerank;
+pub mod linkpred;
+pub mod influence;
+pub mod epidemic;
+pub mod robustness;
+pub mod cascade;
+pub mod generate;
+pub mod nullmodel;
+pub mod smallworld;
+pub mod powerlaw;
+pub mod inequality;
+pub mod layout;
+pub mod correlation;
+pub mod textplot;
+pub mod stats;
+pub mod query;
+pub mod group;
+pub mod flow;
+pub mod spectral;
+pub mod temporal;
+pub mod dynamic;
+pub mod stream;
+pub mod unionfind;
+pub mod maildir;
+pub mod bipartite;
+pub mod mapping;
+pub mod datasets;
+pub mod source;
+pub mod nodefilter;
+pub mod pseudonym;
+pub mod sampling;
+pub mod coarsen;
+pub mod compressed;
+pub mod memory;
+pub mod checkpoint;
+pub mod cache;
+pub mod pipeline;
+pub mod rundir;
+pub mod table;
+#[cfg(feature = "verify")]
+pub mod verify;
@@ -0,0 +1,312 @@
+// Includes functions for link prediction: scoring unconnected node pairs by how likely
+// they are to exchange emails, using common neighbors, Jaccard, Adamic–Adar and
+// preferential attachment, and listing the most likely missing edges.
+// An evaluation harness hides edges and measures how well each predictor recovers them.
+
+use crate::graph::Graph;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt;
+
+// Heap entry ranking a pair by score bits, then by ascending node IDs.
+type RankedPair = Reverse<(u64, Reverse<(usize, usize)>)>;
+
+// The available link prediction scores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LinkPredictor {
+    // Number of shared neighbors
+    CommonNeighbors,
+    // Shared neighbors divided by the size of the combined neighborhood
+    Jaccard,
+    // Shared neighbors weighted by 1 / ln(degree), so rare contacts count more
+    AdamicAdar,
+    // Product of the two degrees
+    PreferentialAttachment,
+}
+
+impl LinkPredictor {
+    pub const ALL: [LinkPredictor; 4] = [
+        LinkPredictor::CommonNeighbors,
+        LinkPredictor::Jaccard,
+        LinkPredictor::AdamicAdar,
+        LinkPredictor::PreferentialAttachment,
+    ];
+}
+
+impl fmt::Display for LinkPredictor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            LinkPredictor::CommonNeighbors => "Common neighbors",
+            LinkPredictor::Jaccard => "Jaccard",
+            LinkPredictor::AdamicAdar => "Adamic–Adar",
+            LinkPredictor::PreferentialAttachment => "Preferential attachment",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// A predicted missing edge with the email addresses of both ends, when known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PredictedLink {
+    pub source: usize,
+    pub target: usize,
+    pub score: f64,
+    pub source_email: Option<String>,
+    pub target_email: Option<String>,
+}
+
+// Scores the pair (u, v) of compact indices from its shared neighbors.
+pub(crate) fn score_indices(graph: &Graph, predictor: LinkPredictor, u: usize, v: usize) -> f64 {
+    let (du, dv) = (graph.degree(u), graph.degree(v));
+    if predictor == LinkPredictor::PreferentialAttachment {
+        return (du * dv) as f64;
+    }
+    let (nu, nv) = (graph.neighbors(u), graph.neighbors(v));
+    let (mut i, mut j) = (0, 0);
+    let (mut common, mut adamic_adar) = (0usize, 0.0);
+    while i < nu.len() && j < nv.len() {
+        match nu[i].cmp(&nv[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                common += 1;
+                adamic_adar += 1.0 / (graph.degree(nu[i]) as f64).ln();
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    match predictor {
+        LinkPredictor::CommonNeighbors => common as f64,
+        LinkPredictor::Jaccard if du + dv > common => common as f64 / (du + dv - common) as f64,
+        LinkPredictor::Jaccard => 0.0,
+        _ => adamic_adar,
+    }
+}
+
+// Scores a pair of nodes. Unknown node IDs score 0.
+// Its inputs are - `graph`: the graph - `predictor`: which score - `u`, `v`: node IDs
+// Its outputs are - `f64`: the link prediction score
+pub fn score(graph: &Graph, predictor: LinkPredictor, u: usize, v: usize) -> f64 {
+    match (graph.index_of(u), graph.index_of(v)) {
+        (Some(a), Some(b)) => score_indices(graph, predictor, a, b),
+        _ => 0.0,
+    }
+}
+
+// Returns the `n` unconnected pairs with the highest score, with their emails attached.
+// Candidates are the pairs two hops apart (the only pairs with a shared neighbor), found by
+// walking u -> w -> x for every u; preferential attachment ranks the same candidates.
+// Ties are broken by ascending node IDs.
+// Its inputs are - `graph`: the graph - `email_map`: node ID -> (email, folder)
+// - `predictor`: which score - `n`: number of links to return
+// Its outputs are - `Vec<PredictedLink>`: predicted links, highest score first
+pub fn top_predicted_links(
+    graph: &Graph,
+    email_map: &HashMap<usize, (String, String)>,
+    predictor: LinkPredictor,
+    n: usize,
+) -> Vec<PredictedLink> {
+    let count = graph.node_count();
+    let mut common = vec![0usize; count];
+    let mut adamic_adar = vec![0.0; count];
+    let mut adjacent = vec![false; count];
+    let mut touched = Vec::new();
+    // Min-heap of the best n pairs so far; scores are non-negative, so their bit patterns sort like the values
+    let mut best: BinaryHeap<RankedPair> = BinaryHeap::new();
+
+    for u in 0..count {
+        for &w in graph.neighbors(u) {
+            adjacent[w] = true;
+        }
+        let weight = |w: usize| 1.0 / (graph.degree(w) as f64).ln();
+        for &w in graph.neighbors(u) {
+            for &x in graph.neighbors(w) {
+                if x <= u || adjacent[x] {
+                    continue;
+                }
+                if common[x] == 0 {
+                    touched.push(x);
+                }
+                common[x] += 1;
+                adamic_adar[x] += weight(w);
+            }
+        }
+
+        for &x in &touched {
+            let (du, dx) = (graph.degree(u), graph.degree(x));
+            let value = match predictor {
+                LinkPredictor::CommonNeighbors => common[x] as f64,
+                LinkPredictor::Jaccard => common[x] as f64 / (du + dx - common[x]) as f64,
+                LinkPredictor::AdamicAdar => adamic_adar[x],
+                LinkPredictor::PreferentialAttachment => (du * dx) as f64,
+            };
+            let (a, b) = (graph.node_id(u), graph.node_id(x));
+            let pair = Reverse((value.to_bits(), Reverse((a.min(b), a.max(b)))));
+            if best.len() < n {
+                best.push(pair);
+            } else if n > 0 && pair < *best.peek().unwrap() {
+                best.pop();
+                best.push(pair);
+            }
+            common[x] = 0;
+            adamic_adar[x] = 0.0;
+        }
+        touched.clear();
+        for &w in graph.neighbors(u) {
+            adjacent[w] = false;
+        }
+    }
+
+    let email = |id: usize| email_map.get(&id).map(|(address, _)| address.clone());
+    best.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((bits, Reverse((source, target))))| PredictedLink {
+            source,
+            target,
+            score: f64::from_bits(bits),
+            source_email: email(source),
+            target_email: email(target),
+        })
+        .collect()
+}
+
+// Accuracy of one predictor on hidden edges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkPredictionEval {
+    pub predictor: LinkPredictor,
+    // Probability that a hidden edge scores higher than a random non-edge (ties count half)
+    pub auc: f64,
+    // Fraction of the top-k predicted links that are hidden edges
+    pub precision_at_k: f64,
+}
+
+// Evaluates every predictor by hiding a random fraction of the edges, scoring on the rest,
+// and checking how well the hidden edges are recovered. AUC compares `samples` random pairs
+// of a hidden edge and a non-edge of the full graph (fewer if non-edges are too rare to find);
+// precision@k checks `top_predicted_links`.
+// Its inputs are - `graph`: the full graph - `hidden_fraction`: fraction of edges to hide
+// - `k`: number of top predictions for precision - `samples`: AUC comparisons - `seed`: RNG seed
+// Its outputs are - `Vec<LinkPredictionEval>`: one result per predictor, in `LinkPredictor::ALL` order
+pub fn evaluate_link_prediction(
+    graph: &Graph,
+    hidden_fraction: f64,
+    k: usize,
+    samples: usize,
+    seed: u64,
+) -> Vec<LinkPredictionEval> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut edges: Vec<(usize, usize)> = Vec::with_capacity(graph.edge_count());
+    for u in 0..graph.node_count() {
+        for &v in graph.neighbors(u) {
+            if u < v {
+                edges.push((graph.node_id(u), graph.node_id(v)));
+            }
+        }
+    }
+    edges.shuffle(&mut rng);
+    let hidden_count = ((edges.len() as f64 * hidden_fraction).round() as usize).min(edges.len());
+    let (hidden, kept) = edges.split_at(hidden_count);
+    let train = Graph::from_edges(kept);
+    let hidden_set: HashSet<(usize, usize)> = hidden.iter().map(|&(a, b)| (a.min(b), a.max(b))).collect();
+
+    // Random pairs that are not edges of the full graph; a dense graph may not have `samples` of
+    // them, so the draws are capped and fewer comparisons are made
+    let n = graph.node_count();
+    let mut non_edges = Vec::with_capacity(samples);
+    let mut attempts = 0;
+    while non_edges.len() < samples && n > 1 && attempts < samples.saturating_mul(100) {
+        attempts += 1;
+        let (u, v) = (rng.gen_range(0..n), rng.gen_range(0..n));
+        if u != v && graph.neighbors(u).binary_search(&v).is_err() {
+            non_edges.push((graph.node_id(u), graph.node_id(v)));
+        }
+    }
+    let positives: Vec<(usize, usize)> = (0..non_edges.len()).filter_map(|_| hidden.choose(&mut rng).copied()).collect();
+
+    LinkPredictor::ALL
+        .iter()
+        .map(|&predictor| {
+            let mut wins = 0.0;
+            for (&(a, b), &(c, d)) in positives.iter().zip(&non_edges) {
+                let (hit, miss) = (score(&train, predictor, a, b), score(&train, predictor, c, d));
+                wins += if hit > miss { 1.0 } else if hit == miss { 0.5 } else { 0.0 };
+            }
+            let auc = if positives.is_empty() { 0.0 } else { wins / positives.len() as f64 };
+
+            let top = top_predicted_links(&train, &HashMap::new(), predictor, k);
+            let correct = top.iter().filter(|link| hidden_set.contains(&(link.source, link.target))).count();
+            let precision_at_k = if top.is_empty() { 0.0 } else { correct as f64 / top.len() as f64 };
+            LinkPredictionEval { predictor, auc, precision_at_k }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests the four scores on a small graph and the ranking of the missing edges.
+    // Nodes 1 and 4 share neighbors 2 and 3; node 5 hangs off node 3.
+    #[test]
+    fn test_link_prediction() {
+        let edges = vec![(1, 2), (1, 3), (2, 4), (3, 4), (3, 5)];
+        let graph = Graph::from_edges(&edges);
+
+        assert_eq!(score(&graph, LinkPredictor::CommonNeighbors, 1, 4), 2.0);
+        assert_eq!(score(&graph, LinkPredictor::Jaccard, 1, 4), 1.0);
+        let expected = 1.0 / 2f64.ln() + 1.0 / 3f64.ln();
+        assert!((score(&graph, LinkPredictor::AdamicAdar, 1, 4) - expected).abs() < 1e-12);
+        assert_eq!(score(&graph, LinkPredictor::PreferentialAttachment, 1, 5), 2.0);
+
+        let email_map: HashMap<usize, (String, String)> =
+            vec![(1, ("a@enron.com".to_string(), "x".to_string()))].into_iter().collect();
+        let top = top_predicted_links(&graph, &email_map, LinkPredictor::CommonNeighbors, 3);
+        assert_eq!(top.len(), 3);
+        assert_eq!((top[0].source, top[0].target, top[0].score), (1, 4, 2.0));
+        assert_eq!(top[0].source_email.as_deref(), Some("a@enron.com"));
+        assert_eq!(top[0].target_email, None);
+        // (2, 3) also shares two neighbors; (1, 5) and (4, 5) share one, and ties go to the lowest IDs
+        assert_eq!((top[1].source, top[1].target, top[1].score), (2, 3, 2.0));
+        assert_eq!((top[2].source, top[2].target, top[2].score), (1, 5, 1.0));
+    }
+    // Tests the evaluation harness on two 10-cliques joined by one edge, where hidden edges
+    // are easy to recover from common neighbors.
+    #[test]
+    fn test_evaluate_link_prediction() {
+        let mut edges = Vec::new();
+        for base in [0, 20] {
+            for a in 0..10 {
+                for b in a + 1..10 {
+                    edges.push((base + a, base + b));
+                }
+            }
+        }
+        edges.push((0, 20));
+        let graph = Graph::from_edges(&edges);
+        let results = evaluate_link_prediction(&graph, 0.1, 5, 500, 1);
+
+        assert_eq!(results.len(), 4);
+        let common = &results[0];
+        assert_eq!(common.predictor, LinkPredictor::CommonNeighbors);
+        assert!(common.auc > 0.95);
+        assert_eq!(common.precision_at_k, 1.0);
+        assert!(results.iter().all(|r| (0.0..=1.0).contains(&r.auc)));
+    }
+
+    // Tests that the evaluation returns on a complete graph (K5), which has no non-edges to
+    // compare the hidden edges against, and on an empty graph.
+    #[test]
+    fn test_evaluate_link_prediction_complete_graph() {
+        let edges: Vec<(usize, usize)> = (0..5).flat_map(|a| (a + 1..5).map(move |b| (a, b))).collect();
+        let results = evaluate_link_prediction(&Graph::from_edges(&edges), 0.2, 3, 50, 7);
+        assert_eq!(results.len(), 4);
+        assert!(results.iter().all(|r| r.auc == 0.0 && (0.0..=1.0).contains(&r.precision_at_k)));
+
+        let results = evaluate_link_prediction(&Graph::from_edges(&[]), 0.2, 3, 50, 7);
+        assert!(results.iter().all(|r| r.auc == 0.0));
+    }
+}
@@ -2,110 +2,1882 @@
 // It loads the dataset, calculates degree, closeness, and betweenness centralities,
 // It also performs clustering (connected components + k-means), and generates plots.
 
-mod graph;
-mod cluster;
-mod plot;
+use project::graph::*;
+use project::plot::*;
+use project::distance;
+use project::connectivity;
+use project::directed;
+use project::cliques;
+use project::motifs;
+use project::roles;
+use project::cartography;
+use project::embedding;
+use project::pagerank;
+use project::linkpred;
+use project::influence;
+use project::epidemic;
+use project::robustness;
+use project::cascade;
+use project::generate;
+use project::nullmodel;
+use project::smallworld;
+use project::powerlaw;
+use project::inequality;
+use project::layout;
+use project::correlation;
+use project::textplot;
+use project::stats;
+use project::query;
+use project::group;
+use project::flow;
+use project::temporal;
+use project::stream;
+use project::unionfind;
+use project::maildir;
+use project::bipartite;
+use project::mapping;
+use project::datasets;
+use project::source;
+use project::nodefilter;
+use project::pseudonym;
+use project::sampling;
+use project::coarsen;
+use project::compressed;
+use project::memory;
+use project::checkpoint;
+use project::cache;
+use project::rundir;
+use project::table::{self, Align, Table};
+use project::pipeline::{AnalysisPipeline, AnalysisResult, BetweennessMode, CentralityScope};
+use project::cluster::{self, blockmodel, component_summary, find_clusters, kmeans_steps, kmeans_vectors, normalize_vectors, Features, KMeansStep};
+use project::robustness::RobustnessPoint;
+use std::collections::{HashMap, HashSet};
+use std::io::BufReader;
+use std::time::Duration;
 
-use graph::*;
-use plot::*;
-use std::collections::HashMap;
+// Returns the value of a command-line flag, given either as `--ppr <email>` or as `--ppr=<email>`.
+fn arg_value(flag: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    let prefix = format!("{}=", flag);
+    args.iter().enumerate().find_map(|(i, arg)| if arg == flag { args.get(i + 1).cloned() } else { arg.strip_prefix(&prefix).map(str::to_string) })
+}
+
+// Whether a flag whose value is optional was given, e.g. `--verify` or `--verify=50`.
+fn has_flag(flag: &str) -> bool {
+    std::env::args().any(|arg| arg == flag || arg.starts_with(&format!("{}=", flag)))
+}
+
+// Returns the positive whole number following a command-line flag, or `default` if the flag is
+// not given. Exits with an error if the value is 0 or not a number.
+fn positive_arg(flag: &str, default: usize) -> usize {
+    match arg_value(flag) {
+        None => default,
+        Some(value) => match value.parse() {
+            Ok(n) if n > 0 => n,
+            _ => {
+                eprintln!("{} expects a positive whole number, got {}", flag, value);
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
+// Options of the analysis, read once from the command line.
+struct Options {
+    // Directed mode keeps the direction of each (sender, receiver) edge; it is also on for
+    // sources whose edges are directed
+    directed: bool,
+    // Graphlet mode appends graphlet degree vectors to the k-means features
+    use_graphlets: bool,
+    // Embedding mode learns node2vec vectors, exports them and appends them to the k-means features
+    use_embeddings: bool,
+    // Spectral mode appends Laplacian eigenmap coordinates to the k-means features
+    use_spectral: bool,
+    // Subgraph-centrality mode appends the (log) subgraph centrality within the top nodes to the k-means features
+    use_subgraph_centrality: bool,
+    // Optionally print text versions of the main plots, for terminals without an image viewer
+    text_plots: bool,
+    // Optionally record every k-means iteration and animate it as kmeans.gif
+    kmeans_gif: bool,
+    // Optionally restrict the whole analysis to the giant component, so closeness and distances
+    // are not distorted by tiny isolated components
+    giant_only: bool,
+    // Rankings are printed as tables of aligned columns (colored on a terminal unless `$NO_COLOR`
+    // is set); `--plain` prints them tab-separated and in full, for other programs to read
+    table_style: table::TableStyle,
+    // Closeness, betweenness and k-means work on the nodes of `--centrality-scope all|top-n|component`
+    // (default top-n: the `--top-n N` nodes of highest degree, default 1000), which are clustered
+    // into `--k K` groups (default 5) by at most `--max-iters N` k-means iterations (default 100)
+    scope: CentralityScope,
+    top_n: usize,
+    k: usize,
+    max_iters: usize,
+    // `--betweenness-norm none|max|pairs|rescaled` chooses the scaling (default: divide by the max)
+    normalization: BetweennessNormalization,
+    // Optionally measure activity by strength (`--degree-measure strength`), the sum of edge
+    // weights, in the top-10 report and the clustering features
+    measure: DegreeMeasure,
+    // `--closeness-mode standard|wf` chooses plain or component-adjusted (Wasserman-Faust) closeness
+    closeness_mode: ClosenessMode,
+    // Optionally limit the worker threads of every parallel computation (`--threads N`)
+    threads: usize,
+}
+
+impl Options {
+    // Reads the options from the command line. Exits with an error for an unknown scope or
+    // betweenness normalization, and falls back to the default for other unknown values.
+    fn from_args() -> Options {
+        let flag = |name: &str| std::env::args().any(|arg| arg == name);
+        let scope = match arg_value("--centrality-scope").as_deref() {
+            Some("all") => CentralityScope::All,
+            Some("component") => CentralityScope::Component,
+            Some("top-n") | None => CentralityScope::TopN,
+            Some(other) => {
+                eprintln!("Unknown centrality scope: {} (expected all, top-n or component)", other);
+                std::process::exit(1);
+            }
+        };
+        let normalization = match arg_value("--betweenness-norm").as_deref() {
+            Some("none") => BetweennessNormalization::None,
+            Some("pairs") => BetweennessNormalization::Pairs,
+            Some("rescaled") => BetweennessNormalization::Rescaled,
+            Some("max") | None => BetweennessNormalization::Max,
+            Some(other) => {
+                eprintln!("Unknown betweenness normalization: {} (expected none, max, pairs or rescaled)", other);
+                std::process::exit(1);
+            }
+        };
+        let measure = match arg_value("--degree-measure").as_deref() {
+            Some("strength") => DegreeMeasure::Strength,
+            Some("degree") | None => DegreeMeasure::Degree,
+            Some(other) => {
+                eprintln!("Unknown degree measure: {} (expected degree or strength)", other);
+                DegreeMeasure::Degree
+            }
+        };
+        let closeness_mode = match arg_value("--closeness-mode").as_deref() {
+            Some("wf") => ClosenessMode::WassermanFaust,
+            Some("standard") | None => ClosenessMode::Standard,
+            Some(other) => {
+                eprintln!("Unknown closeness mode: {} (expected standard or wf)", other);
+                ClosenessMode::Standard
+            }
+        };
+        Options {
+            directed: flag("--directed"),
+            use_graphlets: flag("--graphlets"),
+            use_embeddings: flag("--embeddings"),
+            use_spectral: flag("--spectral"),
+            use_subgraph_centrality: flag("--subgraph-centrality"),
+            text_plots: flag("--text-plots"),
+            kmeans_gif: flag("--kmeans-gif"),
+            giant_only: flag("--giant-only"),
+            table_style: table::TableStyle::for_stdout(flag("--plain")),
+            scope,
+            top_n: positive_arg("--top-n", 1000),
+            k: positive_arg("--k", 5),
+            max_iters: positive_arg("--max-iters", 100),
+            normalization,
+            measure,
+            closeness_mode,
+            threads: arg_value("--threads").and_then(|t| t.parse().ok()).unwrap_or(0),
+        }
+    }
+}
 
 fn main() {
-    // Load the edge list and email mapping
-    let edges = read_file("email-Enron (1).txt");
-    let email_map = load_email_mapping("email_to_node.csv");
-
-    // Compute degree centrality
-    let degree = compute_degree(&edges);
-    let mut deg_sorted: Vec<_> = degree.clone().into_iter().collect();
-    deg_sorted.sort_by(|a, b| b.1.cmp(&a.1));
-
-    // Print Top 10 nodes by Degree Centrality
-    println!("\n🏆 Top 10 by Degree Centrality:");
-    for (i, (node, deg)) in deg_sorted.iter().take(10).enumerate() {
-        if let Some((email, folder)) = email_map.get(node) {
-            println!("{:>2}. Node {} ({}) [{}]: {} connections", i + 1, node, email, folder, deg);
+    let options = Options::from_args();
+    if options.threads > 0 {
+        if let Err(e) = rayon::ThreadPoolBuilder::new().num_threads(options.threads).build_global() {
+            eprintln!("Could not set the thread count: {}", e);
+        }
+    }
+    let mut run_dir = run_directory();
+    let plot_config = plot_config(&run_dir);
+
+    match std::env::args().nth(1).as_deref() {
+        Some("fetch") => return run_fetch(),
+        Some("stream") => return run_stream(),
+        Some("compressed") => return run_compressed(&options.table_style),
+        _ => {}
+    }
+    let Some(network) = load_network(&options, &run_dir, &plot_config) else {
+        return;
+    };
+    if run_command(&network, &options.table_style) {
+        return;
+    }
+    if !run_analysis(&network, &options, &mut run_dir, &plot_config) {
+        std::process::exit(1);
+    }
+}
+
+// Plots, CSV files and the manifest of the run go into a directory of their own inside
+// `--runs-dir DIR` (default runs), named `--run-name NAME` or after the time the run started.
+// A run that writes nothing leaves no directory. Exits with an error if it cannot be created.
+fn run_directory() -> rundir::RunDirectory {
+    let runs_dir = arg_value("--runs-dir").map_or_else(rundir::default_runs_dir, std::path::PathBuf::from);
+    match rundir::RunDirectory::new(&runs_dir, arg_value("--run-name").as_deref()) {
+        Ok(run_dir) => run_dir,
+        Err(e) => {
+            eprintln!("Could not create a run directory in {}: {}", runs_dir.display(), e);
+            std::process::exit(1);
         }
     }
+}
 
-    // Select top 1000 nodes for more computationally expensive centralities
-    let top_nodes: std::collections::HashSet<usize> = deg_sorted.iter().take(1000).map(|(n, _)| *n).collect();
+// Plots go into the run directory unless `--plot-dir DIR` sends them elsewhere, named with
+// `--plot-prefix`, sized by `--plot-width` and `--plot-height`, and styled by `--plot-style classic`.
+fn plot_config(run_dir: &rundir::RunDirectory) -> PlotConfig {
+    let mut plot_config = PlotConfig { output_dir: run_dir.path().to_path_buf(), ..PlotConfig::default() };
+    if let Some(dir) = arg_value("--plot-dir") {
+        plot_config.output_dir = dir.into();
+    }
+    plot_config.prefix = arg_value("--plot-prefix").unwrap_or_default();
+    plot_config.width = arg_value("--plot-width").and_then(|x| x.parse().ok()).unwrap_or(plot_config.width);
+    plot_config.height = arg_value("--plot-height").and_then(|x| x.parse().ok()).unwrap_or(plot_config.height);
+    if arg_value("--plot-style").is_some_and(|style| style.eq_ignore_ascii_case("classic")) {
+        plot_config.style = PlotStyle::classic();
+    }
+    plot_config
+}
 
-    // Compute closeness centrality for top nodes and print Top 10
-    println!("\n🏆 Top 10 by Closeness Centrality:");
-    let closeness = compute_closeness(&edges, &top_nodes);
-    let mut close_sorted: Vec<_> = closeness.clone().into_iter().collect();
-    close_sorted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+// Fetch mode: `fetch [NAME|all] [--force]` downloads and unpacks SNAP datasets into the cache
+// directory (`$PROJECT_DATA_DIR`, or `data`); without a name it lists the registered datasets.
+// A fetched dataset is analyzed with `--dataset NAME`
+fn run_fetch() {
+    let cache = datasets::cache_dir();
+    let force = std::env::args().any(|arg| arg == "--force");
+    let wanted: Vec<&datasets::Dataset> = match std::env::args().nth(2).filter(|name| !name.starts_with("--")).as_deref() {
+        None => {
+            println!("\n📦 Registered Datasets (cache: {}):", cache.display());
+            for d in &datasets::DATASETS {
+                let status = if d.is_cached(&cache) { "cached" } else { "not fetched" };
+                println!("  {:<15} {:<12} {}", d.name, status, d.description);
+            }
+            return;
+        }
+        Some("all") => datasets::DATASETS.iter().collect(),
+        Some(name) => match datasets::find_dataset(name) {
+            Some(d) => vec![d],
+            None => {
+                let names: Vec<&str> = datasets::DATASETS.iter().map(|d| d.name).collect();
+                eprintln!("Unknown dataset {}; known datasets are {}", name, names.join(", "));
+                return;
+            }
+        },
+    };
+    for d in wanted {
+        println!("Fetching {} from {}", d.name, d.url);
+        match datasets::fetch(d, &cache, force).and_then(|path| datasets::read_edge_list(&path, &d.format).map(|edges| (path, edges))) {
+            Ok((path, edges)) => {
+                let graph = Graph::from_weighted_edges(&edges);
+                println!("  {}: {} nodes, {} edges listed", path.display(), graph.node_count(), edges.len());
+            }
+            Err(e) => eprintln!("  Could not fetch {}: {}", d.name, e),
+        }
+    }
+}
 
-    for (i, (node, score)) in close_sorted.iter().rev().take(10).enumerate() {
-        if let Some((email, folder)) = email_map.get(node) {
-            println!("{:>2}. Node {} ({}) [{}]: {:.5}", i + 1, node, email, folder, score);
+// Streaming mode: `stream [file] [--follow] [--report-every N] [--reservoir M] [--connectivity]`
+// reads edges one at a time from a file (or stdin) and reports running statistics without
+// building the graph; with `--follow` it keeps waiting for new lines at the end of the file
+fn run_stream() {
+    let report_every = arg_value("--report-every").and_then(|n| n.parse().ok()).unwrap_or(100_000);
+    let mut config = stream::StreamConfig::default();
+    config.reservoir = positive_arg("--reservoir", config.reservoir);
+    if config.reservoir < stream::MIN_RESERVOIR {
+        eprintln!("--reservoir must hold at least {} edges, got {}", stream::MIN_RESERVOIR, config.reservoir);
+        std::process::exit(1);
+    }
+    let mut stats = stream::StreamStats::new(config);
+    let report = |s: &stream::StreamSummary| {
+        println!(
+            "{:>10} lines  {:>9} edges  {:>8} nodes  {:>7} components (largest {})  max degree {}  ~{:.0} triangles",
+            s.edges_read, s.edges, s.nodes, s.components, s.largest_component, s.max_degree, s.triangles
+        );
+    };
+    println!("\n🌊 Streaming Edge Statistics:");
+    match std::env::args().nth(2).filter(|path| !path.starts_with("--")) {
+        Some(path) => {
+            let file = match std::fs::File::open(&path) {
+                Ok(file) => file,
+                Err(e) => {
+                    eprintln!("Could not open {}: {}", path, e);
+                    return;
+                }
+            };
+            if std::env::args().any(|arg| arg == "--follow") {
+                let follow = stream::FollowReader::new(BufReader::new(file), Duration::from_millis(500));
+                stats.consume(BufReader::new(follow), report_every, report);
+            } else {
+                stats.consume(BufReader::new(file), report_every, report);
+            }
+        }
+        None => stats.consume(std::io::stdin().lock(), report_every, report),
+    }
+    let summary = stats.summary();
+    report(&summary);
+    println!("Degree distribution (log2 bins):");
+    for (i, count) in summary.degree_bins.iter().enumerate() {
+        println!("  {:>6} - {:<6} {}", 1usize << i, (1usize << (i + 1)) - 1, count);
+    }
+    // With `--connectivity`, a streamed file is followed by "<node> <node>" queries on stdin
+    if std::env::args().any(|arg| arg == "--connectivity") {
+        println!("Enter two node IDs per line:");
+        for line in std::io::stdin().lines().map_while(Result::ok) {
+            let ids: Vec<usize> = line.split_whitespace().filter_map(|x| x.parse().ok()).collect();
+            let [u, v] = ids[..] else {
+                eprintln!("Expected two node IDs");
+                continue;
+            };
+            let sizes = (stats.component_size(u), stats.component_size(v));
+            if stats.same_component(u, v) {
+                println!("{} and {}: connected (component of {} nodes)", u, v, sizes.0.unwrap_or(1));
+            } else {
+                println!("{} and {}: not connected (components of {} and {} nodes)", u, v, sizes.0.unwrap_or(0), sizes.1.unwrap_or(0));
+            }
+        }
+    }
+}
+
+// Compressed mode: `compressed [file | --dataset NAME] [--top-k N]` loads a SNAP edge list
+// straight into the delta-varint adjacency, without building a `Graph`, and ranks the nodes
+// by PageRank and closeness computed on the compressed lists
+fn run_compressed(table_style: &table::TableStyle) {
+    let top_k = arg_value("--top-k").and_then(|k| k.parse().ok()).unwrap_or(10);
+    let (path, format) = match arg_value("--dataset") {
+        Some(name) => match datasets::find_dataset(&name) {
+            Some(d) => (d.path(&datasets::cache_dir()), d.format),
+            None => {
+                eprintln!("Unknown dataset {}; run `fetch` to list the registered datasets", name);
+                return;
+            }
+        },
+        None => {
+            let path = std::env::args().nth(2).filter(|path| !path.starts_with("--")).unwrap_or_else(|| "email-Enron (1).txt".to_string());
+            (path.into(), datasets::EdgeListFormat { comment: "#", directed: false, weighted: false })
+        }
+    };
+    let pairs: Vec<(usize, usize)> = match datasets::read_edge_list(&path, &format) {
+        Ok(edges) => edges.into_iter().map(|(u, v, _)| (u, v)).collect(),
+        Err(e) => {
+            eprintln!("Could not read {}: {}", path.display(), e);
+            return;
+        }
+    };
+    let graph = compressed::CompressedGraph::from_edges(&pairs);
+    drop(pairs);
+    println!("\n🗜️ Compressed Adjacency ({}):", path.display());
+    println!("Nodes: {}, edges: {}", graph.node_count(), graph.edge_count());
+    // A `Graph` of the same size, for comparison
+    let uncompressed = memory::GraphShape { nodes: graph.node_count(), edges: graph.edge_count(), weighted: false, threads: 1 };
+    println!(
+        "Neighbor lists: {} ({:.2} bytes per edge direction); {} in all, against at least {} as a `Graph`",
+        memory::format_bytes(graph.encoded_bytes()),
+        graph.encoded_bytes() as f64 / (2 * graph.edge_count()).max(1) as f64,
+        memory::format_bytes(graph.heap_bytes()),
+        memory::format_bytes(uncompressed.graph_bytes())
+    );
+
+    let ranks = pagerank::pagerank(&graph, 0.85, 1e-8, 100);
+    let mut top: Vec<(usize, f64)> = ranks.into_iter().collect();
+    top.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    top.truncate(top_k);
+    let nodes: HashSet<usize> = top.iter().map(|&(id, _)| id).collect();
+    let closeness = distance::batched_closeness(&graph, &nodes);
+    let mut ranks = Table::new(&[("Rank", Align::Right), ("Node", Align::Right), ("PageRank", Align::Right), ("Closeness", Align::Right)]);
+    for (i, (id, rank)) in top.iter().enumerate() {
+        ranks.row(vec![(i + 1).to_string(), id.to_string(), format!("{:.6}", rank), format!("{:.4}", closeness[id])]);
+    }
+    ranks.print(table_style);
+}
+
+// The graph to analyze, as loaded and then filtered, sampled or coarsened.
+struct Network {
+    graph: Graph,
+    // The weighted edge list the graph was built from, kept to the nodes still in the graph
+    weighted_edges: Vec<(usize, usize, f64)>,
+    // Two-way index of the address mapping shown in reports (pseudonymized if asked), used by
+    // every command that takes addresses
+    emails: mapping::EmailIndex,
+    // Whether edges keep their direction, by `--directed` or because the source is directed
+    directed: bool,
+}
+
+impl Network {
+    // The edge list without its weights.
+    fn edges(&self) -> Vec<(usize, usize)> {
+        self.weighted_edges.iter().map(|&(u, v, _)| (u, v)).collect()
+    }
+
+    // Drops the edges of nodes the graph no longer has.
+    fn retain_graph_edges(&mut self) {
+        let graph = &self.graph;
+        self.weighted_edges.retain(|&(u, v, _)| graph.index_of(u).is_some() && graph.index_of(v).is_some());
+    }
+}
+
+// Loads the graph from the chosen source, then applies the temporal or bipartite view,
+// pseudonymization, filters, ego network, sampling, reordering and coarsening asked for.
+// Returns `None`, after reporting why, if any of them fails.
+fn load_network(options: &Options, run_dir: &rundir::RunDirectory, plot_config: &PlotConfig) -> Option<Network> {
+    let source = graph_source(options.directed)?;
+    let (mut weighted_edges, email_map) = match source.load_edges().and_then(|edges| Ok((edges, source.load_attributes()?))) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("Could not load the {}: {}", source.describe(), e);
+            return None;
+        }
+    };
+    if let Some(summary) = source.summary() {
+        println!("Loaded the {}: {}", source.describe(), summary);
+    }
+    let directed = options.directed || source.directed();
+    // Pseudonymization: `--pseudonymize [--salt S]` replaces every address, folder and node ID by
+    // a stable salted-hash pseudonym in all tables, plots and exports, so results can be shared;
+    // the filters below still see the real addresses. The salt may also come from
+    // $PROJECT_PSEUDONYM_SALT, and the same salt gives the same pseudonyms in every run
+    let pseudonymizer = std::env::args().any(|arg| arg == "--pseudonymize").then(|| {
+        let salt = arg_value("--salt").or_else(|| std::env::var("PROJECT_PSEUDONYM_SALT").ok()).unwrap_or_else(|| {
+            eprintln!("No --salt given; using a random salt, so the pseudonyms will differ from other runs");
+            format!("{:016x}", rand::random::<u64>())
+        });
+        pseudonym::Pseudonymizer::new(&salt)
+    });
+    let (real_email_map, email_map) = match &pseudonymizer {
+        Some(p) => {
+            println!("Pseudonymizing {} addresses and {} edges", email_map.len(), weighted_edges.len());
+            weighted_edges = p.relabel_edges(&weighted_edges);
+            (p.relabel_keys(&email_map), p.apply(&email_map))
+        }
+        None => (email_map.clone(), email_map),
+    };
+    if let Some(path) = arg_value("--temporal") {
+        weighted_edges = temporal_edges(&path, pseudonymizer.as_ref(), &email_map, run_dir, plot_config)?;
+    }
+    if let Some(path) = arg_value("--bipartite") {
+        weighted_edges = bipartite_edges(&path, pseudonymizer.as_ref())?;
+    }
+
+    let graph = Graph::from_weighted_edges(&weighted_edges);
+    let mut network = Network { graph, weighted_edges, emails: mapping::EmailIndex::new(email_map), directed };
+    let unmapped = network.emails.missing(&network.graph).len();
+    if unmapped > 0 {
+        println!("{} nodes have no address in the mapping and are shown by node ID", unmapped);
+    }
+    filter_by_address(&mut network, &real_email_map);
+    // `--node-filter <file>` applies the include/exclude lists and degree bounds of a filter file
+    // (see nodefilter.rs), e.g. to keep mailing lists and bots out of every centrality ranking
+    if let Some(path) = arg_value("--node-filter") {
+        apply_node_filter(&mut network, &path, &real_email_map)?;
+    }
+    // Ego mode: `--ego <email> [--radius R]` runs the whole analysis on the R-hop
+    // neighborhood (default 2) of one person
+    if let Some(email) = arg_value("--ego") {
+        let radius = arg_value("--radius").and_then(|r| r.parse().ok()).unwrap_or(2);
+        let Some(ego) = ego_network(&network.graph, &network.emails, &email, radius) else {
+            eprintln!("Unknown email address: {}", email);
+            return None;
+        };
+        network.graph = ego;
+        network.retain_graph_edges();
+        println!("Ego network of {} (radius {}): {} nodes, {} edges", email, radius, network.graph.node_count(), network.graph.edge_count());
+    }
+    if let Some(method) = arg_value("--sample") {
+        sample_network(&mut network, &method)?;
+    }
+    // Optionally relabel the compact indices (`--reorder bfs|degree`) so traversals touch
+    // nearby memory; results are keyed by node ID and do not change
+    match arg_value("--reorder").as_deref() {
+        Some("bfs") => network.graph = network.graph.reordered(NodeOrder::Bfs),
+        Some("degree") => network.graph = network.graph.reordered(NodeOrder::Degree),
+        Some(other) => eprintln!("Unknown node order: {} (expected bfs or degree)", other),
+        None => {}
+    }
+    if options.giant_only {
+        network.graph = largest_component(&network.graph);
+        network.retain_graph_edges();
+        println!("Restricted to the giant component: {} nodes, {} edges", network.graph.node_count(), network.graph.edge_count());
+    }
+    match arg_value("--coarsen") {
+        Some(method) => coarsen_network(network, &method, plot_config),
+        None => Some(network),
+    }
+}
+
+// Chooses where the graph comes from; every source yields weighted edges and an email mapping.
+// Edge weights come from an optional third column of an edge list (1 when missing). Returns
+// `None`, after reporting why, for a dataset that is unknown or not fetched.
+fn graph_source(directed: bool) -> Option<Box<dyn source::GraphSource>> {
+    let source: Box<dyn source::GraphSource> = if let Some(dir) = arg_value("--maildir") {
+        // Maildir mode: `--maildir <dir> [--header-weights TO,CC,BCC]` reads the raw mailboxes,
+        // giving directed edges weighted by header (default 1, 0.5 and 0.25 per message); addresses
+        // missing from the mapping get new node IDs and join it with their mailbox folder
+        let mut weights = maildir::HeaderWeights::default();
+        if let Some(spec) = arg_value("--header-weights") {
+            match spec.split(',').map(|w| w.trim().parse::<f64>()).collect::<Result<Vec<_>, _>>().as_deref() {
+                Ok(&[to, cc, bcc]) => weights = maildir::HeaderWeights { to, cc, bcc },
+                _ => eprintln!("Unknown header weights {}, expected TO,CC,BCC; using {}/{}/{}", spec, weights.to, weights.cc, weights.bcc),
+            }
+        }
+        Box::new(source::MaildirSource::new(&dir, Some("email_to_node.csv"), weights))
+    } else if let Some(path) = arg_value("--graphml") {
+        // `--graphml <file>` reads a GraphML graph, with addresses from its node data
+        Box::new(source::GraphMlSource::new(&path))
+    } else if let Some(path) = arg_value("--csv") {
+        // `--csv <file> [--mapping <csv>]` reads a `source,target[,weight]` edge list
+        Box::new(source::CsvSource { path, directed, mapping: arg_value("--mapping") })
+    } else if let Some(name) = arg_value("--dataset") {
+        // `--dataset NAME` reads a fetched dataset of the registry in its own format; datasets
+        // without an email mapping are labelled by node ID
+        let Some(dataset) = datasets::find_dataset(&name) else {
+            eprintln!("Unknown dataset {}; run `fetch` to list the registered datasets", name);
+            return None;
+        };
+        let path = dataset.path(&datasets::cache_dir());
+        if !path.is_file() {
+            eprintln!("{} is missing; run `fetch {}` first", path.display(), dataset.name);
+            return None;
+        }
+        let mapping = dataset.mapping.map(str::to_string);
+        Box::new(source::SnapSource { path: path.to_string_lossy().into_owned(), format: dataset.format, mapping })
+    } else {
+        let format = datasets::EdgeListFormat { comment: "#", directed: false, weighted: true };
+        Box::new(source::SnapSource { path: "email-Enron (1).txt".to_string(), format, mapping: Some("email_to_node.csv".to_string()) })
+    };
+    Some(source)
+}
+
+// Temporal mode: `--temporal <file> [--from DATE] [--to DATE]` loads a timestamped edge list
+// instead and analyzes the emails sent in [from, to), weighted by their number per pair.
+// `--time-series <csv> [--window-months N]` tracks every person over sliding monthly windows,
+// and `--temporal-gif` animates the windows as temporal.gif. Returns the edges of the slice,
+// or `None` if the file has no timestamped edges.
+fn temporal_edges(
+    path: &str,
+    pseudonymizer: Option<&pseudonym::Pseudonymizer>,
+    email_map: &HashMap<usize, (String, String)>,
+    run_dir: &rundir::RunDirectory,
+    plot_config: &PlotConfig,
+) -> Option<Vec<(usize, usize, f64)>> {
+    let mut dated = temporal::read_temporal_file(path);
+    if let Some(p) = pseudonymizer {
+        let ids = p.node_ids(dated.iter().flat_map(|e| [e.u, e.v]));
+        for e in &mut dated {
+            (e.u, e.v) = (ids[&e.u], ids[&e.v]);
+        }
+    }
+    let temporal = temporal::TemporalGraph::new(dated);
+    let Some((first, last)) = temporal.time_range() else {
+        eprintln!("No timestamped edges in {}", path);
+        return None;
+    };
+    let time_series = arg_value("--time-series");
+    let temporal_gif = std::env::args().any(|arg| arg == "--temporal-gif");
+    if time_series.is_some() || temporal_gif {
+        let months = arg_value("--window-months").and_then(|m| m.parse().ok()).unwrap_or(1);
+        let windows = temporal::monthly_windows(&temporal, months, 1000);
+        println!("\n📅 Sliding Windows of {} Month(s):", months);
+        println!("{:<10} {:>7} {:>7} {:>11}  Top broker (betweenness)", "Start", "Nodes", "Edges", "Communities");
+        for w in &windows {
+            let communities = w.community.values().max().map_or(0, |&c| c + 1);
+            let broker = stats::top_k(&w.betweenness, 1).first().map_or(String::from("-"), |&(node, score)| {
+                let email = email_map.get(&node).map_or("?", |(e, _)| e.as_str());
+                format!("{} ({:.4})", email, score)
+            });
+            println!("{:<10} {:>7} {:>7} {:>11}  {}", temporal::format_date(w.start), w.degree.len(), w.edges, communities, broker);
+        }
+        if let Some(csv) = time_series {
+            let path = run_dir.file(&csv);
+            match temporal::write_time_series_csv(&path, &windows) {
+                Ok(()) => println!("Wrote the per-node time series to {}", path.display()),
+                Err(e) => eprintln!("Failed to write {}: {}", path.display(), e),
+            }
+        }
+        if temporal_gif {
+            // Lay out the 300 busiest people of the whole period once, so every frame shares the positions
+            let whole = temporal.graph_between(first, last + 1);
+            let activity: HashMap<usize, usize> = (0..whole.node_count()).map(|v| (whole.node_id(v), whole.degree(v))).collect();
+            let busiest = stats::top_k(&activity, 300).into_iter().map(|(node, _)| node).collect();
+            let positions = layout::force_directed_layout(&whole, &busiest, 100, 42);
+            if let Err(e) = plot_temporal_animation(&temporal, &windows, &positions, 500, plot_config) {
+                eprintln!("Failed to draw the temporal animation: {}", e);
+            }
+        }
+    }
+    let from = arg_value("--from").and_then(|t| temporal::parse_time(&t)).unwrap_or(first);
+    let to = arg_value("--to").and_then(|t| temporal::parse_time(&t)).unwrap_or(last + 1);
+    let weighted_edges = temporal.weighted_slice(from, to);
+    println!(
+        "Temporal slice {} to {}: {} emails between {} pairs",
+        temporal::format_date(from), temporal::format_date(to), temporal.between(from, to).len(), weighted_edges.len()
+    );
+    Some(weighted_edges)
+}
+
+// Bipartite mode: `--bipartite <file> [--projection shared|newman] [--max-thread N]` loads
+// "person thread" memberships and analyzes their projection onto people, where two people
+// are linked by the threads they share; threads with more than N members are left out.
+// Returns the projected edges, or `None` if the file cannot be read.
+fn bipartite_edges(path: &str, pseudonymizer: Option<&pseudonym::Pseudonymizer>) -> Option<Vec<(usize, usize, f64)>> {
+    let bipartite = match bipartite::BipartiteGraph::from_file(path) {
+        Ok(bipartite) => bipartite,
+        Err(e) => {
+            eprintln!("Could not read {}: {}", path, e);
+            return None;
+        }
+    };
+    let weighting = match arg_value("--projection").as_deref() {
+        Some("newman") => bipartite::ProjectionWeight::Newman,
+        Some("shared") | None => bipartite::ProjectionWeight::Shared,
+        Some(other) => {
+            eprintln!("Unknown projection weighting: {} (expected shared or newman)", other);
+            bipartite::ProjectionWeight::Shared
+        }
+    };
+    let max_thread = arg_value("--max-thread").and_then(|k| k.parse().ok()).unwrap_or(0);
+    let mut weighted_edges = bipartite.project_people(weighting, max_thread);
+    if let Some(p) = pseudonymizer {
+        weighted_edges = p.relabel_edges(&weighted_edges);
+    }
+    println!(
+        "Bipartite graph {}: {} people in {} threads, projected onto {} pairs ({})",
+        path, bipartite.thread_counts().len(), bipartite.members.len(), weighted_edges.len(), weighting
+    );
+    Some(weighted_edges)
+}
+
+// Filters: `--domain <domain>` keeps only addresses of that domain and `--folders <a,b,...>`
+// only the people found in those mailbox folders, before anything is computed
+fn filter_by_address(network: &mut Network, real_email_map: &HashMap<usize, (String, String)>) {
+    let domain = arg_value("--domain").map(|d| format!("@{}", d.trim_start_matches('@').to_lowercase()));
+    let folders: Option<Vec<String>> = arg_value("--folders").map(|f| f.split(',').map(|x| x.trim().to_string()).collect());
+    if domain.is_none() && folders.is_none() {
+        return;
+    }
+    network.graph = filter_nodes(&network.graph, real_email_map, |email, folder| {
+        domain.as_ref().is_none_or(|d| email.to_lowercase().ends_with(d.as_str()))
+            && folders.as_ref().is_none_or(|f| f.iter().any(|x| x == folder))
+    });
+    network.retain_graph_edges();
+    println!("Filtered to {} nodes and {} edges", network.graph.node_count(), network.graph.edge_count());
+}
+
+// Applies the node filter file at `path`. Returns `None`, after reporting why, if it is invalid.
+fn apply_node_filter(network: &mut Network, path: &str, real_email_map: &HashMap<usize, (String, String)>) -> Option<()> {
+    let filter = match nodefilter::NodeFilter::from_file(path) {
+        Ok(filter) => filter,
+        Err(e) => {
+            eprintln!("Invalid node filter: {}", e);
+            return None;
+        }
+    };
+    let (kept, report) = filter.apply(&network.graph, real_email_map);
+    network.graph = kept;
+    network.retain_graph_edges();
+    println!(
+        "Node filter {} removed {} nodes ({} not included, {} by ID, {} by address, {} by degree), leaving {} nodes and {} edges",
+        path, report.removed(), report.not_included, report.excluded_ids, report.excluded_email, report.degree, network.graph.node_count(), network.graph.edge_count()
+    );
+    Some(())
+}
+
+// Sampling: `--sample random-node|random-edge|snowball|forest-fire [--sample-size N] [--sample-seed S]`
+// runs the analysis on a sample of N nodes (default 1000), to try expensive metrics cheaply first.
+// Returns `None`, after reporting it, for an unknown method.
+fn sample_network(network: &mut Network, method: &str) -> Option<()> {
+    let mut config = sampling::SamplingConfig::default();
+    config.method = match method {
+        "random-node" => sampling::SamplingMethod::RandomNode,
+        "random-edge" => sampling::SamplingMethod::RandomEdge,
+        "snowball" => sampling::SamplingMethod::Snowball,
+        "forest-fire" => sampling::SamplingMethod::ForestFire,
+        other => {
+            eprintln!("Unknown sampling method: {} (expected random-node, random-edge, snowball or forest-fire)", other);
+            return None;
+        }
+    };
+    config.size = arg_value("--sample-size").and_then(|n| n.parse().ok()).unwrap_or(config.size);
+    config.seed = arg_value("--sample-seed").and_then(|s| s.parse().ok()).unwrap_or(config.seed);
+    network.graph = sampling::sample(&network.graph, &config);
+    // A sample may leave out edges between the nodes it keeps
+    let kept: HashSet<(usize, usize)> = network.graph.edges().into_iter().flat_map(|(u, v)| [(u, v), (v, u)]).collect();
+    network.weighted_edges.retain(|&(u, v, _)| kept.contains(&(u, v)));
+    println!("{} sample: {} nodes, {} edges", config.method, network.graph.node_count(), network.graph.edge_count());
+    Some(())
+}
+
+// Coarsening: `--coarsen louvain|lpa|components` merges each community (or connected component)
+// into a super-node, prints and plots the summary graph as summary.png; with `--coarsen-analyze`
+// the rest of the analysis runs on the summary graph, whose nodes are labelled by community.
+// Returns `None`, after reporting it, for an unknown method.
+fn coarsen_network(network: Network, method: &str, plot_config: &PlotConfig) -> Option<Network> {
+    let Network { graph, emails, .. } = &network;
+    let assignment = match method {
+        "louvain" => cluster::louvain(graph, 42),
+        "lpa" => cluster::label_propagation(graph, 100, 42),
+        "components" => {
+            let mut components = graph.components();
+            components.sort_by_key(|c| std::cmp::Reverse(c.len()));
+            components.iter().enumerate().flat_map(|(i, c)| c.iter().map(move |&v| (v, i))).map(|(v, i)| (graph.node_id(v), i)).collect()
+        }
+        other => {
+            eprintln!("Unknown coarsening: {} (expected louvain, lpa or components)", other);
+            return None;
+        }
+    };
+    let summary = coarsen::coarsen(graph, &assignment);
+    println!(
+        "\n🧩 Summary Graph ({}): {} super-nodes, {} edges between them, modularity {:.4}",
+        method, summary.graph.node_count(), summary.graph.edge_count(), coarsen::modularity(graph, &assignment)
+    );
+    println!("{:>10} {:>7} {:>10} {:>10}  Most connected member", "Super-node", "Nodes", "Internal", "External");
+    for c in 0..summary.graph.node_count().min(10) {
+        let Some(v) = summary.graph.index_of(c) else { continue };
+        let external = summary.graph.weighted_neighbors(v).fold(0.0, |total, (_, w)| total + w);
+        let hub = summary.members[&c].iter().copied().max_by_key(|&node| (graph.index_of(node).map_or(0, |i| graph.degree(i)), std::cmp::Reverse(node)));
+        println!("{:>10} {:>7} {:>10.0} {:>10.0}  {}", c, summary.members[&c].len(), summary.internal[&c], external, hub.map_or(String::from("-"), |node| emails.label(node)));
+    }
+    // Lay out at most the 2000 largest super-nodes; the layout compares every pair
+    let shown: HashSet<usize> = (0..summary.graph.node_count().min(2000)).collect();
+    let positions = layout::force_directed_layout(&summary.graph, &shown, 100, 42);
+    if let Err(e) = plot_summary_graph(&summary, &positions, plot_config) {
+        eprintln!("Failed to draw the summary graph: {}", e);
+    }
+    if !std::env::args().any(|arg| arg == "--coarsen-analyze") {
+        return Some(network);
+    }
+    let labels: HashMap<usize, (String, String)> =
+        summary.members.iter().map(|(&c, members)| (c, (format!("community {} ({} nodes)", c, members.len()), method.to_string()))).collect();
+    let g = &summary.graph;
+    let weighted_edges = (0..g.node_count())
+        .flat_map(|v| g.weighted_neighbors(v).filter(move |&(w, _)| w > v).map(move |(w, weight)| (g.node_id(v), g.node_id(w), weight)))
+        .collect();
+    println!("Analyzing the summary graph instead of the {} original nodes", graph.node_count());
+    Some(Network { graph: summary.graph, weighted_edges, emails: mapping::EmailIndex::new(labels), directed: network.directed })
+}
+
+// Runs the command given instead of the full analysis, if any: a subcommand on the loaded
+// graph, `--verify`, `--eval-links` or `--ppr`. Returns whether one ran.
+fn run_command(network: &Network, table_style: &table::TableStyle) -> bool {
+    match std::env::args().nth(1).as_deref() {
+        Some("simulate") => run_simulation(network, table_style),
+        Some("search") => run_search(network),
+        Some("hops") => run_hops(network),
+        Some("path") => run_path(network, table_style),
+        Some("distance") => run_distance_oracle(network),
+        _ if has_flag("--verify") => run_verify(network),
+        _ if std::env::args().any(|arg| arg == "--eval-links") => run_link_evaluation(network),
+        _ => match arg_value("--ppr") {
+            Some(query) => run_ppr(network, &query, table_style),
+            None => return false,
+        },
+    }
+    true
+}
+
+// Simulation mode: `simulate [--model sir|sis] [--beta B] [--gamma G] [--steps N] [--runs R]
+// [--seed <email>[,<email>...]]` runs an epidemic simulation and prints the curves
+fn run_simulation(network: &Network, table_style: &table::TableStyle) {
+    let mut config = epidemic::EpidemicConfig::default();
+    if arg_value("--model").is_some_and(|m| m.eq_ignore_ascii_case("sis")) {
+        config.model = epidemic::EpidemicModel::Sis;
+    }
+    config.transmission = arg_value("--beta").and_then(|x| x.parse().ok()).unwrap_or(config.transmission);
+    config.recovery = arg_value("--gamma").and_then(|x| x.parse().ok()).unwrap_or(config.recovery);
+    config.steps = arg_value("--steps").and_then(|x| x.parse().ok()).unwrap_or(config.steps);
+    config.runs = arg_value("--runs").and_then(|x| x.parse().ok()).unwrap_or(config.runs);
+    for email in arg_value("--seed").unwrap_or_default().split(',').filter(|e| !e.is_empty()) {
+        match network.emails.node(email) {
+            Some(node) => config.initial.push(node),
+            None => eprintln!("Unknown email address: {}", email),
+        }
+    }
+
+    let result = epidemic::simulate(&network.graph, &config);
+    println!(
+        "\n🦠 {} Simulation (beta = {}, gamma = {}, {} runs):",
+        config.model, config.transmission, config.recovery, config.runs
+    );
+    println!("{:>4} {:>12} {:>12} {:>12}", "Step", "Susceptible", "Infected", "Recovered");
+    for step in 0..result.infected.len() {
+        println!(
+            "{:>4} {:>12.1} {:>12.1} {:>12.1}",
+            step, result.susceptible[step], result.infected[step], result.recovered[step]
+        );
+    }
+
+    println!("\n🏆 Top 10 by Infection Probability:");
+    let top = stats::top_k(&result.infection_probability, 10);
+    table::ranking(&top, network.emails.map(), &["Probability"], |p| vec![format!("{:.2}", p)]).print(table_style);
+}
+
+// Search: `search <text> [--max-edits N] [--limit N]` lists the people whose address
+// contains or starts with the text, or has a name within N edits of it (default 1, or 2 for
+// queries of 6+ characters), with their degree and PageRank ranks
+fn run_search(network: &Network) {
+    let (graph, emails) = (&network.graph, &network.emails);
+    let text = std::env::args().nth(2).unwrap_or_default();
+    let max_edits = arg_value("--max-edits").and_then(|n| n.parse().ok()).unwrap_or(if text.len() >= 6 { 2 } else { 1 });
+    let limit = arg_value("--limit").and_then(|n| n.parse().ok()).unwrap_or(25);
+    let matches = emails.search(&text, max_edits);
+    if matches.is_empty() {
+        eprintln!("No address matches {}", text);
+        return;
+    }
+    let degree: HashMap<usize, usize> = (0..graph.node_count()).map(|v| (graph.node_id(v), graph.degree(v))).collect();
+    let degree_rank = stats::ranks(&degree);
+    let pagerank_rank = stats::ranks(&pagerank::pagerank(graph, 0.85, 1e-10, 200));
+    let rank = |ranks: &HashMap<usize, usize>, node: usize| ranks.get(&node).map_or(String::from("-"), |r| format!("#{}", r));
+    println!("\n🔎 {} Matches for \"{}\" ({} shown):", matches.len(), text, matches.len().min(limit));
+    println!("{:>7}  {:<40} {:<14} {:<10} {:>7} {:>8} {:>9}", "Node", "Email", "Folder", "Match", "Degree", "Deg rank", "PR rank");
+    for m in matches.iter().take(limit) {
+        println!(
+            "{:>7}  {:<40} {:<14} {:<10} {:>7} {:>8} {:>9}",
+            m.node,
+            emails.label(m.node),
+            emails.folder(m.node).unwrap_or(""),
+            m.kind.to_string(),
+            degree.get(&m.node).map_or(0, |&d| d),
+            rank(&degree_rank, m.node),
+            rank(&pagerank_rank, m.node)
+        );
+    }
+}
+
+// Neighborhood query: `hops <email|node> [--hops N] [--json]` lists everyone within N hops
+// (default 2) with their distance and degree
+fn run_hops(network: &Network) {
+    let email_map = network.emails.map();
+    let target = std::env::args().nth(2).unwrap_or_default();
+    let hops = arg_value("--hops").and_then(|h| h.parse().ok()).unwrap_or(2);
+    let neighbors = network.emails.resolve(&target).and_then(|node| query::n_hop_neighbors(&network.graph, node, hops));
+    match neighbors {
+        Some(neighbors) if std::env::args().any(|arg| arg == "--json") => println!("{}", query::neighbors_json(&neighbors, email_map)),
+        Some(neighbors) => {
+            println!("\n🔭 {} Nodes within {} Hops of {}:", neighbors.len(), hops, target);
+            for line in query::neighbors_table(&neighbors, email_map) {
+                println!("{}", line);
+            }
+        }
+        None => eprintln!("Unknown node or email address: {}", target),
+    }
+}
+
+// Path query: `path <email|node> <email|node> [--all N]` prints one shortest chain of
+// correspondents between two people (or the number of shortest chains and up to N of them)
+fn run_path(network: &Network, table_style: &table::TableStyle) {
+    let (graph, emails, email_map) = (&network.graph, &network.emails, network.emails.map());
+    let from = std::env::args().nth(2).unwrap_or_default();
+    let to = std::env::args().nth(3).unwrap_or_default();
+    if let Some(limit) = arg_value("--all").and_then(|n| n.parse().ok()) {
+        let ends = emails.resolve(&from).zip(emails.resolve(&to));
+        match ends.and_then(|(a, b)| query::all_shortest_paths(graph, a, b, limit)) {
+            Some(all) => {
+                println!("\n🧭 {} Shortest Paths of {} Hops from {} to {} ({} shown):", all.count, all.length, from, to, all.paths.len());
+                for path in &all.paths {
+                    let hops: Vec<String> = path
+                        .iter()
+                        .map(|node| email_map.get(node).map_or_else(|| node.to_string(), |(email, _)| email.clone()))
+                        .collect();
+                    println!("{}", hops.join(" → "));
+                }
+            }
+            None => eprintln!("No path between {} and {} (unknown address or not connected)", from, to),
+        }
+        return;
+    }
+    match query::shortest_path(graph, emails, &from, &to) {
+        Some(path) => {
+            println!("\n🧭 Shortest Path from {} to {} ({} hops):", from, to, path.len() - 1);
+            let mut hops = Table::new(&[("Hop", Align::Right), ("Node", Align::Right), ("Email", Align::Left), ("Folder", Align::Left)]);
+            for (i, node) in path.iter().enumerate() {
+                let (email, folder) = email_map.get(node).map_or(("?", "?"), |(e, f)| (e.as_str(), f.as_str()));
+                hops.row(vec![i.to_string(), node.to_string(), email.to_string(), folder.to_string()]);
+            }
+            hops.print(table_style);
+        }
+        None => eprintln!("No path between {} and {} (unknown address or not connected)", from, to),
+    }
+}
+
+// Distance oracle: `distance [--landmarks K]` builds a landmark index (default 16 landmarks)
+// once, then answers "<email|node> <email|node>" queries read line by line from stdin
+fn run_distance_oracle(network: &Network) {
+    let (graph, emails) = (&network.graph, &network.emails);
+    let count = arg_value("--landmarks").and_then(|k| k.parse().ok()).unwrap_or(16);
+    let index = distance::LandmarkIndex::build(graph, count);
+    // Components answer "not connected" without a search
+    let mut components = unionfind::UnionFind::from_graph(graph);
+    println!("\n📍 Landmark index ready ({} landmarks); enter two addresses or node IDs per line:", count);
+    for line in std::io::stdin().lines().map_while(Result::ok) {
+        let ends: Vec<&str> = line.split_whitespace().collect();
+        let [from, to] = ends[..] else {
+            eprintln!("Expected two addresses or node IDs");
+            continue;
+        };
+        let pair = emails.resolve(from).zip(emails.resolve(to));
+        let Some((a, b)) = pair.and_then(|(a, b)| graph.index_of(a).zip(graph.index_of(b))) else {
+            println!("{} → {}: unknown address", from, to);
+            continue;
+        };
+        if !components.same_set(a, b) {
+            let sizes = (components.set_size(a), components.set_size(b));
+            println!("{} → {}: not connected (components of {} and {} nodes)", from, to, sizes.0, sizes.1);
+            continue;
+        }
+        match index.estimate(graph, graph.node_id(a), graph.node_id(b)) {
+            Some(d) if d.lower == d.upper => println!("{} → {}: {} hops", from, to, d.lower),
+            Some(d) => println!("{} → {}: {} to {} hops", from, to, d.lower, d.upper),
+            None => println!("{} → {}: not connected", from, to),
+        }
+    }
+}
+
+// Verification mode: `--verify [samples]` cross-checks degrees, components and shortest-path
+// distances against petgraph on sampled subgraphs (default 20), exiting with status 1 if
+// anything differs; petgraph is only built with `--features verify`
+#[cfg_attr(not(feature = "verify"), allow(unused_variables))]
+fn run_verify(network: &Network) {
+    #[cfg(feature = "verify")]
+    {
+        let mut config = project::verify::VerifyConfig::default();
+        config.samples = arg_value("--verify").and_then(|n| n.parse().ok()).unwrap_or(config.samples);
+        println!(
+            "\n🔬 Reference Check against petgraph ({} samples of {} nodes, {} sources each):",
+            config.samples, config.sample_size, config.sources
+        );
+        let mut failed = false;
+        for check in project::verify::verify(&network.graph, &config) {
+            println!("{:<28} {:>9} checked {:>6} mismatches", check.name, check.checked, check.failures.len());
+            for failure in check.failures.iter().take(3) {
+                println!("  {}", failure);
+            }
+            failed |= !check.failures.is_empty();
+        }
+        if failed {
+            std::process::exit(1);
+        }
+    }
+    #[cfg(not(feature = "verify"))]
+    eprintln!("Verification needs petgraph: rebuild with `cargo run --release --features verify -- --verify`");
+}
+
+// Evaluation mode: `--eval-links` hides 10% of the edges and reports how well
+// each link predictor recovers them
+fn run_link_evaluation(network: &Network) {
+    println!("\n🧪 Link Prediction Evaluation (10% of edges hidden):");
+    println!("{:<24} {:>7} {:>14}", "Predictor", "AUC", "Precision@100");
+    for result in linkpred::evaluate_link_prediction(&network.graph, 0.1, 100, 10000, 42) {
+        println!("{:<24} {:>7.4} {:>14.2}", result.predictor.to_string(), result.auc, result.precision_at_k);
+    }
+}
+
+// Query mode: `--ppr <email>[,<email>...] [--top-k N]` ranks the nodes most relevant
+// to the seeds' neighborhood by personalized PageRank
+fn run_ppr(network: &Network, query: &str, table_style: &table::TableStyle) {
+    let top_k = arg_value("--top-k").and_then(|k| k.parse().ok()).unwrap_or(10);
+    let mut seeds = Vec::new();
+    for email in query.split(',') {
+        match network.emails.node(email) {
+            Some(node) => seeds.push(node),
+            None => eprintln!("Unknown email address: {}", email),
+        }
+    }
+    if seeds.is_empty() {
+        return;
+    }
+    let ppr = pagerank::personalized_pagerank(&network.graph, &seeds, 0.85, 1e-10, 200);
+    let others: HashMap<usize, f64> = ppr.into_iter().filter(|(node, _)| !seeds.contains(node)).collect();
+    println!("\n🎯 Top {} Nodes by Personalized PageRank from {}:", top_k, query);
+    table::ranking(&stats::top_k(&others, top_k), network.emails.map(), &["Score"], |s| vec![format!("{:.6}", s)]).print(table_style);
+}
+
+// Runs the full analysis: the network summary and baselines, the centrality pipeline and the
+// reports built on it, the clusterings, the plots and the manifest of the run. Returns false
+// if any plot failed, after drawing the others.
+fn run_analysis(network: &Network, options: &Options, run_dir: &mut rundir::RunDirectory, plot_config: &PlotConfig) -> bool {
+    let (graph, email_map, table_style) = (&network.graph, network.emails.map(), &options.table_style);
+    let edges = network.edges();
+    let metric_cache = metric_cache(graph);
+    let memory_report = print_memory_report(network, options);
+
+    let summary = print_network_summary(network, &edges);
+    let giant = print_distances(graph, email_map);
+    print_baselines(graph, &summary);
+    print_significance(graph);
+
+    let analysis = run_pipeline(network, &edges, options, plot_config, &metric_cache);
+    // One row per node in scope with all of its metrics, next to the plots
+    if let Err(e) = analysis.write_csv(run_dir.file("metrics.csv"), email_map) {
+        eprintln!("Failed to write metrics.csv: {}", e);
+    }
+    let degree = analysis.degree.unwrap_or_default();
+    let top_nodes = analysis.nodes;
+    // How the nodes in scope are described in the reports, e.g. "top 1000 nodes"
+    let scope_label = match options.scope {
+        CentralityScope::All => "all nodes".to_string(),
+        CentralityScope::TopN => format!("top {} nodes", top_nodes.len()),
+        CentralityScope::Component => format!("giant component, {} nodes", top_nodes.len()),
+    };
+    let closeness = analysis.closeness.unwrap_or_default();
+    let betweenness = analysis.betweenness.unwrap_or_default();
+    let features = analysis.features.unwrap_or_default();
+
+    print_degree_rankings(network, options, &analysis.activity.unwrap_or_default(), &analysis.in_out.unwrap_or_default());
+    print_closeness_rankings(network, options, &closeness, &scope_label, &metric_cache);
+    let between_sorted = print_betweenness_rankings(network, options, &betweenness, &scope_label, &giant);
+    print_structure(network, table_style);
+    let curves = print_robustness(graph, &betweenness);
+    print_cascades(network, &between_sorted, table_style);
+
+    // Closeness and betweenness of exactly the nodes in scope, zero scores included, so the
+    // reports below describe the population their labels name
+    let in_scope = |m: &HashMap<usize, f64>| -> HashMap<usize, f64> {
+        top_nodes.iter().map(|&n| (n, m.get(&n).copied().unwrap_or(0.0))).collect()
+    };
+    let scoped_closeness = in_scope(&closeness);
+    let scoped_betweenness = in_scope(&betweenness);
+    let lorenz = print_concentration(&degree, &scoped_closeness, &scoped_betweenness, &scope_label);
+
+    // How similarly the centrality measures score the top nodes
+    let as_f64 = |m: &HashMap<usize, usize>| m.iter().map(|(&n, &d)| (n, d as f64)).collect();
+    let measures = [
+        ("Degree", as_f64(&degree)),
+        ("Closeness", scoped_closeness),
+        ("Betweenness", scoped_betweenness),
+        ("PageRank", analysis.pagerank.unwrap_or_default()),
+    ];
+    let names: Vec<&str> = measures.iter().map(|(name, _)| *name).collect();
+    let spearman = print_correlations(&measures, &scope_label);
+
+    let clusters = print_components(network, &edges, &degree, table_style);
+    // All-pairs distances and subgraph centrality work on the subgraph induced by the top nodes
+    let mut core_nodes: Vec<usize> = top_nodes.iter().filter_map(|&id| graph.index_of(id)).collect();
+    core_nodes.sort_unstable();
+    let core = graph.subgraph(&core_nodes);
+    print_group_centrality(network, &core, table_style);
+
+    // Laplacian eigenmap of the giant component, used for plotting and optionally for clustering
+    let spectral = embedding::laplacian_eigenmap(graph, &giant, 2, 1000, 42);
+    println!("\n🌈 Laplacian Eigenmap (giant component):");
+    for (i, value) in spectral.eigenvalues.iter().enumerate() {
+        println!("Eigenvalue {}: {:.5}", i + 1, value);
+    }
+
+    // Subgraph centrality (closed walks weighted by 1 / k!) within the subgraph of the top nodes
+    let subgraph_scores = if options.use_subgraph_centrality { project::spectral::subgraph_centrality(&core) } else { HashMap::new() };
+    if options.use_subgraph_centrality {
+        println!("\n🔁 Top 10 by Subgraph Centrality (within the top {} nodes, natural log):", core.node_count());
+        let top = stats::top_k(&subgraph_scores, 10);
+        table::ranking(&top, email_map, &["ln(centrality)"], |s| vec![format!("{:.3}", s.ln())]).print(table_style);
+    }
+
+    let reclustered = recluster(network, options, &features, &spectral.coords, &subgraph_scores, run_dir);
+    let (assignments, kmeans_trace) = match &reclustered {
+        Some((assignments, trace)) => (assignments.clone(), trace.as_slice()),
+        None => (analysis.assignments.unwrap_or_default(), &[][..]),
+    };
+    print_clusters(network, &assignments, options.k, &top_nodes);
+
+    // Generate plots
+    let log_histogram = HistogramOptions { bins: 50, log_bins: true, log_y: true };
+    if options.text_plots {
+        print_text_plots(&degree, &betweenness, email_map, &log_histogram);
+    }
+    // Every plot is drawn even if an earlier one fails; any failure is reported and ends the run with an error
+    type PlotResult = Result<(), Box<dyn std::error::Error>>;
+    let mut plots: Vec<(&str, PlotResult)> = Vec::new();
+    // The pipeline drew the cluster plots of its own clusters; redraw them for the new ones
+    if reclustered.is_some() {
+        plots.push(("clusters", plot_clusters(&features, &assignments, plot_config)));
+        plots.push(("3D clusters", plot_clusters_3d(&features, &assignments, plot_config)));
+        plots.push(("cluster profiles", plot_cluster_profiles(&features, &assignments, plot_config)));
+        plots.push(("dashboard", plot_dashboard(&degree, &betweenness, &closeness, &features, &assignments, &log_histogram, plot_config)));
+    }
+    if !kmeans_trace.is_empty() {
+        plots.push(("k-means animation", plot_kmeans_animation(&features, kmeans_trace, 500, plot_config)));
+    }
+    plots.push(("scatter matrix", plot_scatter_matrix(&measures, plot_config)));
+    plots.push(("correlation heatmap", plot_correlation_heatmap(&names, &spearman, correlation::CorrelationMethod::Spearman, plot_config)));
+    plots.push(("cluster sizes", plot_cluster_sizes(&clusters, &assignments, 30, plot_config)));
+    plots.push(("spectral embedding", plot_embedding_2d(&spectral.coords, &assignments, plot_config)));
+    plots.push(("robustness curves", plot_robustness(&curves, plot_config)));
+    plots.push(("Lorenz curves", plot_lorenz_curves(&lorenz, plot_config)));
+    let positions = layout::force_directed_layout(graph, &top_nodes, 100, 42);
+    plots.push(("communities", plot_communities(graph, &positions, &assignments, plot_config)));
+    plots.push(("adjacency matrix", plot_adjacency_matrix(graph, &assignments, plot_config)));
+    let mut plot_failed = false;
+    for (name, result) in plots {
+        if let Err(e) = result {
+            eprintln!("Failed to draw the {}: {}", name, e);
+            plot_failed = true;
+        }
+    }
+
+    write_manifest(run_dir, network, options);
+    if let Some(dir) = metric_cache.dir() {
+        let (reused, computed) = metric_cache.stats();
+        println!("\n💾 Metric cache ({}): {} maps reused, {} computed", dir.display(), reused, computed);
+    }
+    if let Some(peak) = memory::peak_rss().filter(|_| memory_report) {
+        println!("\n🧠 Peak resident memory of the run: {}", memory::format_bytes(peak));
+    }
+    !plot_failed
+}
+
+// Centrality maps are cached under the graph's fingerprint and their parameters in
+// `--cache-dir DIR` (default `$PROJECT_CACHE_DIR`, or metric_cache), so that a rerun on the
+// same graph reads them back; `--no-cache` computes everything
+fn metric_cache(graph: &Graph) -> cache::MetricCache {
+    if std::env::args().any(|arg| arg == "--no-cache") {
+        cache::MetricCache::disabled()
+    } else {
+        cache::MetricCache::new(&arg_value("--cache-dir").map_or_else(cache::default_cache_dir, std::path::PathBuf::from), graph)
+    }
+}
+
+// Memory accounting: `--memory-report` prints the estimated memory of the loaded graph and the
+// peak auxiliary memory of every step this run will take; `--memory-budget SIZE` (e.g. 4G) also
+// warns, before any of them runs, about the steps likely to exceed it. Returns whether the
+// report was printed.
+fn print_memory_report(network: &Network, options: &Options) -> bool {
+    let graph = &network.graph;
+    let memory_budget = arg_value("--memory-budget").and_then(|b| {
+        let size = memory::parse_size(&b);
+        if size.is_none() {
+            eprintln!("Unknown memory budget {}, expected a size such as 512M or 4G", b);
+        }
+        size
+    });
+    if memory_budget.is_none() && !std::env::args().any(|arg| arg == "--memory-report") {
+        return false;
+    }
+    let shape = memory::GraphShape::of(graph);
+    let top = match options.scope {
+        CentralityScope::All => graph.node_count(),
+        CentralityScope::TopN => graph.node_count().min(options.top_n),
+        CentralityScope::Component => graph.components().iter().map(|c| c.len()).max().unwrap_or(0),
+    };
+    let mut plan = vec![
+        memory::Algorithm::DistanceStats,
+        memory::Algorithm::HyperAnf { log2m: 8 },
+        memory::Algorithm::Baselines,
+        memory::Algorithm::NullModel,
+        memory::Algorithm::BatchedCloseness,
+        memory::Algorithm::Betweenness { sources: top },
+    ];
+    if std::env::args().any(|arg| arg == "--closeness-all") {
+        plan.push(memory::Algorithm::ClosenessAll);
+    }
+    if std::env::args().any(|arg| arg == "--betweenness-topk") {
+        plan.push(memory::Algorithm::Betweenness { sources: graph.node_count() });
+    }
+    if top <= arg_value("--apsp-max").and_then(|m| m.parse().ok()).unwrap_or(5000) {
+        plan.push(memory::Algorithm::AllPairs { nodes: top });
+    }
+    plan.push(memory::Algorithm::Eigenmap { dimensions: 2 });
+    if options.use_subgraph_centrality {
+        plan.push(memory::Algorithm::SubgraphCentrality { nodes: top });
+    }
+    if options.use_embeddings {
+        plan.push(memory::Algorithm::Node2Vec(embedding::Node2VecConfig::default()));
+    }
+    if options.use_graphlets {
+        plan.push(memory::Algorithm::Graphlets);
+    }
+
+    let (graph_bytes, mapping_bytes) = (graph.heap_bytes(), memory::mapping_bytes(network.emails.map()));
+    // Whatever else the process holds (edge lists, indexes) counts against the budget too
+    let base = memory::current_rss().unwrap_or(0).max(graph_bytes + mapping_bytes);
+    println!("\n🧠 Memory Estimate (threads: {}):", shape.threads);
+    println!("Graph: {} ({} nodes, {} edges)", memory::format_bytes(graph_bytes), shape.nodes, shape.edges);
+    println!("Email mapping: {}", memory::format_bytes(mapping_bytes));
+    if let Some(rss) = memory::current_rss() {
+        println!("Resident memory so far: {}", memory::format_bytes(rss));
+    }
+    println!("{:<34} {:>12} {:>12}", "Step", "Auxiliary", "Total");
+    for step in &plan {
+        let aux = step.aux_bytes(&shape);
+        println!("{:<34} {:>12} {:>12}", step.to_string(), memory::format_bytes(aux), memory::format_bytes(base + aux));
+    }
+    if let Some(budget) = memory_budget {
+        let over = memory::over_budget(base, &plan, &shape, budget);
+        if over.is_empty() {
+            println!("Every step fits the budget of {}", memory::format_bytes(budget));
+        }
+        for (step, total) in over {
+            eprintln!("⚠️ {} is likely to exceed the budget of {}: about {}", step, memory::format_bytes(budget), memory::format_bytes(total));
         }
     }
+    true
+}
+
+// Prints basic network statistics, and the reciprocity of the directed edge list; an
+// undirected list (SNAP lists every edge both ways) is trivially reciprocal, so it is only
+// reported in directed mode.
+fn print_network_summary(network: &Network, edges: &[(usize, usize)]) -> GraphSummary {
+    let summary = network.graph.summary();
+    println!("\n📊 Network Summary:");
+    println!("Nodes: {}", summary.nodes);
+    println!("Edges: {}", summary.edges);
+    println!("Density: {:.6}", summary.density);
+    println!("Average degree: {:.3}", summary.avg_degree);
+    println!("Max degree: {}", summary.max_degree);
+    println!("Connected components: {}", summary.components);
+    println!("Giant component size: {} nodes", summary.giant_component);
+    if network.directed {
+        let recip = directed::reciprocity(edges);
+        println!("Reciprocity: {:.3} ({} mutual pairs, {} one-way pairs)", recip.overall, recip.mutual, recip.one_way);
+    }
+    summary
+}
+
+// Prints the diameter and path lengths of the giant component, the HyperANF distance
+// distribution, and the eccentricity-based center and periphery. Returns the compact
+// indices of the giant component.
+fn print_distances(graph: &Graph, email_map: &HashMap<usize, (String, String)>) -> Vec<usize> {
+    let distances = distance::distance_stats(graph, true);
+    println!("Diameter (giant component): {}", distances.diameter);
+    println!("Average shortest path length (giant component): {:.3}", distances.avg_path_length);
 
-    // Compute betweenness centrality for top nodes and print Top 10
-    println!("\n🏆 Top 10 by Betweenness Centrality (top 1000 nodes only):");
-    let betweenness = compute_betweenness(&edges, &top_nodes);
-    let mut between_sorted: Vec<_> = betweenness.clone().into_iter().collect();
-    between_sorted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    // Approximate distance distribution with HyperANF
+    let neighborhood = distance::hyper_anf(graph, 8, 50);
+    println!("Effective diameter (90%, HyperANF): {:.2}", neighborhood.effective_diameter(0.9));
+    println!("Distance distribution (HyperANF):");
+    for (t, pairs) in neighborhood.distance_distribution().iter().enumerate() {
+        println!("  distance {:>2}: {:.0} pairs", t + 1, pairs);
+    }
 
-    for (i, (node, score)) in between_sorted.iter().take(10).enumerate() {
+    // Eccentricity-based center and periphery of the giant component
+    let giant = graph.components().into_iter().max_by_key(|c| c.len()).unwrap_or_default();
+    let ecc = distance::eccentricity(graph, &giant);
+    println!("Radius (giant component): {}", ecc.radius);
+    println!("Center ({} nodes, first 10 shown):", ecc.center.len());
+    for node in ecc.center.iter().take(10) {
         if let Some((email, folder)) = email_map.get(node) {
-            println!("{:>2}. Node {} ({}) [{}]: {:.5}", i + 1, node, email, folder, score);
+            println!("  Node {} ({}) [{}]", node, email, folder);
+        }
+    }
+    println!("Periphery: {} nodes at eccentricity {}", ecc.periphery.len(), ecc.diameter);
+    giant
+}
+
+// Compares the graph against same-size synthetic graphs.
+fn print_baselines(graph: &Graph, summary: &GraphSummary) {
+    let n = summary.nodes;
+    let per_node = (summary.edges as f64 / n as f64).round() as usize;
+    let erdos_renyi = Graph::from_edges(&generate::erdos_renyi(n, summary.edges, 42));
+    let barabasi_albert = Graph::from_edges(&generate::barabasi_albert(n, per_node, 42));
+    let watts_strogatz = Graph::from_edges(&generate::watts_strogatz(n, 2 * per_node, 0.1, 42));
+    let rewired = Graph::from_edges(&generate::degree_preserving_rewire(graph, 10, 42));
+    let baselines = [
+        ("Enron", graph),
+        ("Same degrees", &rewired),
+        ("Erdős–Rényi", &erdos_renyi),
+        ("Barabási–Albert", &barabasi_albert),
+        ("Watts–Strogatz", &watts_strogatz),
+    ];
+    println!("\n🎲 Synthetic Baselines of the Same Size:");
+    println!("{:<16} {:>7} {:>8} {:>10} {:>11} {:>7} {:>10}", "Graph", "Nodes", "Edges", "Max degree", "Components", "Giant", "Eff. diam.");
+    for (name, baseline) in baselines {
+        let s = baseline.summary();
+        println!(
+            "{:<16} {:>7} {:>8} {:>10} {:>11} {:>7} {:>10.2}",
+            name, s.nodes, s.edges, s.max_degree, s.components, s.giant_component,
+            distance::hyper_anf(baseline, 8, 50).effective_diameter(0.9)
+        );
+    }
+}
+
+// Prints the significance of clustering and assortativity against degree-preserving
+// randomizations, and the small-world coefficients against degree-preserving random and
+// ring-lattice references.
+fn print_significance(graph: &Graph) {
+    let nulls = nullmodel::null_graphs(graph, nullmodel::NullModel::SameDegrees, 10, 42);
+    let tests = [
+        nullmodel::significance(graph, &nulls, "Transitivity", |g| g.transitivity()),
+        nullmodel::significance(graph, &nulls, "Degree assortativity", |g| g.degree_assortativity()),
+    ];
+    println!("\n🧪 Null-Model Significance (10 degree-preserving randomizations):");
+    for test in &tests {
+        println!(
+            "{:<22} observed {:.4}, random {:.4} ± {:.4}, z = {:.1}, p = {:.3}",
+            test.name, test.observed, test.null_mean, test.null_std, test.z_score, test.p_value
+        );
+    }
+
+    let world = smallworld::small_world(graph, 3, 100, 42);
+    println!("\n🌍 Small-World Coefficients (3 random references, 100 BFS sources):");
+    println!("Clustering: {:.4} (random {:.4}, lattice {:.4})", world.clustering, world.random_clustering, world.lattice_clustering);
+    println!("Path length: {:.3} (random {:.3})", world.path_length, world.random_path_length);
+    println!("Sigma: {:.2}, omega: {:.3}", world.sigma, world.omega);
+}
+
+// Degree of every node; closeness and betweenness of the nodes in scope (from them as
+// sources); PageRank; k-means of the nodes in scope; and the main plots. Exits with an
+// error if the pipeline fails.
+fn run_pipeline(network: &Network, edges: &[(usize, usize)], options: &Options, plot_config: &PlotConfig, metric_cache: &cache::MetricCache) -> AnalysisResult {
+    let pipeline = AnalysisPipeline::new()
+        .scope(options.scope)
+        .top_nodes(options.top_n)
+        .max_iters(options.max_iters)
+        .with_degree()
+        .degree_measure(options.measure)
+        .with_closeness()
+        .closeness_mode(options.closeness_mode)
+        .with_betweenness(BetweennessMode::Scope)
+        .betweenness_normalization(options.normalization)
+        .with_pagerank()
+        .with_kmeans(options.k)
+        .with_plot_config(plot_config.clone())
+        .cache(metric_cache);
+    // In directed mode the degree stage also counts who sent to whom
+    let pipeline = if network.directed { pipeline.directed(edges) } else { pipeline };
+    match pipeline.run(&network.graph, network.emails.map()) {
+        Ok(analysis) => analysis,
+        Err(e) => {
+            eprintln!("Analysis failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// Prints the top 10 by degree (or strength), the power-law fit of the degree distribution,
+// and in directed mode the top senders and receivers.
+fn print_degree_rankings(network: &Network, options: &Options, activity: &HashMap<usize, f64>, in_out: &HashMap<usize, InOutDegree>) {
+    let (graph, email_map, table_style) = (&network.graph, network.emails.map(), &options.table_style);
+    match options.measure {
+        DegreeMeasure::Degree => println!("\n🏆 Top 10 by Degree Centrality:"),
+        DegreeMeasure::Strength => println!("\n🏆 Top 10 by Strength Centrality:"),
+    }
+    let top_activity = stats::top_k(activity, 10);
+    match options.measure {
+        DegreeMeasure::Degree => table::ranking(&top_activity, email_map, &["Connections"], |v| vec![v.to_string()]),
+        DegreeMeasure::Strength => table::ranking(&top_activity, email_map, &["Total weight"], |v| vec![format!("{:.1}", v)]),
+    }
+    .print(table_style);
+
+    // Scale-free assessment of the (undirected) degree distribution
+    let degrees: Vec<usize> = (0..graph.node_count()).map(|v| graph.degree(v)).collect();
+    if let Some(fit) = powerlaw::fit_power_law(&degrees, 50) {
+        println!("\n📈 Power-Law Fit of the Degree Distribution:");
+        println!("alpha = {:.3}, xmin = {}, KS distance = {:.4}, tail = {} nodes", fit.alpha, fit.xmin, fit.ks, fit.tail_size);
+    }
+
+    // In directed mode, senders and receivers are ranked separately
+    if network.directed {
+        let out_degree: HashMap<usize, usize> = in_out.iter().map(|(&node, deg)| (node, deg.out_deg)).collect();
+        println!("\n🏆 Top 10 Senders (Out-Degree):");
+        table::ranking(&stats::top_k(&out_degree, 10), email_map, &["Recipients"], |d| vec![d.to_string()]).print(table_style);
+
+        let in_degree: HashMap<usize, usize> = in_out.iter().map(|(&node, deg)| (node, deg.in_deg)).collect();
+        println!("\n🏆 Top 10 Receivers (In-Degree):");
+        table::ranking(&stats::top_k(&in_degree, 10), email_map, &["Senders"], |d| vec![d.to_string()]).print(table_style);
+    }
+}
+
+// Prints the top 10 by closeness, and the sampled or all-node closeness asked for.
+fn print_closeness_rankings(network: &Network, options: &Options, closeness: &HashMap<usize, f64>, scope_label: &str, metric_cache: &cache::MetricCache) {
+    let graph = &network.graph;
+    println!("\n🏆 Top 10 by Closeness Centrality ({}):", options.closeness_mode);
+    table::ranking(&stats::top_k(closeness, 10), network.emails.map(), &["Closeness"], |s| vec![format!("{:.5}", s)]).print(&options.table_style);
+
+    // `--closeness-sample [epsilon]` estimates closeness for every node from sampled pivots
+    // (default epsilon 0.1) and checks the estimate against the exact scores of the nodes in scope
+    if has_flag("--closeness-sample") {
+        let epsilon = arg_value("--closeness-sample").and_then(|e| e.parse().ok()).unwrap_or(0.1);
+        let pivots = distance::pivots_for_error(graph.node_count(), epsilon).min(graph.node_count());
+        let mut estimate = distance::approximate_closeness(graph, pivots, 42);
+        adjust_closeness(graph, &mut estimate, options.closeness_mode);
+        let worst = closeness
+            .iter()
+            .filter(|(_, &exact)| exact > 0.0)
+            .map(|(node, exact)| (estimate[node] - exact).abs() / exact)
+            .fold(0.0, f64::max);
+        println!(
+            "Sampled closeness of all {} nodes ({} pivots, epsilon {}): max relative error on the {} {:.2}%",
+            estimate.len(), pivots, epsilon, scope_label, 100.0 * worst
+        );
+    }
+
+    // `--closeness-all` also computes closeness for every node, not only the nodes in scope
+    if std::env::args().any(|arg| arg == "--closeness-all") {
+        let all_nodes: HashSet<usize> = (0..graph.node_count()).map(|v| graph.node_id(v)).collect();
+        let start = std::time::Instant::now();
+        let mut all_closeness = metric_cache.get_or_compute("hop-closeness", "sources=all", || parallel_closeness(graph, &all_nodes, options.threads));
+        adjust_closeness(graph, &mut all_closeness, options.closeness_mode);
+        if let Some(s) = stats::describe(&all_closeness, &[]) {
+            println!(
+                "Closeness of all {} nodes ({:.1}s): mean {:.5}, median {:.5}, max {:.5}",
+                s.count, start.elapsed().as_secs_f64(), s.mean, s.median, s.max
+            );
+        }
+    }
+}
+
+// Prints the top 10 by betweenness, and the whole-graph, checkpointed or current-flow
+// betweenness asked for. Returns the top 10 nodes in scope by betweenness.
+fn print_betweenness_rankings(network: &Network, options: &Options, betweenness: &HashMap<usize, f64>, scope_label: &str, giant: &[usize]) -> Vec<(usize, f64)> {
+    let (graph, email_map, table_style) = (&network.graph, network.emails.map(), &options.table_style);
+    // Weighted edge lists switch to Dijkstra-based shortest paths automatically
+    let paths = if graph.is_weighted() { "weighted paths" } else { "hop counts" };
+    println!("\n🏆 Top 10 by Betweenness Centrality ({} only, {}, {} normalization):", scope_label, paths, options.normalization);
+    let between_sorted = stats::top_k(betweenness, 10);
+    table::ranking(&between_sorted, email_map, &["Betweenness"], |s| vec![format!("{:.5}", s)]).print(table_style);
+
+    // `--betweenness-topk` finds the top 10 over the whole graph by progressive source sampling
+    if std::env::args().any(|arg| arg == "--betweenness-topk") {
+        let result = top_k_betweenness(&graph.reordered(NodeOrder::Bfs), &TopKBetweennessConfig::default());
+        println!(
+            "\n🏆 Top 10 by Betweenness Centrality (all nodes, {} sampled sources{}):",
+            result.samples,
+            if result.separated { "" } else { ", not separated" }
+        );
+        table::ranking(&result.top, email_map, &["Betweenness"], |s| vec![format!("{:.0}", s)]).print(table_style);
+    }
+
+    // `--betweenness-all [--checkpoint FILE] [--checkpoint-every SECS]` computes exact betweenness
+    // from every source, saving progress to the checkpoint file (default betweenness.checkpoint,
+    // every 60 seconds); an interrupted run started again with the same options resumes from it
+    if std::env::args().any(|arg| arg == "--betweenness-all") {
+        let mut config = checkpoint::CheckpointConfig::default();
+        if let Some(path) = arg_value("--checkpoint") {
+            config.path = path.into();
+        }
+        if let Some(secs) = arg_value("--checkpoint-every").and_then(|s| s.parse().ok()) {
+            config.interval = Duration::from_secs(secs);
+        }
+        let all_nodes: HashSet<usize> = (0..graph.node_count()).map(|v| graph.node_id(v)).collect();
+        let start = std::time::Instant::now();
+        let progress = |done: usize, total: usize| {
+            eprintln!("  checkpoint: {}/{} sources ({:.0}s)", done, total, start.elapsed().as_secs_f64());
+        };
+        match checkpoint::resumable_betweenness(&graph.reordered(NodeOrder::Bfs), &all_nodes, options.normalization, &config, progress) {
+            Ok(result) => {
+                if result.discarded {
+                    eprintln!("Ignored {}: it belongs to another graph or source set", config.path.display());
+                }
+                println!(
+                    "\n🏆 Top 10 by Betweenness Centrality (all {} sources, {} resumed from a checkpoint, {:.1}s):",
+                    result.sources, result.resumed, start.elapsed().as_secs_f64()
+                );
+                let top = stats::top_k(&result.centrality, 10);
+                table::ranking(&top, email_map, &["Betweenness"], |s| vec![format!("{:.5}", s)]).print(table_style);
+            }
+            Err(e) => eprintln!("Betweenness checkpoint {} failed: {}", config.path.display(), e),
+        }
+    }
+
+    // `--current-flow [pairs]` ranks the giant component by current-flow betweenness, which
+    // counts every route between two people rather than only the shortest (default 100 pairs)
+    if has_flag("--current-flow") {
+        let mut config = flow::CurrentFlowConfig::default();
+        config.pairs = arg_value("--current-flow").and_then(|p| p.parse().ok()).unwrap_or(config.pairs);
+        let current_flow = flow::current_flow_betweenness(graph, giant, &config);
+        println!("\n🌊 Top 10 by Current-Flow Betweenness (giant component, {} sampled pairs):", config.pairs);
+        table::ranking(&stats::top_k(&current_flow, 10), email_map, &["Current flow"], |s| vec![format!("{:.5}", s)]).print(table_style);
+    }
+    between_sorted
+}
+
+// Prints the structural reports: articulation points, biconnected components, large cliques,
+// the motif census, predicted links and the best broadcast seeds.
+fn print_structure(network: &Network, table_style: &table::TableStyle) {
+    let (graph, email_map) = (&network.graph, network.emails.map());
+    // Find articulation points and print the ones that cut off the most nodes
+    let articulation = connectivity::articulation_points(graph);
+    println!("\n🏆 Top 10 Articulation Points ({} in total) by Nodes Cut Off:", articulation.len());
+    table::ranking(&stats::top_k(&articulation, 10), email_map, &["Nodes cut off"], |n| vec![n.to_string()]).print(table_style);
+
+    // Decompose the network into biconnected components (redundantly connected blocks)
+    let block_cut = connectivity::biconnected_components(graph);
+    let largest_block = block_cut.blocks.iter().map(|b| b.len()).max().unwrap_or(0);
+    let bridges = block_cut.blocks.iter().filter(|b| b.len() == 2).count();
+    println!("\n🧱 Biconnected Components:");
+    println!("Blocks: {} (largest has {} nodes)", block_cut.blocks.len(), largest_block);
+    println!("Bridges (single-edge blocks): {}", bridges);
+    println!("Block-cut tree: {} cut nodes, {} tree edges", block_cut.cut_nodes.len(), block_cut.edges.len());
+
+    // Enumerate large maximal cliques (fully connected email groups)
+    let large_cliques = cliques::maximal_cliques(graph, 15);
+    println!("\n👥 Maximal Cliques with at least 15 members: {}", large_cliques.len());
+    for (i, clique) in large_cliques.iter().take(3).enumerate() {
+        let members: Vec<&str> = clique
+            .iter()
+            .filter_map(|node| email_map.get(node).map(|(email, _)| email.as_str()))
+            .collect();
+        println!("Clique {} ({} members): {}", i + 1, clique.len(), members.join(", "));
+    }
+
+    // Motif census with z-scores against degree-preserving randomizations
+    println!("\n🔺 Motif Census (z-scores vs 10 degree-preserving randomizations):");
+    for motif in motifs::motif_zscores(graph, true, 10, 42) {
+        let z = motif.z_score.map_or_else(|| "n/a".to_string(), |z| format!("{:.1}", z));
+        println!("{:<16} {:>12} (random mean {:.1}, z = {})", motif.name, motif.count, motif.random_mean, z);
+    }
+
+    // Most likely missing edges by Adamic–Adar
+    println!("\n🔗 Top 10 Predicted Links (Adamic–Adar):");
+    let predicted = linkpred::top_predicted_links(graph, email_map, linkpred::LinkPredictor::AdamicAdar, 10);
+    let mut links = Table::new(&[
+        ("#", Align::Right),
+        ("Node", Align::Right),
+        ("Email", Align::Left),
+        ("Node", Align::Right),
+        ("Email", Align::Left),
+        ("Score", Align::Right),
+    ]);
+    for (i, link) in predicted.iter().enumerate() {
+        links.row(vec![
+            (i + 1).to_string(),
+            link.source.to_string(),
+            link.source_email.clone().unwrap_or_else(|| "unknown".to_string()),
+            link.target.to_string(),
+            link.target_email.clone().unwrap_or_else(|| "unknown".to_string()),
+            format!("{:.3}", link.score),
+        ]);
+    }
+    links.print(table_style);
+
+    // Best broadcast seeds under the independent cascade model
+    println!("\n📣 Best Broadcast Seeds (independent cascade, p = 0.01, 100 simulations):");
+    let seeds = influence::greedy_influence_maximization(graph, 5, 0.01, 100, 100, 42);
+    table::ranking(&seeds, email_map, &["Expected reach"], |r| vec![format!("{:.1}", r)]).print(table_style);
+}
+
+// Prints the robustness under targeted and random removal of up to 2000 nodes, and returns
+// the curve of each removal strategy.
+fn print_robustness(graph: &Graph, betweenness: &HashMap<usize, f64>) -> Vec<(String, Vec<RobustnessPoint>)> {
+    println!("\n🛡️ Robustness (giant component fraction after removing nodes):");
+    let strategies = [robustness::RemovalStrategy::Degree, robustness::RemovalStrategy::Betweenness, robustness::RemovalStrategy::Random];
+    let mut curves = Vec::new();
+    for strategy in strategies {
+        let order = robustness::removal_order(graph, strategy, betweenness, 42);
+        curves.push((strategy.to_string(), robustness::robustness_curve(graph, &order, 200, 2000, 20, 42)));
+    }
+    println!("{:>8} {:>12} {:>12} {:>12}", "Removed", "Degree", "Betweenness", "Random");
+    for i in 0..curves[0].1.len() {
+        println!(
+            "{:>8} {:>12.3} {:>12.3} {:>12.3}",
+            curves[0].1[i].removed, curves[0].1[i].giant_fraction, curves[1].1[i].giant_fraction, curves[2].1[i].giant_fraction
+        );
+    }
+    curves
+}
+
+// Prints the load-based cascading failures triggered by the top 5 betweenness nodes.
+fn print_cascades(network: &Network, between_sorted: &[(usize, f64)], table_style: &table::TableStyle) {
+    println!("\n💥 Cascading Failures (tolerance 0.2, loads from 50 sampled sources):");
+    let triggers: Vec<usize> = between_sorted.iter().take(5).map(|(node, _)| *node).collect();
+    let failures: Vec<(usize, (usize, usize))> =
+        cascade::cascading_failures(&network.graph, &triggers, 0.2, 50, 42).iter().map(|r| (r.trigger, (r.failed.len(), r.rounds))).collect();
+    table::ranking(&failures, network.emails.map(), &["Failed", "Rounds"], |&(failed, rounds)| vec![failed.to_string(), rounds.to_string()])
+        .print(table_style);
+}
+
+// Prints how concentrated communication is among a few employees, and returns the Lorenz
+// curves of degree and betweenness.
+fn print_concentration(
+    degree: &HashMap<usize, usize>,
+    scoped_closeness: &HashMap<usize, f64>,
+    scoped_betweenness: &HashMap<usize, f64>,
+    scope_label: &str,
+) -> Vec<(String, Vec<(f64, f64)>)> {
+    println!("\n⚖️ Concentration of Centrality (Gini coefficient):");
+    println!("Degree (all nodes): {:.3}", inequality::gini(degree));
+    println!("Closeness ({}): {:.3}", scope_label, inequality::gini(scoped_closeness));
+    println!("Betweenness ({}): {:.3}", scope_label, inequality::gini(scoped_betweenness));
+    vec![
+        ("Degree (all nodes)".to_string(), inequality::lorenz_curve(degree)),
+        (format!("Betweenness ({})", scope_label), inequality::lorenz_curve(scoped_betweenness)),
+    ]
+}
+
+// Prints the whole distributions of the centrality measures rather than only the top 10,
+// how they correlate and agree in rank, and how much their top-k nodes overlap. Returns the
+// Spearman correlation matrix.
+fn print_correlations(measures: &[(&str, HashMap<usize, f64>)], scope_label: &str) -> Vec<Vec<f64>> {
+    println!("\n📏 Distribution of Centrality Measures (closeness and betweenness for the {}):", scope_label);
+    println!(
+        "{:<12} {:>6} {:>11} {:>11} {:>11} {:>11} {:>11} {:>11} {:>11}",
+        "Measure", "Nodes", "Mean", "Std", "Min", "Median", "P90", "P99", "Max"
+    );
+    for (name, values) in measures {
+        if let Some(s) = stats::describe(values, &[90.0, 99.0]) {
+            println!(
+                "{:<12} {:>6} {:>11.4e} {:>11.4e} {:>11.4e} {:>11.4e} {:>11.4e} {:>11.4e} {:>11.4e}",
+                name, s.count, s.mean, s.std_dev, s.min, s.median, s.percentiles[0].1, s.percentiles[1].1, s.max
+            );
+        }
+    }
+
+    let names: Vec<&str> = measures.iter().map(|(name, _)| *name).collect();
+    let spearman = correlation::correlation_matrix(measures, correlation::CorrelationMethod::Spearman);
+    println!("\n📊 Spearman Correlation of Centrality Measures ({}):", scope_label);
+    println!("{:>12} {}", "", names.iter().map(|n| format!("{:>12}", n)).collect::<String>());
+    for (name, row) in names.iter().zip(&spearman) {
+        println!("{:>12} {}", name, row.iter().map(|r| format!("{:>12.3}", r)).collect::<String>());
+    }
+    println!("\n📊 Rank Agreement Between Centrality Measures:");
+    for i in 0..measures.len() {
+        for j in (i + 1)..measures.len() {
+            let agreement = correlation::rank_agreement(&measures[i].1, &measures[j].1);
+            println!(
+                "{} vs {} ({} nodes): Spearman rho = {:.3}, Kendall tau = {:.3}",
+                measures[i].0, measures[j].0, agreement.common, agreement.spearman, agreement.kendall
+            );
         }
     }
 
-    // Find clusters and print leaders
+    let ks = [10, 50, 100];
+    println!("\n🤝 Top-k Overlap Between Centrality Measures (Jaccard, k = {:?}):", ks);
+    for (first, second, overlaps) in correlation::top_k_overlaps(measures, &ks) {
+        let values: Vec<String> = overlaps.iter().map(|j| format!("{:.2}", j)).collect();
+        println!("{} vs {}: {}", first, second, values.join(", "));
+    }
+    spearman
+}
+
+// Finds the connected components and prints their leaders by degree and the size, density
+// and diameter of the largest ones. Returns the components, largest first.
+fn print_components(network: &Network, edges: &[(usize, usize)], degree: &HashMap<usize, usize>, table_style: &table::TableStyle) -> Vec<HashSet<usize>> {
     println!("\n🏆 Cluster Leaders by Degree:");
-    let clusters = find_clusters(&edges);
+    let clusters = find_clusters(edges);
+    let mut leaders = Table::new(&[
+        ("Cluster", Align::Right),
+        ("Nodes", Align::Right),
+        ("Leader", Align::Right),
+        ("Email", Align::Left),
+        ("Folder", Align::Left),
+        ("Degree", Align::Right),
+    ]);
     for (i, cluster) in clusters.iter().enumerate().take(10) {
         let leader = cluster
             .iter()
-            .max_by_key(|&&n| degree.get(&n).unwrap_or(&0))
+            .max_by_key(|&&n| (degree.get(&n).unwrap_or(&0), std::cmp::Reverse(n)))
             .unwrap();
         let degree_score = degree.get(leader).unwrap_or(&0);
-        if let Some((email, folder)) = email_map.get(leader) {
-            println!(
-                "🧩 Cluster {} ({} nodes) → Node {} ({}) [{}], Degree: {}",
-                i + 1, cluster.len(), leader, email, folder, degree_score
-            );
+        if let Some((email, folder)) = network.emails.map().get(leader) {
+            leaders.row(vec![(i + 1).to_string(), cluster.len().to_string(), leader.to_string(), email.clone(), folder.clone(), degree_score.to_string()]);
         }
     }
+    leaders.print(table_style);
 
-    use cluster::{find_clusters, kmeans, normalize_features}; 
+    // Size, density and diameter of the largest components
+    let mut summaries = component_summary(&network.graph, &clusters);
+    summaries.sort_by_key(|c| std::cmp::Reverse(c.size));
+    println!("\n🧩 Largest Connected Components ({} in total):", summaries.len());
+    println!("{:>8} {:>8} {:>10} {:>9}", "Nodes", "Edges", "Density", "Diameter");
+    for c in summaries.iter().take(5) {
+        println!("{:>8} {:>8} {:>10.4} {:>9}", c.size, c.edges, c.density, c.diameter);
+    }
+    clusters
+}
 
-    // Prepare feature vectors for K-Means clustering: (degree, closeness, betweenness)
-    let mut features = HashMap::new();
-    for &node in top_nodes.iter() {
-        let deg = *degree.get(&node).unwrap_or(&0) as f64;
-        let close = *closeness.get(&node).unwrap_or(&0.0);
-        let between = *betweenness.get(&node).unwrap_or(&0.0);
-        features.insert(node, (deg, close, between));
+// Prints the centrality of whole mailbox folders treated as one unit, and the all-pairs
+// distances within `core`, the subgraph induced by the top nodes, with the closeness of
+// the folders inside it.
+fn print_group_centrality(network: &Network, core: &Graph, table_style: &table::TableStyle) {
+    let mut groups: Vec<(String, HashSet<usize>)> = group::groups_by_folder(network.emails.map()).into_iter().collect();
+    groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+    println!("\n👥 Group Centrality of the 8 Largest Folders (betweenness from 100 sampled sources):");
+    println!("{:<16} {:>7} {:>8} {:>10} {:>12}", "Folder", "Members", "Degree", "Closeness", "Betweenness");
+    for (folder, members) in groups.iter().take(8) {
+        let g = group::group_centrality(&network.graph, members, 100, 42);
+        println!("{:<16} {:>7} {:>8} {:>10.4} {:>12.4}", folder, g.size, g.degree, g.closeness, g.betweenness);
+    }
+
+    // All-pairs distances of the core, stored once as a u16 matrix and reused for
+    // eccentricity, closeness and group closeness (`--apsp-max N` caps its size)
+    let apsp_max = arg_value("--apsp-max").and_then(|m| m.parse().ok()).unwrap_or(5000);
+    match distance::DistanceMatrix::build(core, apsp_max) {
+        Some(matrix) => {
+            let core_ecc = matrix.eccentricity(core);
+            let core_closeness = matrix.closeness(core);
+            println!("\n🗂️ All-Pairs Distances within the Top {} Nodes:", core.node_count());
+            println!(
+                "Radius: {}, diameter: {}",
+                core_ecc.values().filter(|&&e| e > 0).min().unwrap_or(&0),
+                core_ecc.values().max().unwrap_or(&0)
+            );
+            let top = stats::top_k(&core_closeness, 5);
+            let with_ecc: Vec<(usize, (f64, usize))> = top.iter().map(|&(node, score)| (node, (score, core_ecc[&node]))).collect();
+            table::ranking(&with_ecc, network.emails.map(), &["Closeness", "Eccentricity"], |(score, ecc)| vec![format!("{:.4}", score), ecc.to_string()])
+                .print(table_style);
+            for (folder, members) in groups.iter().take(8) {
+                println!("Group closeness of {} within the top nodes: {:.4}", folder, matrix.group_closeness(core, members));
+            }
+        }
+        None => println!("\n🗂️ Skipping all-pairs distances: {} nodes exceed --apsp-max {}", core.node_count(), apsp_max),
     }
+}
 
-    // Normalize features to avoid scaling bias
-    normalize_features(&mut features);
+// The pipeline clustered the (normalized) centrality features into k groups; cluster again
+// with graphlet degree vectors (log-scaled, since orbit counts span many orders), node2vec
+// embeddings, spectral coordinates and/or log subgraph centrality appended, or step by step
+// for the animation. Returns the new clusters and the k-means steps (empty unless animated),
+// or `None` if none of these was asked for.
+fn recluster(
+    network: &Network,
+    options: &Options,
+    features: &HashMap<usize, Features>,
+    spectral_coords: &HashMap<usize, Vec<f64>>,
+    subgraph_scores: &HashMap<usize, f64>,
+    run_dir: &rundir::RunDirectory,
+) -> Option<(HashMap<usize, usize>, Vec<KMeansStep>)> {
+    let (k, max_iters) = (options.k, options.max_iters);
+    if options.use_graphlets || options.use_embeddings || options.use_spectral || options.use_subgraph_centrality {
+        let gdv = if options.use_graphlets { motifs::graphlet_degree_vectors(&network.graph) } else { HashMap::new() };
+        let embeddings = if options.use_embeddings {
+            let embeddings = embedding::node2vec(&network.graph, &embedding::Node2VecConfig::default());
+            let path = run_dir.file("node_embeddings.csv");
+            match embedding::write_embeddings_csv(&path, &embeddings) {
+                Ok(()) => println!("\n🧬 Wrote {} node2vec embeddings to {}", embeddings.len(), path.display()),
+                Err(e) => eprintln!("Failed to write node_embeddings.csv: {}", e),
+            }
+            embeddings
+        } else {
+            HashMap::new()
+        };
+        let mut vectors: HashMap<usize, Vec<f64>> = HashMap::new();
+        for (&node, &(deg, close, between)) in features {
+            let mut vector = vec![deg, close, between];
+            if let Some(orbits) = gdv.get(&node) {
+                vector.extend(orbits.iter().map(|&count| (1.0 + count as f64).ln()));
+            }
+            if let Some(embedded) = embeddings.get(&node) {
+                vector.extend(embedded);
+            }
+            if options.use_spectral {
+                vector.extend(spectral_coords.get(&node).cloned().unwrap_or_else(|| vec![0.0; 2]));
+            }
+            if options.use_subgraph_centrality {
+                vector.push(subgraph_scores.get(&node).map_or(0.0, |score| score.ln()));
+            }
+            vectors.insert(node, vector);
+        }
+        normalize_vectors(&mut vectors);
+        Some((kmeans_vectors(&vectors, k, max_iters), Vec::new()))
+    } else if options.kmeans_gif {
+        let vectors: HashMap<usize, Vec<f64>> = features.iter().map(|(&node, &(d, c, b))| (node, vec![d, c, b])).collect();
+        let kmeans_trace = kmeans_steps(&vectors, k, max_iters);
+        if kmeans_trace.len() < max_iters {
+            println!("\n🎞️ K-means converged after {} iterations", kmeans_trace.len());
+        } else {
+            println!("\n🎞️ K-means stopped after {} iterations (--max-iters) before converging", max_iters);
+        }
+        let assignments = kmeans_trace.last().map(|step| step.assignments.clone()).unwrap_or_default();
+        Some((assignments, kmeans_trace))
+    } else {
+        None
+    }
+}
 
-    // Apply k-means clustering for k = 5 groups
-    let assignments = kmeans(&features, 5, 100);
+// Prints the structural roles, the members of each k-means cluster with their roles, the
+// functional cartography of the clusters, and a blockmodel of the top nodes.
+fn print_clusters(network: &Network, assignments: &HashMap<usize, usize>, k: usize, top_nodes: &HashSet<usize>) {
+    let (graph, email_map) = (&network.graph, network.emails.map());
+    // Discover structural roles (RolX-style) to show next to the k-means clusters
+    let roles = roles::discover_roles(graph, 4, 2, 42);
+    println!("\n🎭 Structural Roles:");
+    for (r, label) in roles.labels.iter().enumerate() {
+        let size = roles.assignments.values().filter(|&&x| x == r).count();
+        println!("Role {} ({}): {} nodes", r, label, size);
+    }
+    let role_of = |node: &usize| roles.assignments.get(node).map_or("unknown", |&r| roles.labels[r].as_str());
 
-    println!("\n🕸️ K-Means Clustering (5 clusters):");
-    for i in 0..5 {
+    // Members are listed in node ID order, so two runs can be diffed
+    let mut members: Vec<(usize, usize)> = assignments.iter().map(|(&node, &cluster_id)| (node, cluster_id)).collect();
+    members.sort_unstable();
+    println!("\n🕸️ K-Means Clustering ({} clusters):", k);
+    for i in 0..k {
         println!("Cluster {}:", i);
-        for (&node, &cluster_id) in &assignments {
+        for &(node, cluster_id) in &members {
             if cluster_id == i {
                 if let Some((email, folder)) = email_map.get(&node) {
-                    println!("  Node {} ({}) [{}]", node, email, folder);
+                    println!("  Node {} ({}) [{}] role: {}", node, email, folder, role_of(&node));
                 }
             }
         }
     }
 
-    // Generate plots 
-    plot_degree_histogram(&degree).unwrap();
-    plot_closeness_vs_degree(&degree, &closeness).unwrap();
-    plot_betweenness_histogram(&betweenness).unwrap();
-    plot_clusters(&features, &assignments).unwrap();
+    // Functional cartography of the k-means partition
+    let carto = cartography::functional_cartography(graph, assignments);
+    let mut role_counts: HashMap<cartography::CartographyRole, usize> = HashMap::new();
+    for scores in carto.values() {
+        *role_counts.entry(scores.role).or_insert(0) += 1;
+    }
+    let mut role_counts: Vec<_> = role_counts.into_iter().collect();
+    role_counts.sort_by_key(|(role, _)| role.to_string());
+    println!("\n🗺️ Functional Cartography of the K-Means Clusters:");
+    for (role, count) in &role_counts {
+        println!("{}: {} nodes", role, count);
+    }
+    let hub_z: HashMap<usize, f64> = carto.iter().filter(|(_, c)| c.role.is_hub()).map(|(&node, c)| (node, c.within_z)).collect();
+    println!("Hubs (top 10 by within-module z-score):");
+    for (node, _) in stats::top_k(&hub_z, 10) {
+        let scores = &carto[&node];
+        if let Some((email, folder)) = email_map.get(&node) {
+            println!(
+                "  Node {} ({}) [{}]: z = {:.2}, P = {:.2}, {}",
+                node, email, folder, scores.within_z, scores.participation, scores.role
+            );
+        }
+    }
+
+    // Blockmodel of structurally equivalent nodes among the top nodes
+    match blockmodel(graph, top_nodes, 5, 50) {
+        Ok(model) => {
+            println!("\n🧮 Blockmodel (5 blocks, block sizes {:?}), image matrix of densities:", model.sizes);
+            for row in &model.image {
+                let cells: Vec<String> = row.iter().map(|d| format!("{:.3}", d)).collect();
+                println!("  {}", cells.join("  "));
+            }
+        }
+        Err(e) => eprintln!("\nSkipping the blockmodel: {}", e),
+    }
+}
+
+// Prints text versions of the degree and betweenness histograms and of the top 10 by degree.
+fn print_text_plots(degree: &HashMap<usize, usize>, betweenness: &HashMap<usize, f64>, email_map: &HashMap<usize, (String, String)>, options: &HistogramOptions) {
+    let degree_values: Vec<f64> = degree.values().map(|&d| d as f64).collect();
+    let between_values: Vec<f64> = betweenness.values().copied().collect();
+    println!("\n📟 Text Plots (log bins, log counts):");
+    println!("Degree:      {}", textplot::histogram_sparkline(&degree_values, options));
+    println!("Betweenness: {}", textplot::histogram_sparkline(&between_values, options));
+    println!("\nTop 10 by Degree:");
+    let rows: Vec<(String, f64)> = stats::top_k(degree, 10)
+        .iter()
+        .map(|(node, deg)| (email_map.get(node).map_or_else(|| node.to_string(), |(email, _)| email.clone()), *deg as f64))
+        .collect();
+    for line in textplot::bar_chart(&rows, 40) {
+        println!("{}", line);
+    }
+}
+
+// The manifest records the parameters of the run and lists the files it wrote
+// The salt of `--pseudonymize` is never recorded, so a shared run directory does not reveal it
+fn write_manifest(run_dir: &mut rundir::RunDirectory, network: &Network, options: &Options) {
+    run_dir.record("command", rundir::command_line(std::env::args()));
+    run_dir.record("version", env!("CARGO_PKG_VERSION"));
+    run_dir.record("nodes", network.graph.node_count());
+    run_dir.record("edges", network.graph.edge_count());
+    run_dir.record("graph", format!("{:016x}", network.graph.fingerprint()));
+    run_dir.record("directed", network.directed);
+    run_dir.record("scope", options.scope);
+    run_dir.record("top_n", options.top_n);
+    run_dir.record("k", options.k);
+    run_dir.record("max_iters", options.max_iters);
+    run_dir.record("degree_measure", options.measure);
+    run_dir.record("closeness_mode", options.closeness_mode);
+    run_dir.record("betweenness_norm", options.normalization);
+    run_dir.record("threads", rayon::current_num_threads());
+    match run_dir.write_manifest() {
+        Ok(_) => println!("\n📁 Outputs of the run in {}", run_dir.path().display()),
+        Err(e) => eprintln!("Failed to write the manifest of the run in {}: {}", run_dir.path().display(), e),
+    }
 }
 // Intermediate commit: updated main.rs
 // Intermediate commit: modified main.rs
@@ -0,0 +1,219 @@
+// Includes a two-way index between node IDs and email addresses, built once from the mapping
+// CSV so that looking up a person by address is a hash lookup instead of a scan over every
+// row. Addresses are matched without regard to case or surrounding angle brackets, and nodes
+// missing from the CSV still get a printable label. A fuzzy search finds people from part of
+// an address or a misspelled name.
+
+use crate::graph::{load_email_mapping, Graph};
+use std::collections::HashMap;
+
+// Normalized form of an address for lookups: trimmed, without <...>, lowercased.
+fn normalize(email: &str) -> String {
+    email.trim().trim_start_matches('<').trim_end_matches('>').trim().to_lowercase()
+}
+
+// How well an address matched a search, best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    // The whole address or its local part (before the @)
+    Exact,
+    // The start of the address or of a name in it
+    Prefix,
+    // Anywhere in the address
+    Substring,
+    // A name in the address within this many edits
+    Fuzzy(usize),
+}
+
+impl std::fmt::Display for MatchKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MatchKind::Exact => write!(f, "exact"),
+            MatchKind::Prefix => write!(f, "prefix"),
+            MatchKind::Substring => write!(f, "substring"),
+            MatchKind::Fuzzy(edits) => write!(f, "{} edit(s)", edits),
+        }
+    }
+}
+
+// One search result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub node: usize,
+    pub kind: MatchKind,
+}
+
+// Levenshtein distance between two strings (insertions, deletions and substitutions of chars).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            curr[j + 1] = (prev[j] + usize::from(ca != cb)).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+// How an address matches a normalized query, if it does. The names of an address are the
+// pieces of its local part, e.g. "jeff" and "skilling" in jeff.skilling@enron.com.
+fn match_address(address: &str, query: &str, max_edits: usize) -> Option<MatchKind> {
+    let local = address.split('@').next().unwrap_or(address);
+    let names = || local.split(['.', '_', '-']).filter(|name| !name.is_empty());
+    if address == query || local == query {
+        Some(MatchKind::Exact)
+    } else if address.starts_with(query) || names().any(|name| name.starts_with(query)) {
+        Some(MatchKind::Prefix)
+    } else if address.contains(query) {
+        Some(MatchKind::Substring)
+    } else {
+        names().chain(std::iter::once(local)).map(|name| edit_distance(name, query)).filter(|&d| d <= max_edits).min().map(MatchKind::Fuzzy)
+    }
+}
+
+// Node ID <-> (email, folder) mapping with reverse lookup.
+#[derive(Debug, Clone, Default)]
+pub struct EmailIndex {
+    by_node: HashMap<usize, (String, String)>,
+    by_email: HashMap<String, usize>,
+}
+
+impl EmailIndex {
+    // Indexes a node ID -> (email, folder) mapping. If several nodes share an address, the
+    // lookup returns the smallest node ID.
+    // Its inputs are - `map`: node ID -> (email, folder), as from `load_email_mapping`
+    // Its outputs are - `EmailIndex`: the two-way index
+    pub fn new(map: HashMap<usize, (String, String)>) -> EmailIndex {
+        let mut by_email: HashMap<String, usize> = HashMap::new();
+        for (&node, (email, _)) in &map {
+            let entry = by_email.entry(normalize(email)).or_insert(node);
+            *entry = (*entry).min(node);
+        }
+        EmailIndex { by_node: map, by_email }
+    }
+
+    // Loads and indexes a mapping CSV (node_id,email,employee_folder).
+    pub fn from_file(path: &str) -> EmailIndex {
+        EmailIndex::new(load_email_mapping(path))
+    }
+
+    // Adds or replaces the address and folder of a node.
+    pub fn insert(&mut self, node: usize, email: &str, folder: &str) {
+        if let Some((old, _)) = self.by_node.insert(node, (email.to_string(), folder.to_string())) {
+            if self.by_email.get(&normalize(&old)) == Some(&node) {
+                self.by_email.remove(&normalize(&old));
+            }
+        }
+        let entry = self.by_email.entry(normalize(email)).or_insert(node);
+        *entry = (*entry).min(node);
+    }
+
+    // Node ID of an address, ignoring case.
+    pub fn node(&self, email: &str) -> Option<usize> {
+        self.by_email.get(&normalize(email)).copied()
+    }
+
+    // Resolves a query target given either as a node ID or as an email address.
+    pub fn resolve(&self, target: &str) -> Option<usize> {
+        target.trim().parse().ok().or_else(|| self.node(target))
+    }
+
+    pub fn email(&self, node: usize) -> Option<&str> {
+        self.by_node.get(&node).map(|(email, _)| email.as_str())
+    }
+
+    pub fn folder(&self, node: usize) -> Option<&str> {
+        self.by_node.get(&node).map(|(_, folder)| folder.as_str())
+    }
+
+    // Printable name of a node: its address, or "Node <id>" if it is missing from the mapping.
+    pub fn label(&self, node: usize) -> String {
+        self.email(node).map_or_else(|| format!("Node {}", node), str::to_string)
+    }
+
+    // The underlying node ID -> (email, folder) map, for functions that take one.
+    pub fn map(&self) -> &HashMap<usize, (String, String)> {
+        &self.by_node
+    }
+
+    // Finds the people whose address matches a query exactly, by prefix, as a substring, or
+    // (for the names in it) within `max_edits` edits, ignoring case.
+    // Its inputs are - `query`: part of an address or a name - `max_edits`: edit distance allowed
+    // Its outputs are - `Vec<SearchMatch>`: matching nodes, best matches first, then by address
+    pub fn search(&self, query: &str, max_edits: usize) -> Vec<SearchMatch> {
+        let query = normalize(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut matches: Vec<(SearchMatch, String)> = self
+            .by_node
+            .iter()
+            .filter_map(|(&node, (email, _))| {
+                let address = email.to_lowercase();
+                let kind = match_address(&address, &query, max_edits)?;
+                Some((SearchMatch { node, kind }, address))
+            })
+            .collect();
+        matches.sort_by(|(a, x), (b, y)| a.kind.cmp(&b.kind).then_with(|| x.cmp(y)).then(a.node.cmp(&b.node)));
+        matches.into_iter().map(|(m, _)| m).collect()
+    }
+
+    // Node IDs of the graph that have no address in the mapping, sorted.
+    pub fn missing(&self, graph: &Graph) -> Vec<usize> {
+        let mut missing: Vec<usize> = (0..graph.node_count()).map(|v| graph.node_id(v)).filter(|id| !self.by_node.contains_key(id)).collect();
+        missing.sort_unstable();
+        missing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests reverse lookups ignoring case and brackets, the smallest ID winning a shared
+    // address, replacing an address, and labels and listing of nodes missing from the mapping.
+    #[test]
+    fn test_email_index() {
+        let map = HashMap::from([
+            (1, ("Kenneth.Lay@enron.com".to_string(), "lay-k".to_string())),
+            (5, ("jeff@enron.com".to_string(), "skilling-j".to_string())),
+            (3, ("JEFF@enron.com".to_string(), "skilling-j".to_string())),
+        ]);
+        let mut index = EmailIndex::new(map);
+        assert_eq!(index.node(" <kenneth.lay@ENRON.com> "), Some(1));
+        assert_eq!(index.node("jeff@enron.com"), Some(3));
+        assert_eq!((index.resolve("5"), index.resolve("nobody@enron.com")), (Some(5), None));
+        assert_eq!(index.folder(1), Some("lay-k"));
+
+        index.insert(1, "ken@enron.com", "lay-k");
+        assert_eq!((index.node("kenneth.lay@enron.com"), index.node("ken@enron.com")), (None, Some(1)));
+
+        let graph = Graph::from_edges(&[(1, 2), (2, 3), (3, 4)]);
+        assert_eq!(index.missing(&graph), vec![2, 4]);
+        assert_eq!((index.label(2), index.label(3)), ("Node 2".to_string(), "JEFF@enron.com".to_string()));
+    }
+
+    // Tests that a search ranks exact, prefix, substring and misspelled-name matches in that
+    // order, and leaves out addresses beyond the edit distance.
+    #[test]
+    fn test_search() {
+        let addresses = ["skilling@enron.com", "jeff.skilling@enron.com", "skillingj@aol.com", "j.skiling@enron.com", "joe.smith@enron.com", "askillings@x.com"];
+        let index = EmailIndex::new(addresses.iter().enumerate().map(|(i, a)| (i, (a.to_string(), String::new()))).collect());
+        let found: Vec<(usize, MatchKind)> = index.search("Skilling", 1).into_iter().map(|m| (m.node, m.kind)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (0, MatchKind::Exact),
+                (1, MatchKind::Prefix),
+                (2, MatchKind::Prefix),
+                (5, MatchKind::Substring),
+                (3, MatchKind::Fuzzy(1))
+            ]
+        );
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert!(index.search("  ", 2).is_empty());
+    }
+}
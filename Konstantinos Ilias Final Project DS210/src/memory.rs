@@ -0,0 +1,210 @@
+// Includes memory accounting: the estimated heap memory of a loaded graph and its email mapping,
+// the peak auxiliary memory each algorithm of the analysis allocates on top of them (per worker
+// thread where the work is parallel), and the memory the process actually used, read from
+// /proc on Linux. Before a long run the estimates are checked against a memory budget, so that
+// a combination of options that would not fit is flagged before the machine starts swapping.
+
+use crate::embedding::Node2VecConfig;
+use crate::graph::Graph;
+use crate::motifs::ORBITS;
+use std::collections::HashMap;
+use std::mem::size_of;
+
+// Heap bytes per entry of a `HashMap<K, V>` holding `capacity` entries: the key, the value and a
+// control byte per bucket, with the buckets kept at most 7/8 full.
+fn hash_map_bytes<K, V>(capacity: usize) -> usize {
+    capacity * (size_of::<K>() + size_of::<V>() + 1) * 8 / 7
+}
+
+// Estimated heap memory of the email mapping, with the text of every address and folder.
+// Its inputs are - `map`: node ID -> (email, folder)
+// Its outputs are - `usize`: bytes
+pub fn mapping_bytes(map: &HashMap<usize, (String, String)>) -> usize {
+    let text: usize = map.values().map(|(email, folder)| email.capacity() + folder.capacity()).sum();
+    hash_map_bytes::<usize, (String, String)>(map.capacity()) + text
+}
+
+// Size of a graph, which is all the estimates need to know about it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphShape {
+    pub nodes: usize,
+    pub edges: usize,
+    pub weighted: bool,
+    // Worker threads running the parallel algorithms at once
+    pub threads: usize,
+}
+
+impl GraphShape {
+    // Shape of a loaded graph, with the thread count of the rayon pool.
+    pub fn of(graph: &Graph) -> GraphShape {
+        GraphShape { nodes: graph.node_count(), edges: graph.edge_count(), weighted: graph.is_weighted(), threads: rayon::current_num_threads() }
+    }
+
+    // Estimated heap memory of a `Graph` of this shape, e.g. a copy or a randomized baseline:
+    // the ID table, the ID index and one neighbor list (and weight list) per node. It is a lower
+    // bound, since lists grown edge by edge keep spare capacity (at least 4 slots for a leaf).
+    pub fn graph_bytes(&self) -> usize {
+        let lists = if self.weighted { 2 } else { 1 };
+        let per_node = size_of::<usize>() + hash_map_bytes::<usize, usize>(1) + lists * size_of::<Vec<usize>>();
+        self.nodes * per_node + 2 * self.edges * lists * size_of::<usize>()
+    }
+}
+
+// A step of the analysis whose auxiliary memory is estimated.
+#[derive(Debug, Clone)]
+pub enum Algorithm {
+    // Exact diameter and path length: one BFS distance and order buffer per thread
+    DistanceStats,
+    // HyperANF with 2^log2m registers per node, current and next counters
+    HyperAnf { log2m: u32 },
+    // Four same-size synthetic graphs held at once, plus their edge lists
+    Baselines,
+    // Degree-preserving randomizations, one copy of the graph at a time
+    NullModel,
+    // Batched bit-parallel BFS: three 64-bit words per node for each batch of 64 sources
+    BatchedCloseness,
+    // One BFS per node of the whole graph, with a result per node
+    ClosenessAll,
+    // Brandes from the given number of sources, on a reordered copy of the graph
+    Betweenness { sources: usize },
+    // u16 all-pairs distance matrix of the top nodes
+    AllPairs { nodes: usize },
+    // Dense adjacency matrix and eigenvectors of the top nodes
+    SubgraphCentrality { nodes: usize },
+    // Random walks of node2vec and the two embedding tables
+    Node2Vec(Node2VecConfig),
+    // Graphlet degree vectors of every node, on an oriented copy of the edges
+    Graphlets,
+    // Power iteration on the giant component for a few eigenvectors
+    Eigenmap { dimensions: usize },
+}
+
+impl std::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Algorithm::DistanceStats => write!(f, "distance statistics"),
+            Algorithm::HyperAnf { log2m } => write!(f, "HyperANF (2^{} registers)", log2m),
+            Algorithm::Baselines => write!(f, "synthetic baselines"),
+            Algorithm::NullModel => write!(f, "null-model randomizations"),
+            Algorithm::BatchedCloseness => write!(f, "closeness (batched BFS)"),
+            Algorithm::ClosenessAll => write!(f, "closeness of all nodes"),
+            Algorithm::Betweenness { sources } => write!(f, "betweenness ({} sources)", sources),
+            Algorithm::AllPairs { nodes } => write!(f, "all-pairs distances ({} nodes)", nodes),
+            Algorithm::SubgraphCentrality { nodes } => write!(f, "subgraph centrality ({} nodes)", nodes),
+            Algorithm::Node2Vec(_) => write!(f, "node2vec embeddings"),
+            Algorithm::Graphlets => write!(f, "graphlet degree vectors"),
+            Algorithm::Eigenmap { dimensions } => write!(f, "Laplacian eigenmap ({} dimensions)", dimensions),
+        }
+    }
+}
+
+impl Algorithm {
+    // Estimated peak memory the algorithm allocates on top of the loaded graph.
+    // Its inputs are - `shape`: size of the graph and number of threads
+    // Its outputs are - `usize`: bytes
+    pub fn aux_bytes(&self, shape: &GraphShape) -> usize {
+        let (n, m, t) = (shape.nodes, shape.edges, shape.threads.max(1));
+        let word = size_of::<usize>();
+        match self {
+            Algorithm::DistanceStats => t * 2 * n * word,
+            Algorithm::HyperAnf { log2m } => 2 * n * (1 << log2m),
+            Algorithm::Baselines => 4 * GraphShape { weighted: false, ..*shape }.graph_bytes() + 2 * m * word,
+            Algorithm::NullModel => GraphShape { weighted: false, ..*shape }.graph_bytes() + 4 * m * word,
+            Algorithm::BatchedCloseness => t * 3 * n * size_of::<u64>(),
+            Algorithm::ClosenessAll => t * 2 * n * word + hash_map_bytes::<usize, f64>(n),
+            // order, dist, sigma, delta and a score accumulator per thread
+            Algorithm::Betweenness { sources } => shape.graph_bytes() + t.min((*sources).max(1)) * 5 * n * word,
+            Algorithm::AllPairs { nodes } => nodes * nodes * size_of::<u16>(),
+            Algorithm::SubgraphCentrality { nodes } => 2 * nodes * nodes * size_of::<f64>(),
+            Algorithm::Node2Vec(config) => {
+                let walks = n * config.walks_per_node * config.walk_length * word;
+                walks + 2 * n * config.dimensions * size_of::<f64>()
+            }
+            Algorithm::Graphlets => 2 * m * word + hash_map_bytes::<usize, [u64; ORBITS]>(n) + t * n * word,
+            Algorithm::Eigenmap { dimensions } => (2 * dimensions + 4) * n * size_of::<f64>(),
+        }
+    }
+}
+
+// The algorithms of a run whose loaded data plus auxiliary memory would exceed the budget.
+// Its inputs are - `base`: bytes in use before the algorithms run - `plan`: the algorithms
+// - `shape`: graph size and threads - `budget`: bytes available
+// Its outputs are - `Vec<(&Algorithm, usize)>`: each algorithm over budget and its estimated total
+pub fn over_budget<'a>(base: usize, plan: &'a [Algorithm], shape: &GraphShape, budget: usize) -> Vec<(&'a Algorithm, usize)> {
+    plan.iter().map(|a| (a, base + a.aux_bytes(shape))).filter(|&(_, total)| total > budget).collect()
+}
+
+// Parses a byte count such as `512M`, `2G`, `1.5GB` or `100000` (binary units, case-insensitive).
+pub fn parse_size(text: &str) -> Option<usize> {
+    let text = text.trim().to_ascii_uppercase();
+    let text = text.strip_suffix("IB").or_else(|| text.strip_suffix('B')).unwrap_or(&text);
+    let (number, scale) = match text.char_indices().last()? {
+        (i, 'K') => (&text[..i], 1u64 << 10),
+        (i, 'M') => (&text[..i], 1 << 20),
+        (i, 'G') => (&text[..i], 1 << 30),
+        (i, 'T') => (&text[..i], 1 << 40),
+        _ => (text, 1),
+    };
+    let value: f64 = number.trim().parse().ok()?;
+    (value >= 0.0).then_some((value * scale as f64) as usize)
+}
+
+// Formats a byte count with a binary unit, e.g. `12.3 MB`.
+pub fn format_bytes(bytes: usize) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} B", bytes) } else { format!("{:.1} {}", value, units[unit]) }
+}
+
+// Reads a `kB` field of /proc/self/status, if the system has one.
+fn proc_status(field: &str) -> Option<usize> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with(field))?;
+    let kb: usize = line[field.len()..].trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kb * 1024)
+}
+
+// Memory the process holds right now (resident set), on Linux.
+pub fn current_rss() -> Option<usize> {
+    proc_status("VmRSS:")
+}
+
+// Most memory the process has held at once so far (peak resident set), on Linux.
+pub fn peak_rss() -> Option<usize> {
+    proc_status("VmHWM:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests size parsing and formatting, that the shape estimate is close to the measured heap
+    // of a graph, and that the budget check flags exactly the algorithms that do not fit.
+    #[test]
+    fn test_memory_estimates() {
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("2k"), Some(2048));
+        assert_eq!(parse_size("1.5GB"), Some(3 << 29));
+        assert_eq!(parse_size("4 MiB"), Some(4 << 20));
+        assert_eq!(parse_size("lots"), None);
+        assert_eq!((format_bytes(100), format_bytes(3 << 20)), ("100 B".to_string(), "3.0 MB".to_string()));
+
+        let edges: Vec<(usize, usize)> = (0..2000).map(|v| (v, (v * 13 + 5) % 2000)).collect();
+        let graph = Graph::from_edges(&edges);
+        let shape = GraphShape { threads: 4, ..GraphShape::of(&graph) };
+        let (estimate, measured) = (shape.graph_bytes(), graph.heap_bytes());
+        assert!(estimate <= measured && 3 * estimate > measured, "{} vs {}", estimate, measured);
+
+        let plan = [Algorithm::BatchedCloseness, Algorithm::AllPairs { nodes: 5000 }];
+        assert_eq!(Algorithm::AllPairs { nodes: 5000 }.aux_bytes(&shape), 50_000_000);
+        let over = over_budget(1 << 20, &plan, &shape, 10 << 20);
+        assert_eq!(over.len(), 1);
+        assert!(matches!(over[0].0, Algorithm::AllPairs { nodes: 5000 }));
+        assert!(over_budget(1 << 20, &plan, &shape, 1 << 30).is_empty());
+    }
+}
@@ -0,0 +1,398 @@
+// Includes functions for a motif census: counting the connected 3-node and 4-node
+// subgraph patterns of the network and comparing the counts against randomized graphs.
+
+use crate::cliques::degeneracy_order;
+use crate::graph::Graph;
+use crate::nullmodel::{null_graphs, NullModel};
+use std::collections::HashMap;
+
+// Names of the motifs in the order they appear in a census.
+pub const THREE_NODE_MOTIFS: [&str; 2] = ["open wedge", "triangle"];
+pub const FOUR_NODE_MOTIFS: [&str; 6] = ["3-star", "4-path", "tailed triangle", "4-cycle", "diamond", "4-clique"];
+
+// Significance of one motif against the randomized baseline.
+#[derive(Debug, Clone)]
+pub struct MotifSignificance {
+    pub name: &'static str,
+    pub count: u64,
+    pub random_mean: f64,
+    pub random_std: f64,
+    // None when every randomized graph has the same count, so there is no spread to measure by
+    pub z_score: Option<f64>,
+}
+
+// Orients every edge from lower to higher degeneracy rank, so each node keeps only a short
+// list of "later" neighbors and every small clique is enumerated exactly once.
+fn oriented(graph: &Graph) -> (Vec<usize>, Vec<Vec<usize>>) {
+    let order = degeneracy_order(graph);
+    let mut rank = vec![0; graph.node_count()];
+    for (i, &v) in order.iter().enumerate() {
+        rank[v] = i;
+    }
+    let out = (0..graph.node_count())
+        .map(|v| {
+            let mut later: Vec<usize> = graph.neighbors(v).iter().copied().filter(|&w| rank[w] > rank[v]).collect();
+            later.sort_unstable();
+            later
+        })
+        .collect();
+    (rank, out)
+}
+
+fn choose(n: u64, k: u64) -> u64 {
+    match k {
+        2 => n * n.saturating_sub(1) / 2,
+        3 => n * n.saturating_sub(1) * n.saturating_sub(2) / 6,
+        _ => unreachable!(),
+    }
+}
+
+// Counts induced connected motifs exactly.
+// Triangles and 4-cliques are enumerated over the degeneracy orientation and 4-cycles with
+// Chiba–Nishizeki wedge counting; the other patterns follow from degree and triangle
+// counts, and non-induced counts are converted to induced ones by inclusion–exclusion.
+// Its inputs are - `graph`: the graph - `four_node`: also count the six 4-node motifs
+// Its outputs are - `Vec<(&str, u64)>`: motif name -> number of induced occurrences
+pub fn motif_census(graph: &Graph, four_node: bool) -> Vec<(&'static str, u64)> {
+    let n = graph.node_count();
+    let (rank, out) = oriented(graph);
+
+    // Enumerate every triangle once, counting it at its nodes and edges
+    let mut triangles: u64 = 0;
+    let mut node_triangles = vec![0u64; n];
+    let mut edge_triangles: HashMap<(usize, usize), u64> = HashMap::new();
+    let mut cliques4: u64 = 0;
+    for u in 0..n {
+        for &v in &out[u] {
+            let common: Vec<usize> = out[u].iter().copied().filter(|w| out[v].binary_search(w).is_ok()).collect();
+            for &w in &common {
+                triangles += 1;
+                node_triangles[u] += 1;
+                node_triangles[v] += 1;
+                node_triangles[w] += 1;
+                if four_node {
+                    for edge in [(u, v), (u, w), (v, w)] {
+                        *edge_triangles.entry((edge.0.min(edge.1), edge.0.max(edge.1))).or_insert(0) += 1;
+                    }
+                    cliques4 += common.iter().filter(|x| out[w].binary_search(x).is_ok()).count() as u64;
+                }
+            }
+        }
+    }
+
+    let wedges: u64 = (0..n).map(|v| choose(graph.degree(v) as u64, 2)).sum();
+    let mut census = vec![(THREE_NODE_MOTIFS[0], wedges - 3 * triangles), (THREE_NODE_MOTIFS[1], triangles)];
+    if !four_node {
+        return census;
+    }
+
+    // Non-induced counts of each 4-node pattern
+    let stars: u64 = (0..n).map(|v| choose(graph.degree(v) as u64, 3)).sum();
+    let mut paths: u64 = 0;
+    for u in 0..n {
+        for &v in graph.neighbors(u).iter().filter(|&&v| v > u) {
+            paths += (graph.degree(u) as u64 - 1) * (graph.degree(v) as u64 - 1);
+        }
+    }
+    let paths = paths - 3 * triangles;
+    let tailed: u64 = (0..n).map(|v| node_triangles[v] * (graph.degree(v) as u64).saturating_sub(2)).sum();
+    let diamonds: u64 = edge_triangles.values().map(|&t| choose(t, 2)).sum();
+
+    // Each 4-cycle is counted once from its highest-ranked node u through the opposite node w
+    let mut cycles: u64 = 0;
+    let mut wedge_count = vec![0u64; n];
+    let mut touched = Vec::new();
+    for u in 0..n {
+        for &v in graph.neighbors(u).iter().filter(|&&v| rank[v] < rank[u]) {
+            for &w in graph.neighbors(v).iter().filter(|&&w| rank[w] < rank[u]) {
+                if wedge_count[w] == 0 {
+                    touched.push(w);
+                }
+                wedge_count[w] += 1;
+            }
+        }
+        for &w in &touched {
+            cycles += choose(wedge_count[w], 2);
+            wedge_count[w] = 0;
+        }
+        touched.clear();
+    }
+
+    // Convert to induced counts, from the densest pattern down
+    let i_diamonds = diamonds - 6 * cliques4;
+    let i_cycles = cycles - i_diamonds - 3 * cliques4;
+    let i_tailed = tailed - 4 * i_diamonds - 12 * cliques4;
+    let i_stars = stars - i_tailed - 2 * i_diamonds - 4 * cliques4;
+    let i_paths = paths - 2 * i_tailed - 4 * i_cycles - 6 * i_diamonds - 12 * cliques4;
+
+    let counts = [i_stars, i_paths, i_tailed, i_cycles, i_diamonds, cliques4];
+    census.extend(FOUR_NODE_MOTIFS.iter().copied().zip(counts));
+    census
+}
+
+// Number of node orbits in the connected graphlets with 2 to 4 nodes.
+pub const ORBITS: usize = 15;
+
+// Computes each node's graphlet degree vector: how many times it touches each of the
+// 15 orbits (automorphism positions) of the 2-, 3- and 4-node connected graphlets.
+// Orbit 0 is the degree; 1-3 belong to the 3-node path and triangle; 4-14 to the six
+// 4-node graphlets (path 4-5, star 6-7, cycle 8, tailed triangle 9-11, diamond 12-13, clique 14).
+// Like the census, non-induced counts come from degree and triangle formulas and are
+// turned into induced orbit counts with the orbit inclusion–exclusion equations.
+// Its inputs are - `graph`: the graph
+// Its outputs are - `HashMap<usize, [u64; ORBITS]>`: node ID -> graphlet degree vector
+pub fn graphlet_degree_vectors(graph: &Graph) -> HashMap<usize, [u64; ORBITS]> {
+    let n = graph.node_count();
+    let (_, out) = oriented(graph);
+    let deg = |v: usize| graph.degree(v) as u64;
+    let edge = |a: usize, b: usize| (a.min(b), a.max(b));
+
+    // Triangles per node and per edge, and 4-cliques per node
+    let mut triangles = Vec::new();
+    let mut node_tri = vec![0u64; n];
+    let mut edge_tri: HashMap<(usize, usize), u64> = HashMap::new();
+    let mut node_k4 = vec![0u64; n];
+    for u in 0..n {
+        for &v in &out[u] {
+            let common: Vec<usize> = out[u].iter().copied().filter(|w| out[v].binary_search(w).is_ok()).collect();
+            for &w in &common {
+                triangles.push((u, v, w));
+                for x in [u, v, w] {
+                    node_tri[x] += 1;
+                }
+                for (a, b) in [(u, v), (u, w), (v, w)] {
+                    *edge_tri.entry(edge(a, b)).or_insert(0) += 1;
+                }
+                for &x in common.iter().filter(|x| out[w].binary_search(x).is_ok()) {
+                    for y in [u, v, w, x] {
+                        node_k4[y] += 1;
+                    }
+                }
+            }
+        }
+    }
+    let tri_on = |a: usize, b: usize| *edge_tri.get(&edge(a, b)).unwrap_or(&0);
+
+    // Non-induced orbit counts, indexed like the orbits
+    let mut counts = vec![[0u64; ORBITS]; n];
+    let paths_from: Vec<u64> = (0..n).map(|u| graph.neighbors(u).iter().map(|&w| deg(w) - 1).sum()).collect();
+    let mut common = vec![0u64; n];
+    let mut touched = Vec::new();
+    for v in 0..n {
+        let c = &mut counts[v];
+        c[0] = deg(v);
+        c[2] = choose(deg(v), 2);
+        c[3] = node_tri[v];
+        c[7] = choose(deg(v), 3);
+        c[11] = node_tri[v] * deg(v).saturating_sub(2);
+        c[14] = node_k4[v];
+        for &u in graph.neighbors(v) {
+            c[1] += deg(u) - 1;
+            c[4] += paths_from[u] - (deg(v) - 1);
+            c[5] += (deg(v) - 1) * (deg(u) - 1);
+            c[6] += choose(deg(u) - 1, 2);
+            c[9] += node_tri[u] - tri_on(u, v);
+            c[13] += choose(tri_on(u, v), 2);
+            for &w in graph.neighbors(u).iter().filter(|&&w| w != v) {
+                if common[w] == 0 {
+                    touched.push(w);
+                }
+                common[w] += 1;
+            }
+        }
+        c[4] -= 2 * node_tri[v];
+        c[5] -= 2 * node_tri[v];
+        for &w in &touched {
+            c[8] += choose(common[w], 2);
+            common[w] = 0;
+        }
+        touched.clear();
+    }
+    for &(a, b, w) in &triangles {
+        for (v, x, y) in [(a, b, w), (b, a, w), (w, a, b)] {
+            counts[v][10] += deg(x) - 2 + deg(y) - 2;
+            counts[v][12] += tri_on(x, y) - 1;
+        }
+    }
+
+    // Convert to induced orbit counts, from the densest graphlet down
+    (0..n)
+        .map(|v| {
+            let c = counts[v];
+            let mut o = [0u64; ORBITS];
+            o[0] = c[0];
+            o[3] = c[3];
+            o[2] = c[2] - c[3];
+            o[1] = c[1] - 2 * c[3];
+            o[14] = c[14];
+            o[13] = c[13] - 3 * o[14];
+            o[12] = c[12] - 3 * o[14];
+            o[11] = c[11] - 2 * o[13] - 3 * o[14];
+            o[10] = c[10] - 2 * o[12] - 2 * o[13] - 6 * o[14];
+            o[9] = c[9] - 2 * o[12] - 3 * o[14];
+            o[8] = c[8] - o[12] - o[13] - 3 * o[14];
+            o[7] = c[7] - o[11] - o[13] - o[14];
+            o[6] = c[6] - o[9] - o[10] - 2 * o[12] - o[13] - 3 * o[14];
+            o[5] = c[5] - 2 * o[8] - o[10] - 2 * o[11] - 2 * o[12] - 4 * o[13] - 6 * o[14];
+            o[4] = c[4] - 2 * o[8] - 2 * o[9] - o[10] - 4 * o[12] - 2 * o[13] - 6 * o[14];
+            (graph.node_id(v), o)
+        })
+        .collect()
+}
+
+// Compares the motif census of the graph with `samples` degree-preserving randomizations (double
+// edge swaps), reporting each motif's z-score (how many standard deviations above the random mean
+// it is). Keeping the degrees matters: hubs alone create many stars and dense motifs, which a
+// uniform random graph of the same size lacks.
+// Its inputs are - `graph`: the graph - `four_node`: include 4-node motifs
+// - `samples`: number of randomized graphs - `seed`: RNG seed for reproducible baselines
+// Its outputs are - `Vec<MotifSignificance>`: one entry per motif
+pub fn motif_zscores(graph: &Graph, four_node: bool, samples: usize, seed: u64) -> Vec<MotifSignificance> {
+    let real = motif_census(graph, four_node);
+    let baselines: Vec<Vec<(&str, u64)>> =
+        null_graphs(graph, NullModel::SameDegrees, samples, seed).iter().map(|null| motif_census(null, four_node)).collect();
+
+    real.iter()
+        .enumerate()
+        .map(|(i, &(name, count))| {
+            let values: Vec<f64> = baselines.iter().map(|b| b[i].1 as f64).collect();
+            let mean = values.iter().sum::<f64>() / values.len().max(1) as f64;
+            let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len().max(1) as f64;
+            let std = var.sqrt();
+            let z_score = (std > 0.0).then(|| (count as f64 - mean) / std);
+            MotifSignificance { name, count, random_mean: mean, random_std: std, z_score }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    // Tests the 3-node and 4-node census on a diamond with a tail.
+    // Nodes 1,2,3,4 form K4 minus edge 1-2 (a diamond), and node 5 hangs off node 4.
+    #[test]
+    fn test_motif_census() {
+        let edges = vec![(1, 3), (1, 4), (2, 3), (2, 4), (3, 4), (4, 5)];
+        let graph = Graph::from_edges(&edges);
+        let census: HashMap<&str, u64> = motif_census(&graph, true).into_iter().collect();
+
+        assert_eq!(census["triangle"], 2);
+        // Wedges: sum of C(d,2) = 1+1+3+6+0 = 11, minus 3 per triangle
+        assert_eq!(census["open wedge"], 5);
+        assert_eq!(census["4-clique"], 0);
+        assert_eq!(census["diamond"], 1);
+        assert_eq!(census["4-cycle"], 0);
+        // {1,3,4,5} and {2,3,4,5} are triangles with a tail
+        assert_eq!(census["tailed triangle"], 2);
+        // {1,2,4,5} is the only star (center 4)
+        assert_eq!(census["3-star"], 1);
+        // Every 4-node path here has a chord, so none is induced
+        assert_eq!(census["4-path"], 0);
+    }
+
+    // Counts orbits by checking every 3- and 4-node subset, as a reference for the fast version.
+    fn brute_force_gdv(edges: &[(usize, usize)]) -> HashMap<usize, [u64; ORBITS]> {
+        let edge_set: HashSet<(usize, usize)> = edges.iter().flat_map(|&(a, b)| [(a, b), (b, a)]).collect();
+        let mut nodes: Vec<usize> = edge_set.iter().map(|e| e.0).collect();
+        nodes.sort_unstable();
+        nodes.dedup();
+        let mut gdv: HashMap<usize, [u64; ORBITS]> = nodes.iter().map(|&v| (v, [0; ORBITS])).collect();
+        let inner = |v: usize, set: &[usize]| set.iter().filter(|&&w| edge_set.contains(&(v, w))).count();
+
+        for &v in &nodes {
+            gdv.get_mut(&v).unwrap()[0] = nodes.iter().filter(|&&w| edge_set.contains(&(v, w))).count() as u64;
+        }
+        let n = nodes.len();
+        for a in 0..n {
+            for b in a + 1..n {
+                for c in b + 1..n {
+                    let set = [nodes[a], nodes[b], nodes[c]];
+                    let degs: Vec<usize> = set.iter().map(|&v| inner(v, &set)).collect();
+                    let m: usize = degs.iter().sum::<usize>() / 2;
+                    for (&v, &d) in set.iter().zip(&degs) {
+                        let orbit = match (m, d) {
+                            (2, 1) => 1,
+                            (2, 2) => 2,
+                            (3, _) => 3,
+                            _ => continue,
+                        };
+                        gdv.get_mut(&v).unwrap()[orbit] += 1;
+                    }
+                    for d in c + 1..n {
+                        let set = [nodes[a], nodes[b], nodes[c], nodes[d]];
+                        let degs: Vec<usize> = set.iter().map(|&v| inner(v, &set)).collect();
+                        let m: usize = degs.iter().sum::<usize>() / 2;
+                        let is_star = degs.contains(&3);
+                        let is_cycle = degs.iter().all(|&x| x == 2);
+                        if degs.contains(&0) || m < 3 {
+                            continue;
+                        }
+                        for (&v, &k) in set.iter().zip(&degs) {
+                            let orbit = match (m, k) {
+                                (3, 1) if is_star => 6,
+                                (3, 3) => 7,
+                                (3, 1) => 4,
+                                (3, 2) => 5,
+                                (4, _) if is_cycle => 8,
+                                (4, 1) => 9,
+                                (4, 2) => 10,
+                                (4, 3) => 11,
+                                (5, 2) => 12,
+                                (5, 3) => 13,
+                                (6, _) => 14,
+                                _ => unreachable!(),
+                            };
+                            gdv.get_mut(&v).unwrap()[orbit] += 1;
+                        }
+                    }
+                }
+            }
+        }
+        gdv
+    }
+
+    // Tests graphlet degree vectors against a brute-force orbit count.
+    // The graph mixes a 4-clique, a diamond, a 4-cycle, a tail and a path so every orbit occurs.
+    #[test]
+    fn test_graphlet_degree_vectors() {
+        let edges = vec![
+            (1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4),
+            (4, 5), (5, 6), (6, 7), (7, 4), (5, 7),
+            (7, 8), (8, 9), (9, 10), (10, 8), (10, 11), (11, 12), (12, 9),
+        ];
+        let graph = Graph::from_edges(&edges);
+
+        assert_eq!(graphlet_degree_vectors(&graph), brute_force_gdv(&edges));
+    }
+
+    // Tests that a 4-clique is counted once, that the randomizations keep the degrees (so a star,
+    // which no edge swap can change, always has its own census and no z-scores), and that
+    // triangles stand out in a ring of triangles against rewirings of it.
+    #[test]
+    fn test_motif_zscores() {
+        let edges = vec![(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4), (4, 5), (5, 6), (6, 7)];
+        let graph = Graph::from_edges(&edges);
+        let result = motif_zscores(&graph, true, 5, 42);
+
+        assert_eq!(result.len(), 8);
+        let clique = result.iter().find(|m| m.name == "4-clique").unwrap();
+        assert_eq!(clique.count, 1);
+        assert!(result.iter().all(|m| m.z_score.is_none_or(f64::is_finite)));
+
+        let star = Graph::from_edges(&(1..=6).map(|i| (0, i)).collect::<Vec<_>>());
+        for motif in motif_zscores(&star, true, 5, 42) {
+            assert_eq!((motif.random_mean, motif.random_std, motif.z_score), (motif.count as f64, 0.0, None), "{}", motif.name);
+        }
+
+        let mut ring = Vec::new();
+        for t in 0..20 {
+            let base = 3 * t;
+            ring.extend([(base, base + 1), (base + 1, base + 2), (base + 2, base), (base, (base + 3) % 60)]);
+        }
+        let triangles = motif_zscores(&Graph::from_edges(&ring), false, 10, 7).into_iter().find(|m| m.name == "triangle").unwrap();
+        assert_eq!(triangles.count, 20);
+        assert!(triangles.random_mean < 20.0 && triangles.z_score.unwrap() > 2.0);
+    }
+}
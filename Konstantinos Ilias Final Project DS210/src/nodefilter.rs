@@ -0,0 +1,190 @@
+// Includes node filters read from a configuration file, so that mailing lists, bots and other
+// addresses that are not people can be kept out of the analysis without editing code. A filter
+// file has one `key = value` setting per line ('#' starts a comment), and repeated keys add up:
+//
+//     exclude_ids = 12, 45
+//     exclude_email = ^(no.?reply|announce)
+//     exclude_email = @lists\.enron\.com$
+//     min_degree = 2
+//     max_degree = 5000
+//
+// `include_ids` and `include_email` keep only the listed nodes. Email patterns are regular
+// expressions (see `Pattern`) and ignore case.
+
+use crate::graph::Graph;
+use regex::{Regex, RegexBuilder};
+use std::collections::{HashMap, HashSet};
+
+// A compiled email pattern: a regular expression in the syntax of the `regex` crate, matched
+// anywhere in the address unless anchored with `^` or `$`, and ignoring case.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    regex: Regex,
+}
+
+impl Pattern {
+    // Compiles a pattern.
+    // Its inputs are - `source`: the pattern text
+    // Its outputs are - `Result<Pattern, String>`: the pattern, or why it is invalid
+    pub fn new(source: &str) -> Result<Pattern, String> {
+        let regex = RegexBuilder::new(source).case_insensitive(true).build().map_err(|e| format!("invalid pattern {}: {}", source, e))?;
+        Ok(Pattern { regex })
+    }
+
+    // Whether the pattern matches the text (anywhere in it, unless anchored), ignoring case.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.regex.as_str()
+    }
+}
+
+// Which nodes to keep, as read from a filter file.
+#[derive(Debug, Clone, Default)]
+pub struct NodeFilter {
+    // If not empty (together with `include_email`), only these node IDs and matching addresses are kept
+    pub include_ids: HashSet<usize>,
+    pub include_email: Vec<Pattern>,
+    pub exclude_ids: HashSet<usize>,
+    pub exclude_email: Vec<Pattern>,
+    // Degree bounds, measured in the graph before filtering
+    pub min_degree: Option<usize>,
+    pub max_degree: Option<usize>,
+}
+
+// Number of nodes a filter removed, by the first rule each one failed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FilterReport {
+    pub not_included: usize,
+    pub excluded_ids: usize,
+    pub excluded_email: usize,
+    pub degree: usize,
+}
+
+impl FilterReport {
+    pub fn removed(&self) -> usize {
+        self.not_included + self.excluded_ids + self.excluded_email + self.degree
+    }
+}
+
+impl NodeFilter {
+    // Parses the settings of a filter file.
+    // Its inputs are - `text`: the file contents
+    // Its outputs are - `Result<NodeFilter, String>`: the filter, or the first invalid line
+    pub fn parse(text: &str) -> Result<NodeFilter, String> {
+        let mut filter = NodeFilter::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| format!("line {}: {}", number + 1, message);
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(error(format!("expected key = value, found {}", line)));
+            };
+            let (key, value) = (key.trim(), value.trim());
+            let ids = || -> Result<Vec<usize>, String> {
+                value.split(',').map(|id| id.trim().parse().map_err(|_| error(format!("{} is not a node ID", id.trim())))).collect()
+            };
+            let degree = || value.parse::<usize>().map_err(|_| error(format!("{} is not a degree", value)));
+            match key {
+                "include_ids" => filter.include_ids.extend(ids()?),
+                "exclude_ids" => filter.exclude_ids.extend(ids()?),
+                "include_email" => filter.include_email.push(Pattern::new(value).map_err(error)?),
+                "exclude_email" => filter.exclude_email.push(Pattern::new(value).map_err(error)?),
+                "min_degree" => filter.min_degree = Some(degree()?),
+                "max_degree" => filter.max_degree = Some(degree()?),
+                _ => return Err(error(format!("unknown setting {}", key))),
+            }
+        }
+        Ok(filter)
+    }
+
+    // Reads a filter file.
+    pub fn from_file(path: &str) -> Result<NodeFilter, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path, e))?;
+        NodeFilter::parse(&text).map_err(|e| format!("{}, {}", path, e))
+    }
+
+    // Whether the filter keeps a node, and if not the rule that removed it.
+    fn check(&self, id: usize, email: &str, degree: usize, report: &mut FilterReport) -> bool {
+        let included = self.include_ids.contains(&id) || self.include_email.iter().any(|p| p.is_match(email));
+        let counter = if !(included || self.include_ids.is_empty() && self.include_email.is_empty()) {
+            &mut report.not_included
+        } else if self.exclude_ids.contains(&id) {
+            &mut report.excluded_ids
+        } else if self.exclude_email.iter().any(|p| p.is_match(email)) {
+            &mut report.excluded_email
+        } else if self.min_degree.is_some_and(|d| degree < d) || self.max_degree.is_some_and(|d| degree > d) {
+            &mut report.degree
+        } else {
+            return true;
+        };
+        *counter += 1;
+        false
+    }
+
+    // Applies the filter to a graph. Nodes missing from the mapping have an empty address.
+    // Its inputs are - `graph`: the graph - `email_map`: node ID -> (email, folder)
+    // Its outputs are - `(Graph, FilterReport)`: the induced subgraph of the kept nodes, and why nodes were removed
+    pub fn apply(&self, graph: &Graph, email_map: &HashMap<usize, (String, String)>) -> (Graph, FilterReport) {
+        let mut report = FilterReport::default();
+        let nodes: Vec<usize> = (0..graph.node_count())
+            .filter(|&v| {
+                let id = graph.node_id(v);
+                let email = email_map.get(&id).map_or("", |(e, _)| e.as_str());
+                self.check(id, email, graph.degree(v), &mut report)
+            })
+            .collect();
+        (graph.subgraph(&nodes), report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests the pattern syntax: anchors (also inside alternatives), classes, escapes, groups,
+    // counted repeats, case, and invalid patterns.
+    #[test]
+    fn test_pattern() {
+        let matches = |p: &str, t: &str| Pattern::new(p).unwrap().is_match(t);
+        assert!(matches("@lists\\.enron\\.com$", "ann@LISTS.enron.com"));
+        assert!(!matches("@lists\\.enron\\.com$", "ann@lists.enron.com.au"));
+        assert!(matches("^(no.?reply|announce)", "no-reply@enron.com") && matches("^(no.?reply|announce)", "announcements@x.com"));
+        assert!(!matches("^(no.?reply|announce)", "jeff.announce@x.com"));
+        assert!(matches("^no-reply|^announce", "announce@x.com") && !matches("^no-reply|^announce", "x.announce@x.com"));
+        assert!(matches("^[a-z]+\\d{2,3}@", "user42@aol.com") && !matches("^[a-z]+\\d{2,3}@", "user4@aol.com"));
+        assert!(matches("^a.*b.*c$", "axxbyyc") && matches("^(ab)*$", "abab") && !matches("^(ab)*$", "aba"));
+        assert!(matches("[^@]+@x", "bob@x") && matches("^$", ""));
+        assert_eq!(Pattern::new("^all-").unwrap().as_str(), "^all-");
+        for bad in ["(a", "a)", "*a", "[a-", "a{2", "\\"] {
+            assert!(Pattern::new(bad).is_err(), "{}", bad);
+        }
+    }
+
+    // Tests parsing a filter file and applying it: excluded IDs, excluded addresses and a
+    // minimum degree each remove their nodes, include lists keep only theirs, and invalid
+    // lines are reported with their number.
+    #[test]
+    fn test_node_filter() {
+        let graph = Graph::from_edges(&[(1, 2), (1, 3), (1, 4), (2, 3), (4, 5), (6, 1)]);
+        let email_map = HashMap::from([
+            (2, ("all-staff@lists.enron.com".to_string(), String::new())),
+            (3, ("jeff@enron.com".to_string(), String::new())),
+        ]);
+        let filter = NodeFilter::parse("# test\nexclude_ids = 6\nexclude_email = ^all-\nmin_degree = 2\n").unwrap();
+        let (kept, report) = filter.apply(&graph, &email_map);
+        assert_eq!(kept.edges(), vec![(1, 3), (1, 4)]);
+        assert_eq!(report, FilterReport { not_included: 0, excluded_ids: 1, excluded_email: 1, degree: 1 });
+
+        let include = NodeFilter::parse("include_ids = 1, 4\ninclude_email = JEFF@").unwrap();
+        let (kept, report) = include.apply(&graph, &email_map);
+        assert_eq!((kept.edges(), report.removed()), (vec![(1, 3), (1, 4)], 3));
+
+        assert_eq!(NodeFilter::parse("min_degree = 2\nmax_degree = lots").unwrap_err(), "line 2: lots is not a degree");
+        assert!(NodeFilter::parse("exclude = 4").is_err() && NodeFilter::parse("exclude_email = (a").is_err());
+    }
+}
@@ -0,0 +1,93 @@
+// Includes a harness for null-model significance testing: any metric is computed on the real
+// network and on randomized versions of it, and the real value is compared with the random
+// distribution through an empirical z-score and p-value.
+
+use crate::generate::{degree_preserving_rewire, erdos_renyi};
+use crate::graph::Graph;
+
+// How the randomized graphs are generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullModel {
+    // Uniform random graphs with the same number of nodes and edges
+    SameSize,
+    // Double edge swaps that keep every node's degree
+    SameDegrees,
+}
+
+// Result of testing one metric.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Significance {
+    pub name: String,
+    pub observed: f64,
+    pub null_mean: f64,
+    pub null_std: f64,
+    pub z_score: f64,
+    // Two-sided empirical p-value: share of randomized graphs at least as far from the null mean
+    pub p_value: f64,
+}
+
+// Generates `samples` randomized graphs under the null model.
+// Its inputs are - `graph`: the graph - `model`: randomization - `samples`: number of graphs - `seed`: RNG seed
+// Its outputs are - `Vec<Graph>`: the randomized graphs
+pub fn null_graphs(graph: &Graph, model: NullModel, samples: usize, seed: u64) -> Vec<Graph> {
+    (0..samples as u64)
+        .map(|i| {
+            let edges = match model {
+                NullModel::SameSize => erdos_renyi(graph.node_count(), graph.edge_count(), seed.wrapping_add(i)),
+                NullModel::SameDegrees => degree_preserving_rewire(graph, 10, seed.wrapping_add(i)),
+            };
+            Graph::from_edges(&edges)
+        })
+        .collect()
+}
+
+// Compares a metric on the real graph with its values on the randomized graphs.
+// The p-value is (1 + extreme samples) / (1 + samples), so it is never exactly 0.
+// Its inputs are - `graph`: the real graph - `nulls`: randomized graphs - `name`: label for the report
+// - `metric`: any function of a graph
+// Its outputs are - `Significance`: observed value, null mean and spread, z-score and p-value
+pub fn significance<F: Fn(&Graph) -> f64>(graph: &Graph, nulls: &[Graph], name: &str, metric: F) -> Significance {
+    let observed = metric(graph);
+    let values: Vec<f64> = nulls.iter().map(&metric).collect();
+    let count = values.len().max(1) as f64;
+    let null_mean = values.iter().sum::<f64>() / count;
+    let null_std = (values.iter().map(|v| (v - null_mean).powi(2)).sum::<f64>() / count).sqrt();
+    // Without any variation in the null values, a differing value is infinitely significant
+    let z_score = if null_std > 0.0 {
+        (observed - null_mean) / null_std
+    } else if observed == null_mean {
+        0.0
+    } else {
+        (observed - null_mean).signum() * f64::INFINITY
+    };
+    let extreme = values.iter().filter(|v| (*v - null_mean).abs() >= (observed - null_mean).abs()).count();
+    let p_value = (1 + extreme) as f64 / (1 + values.len()) as f64;
+    Significance { name: name.to_string(), observed, null_mean, null_std, z_score, p_value }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that the clustering of a graph made of triangles stands out against degree-preserving
+    // randomizations, while its degree sequence (and so its edge count) does not.
+    #[test]
+    fn test_significance() {
+        let mut edges = Vec::new();
+        for t in 0..20 {
+            let base = 3 * t;
+            edges.extend([(base, base + 1), (base + 1, base + 2), (base + 2, base)]);
+            edges.push((base, (base + 3) % 60));
+        }
+        let graph = Graph::from_edges(&edges);
+        let nulls = null_graphs(&graph, NullModel::SameDegrees, 20, 1);
+
+        let clustering = significance(&graph, &nulls, "transitivity", |g| g.transitivity());
+        assert!(clustering.z_score > 3.0);
+        assert!((clustering.p_value - 1.0 / 21.0).abs() < 1e-12);
+
+        let size = significance(&graph, &nulls, "edges", |g| g.edge_count() as f64);
+        assert_eq!(size.z_score, 0.0);
+        assert_eq!(size.p_value, 1.0);
+    }
+}
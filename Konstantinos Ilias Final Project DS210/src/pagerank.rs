@@ -0,0 +1,120 @@
+// Includes functions to compute PageRank and personalized PageRank by power iteration.
+// Personalized PageRank restarts every walk at a seed node (or set of seed nodes),
+// so its scores rank how relevant every other node is to the seeds' neighborhood.
+
+use crate::graph::Adjacency;
+use std::collections::HashMap;
+
+// Runs power iteration with the given restart distribution over compact indices.
+// Nodes without neighbors send their rank back through the restart distribution.
+fn power_iteration<G: Adjacency>(graph: &G, restart: &[f64], damping: f64, tolerance: f64, max_iters: usize) -> Vec<f64> {
+    let n = graph.node_count();
+    let mut rank = restart.to_vec();
+    for _ in 0..max_iters {
+        let mut next = vec![0.0; n];
+        let mut dangling = 0.0;
+        for (v, &r) in rank.iter().enumerate() {
+            let degree = graph.degree(v);
+            if degree == 0 {
+                dangling += r;
+                continue;
+            }
+            let share = r / degree as f64;
+            for w in graph.neighbor_iter(v) {
+                next[w] += share;
+            }
+        }
+        for (x, &r) in next.iter_mut().zip(restart) {
+            *x = damping * (*x + dangling * r) + (1.0 - damping) * r;
+        }
+        let change: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+        rank = next;
+        if change < tolerance {
+            break;
+        }
+    }
+    rank
+}
+
+// Computes the PageRank of every node, restarting uniformly at random.
+// Its inputs are - `graph`: the graph - `damping`: probability of following an edge (usually 0.85)
+// - `tolerance`: L1 change at which to stop - `max_iters`: iteration limit
+// Its outputs are - `HashMap<usize, f64>`: node ID -> PageRank (sums to 1)
+pub fn pagerank<G: Adjacency>(graph: &G, damping: f64, tolerance: f64, max_iters: usize) -> HashMap<usize, f64> {
+    let n = graph.node_count();
+    let restart = vec![1.0 / n.max(1) as f64; n];
+    let rank = power_iteration(graph, &restart, damping, tolerance, max_iters);
+    (0..n).map(|v| (graph.node_id(v), rank[v])).collect()
+}
+
+// Computes personalized PageRank, restarting uniformly at one of the seed nodes.
+// Seed IDs that are not in the graph are ignored; with no valid seed every score is 0.
+// Its inputs are - `graph`: the graph - `seeds`: seed node IDs - `damping`: probability of following an edge
+// - `tolerance`: L1 change at which to stop - `max_iters`: iteration limit
+// Its outputs are - `HashMap<usize, f64>`: node ID -> personalized PageRank (sums to 1)
+pub fn personalized_pagerank<G: Adjacency>(
+    graph: &G,
+    seeds: &[usize],
+    damping: f64,
+    tolerance: f64,
+    max_iters: usize,
+) -> HashMap<usize, f64> {
+    let n = graph.node_count();
+    let seed_indices: Vec<usize> = seeds.iter().filter_map(|&id| graph.index_of(id)).collect();
+    let mut restart = vec![0.0; n];
+    for &v in &seed_indices {
+        restart[v] += 1.0 / seed_indices.len() as f64;
+    }
+    let rank = power_iteration(graph, &restart, damping, tolerance, max_iters);
+    (0..n).map(|v| (graph.node_id(v), rank[v])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Graph;
+    use crate::walks::uniform_walk;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // Tests PageRank on a star, where the closed form gives the center (1 - d) / n + d / 2.
+    #[test]
+    fn test_pagerank() {
+        let edges = vec![(0, 1), (0, 2), (0, 3), (0, 4)];
+        let graph = Graph::from_edges(&edges);
+        let rank = pagerank(&graph, 0.85, 1e-12, 1000);
+
+        assert!((rank.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        // center = 0.15 / 5 + 0.85 * (sum of leaves) and each leaf = 0.15 / 5 + 0.85 * center / 4
+        let center = (0.03 + 0.85 * 4.0 * 0.03) / (1.0 - 0.85 * 0.85);
+        assert!((rank[&0] - center).abs() < 1e-9);
+        assert!((rank[&1] - rank[&4]).abs() < 1e-12);
+    }
+
+    // Tests personalized PageRank against Monte Carlo random walks with restart.
+    // A path 1-2-3-4-5 is seeded at node 1, so scores fall along the path past its neighbor.
+    #[test]
+    fn test_personalized_pagerank() {
+        let edges = vec![(1, 2), (2, 3), (3, 4), (4, 5)];
+        let graph = Graph::from_edges(&edges);
+        let ppr = personalized_pagerank(&graph, &[1], 0.85, 1e-12, 1000);
+        assert!((ppr.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(ppr[&2] > ppr[&3] && ppr[&3] > ppr[&4] && ppr[&4] > ppr[&5]);
+
+        // The walk ends after a geometric number of steps; its end point is PPR-distributed
+        let mut rng = StdRng::seed_from_u64(3);
+        let start = graph.index_of(1).unwrap();
+        let samples = 100_000;
+        let mut hits = vec![0usize; graph.node_count()];
+        for _ in 0..samples {
+            let mut steps = 1;
+            while rng.gen::<f64>() < 0.85 {
+                steps += 1;
+            }
+            hits[*uniform_walk(&graph, start, steps, &mut rng).last().unwrap()] += 1;
+        }
+        for v in 0..graph.node_count() {
+            assert!((hits[v] as f64 / samples as f64 - ppr[&graph.node_id(v)]).abs() < 0.01);
+        }
+    }
+}
@@ -0,0 +1,758 @@
+// Includes the core centrality analysis as a pipeline: the stages wanted (degree, closeness,
+// betweenness, PageRank, k-means clustering of the centrality features and the plots of the
+// results) are chosen with a builder, each stage pulls in the stages it needs, and running the
+// pipeline executes them in dependency order and returns every result in one `AnalysisResult`.
+// Stages that do not depend on each other run at the same time on the rayon thread pool: a
+// stage is started as soon as the last stage it depends on has finished, so closeness and
+// betweenness run side by side, and the degree plots are drawn while betweenness is computed.
+//
+//     let result = AnalysisPipeline::new()
+//         .with_degree()
+//         .with_betweenness(BetweennessMode::Sampling(256))
+//         .with_kmeans(5)
+//         .with_plots("plots")
+//         .run(&graph, &email_map)?;
+
+use crate::cache::{node_set_hash, MetricCache};
+use crate::cluster::{kmeans, normalize_features, Features};
+use crate::distance;
+use crate::graph::{
+    adjust_closeness, brandes_betweenness, compute_degree, BetweennessNormalization, ClosenessMode,
+    DegreeMeasure, Graph, InOutDegree, NodeOrder,
+};
+use crate::pagerank::pagerank;
+use crate::plot::*;
+use crate::stats::top_k;
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rand::SeedableRng;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+// A step of the analysis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    // Degree (or strength) of every node, and the nodes in the centrality scope
+    Degree,
+    // Closeness of the nodes in scope
+    Closeness,
+    // Betweenness from the sources chosen by the `BetweennessMode`
+    Betweenness,
+    PageRank,
+    // K-means clustering of the nodes in scope by (degree, closeness, betweenness)
+    KMeans,
+    // Degree histogram and CCDF, and the top nodes by degree
+    DegreePlots,
+    // Betweenness histogram and the top nodes by betweenness
+    BetweennessPlots,
+    // The k-means clusters in 2D and 3D and their profiles
+    ClusterPlots,
+    // Closeness against degree, betweenness against closeness and, with k-means, the dashboard
+    ComparisonPlots,
+}
+
+// The plot stages, each planned when the metrics it draws are.
+const PLOT_STAGES: [Stage; 4] = [Stage::DegreePlots, Stage::BetweennessPlots, Stage::ClusterPlots, Stage::ComparisonPlots];
+
+impl std::fmt::Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Stage::Degree => write!(f, "degree"),
+            Stage::Closeness => write!(f, "closeness"),
+            Stage::Betweenness => write!(f, "betweenness"),
+            Stage::PageRank => write!(f, "pagerank"),
+            Stage::KMeans => write!(f, "kmeans"),
+            Stage::DegreePlots => write!(f, "degree plots"),
+            Stage::BetweennessPlots => write!(f, "betweenness plots"),
+            Stage::ClusterPlots => write!(f, "cluster plots"),
+            Stage::ComparisonPlots => write!(f, "comparison plots"),
+        }
+    }
+}
+
+// Nodes whose closeness is computed and that are clustered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CentralityScope {
+    // Every node of the graph
+    All,
+    // The top nodes by degree, as many as set by `top_nodes` (default 1000)
+    TopN,
+    // The largest connected component, where closeness needs no adjustment for reachability
+    Component,
+}
+
+impl std::fmt::Display for CentralityScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CentralityScope::All => write!(f, "all"),
+            CentralityScope::TopN => write!(f, "top-n"),
+            CentralityScope::Component => write!(f, "component"),
+        }
+    }
+}
+
+// Sources of Brandes' algorithm in the betweenness stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BetweennessMode {
+    // The nodes in the centrality scope (needs the degree stage)
+    Scope,
+    // This many sources drawn at random from the whole graph
+    Sampling(usize),
+    // Every node, which is exact but takes a BFS per node
+    Exact,
+}
+
+impl std::fmt::Display for BetweennessMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BetweennessMode::Scope => write!(f, "nodes in scope"),
+            BetweennessMode::Sampling(sources) => write!(f, "{} sampled sources", sources),
+            BetweennessMode::Exact => write!(f, "all sources"),
+        }
+    }
+}
+
+// Stages to run and their parameters.
+#[derive(Debug, Clone)]
+pub struct AnalysisPipeline<'a> {
+    // Metric stages asked for, in the order they were added
+    stages: Vec<Stage>,
+    // Whether the plots of the planned metrics are drawn
+    plots: bool,
+    // Nodes that closeness, betweenness and k-means work on, and how many top nodes by degree
+    // the top-n scope takes
+    scope: CentralityScope,
+    top_nodes: usize,
+    degree_measure: DegreeMeasure,
+    // Directed edge list of a directed run, on which in- and out-degrees are counted
+    directed: Option<&'a [(usize, usize)]>,
+    closeness_mode: ClosenessMode,
+    betweenness: BetweennessMode,
+    normalization: BetweennessNormalization,
+    clusters: usize,
+    // Most k-means iterations
+    max_iters: usize,
+    plot_config: PlotConfig,
+    cache: Option<&'a MetricCache>,
+    seed: u64,
+}
+
+impl Default for AnalysisPipeline<'_> {
+    fn default() -> Self {
+        AnalysisPipeline {
+            stages: Vec::new(),
+            plots: false,
+            scope: CentralityScope::TopN,
+            top_nodes: 1000,
+            degree_measure: DegreeMeasure::Degree,
+            directed: None,
+            closeness_mode: ClosenessMode::Standard,
+            betweenness: BetweennessMode::Scope,
+            normalization: BetweennessNormalization::Max,
+            clusters: 5,
+            max_iters: 100,
+            plot_config: PlotConfig::default(),
+            cache: None,
+            seed: 42,
+        }
+    }
+}
+
+// Everything a run of the pipeline computed. A metric is `None` if its stage did not run.
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisResult {
+    // Stages in the order they finished, with the time each took
+    pub stages: Vec<(Stage, Duration)>,
+    // Node ID -> number of incident edges
+    pub degree: Option<HashMap<usize, usize>>,
+    // Node ID -> degree or strength, as chosen by the degree measure
+    pub activity: Option<HashMap<usize, f64>>,
+    // Node ID -> in- and out-degree, in a directed run
+    pub in_out: Option<HashMap<usize, InOutDegree>>,
+    // The nodes in the centrality scope
+    pub nodes: HashSet<usize>,
+    pub closeness: Option<HashMap<usize, f64>>,
+    pub betweenness: Option<HashMap<usize, f64>>,
+    pub pagerank: Option<HashMap<usize, f64>>,
+    // Normalized (activity, closeness, betweenness) of each node in scope
+    pub features: Option<HashMap<usize, Features>>,
+    // Node ID -> k-means cluster
+    pub assignments: Option<HashMap<usize, usize>>,
+    // Names of the plots written, in the order of the planned stages
+    pub plots: Vec<&'static str>,
+}
+
+impl AnalysisResult {
+    // Writes the metrics of every node in scope as CSV, one row per node in ID order, with an
+    // empty field for a metric that was not computed.
+    // Its inputs are - `path`: the CSV file - `email_map`: node ID -> (email, folder)
+    // Its outputs are - `()`, or the error of writing the file
+    pub fn write_csv(&self, path: impl AsRef<Path>, email_map: &HashMap<usize, (String, String)>) -> std::io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "node,email,folder,degree,activity,closeness,betweenness,pagerank,cluster")?;
+        let field = |value: Option<String>| value.unwrap_or_default();
+        let mut nodes: Vec<&usize> = self.nodes.iter().collect();
+        nodes.sort();
+        for node in nodes {
+            let (email, folder) = email_map.get(node).map_or(("", ""), |(e, f)| (e.as_str(), f.as_str()));
+            let score = |map: &Option<HashMap<usize, f64>>| field(map.as_ref().and_then(|m| m.get(node)).map(|x| x.to_string()));
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{}",
+                node,
+                email,
+                folder,
+                field(self.degree.as_ref().and_then(|m| m.get(node)).map(|d| d.to_string())),
+                score(&self.activity),
+                score(&self.closeness),
+                score(&self.betweenness),
+                score(&self.pagerank),
+                field(self.assignments.as_ref().and_then(|m| m.get(node)).map(|c| c.to_string())),
+            )?;
+        }
+        out.flush()
+    }
+}
+
+impl<'a> AnalysisPipeline<'a> {
+    // A pipeline with no stages and the default parameters of the analysis.
+    pub fn new() -> AnalysisPipeline<'a> {
+        AnalysisPipeline::default()
+    }
+
+    fn with(mut self, stage: Stage) -> Self {
+        if !self.stages.contains(&stage) {
+            self.stages.push(stage);
+        }
+        self
+    }
+
+    pub fn with_degree(self) -> Self {
+        self.with(Stage::Degree)
+    }
+
+    pub fn with_closeness(self) -> Self {
+        self.with(Stage::Closeness)
+    }
+
+    pub fn with_betweenness(mut self, mode: BetweennessMode) -> Self {
+        self.betweenness = mode;
+        self.with(Stage::Betweenness)
+    }
+
+    pub fn with_pagerank(self) -> Self {
+        self.with(Stage::PageRank)
+    }
+
+    // Clusters the nodes in scope into `k` groups; the run fails if `k` is 0.
+    pub fn with_kmeans(mut self, k: usize) -> Self {
+        self.clusters = k;
+        self.with(Stage::KMeans)
+    }
+
+    // Draws the plots of every metric computed into `dir`, with the default size and style.
+    pub fn with_plots(self, dir: impl Into<PathBuf>) -> Self {
+        self.with_plot_config(PlotConfig { output_dir: dir.into(), ..PlotConfig::default() })
+    }
+
+    // Writes the plots with the given directory, prefix, size and style.
+    pub fn with_plot_config(mut self, config: PlotConfig) -> Self {
+        self.plot_config = config;
+        self.plots = true;
+        self
+    }
+
+    // Nodes used by closeness, betweenness and k-means (default: the top nodes by degree).
+    pub fn scope(mut self, scope: CentralityScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    // Number of top nodes by degree in the top-n scope (default 1000).
+    pub fn top_nodes(mut self, k: usize) -> Self {
+        self.top_nodes = k;
+        self
+    }
+
+    // Most iterations of k-means (default 100).
+    pub fn max_iters(mut self, max_iters: usize) -> Self {
+        self.max_iters = max_iters;
+        self
+    }
+
+    pub fn degree_measure(mut self, measure: DegreeMeasure) -> Self {
+        self.degree_measure = measure;
+        self
+    }
+
+    // Makes the run directed: the degree stage also counts the in- and out-degree of every node
+    // on `edges`, (from, to) pairs (the graph itself is undirected).
+    pub fn directed(mut self, edges: &'a [(usize, usize)]) -> Self {
+        self.directed = Some(edges);
+        self
+    }
+
+    pub fn closeness_mode(mut self, mode: ClosenessMode) -> Self {
+        self.closeness_mode = mode;
+        self
+    }
+
+    pub fn betweenness_normalization(mut self, normalization: BetweennessNormalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    // Reads closeness, betweenness and PageRank from the cache when they were computed before.
+    pub fn cache(mut self, cache: &'a MetricCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    // Seed of the sampled betweenness sources (default 42).
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    // Stages a stage needs to have run before it.
+    fn dependencies(&self, stage: Stage) -> Vec<Stage> {
+        match stage {
+            Stage::Degree | Stage::PageRank => Vec::new(),
+            Stage::Closeness => vec![Stage::Degree],
+            Stage::Betweenness if self.betweenness == BetweennessMode::Scope => vec![Stage::Degree],
+            Stage::Betweenness => Vec::new(),
+            Stage::KMeans => vec![Stage::Degree, Stage::Closeness, Stage::Betweenness],
+            Stage::DegreePlots => vec![Stage::Degree],
+            Stage::BetweennessPlots => vec![Stage::Betweenness],
+            Stage::ClusterPlots => vec![Stage::KMeans],
+            Stage::ComparisonPlots if self.stages.contains(&Stage::KMeans) => {
+                vec![Stage::Degree, Stage::Closeness, Stage::Betweenness, Stage::KMeans]
+            }
+            Stage::ComparisonPlots => vec![Stage::Degree, Stage::Closeness, Stage::Betweenness],
+        }
+    }
+
+    fn visit(&self, stage: Stage, order: &mut Vec<Stage>) {
+        if order.contains(&stage) {
+            return;
+        }
+        for dependency in self.dependencies(stage) {
+            self.visit(dependency, order);
+        }
+        order.push(stage);
+    }
+
+    // The stages that will run, each after the stages it depends on: the stages asked for and
+    // their dependencies, then the plots of the metrics among them.
+    pub fn plan(&self) -> Vec<Stage> {
+        let mut order = Vec::new();
+        for &stage in &self.stages {
+            self.visit(stage, &mut order);
+        }
+        if self.plots {
+            for stage in PLOT_STAGES {
+                if self.dependencies(stage).iter().all(|d| order.contains(d)) {
+                    order.push(stage);
+                }
+            }
+        }
+        order
+    }
+
+    // Returns the cached map of a metric if there is a cache, or computes it.
+    fn cached(&self, algorithm: &str, params: &str, compute: impl FnOnce() -> HashMap<usize, f64>) -> HashMap<usize, f64> {
+        match self.cache {
+            Some(cache) => cache.get_or_compute(algorithm, params, compute),
+            None => compute(),
+        }
+    }
+
+    // Runs the planned stages, each as soon as the stages it depends on have finished, with
+    // independent stages in parallel on the rayon thread pool.
+    // Its inputs are - `graph`: the graph - `email_map`: node ID -> (email, folder), used to
+    // label the plots
+    // Its outputs are - `AnalysisResult`: the results of every stage, or the error of a plot
+    pub fn run(&self, graph: &Graph, email_map: &HashMap<usize, (String, String)>) -> Result<AnalysisResult, Box<dyn std::error::Error>> {
+        if self.clusters == 0 && self.stages.contains(&Stage::KMeans) {
+            return Err("k-means needs at least one cluster (k = 0)".into());
+        }
+        let run = Run { pipeline: self, graph, email_map, plan: self.plan(), slots: Slots::default(), progress: Mutex::default() };
+        rayon::scope(|scope| run.spawn_ready(scope));
+
+        let progress = run.progress.into_inner().expect("a stage panicked");
+        if let Some(error) = progress.error {
+            return Err(error.into());
+        }
+        let slots = run.slots;
+        // Plot stages finish in any order; list their plots in the order of the plan
+        let mut plots = slots.plots.into_inner().expect("a plot stage panicked");
+        plots.sort_by_key(|(stage, _)| run.plan.iter().position(|s| s == stage));
+        let (degree, activity, in_out, nodes) = match slots.degree.into_inner() {
+            Some(Degrees { degree, activity, in_out, nodes }) => (Some(degree), Some(activity), in_out, nodes),
+            None => (None, None, None, HashSet::new()),
+        };
+        let (features, assignments) = slots.kmeans.into_inner().unzip();
+        Ok(AnalysisResult {
+            stages: progress.finished,
+            degree,
+            activity,
+            in_out,
+            nodes,
+            closeness: slots.closeness.into_inner(),
+            betweenness: slots.betweenness.into_inner(),
+            pagerank: slots.pagerank.into_inner(),
+            features,
+            assignments,
+            plots: plots.into_iter().flat_map(|(_, drawn)| drawn).collect(),
+        })
+    }
+}
+
+// Results of the stages of a run, each set once by its stage and read by the stages after it.
+#[derive(Default)]
+struct Slots {
+    degree: OnceLock<Degrees>,
+    closeness: OnceLock<HashMap<usize, f64>>,
+    betweenness: OnceLock<HashMap<usize, f64>>,
+    pagerank: OnceLock<HashMap<usize, f64>>,
+    // Features and assignments
+    kmeans: OnceLock<(HashMap<usize, Features>, HashMap<usize, usize>)>,
+    // Plots drawn by each plot stage
+    plots: Mutex<Vec<(Stage, Vec<&'static str>)>>,
+}
+
+// Output of the degree stage.
+struct Degrees {
+    degree: HashMap<usize, usize>,
+    activity: HashMap<usize, f64>,
+    in_out: Option<HashMap<usize, InOutDegree>>,
+    // Nodes in the centrality scope
+    nodes: HashSet<usize>,
+}
+
+// Which stages of a run have started and finished.
+#[derive(Default)]
+struct Progress {
+    started: Vec<Stage>,
+    finished: Vec<(Stage, Duration)>,
+    // The first stage that failed; no stage is started after it
+    error: Option<String>,
+}
+
+// One run of a pipeline on a graph.
+struct Run<'a> {
+    pipeline: &'a AnalysisPipeline<'a>,
+    graph: &'a Graph,
+    email_map: &'a HashMap<usize, (String, String)>,
+    plan: Vec<Stage>,
+    slots: Slots,
+    progress: Mutex<Progress>,
+}
+
+impl<'a> Run<'a> {
+    // Starts every planned stage that has not started and whose dependencies have all finished.
+    // Each stage calls this again when it finishes, so the run needs no scheduler thread.
+    fn spawn_ready<'s>(&'s self, scope: &rayon::Scope<'s>) {
+        let ready: Vec<Stage> = {
+            let mut progress = self.progress.lock().expect("a stage panicked");
+            if progress.error.is_some() {
+                return;
+            }
+            let done = |stage: &Stage| progress.finished.iter().any(|(finished, _)| finished == stage);
+            let ready: Vec<Stage> = self
+                .plan
+                .iter()
+                .copied()
+                .filter(|stage| !progress.started.contains(stage) && self.pipeline.dependencies(*stage).iter().all(done))
+                .collect();
+            progress.started.extend(&ready);
+            ready
+        };
+        for stage in ready {
+            scope.spawn(move |scope| {
+                let start = Instant::now();
+                let outcome = self.execute(stage);
+                {
+                    let mut progress = self.progress.lock().expect("a stage panicked");
+                    match outcome {
+                        Ok(()) => progress.finished.push((stage, start.elapsed())),
+                        Err(e) => {
+                            progress.error.get_or_insert(format!("{} failed: {}", stage, e));
+                        }
+                    }
+                }
+                self.spawn_ready(scope);
+            });
+        }
+    }
+
+    fn execute(&self, stage: Stage) -> Result<(), Box<dyn std::error::Error>> {
+        let (pipeline, graph) = (self.pipeline, self.graph);
+        match stage {
+            // Counted on the graph, where an edge listed in both directions (as in the SNAP files)
+            // is one edge, so the degrees agree with the graph summary
+            Stage::Degree => {
+                let degree: HashMap<usize, usize> = (0..graph.node_count()).map(|v| (graph.node_id(v), graph.degree(v))).collect();
+                let nodes = match pipeline.scope {
+                    CentralityScope::All => (0..graph.node_count()).map(|v| graph.node_id(v)).collect(),
+                    CentralityScope::TopN => top_k(&degree, pipeline.top_nodes).into_iter().map(|(node, _)| node).collect(),
+                    CentralityScope::Component => {
+                        let giant = graph.components().into_iter().max_by_key(|c| c.len()).unwrap_or_default();
+                        giant.into_iter().map(|v| graph.node_id(v)).collect()
+                    }
+                };
+                let activity = match pipeline.degree_measure {
+                    DegreeMeasure::Degree => degree.iter().map(|(&node, &deg)| (node, deg as f64)).collect(),
+                    DegreeMeasure::Strength => (0..graph.node_count()).map(|v| (graph.node_id(v), graph.strength(v))).collect(),
+                };
+                let in_out = pipeline.directed.map(compute_degree);
+                let _ = self.slots.degree.set(Degrees { degree, activity, in_out, nodes });
+            }
+            // Weighted graphs use weighted distances (1 / weight per edge), found by delta-stepping
+            Stage::Closeness => {
+                let nodes = self.nodes();
+                let params = format!("sources={:016x}", node_set_hash(nodes));
+                let mut closeness = pipeline.cached("closeness", &params, || {
+                    if graph.is_weighted() {
+                        distance::weighted_closeness(graph, nodes, distance::default_delta(graph))
+                    } else {
+                        distance::batched_closeness(graph, nodes)
+                    }
+                });
+                adjust_closeness(graph, &mut closeness, pipeline.closeness_mode);
+                let _ = self.slots.closeness.set(closeness);
+            }
+            Stage::Betweenness => {
+                let sources: HashSet<usize> = match pipeline.betweenness {
+                    BetweennessMode::Scope => self.nodes().clone(),
+                    BetweennessMode::Sampling(k) => {
+                        let mut rng = StdRng::seed_from_u64(pipeline.seed);
+                        index::sample(&mut rng, graph.node_count(), k.min(graph.node_count())).into_iter().map(|v| graph.node_id(v)).collect()
+                    }
+                    BetweennessMode::Exact => (0..graph.node_count()).map(|v| graph.node_id(v)).collect(),
+                };
+                let params = format!("sources={:016x} normalization={}", node_set_hash(&sources), pipeline.normalization);
+                let compute = || brandes_betweenness(&graph.reordered(NodeOrder::Bfs), &sources, pipeline.normalization);
+                let _ = self.slots.betweenness.set(pipeline.cached("betweenness", &params, compute));
+            }
+            Stage::PageRank => {
+                let compute = || pagerank(graph, 0.85, 1e-10, 200);
+                let _ = self.slots.pagerank.set(pipeline.cached("pagerank", "damping=0.85 tolerance=1e-10 iterations=200", compute));
+            }
+            Stage::KMeans => {
+                let value = |map: Option<&HashMap<usize, f64>>, node: &usize| map.and_then(|m| m.get(node)).copied().unwrap_or(0.0);
+                let activity = self.slots.degree.get().map(|d| &d.activity);
+                let (closeness, betweenness) = (self.slots.closeness.get(), self.slots.betweenness.get());
+                let mut features: HashMap<usize, Features> = self
+                    .nodes()
+                    .iter()
+                    .map(|node| (*node, (value(activity, node), value(closeness, node), value(betweenness, node))))
+                    .collect();
+                normalize_features(&mut features);
+                let assignments = kmeans(&features, pipeline.clusters, pipeline.max_iters);
+                let _ = self.slots.kmeans.set((features, assignments));
+            }
+            _ => self.plot(stage)?,
+        }
+        Ok(())
+    }
+
+    // The nodes in scope, for the stages that depend on the degree stage.
+    fn nodes(&self) -> &HashSet<usize> {
+        &self.slots.degree.get().expect("runs after the degree stage").nodes
+    }
+
+    fn plot(&self, stage: Stage) -> Result<(), Box<dyn std::error::Error>> {
+        let config = &self.pipeline.plot_config;
+        let log_histogram = HistogramOptions { bins: 50, log_bins: true, log_y: true };
+        let degree = self.slots.degree.get().map(|d| &d.degree);
+        let (closeness, betweenness) = (self.slots.closeness.get(), self.slots.betweenness.get());
+        let kmeans = self.slots.kmeans.get();
+        let drawn = match (stage, degree, closeness, betweenness, kmeans) {
+            (Stage::DegreePlots, Some(degree), ..) => {
+                plot_degree_histogram(degree, &log_histogram, config)?;
+                plot_degree_ccdf(degree, config)?;
+                plot_top_centrality(degree, self.email_map, "Degree", 20, config)?;
+                vec!["degree histogram", "degree CCDF", "top nodes by degree"]
+            }
+            (Stage::BetweennessPlots, _, _, Some(betweenness), _) => {
+                plot_betweenness_histogram(betweenness, &log_histogram, config)?;
+                plot_top_centrality(betweenness, self.email_map, "Betweenness", 20, config)?;
+                vec!["betweenness histogram", "top nodes by betweenness"]
+            }
+            (Stage::ClusterPlots, _, _, _, Some((features, assignments))) => {
+                plot_clusters(features, assignments, config)?;
+                plot_clusters_3d(features, assignments, config)?;
+                plot_cluster_profiles(features, assignments, config)?;
+                vec!["clusters", "clusters in 3D", "cluster profiles"]
+            }
+            (Stage::ComparisonPlots, Some(degree), Some(closeness), Some(betweenness), kmeans) => {
+                plot_closeness_vs_degree(degree, closeness, config)?;
+                plot_betweenness_vs_closeness(degree, closeness, betweenness, config)?;
+                if let Some((features, assignments)) = kmeans {
+                    plot_dashboard(degree, betweenness, closeness, features, assignments, &log_histogram, config)?;
+                    vec!["closeness vs degree", "betweenness vs closeness", "dashboard"]
+                } else {
+                    vec!["closeness vs degree", "betweenness vs closeness"]
+                }
+            }
+            _ => Vec::new(),
+        };
+        self.slots.plots.lock().expect("a plot stage panicked").push((stage, drawn));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that stages pull in their dependencies, that plots are planned for the metrics
+    // computed, that concurrent stages still finish after their dependencies, that a run fills
+    // in exactly the results of the planned stages, that degrees count an edge listed both ways
+    // once, and that each scope selects its nodes.
+    #[test]
+    fn test_analysis_pipeline() {
+        let pipeline = AnalysisPipeline::new().with_kmeans(2).with_pagerank().top_nodes(10);
+        assert_eq!(pipeline.plan(), vec![Stage::Degree, Stage::Closeness, Stage::Betweenness, Stage::KMeans, Stage::PageRank]);
+        let sampled = AnalysisPipeline::new().with_plots("plots").with_betweenness(BetweennessMode::Sampling(4)).with_degree();
+        assert_eq!(sampled.plan(), vec![Stage::Betweenness, Stage::Degree, Stage::DegreePlots, Stage::BetweennessPlots]);
+
+        let edges: Vec<(usize, usize, f64)> = (0..40).flat_map(|v| [(v, (v + 1) % 40, 1.0), (v, (v * 7 + 3) % 40, 1.0)]).collect();
+        let graph = Graph::from_weighted_edges(&edges);
+        let result = pipeline.run(&graph, &HashMap::new()).unwrap();
+        assert!(result.in_out.is_none());
+        let ran: Vec<Stage> = result.stages.iter().map(|&(stage, _)| stage).collect();
+        assert_eq!(ran.len(), 5);
+        for (i, stage) in ran.iter().enumerate() {
+            assert!(pipeline.dependencies(*stage).iter().all(|d| ran[..i].contains(d)), "{} ran too early", stage);
+        }
+        assert_eq!(result.nodes.len(), 10);
+        assert_eq!(result.degree.as_ref().unwrap().len(), 40);
+        // Listing every edge in both directions, as the SNAP files do, leaves the degrees alone
+        let doubled: Vec<(usize, usize, f64)> = edges.iter().flat_map(|&(u, v, w)| [(u, v, w), (v, u, w)]).collect();
+        let both_ways = AnalysisPipeline::new().with_degree().run(&Graph::from_weighted_edges(&doubled), &HashMap::new()).unwrap();
+        assert_eq!(both_ways.degree, result.degree);
+        let pairs: Vec<(usize, usize)> = edges.iter().map(|&(u, v, _)| (u, v)).collect();
+        let directed = AnalysisPipeline::new().with_degree().directed(&pairs).run(&graph, &HashMap::new()).unwrap();
+        let in_out = directed.in_out.unwrap();
+        assert_eq!(in_out[&0], InOutDegree { in_deg: 2, out_deg: 2, degree: 4 });
+        assert_eq!(in_out[&3], InOutDegree { in_deg: 2, out_deg: 2, degree: 4 });
+        assert_eq!(result.closeness.as_ref().unwrap().len(), 10);
+        let assignments = result.assignments.as_ref().unwrap();
+        assert!(result.nodes.iter().all(|node| assignments[node] < 2));
+        assert!(result.pagerank.is_some() && result.plots.is_empty());
+        let csv = std::env::temp_dir().join("project_pipeline_test.csv");
+        result.write_csv(&csv, &HashMap::from([(0, ("a@enron.com".to_string(), "inbox".to_string()))])).unwrap();
+        let rows = std::fs::read_to_string(&csv).unwrap();
+        assert_eq!(rows.lines().count(), 11);
+        let _ = std::fs::remove_file(&csv);
+
+        let exact = AnalysisPipeline::new().with_betweenness(BetweennessMode::Exact).run(&graph, &HashMap::new()).unwrap();
+        assert!(exact.degree.is_none() && exact.nodes.is_empty());
+        let nodes: HashSet<usize> = (0..40).collect();
+        let reference = brandes_betweenness(&graph, &nodes, BetweennessNormalization::Max);
+        let betweenness = exact.betweenness.unwrap();
+        assert_eq!(betweenness.len(), 40);
+        assert!(betweenness.iter().all(|(node, score)| (score - reference[node]).abs() < 1e-9));
+
+        // A separate triangle is outside the component scope but inside the scope of all nodes
+        let mut split = edges.clone();
+        split.extend([(100, 101, 1.0), (101, 102, 1.0), (102, 100, 1.0)]);
+        let graph = Graph::from_weighted_edges(&split);
+        let component = AnalysisPipeline::new().with_closeness().scope(CentralityScope::Component).run(&graph, &HashMap::new()).unwrap();
+        assert_eq!(component.nodes, (0..40).collect());
+        let all = AnalysisPipeline::new().with_kmeans(3).max_iters(1).scope(CentralityScope::All).run(&graph, &HashMap::new()).unwrap();
+        assert_eq!((all.nodes.len(), all.assignments.unwrap().len()), (43, 43));
+        assert!(AnalysisPipeline::new().with_kmeans(0).run(&graph, &HashMap::new()).is_err());
+    }
+
+    // Tests the centrality scopes on edge cases: an empty graph runs every stage under each
+    // scope with nothing in scope, a top-n larger than the graph takes every node, the
+    // component scope of a graph of equal components takes exactly one of them, and directed
+    // degrees are reported whatever the scope.
+    #[test]
+    fn test_centrality_scope_edge_cases() {
+        let scopes = [CentralityScope::All, CentralityScope::TopN, CentralityScope::Component];
+        let empty = Graph::from_edges(&[]);
+        for scope in scopes {
+            let result = AnalysisPipeline::new().scope(scope).with_kmeans(3).with_pagerank().run(&empty, &HashMap::new()).unwrap();
+            assert!(result.nodes.is_empty() && result.assignments.unwrap().is_empty(), "{}", scope);
+        }
+
+        let pairs: Vec<(usize, usize)> = vec![(1, 2), (3, 4), (5, 6), (6, 5)];
+        let graph = Graph::from_edges(&pairs);
+        let top = AnalysisPipeline::new().top_nodes(100).with_closeness().run(&graph, &HashMap::new()).unwrap();
+        assert_eq!(top.nodes.len(), 6);
+        assert_eq!(top.closeness.unwrap().len(), 6);
+        for scope in scopes {
+            let result = AnalysisPipeline::new().scope(scope).top_nodes(1).with_degree().directed(&pairs).run(&graph, &HashMap::new()).unwrap();
+            let expected = if scope == CentralityScope::Component { 2 } else if scope == CentralityScope::TopN { 1 } else { 6 };
+            assert_eq!(result.nodes.len(), expected, "{}", scope);
+            assert_eq!(result.in_out.unwrap()[&5], InOutDegree { in_deg: 1, out_deg: 1, degree: 1 });
+        }
+        let component = AnalysisPipeline::new().scope(CentralityScope::Component).with_degree().run(&graph, &HashMap::new()).unwrap();
+        assert!(component.nodes.iter().all(|node| graph.index_of(*node).is_some_and(|v| graph.degree(v) == 1)));
+    }
+
+    // Tests that running every stage concurrently gives bit for bit the results of a serial run
+    // (one worker thread, so one stage at a time), and that the dependent stages saw the results
+    // of the stages they depend on: k-means clusters the features of the degree, closeness and
+    // betweenness results, and every plot stage drew its plots.
+    #[test]
+    fn test_concurrent_pipeline_matches_serial() {
+        let edges: Vec<(usize, usize, f64)> =
+            (0..60).flat_map(|v| [(v, (v + 1) % 60, 1.0 + (v % 3) as f64), (v, (v * 7 + 5) % 60, 0.5), (v, (v * 13 + 2) % 60, 2.0)]).collect();
+        let graph = Graph::from_weighted_edges(&edges);
+        let dir = std::env::temp_dir().join("project_pipeline_concurrency_test");
+        let pipeline = AnalysisPipeline::new()
+            .top_nodes(30)
+            .with_degree()
+            .degree_measure(DegreeMeasure::Strength)
+            .with_closeness()
+            .with_betweenness(BetweennessMode::Scope)
+            .with_pagerank()
+            .with_kmeans(3)
+            .with_plot_config(PlotConfig { output_dir: dir.clone(), width: 320, height: 240, ..PlotConfig::default() });
+        let run_on = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            pool.install(|| pipeline.run(&graph, &HashMap::new()).map_err(|e| e.to_string())).unwrap()
+        };
+        let bits = |map: &Option<HashMap<usize, f64>>| -> HashMap<usize, u64> { map.as_ref().unwrap().iter().map(|(&n, x)| (n, x.to_bits())).collect() };
+
+        let serial = run_on(1);
+        for threads in [2, 8] {
+            let concurrent = run_on(threads);
+            assert_eq!(concurrent.degree, serial.degree);
+            assert_eq!(bits(&concurrent.activity), bits(&serial.activity));
+            assert_eq!(concurrent.nodes, serial.nodes);
+            assert_eq!(bits(&concurrent.closeness), bits(&serial.closeness));
+            assert_eq!(bits(&concurrent.betweenness), bits(&serial.betweenness));
+            assert_eq!(bits(&concurrent.pagerank), bits(&serial.pagerank));
+            assert_eq!(concurrent.features, serial.features);
+            assert_eq!(concurrent.assignments, serial.assignments);
+            assert_eq!(concurrent.plots, serial.plots);
+            assert_eq!(concurrent.stages.len(), pipeline.plan().len());
+        }
+
+        // The k-means stage clustered the nodes in scope on the outputs of the stages before it
+        let value = |map: &Option<HashMap<usize, f64>>, node: &usize| map.as_ref().unwrap().get(node).copied().unwrap_or(0.0);
+        let mut features: HashMap<usize, Features> = serial
+            .nodes
+            .iter()
+            .map(|node| (*node, (value(&serial.activity, node), value(&serial.closeness, node), value(&serial.betweenness, node))))
+            .collect();
+        normalize_features(&mut features);
+        assert_eq!(serial.features.as_ref(), Some(&features));
+        assert_eq!(serial.assignments, Some(kmeans(&features, 3, 100)));
+        assert_eq!(serial.closeness.as_ref().unwrap().len(), 30);
+        for name in ["degree histogram", "betweenness histogram", "clusters", "dashboard"] {
+            assert!(serial.plots.contains(&name), "{} was not drawn", name);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
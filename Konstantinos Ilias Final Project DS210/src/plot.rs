@@ -1,99 +1,342 @@
 // Generates plots that help with visualizing Enron network analysis results.
 // It includes histograms and scatterplots using the `plotters` crate.
+use plotters::coord::ranged1d::{AsRangedCoord, ValueFormatter};
+use plotters::coord::Shift;
 use plotters::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
 use plotters::style::Color;
+use crate::cluster::{cluster_centroids, Features, KMeansStep};
+use crate::coarsen::CoarseGraph;
+use crate::correlation::CorrelationMethod;
+use crate::graph::Graph;
+use crate::inequality::CentralityValue;
+use crate::robustness::RobustnessPoint;
+use crate::stats::top_k;
+use crate::temporal::{format_date, TemporalGraph, WindowMetrics};
 
-// Plots a histogram of degree centrality.
-// Saves output as `degree_histogram.png`.
-pub fn plot_degree_histogram(degree: &HashMap<usize, usize>) -> Result<(), Box<dyn std::error::Error>> {
-    let root = BitMapBackend::new("degree_histogram.png", (800, 600)).into_drawing_area();
-    root.fill(&WHITE)?;
+// Colors and fonts shared by all plots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotStyle {
+    // Colors for clusters and series, reused cyclically; slots 0-2 also color betweenness,
+    // degree and closeness in the single-measure plots
+    pub palette: Vec<RGBColor>,
+    pub font: String,
+    pub caption_size: u32,
+    // Size of text drawn inside charts, such as heatmap values
+    pub label_size: u32,
+    pub background: RGBColor,
+}
 
-    let mut data: Vec<usize> = degree.values().copied().collect();
-    data.sort_unstable();
+impl Default for PlotStyle {
+    fn default() -> Self {
+        PlotStyle::colorblind()
+    }
+}
+
+impl PlotStyle {
+    // The Okabe–Ito palette, which stays distinguishable under the common kinds of color
+    // blindness and has eight colors, so up to eight clusters never share one.
+    pub fn colorblind() -> Self {
+        PlotStyle {
+            palette: vec![
+                RGBColor(213, 94, 0),
+                RGBColor(0, 114, 178),
+                RGBColor(0, 158, 115),
+                RGBColor(0, 0, 0),
+                RGBColor(86, 180, 233),
+                RGBColor(230, 159, 0),
+                RGBColor(204, 121, 167),
+                RGBColor(240, 228, 66),
+            ],
+            font: String::from("sans-serif"),
+            caption_size: 30,
+            label_size: 16,
+            background: WHITE,
+        }
+    }
+
+    // The original five primary colors.
+    pub fn classic() -> Self {
+        PlotStyle { palette: vec![RED, BLUE, GREEN, BLACK, CYAN], ..PlotStyle::colorblind() }
+    }
+
+    // The `i`-th palette color, wrapping around.
+    pub fn color(&self, i: usize) -> RGBColor {
+        if self.palette.is_empty() { BLACK } else { self.palette[i % self.palette.len()] }
+    }
+
+    fn caption(&self) -> (&str, u32) {
+        (self.font.as_str(), self.caption_size)
+    }
+
+    fn small_caption(&self) -> (&str, u32) {
+        (self.font.as_str(), (self.caption_size * 4 / 5).max(1))
+    }
+
+    fn label(&self) -> (&str, u32) {
+        (self.font.as_str(), self.label_size)
+    }
+}
+
+// Palette slots of the single-measure plots.
+const BETWEENNESS: usize = 0;
+const DEGREE: usize = 1;
+const CLOSENESS: usize = 2;
+
+// Where plot images are written and how large they are. Every plot function takes one,
+// so runs on different datasets can write to separate directories or prefixes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotConfig {
+    // Directory the images are written to; created if it does not exist
+    pub output_dir: PathBuf,
+    // Prepended to every file name, e.g. a dataset or run name
+    pub prefix: String,
+    pub width: u32,
+    pub height: u32,
+    pub style: PlotStyle,
+}
+
+impl Default for PlotConfig {
+    fn default() -> Self {
+        PlotConfig {
+            output_dir: PathBuf::from("."),
+            prefix: String::new(),
+            width: 800,
+            height: 600,
+            style: PlotStyle::default(),
+        }
+    }
+}
+
+impl PlotConfig {
+    // Full path of the image `name`, creating the output directory if needed.
+    pub fn path(&self, name: &str) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(&self.output_dir)?;
+        Ok(self.output_dir.join(format!("{}{}", self.prefix, name)))
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
+
+// A bitmap drawing area: a whole image or one panel of the dashboard.
+type Area<'a> = DrawingArea<BitMapBackend<'a>, Shift>;
+
+// Binning and axis options shared by the histogram plots.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistogramOptions {
+    // Number of bins
+    pub bins: usize,
+    // Logarithmically spaced bins, for heavy-tailed values; values <= 0 are left out
+    pub log_bins: bool,
+    // Logarithmic count axis, so sparsely filled tail bins stay visible
+    pub log_y: bool,
+}
+
+impl Default for HistogramOptions {
+    fn default() -> Self {
+        HistogramOptions { bins: 50, log_bins: false, log_y: false }
+    }
+}
+
+// Splits values into bins, returning the bin edges (one more than the bins) and the counts.
+pub(crate) fn bin_values(values: &[f64], options: &HistogramOptions) -> (Vec<f64>, Vec<usize>) {
+    let bins = options.bins.max(1);
+    let max = values.iter().copied().fold(0.0, f64::max);
+    let edges: Vec<f64> = if options.log_bins {
+        let min = values.iter().copied().filter(|&v| v > 0.0).fold(f64::INFINITY, f64::min).min(max);
+        let (lo, hi) = if min.is_finite() && min > 0.0 { (min, max.max(min * 1.0001)) } else { (1.0, 10.0) };
+        (0..=bins).map(|i| lo * (hi / lo).powf(i as f64 / bins as f64)).collect()
+    } else {
+        let width = if max > 0.0 { max / bins as f64 } else { 1.0 };
+        (0..=bins).map(|i| i as f64 * width).collect()
+    };
 
-    let max = *data.iter().max().unwrap_or(&1);
-    let bins = 50;
-    let bin_width = (max / bins).max(1);
     let mut counts = vec![0; bins];
-    for val in &data {
-        let idx = (*val / bin_width).min(bins - 1);
-        counts[idx] += 1;
+    for &v in values {
+        if options.log_bins && v <= 0.0 {
+            continue;
+        }
+        let idx = edges.partition_point(|&edge| edge <= v).saturating_sub(1);
+        counts[idx.min(bins - 1)] += 1;
     }
+    (edges, counts)
+}
 
-    let mut chart = ChartBuilder::on(&root)
-        .caption("Degree Centrality Distribution", ("sans-serif", 30))
+// Draws binned counts as bars over bin indices, labeling the x axis with each bin's lower edge.
+#[allow(clippy::too_many_arguments)]
+fn draw_bars<Y>(
+    root: &Area,
+    style: &PlotStyle,
+    title: &str,
+    x_desc: &str,
+    edges: &[f64],
+    counts: &[usize],
+    y_range: Y,
+    base: usize,
+    color: RGBColor,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    Y: AsRangedCoord<Value = usize>,
+    Y::CoordDescType: ValueFormatter<usize>,
+{
+    let mut chart = ChartBuilder::on(root)
+        .caption(title, style.caption())
         .margin(20)
         .x_label_area_size(40)
         .y_label_area_size(40)
-        .build_cartesian_2d(0..bins, 0..*counts.iter().max().unwrap_or(&1))?;
+        .build_cartesian_2d(0..counts.len(), y_range)?;
 
+    let label = |i: &usize| {
+        let edge = edges[(*i).min(edges.len() - 1)];
+        if edge >= 100.0 || edge == edge.round() {
+            format!("{:.0}", edge)
+        } else if edge >= 0.01 {
+            format!("{:.2}", edge)
+        } else {
+            format!("{:.1e}", edge)
+        }
+    };
     chart
         .configure_mesh()
-        .x_desc("Degree Bin")
+        .x_desc(x_desc)
         .y_desc("Count")
+        .x_labels((root.dim_in_pixel().0 / 80).max(2) as usize)
+        .x_label_formatter(&label)
         .draw()?;
 
     chart.draw_series(
-        Histogram::vertical(&chart)
-            .style(BLUE.filled())
-            .data((0..counts.len()).map(|i| (i, counts[i]))),
+        counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c > base)
+            .map(|(i, &c)| Rectangle::new([(i, base), (i + 1, c)], color.filled())),
     )?;
+    Ok(())
+}
 
+// Bins the values and draws the histogram with the chosen count axis onto `area`.
+fn draw_histogram(
+    area: &Area,
+    style: &PlotStyle,
+    title: &str,
+    x_desc: &str,
+    values: &[f64],
+    color: RGBColor,
+    options: &HistogramOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (edges, counts) = bin_values(values, options);
+    let max_count = *counts.iter().max().unwrap_or(&1);
+    if options.log_y {
+        // Bars start at 1 since a log axis has no 0; empty bins are not drawn
+        draw_bars(area, style, title, x_desc, &edges, &counts, (1..max_count.max(1) + 1).log_scale(), 1, color)?;
+    } else {
+        draw_bars(area, style, title, x_desc, &edges, &counts, 0..max_count.max(1), 0, color)?;
+    }
     Ok(())
 }
 
-// Plots a histogram of betweenness centrality.
-// Saves output as `betweenness_histogram.png`.
-pub fn plot_betweenness_histogram(between: &HashMap<usize, f64>) -> Result<(), Box<dyn std::error::Error>> {
-    let root = BitMapBackend::new("betweenness_histogram.png", (800, 600)).into_drawing_area();
-    root.fill(&WHITE)?;
+// Draws the histogram into its own image `name`.
+fn plot_histogram(
+    name: &str,
+    title: &str,
+    x_desc: &str,
+    values: &[f64],
+    color: RGBColor,
+    options: &HistogramOptions,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = config.path(name)?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&config.style.background)?;
+    draw_histogram(&root, &config.style, title, x_desc, values, color, options)?;
+    root.present()?;
+    Ok(())
+}
 
-    let mut values: Vec<f64> = between.values().copied().collect();
-    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+// Plots a histogram of degree centrality.
+// Saves output as `degree_histogram.png`.
+pub fn plot_degree_histogram(
+    degree: &HashMap<usize, usize>,
+    options: &HistogramOptions,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let values: Vec<f64> = degree.values().map(|&d| d as f64).collect();
+    plot_histogram("degree_histogram.png", "Degree Centrality Distribution", "Degree", &values, config.style.color(DEGREE), options, config)
+}
 
-    let bins = 50;
-    let max = values.iter().copied().fold(0.0, f64::max);
-    let bin_width = max / bins as f64;
-    let mut counts = vec![0; bins];
-    for v in &values {
-        let idx = (*v / bin_width).floor() as usize;
-        let i = idx.min(bins - 1);
-        counts[i] += 1;
+// Points of the degree CCDF in ascending degree order: one per distinct positive degree k,
+// paired with the fraction of nodes of positive degree that have degree at least k.
+pub(crate) fn ccdf_points(degree: &HashMap<usize, usize>) -> Vec<(f64, f64)> {
+    let mut data: Vec<usize> = degree.values().copied().filter(|&d| d > 0).collect();
+    data.sort_unstable();
+    let n = data.len() as f64;
+
+    let mut points: Vec<(f64, f64)> = Vec::new();
+    for (i, &d) in data.iter().enumerate() {
+        if i == 0 || data[i - 1] != d {
+            points.push((d as f64, (data.len() - i) as f64 / n));
+        }
     }
+    points
+}
+
+// Plots the complementary cumulative degree distribution P(degree >= k) on log-log axes,
+// where a power-law tail shows up as a straight line.
+// Saves output as `degree_ccdf.png`.
+pub fn plot_degree_ccdf(
+    degree: &HashMap<usize, usize>,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let path = config.path("degree_ccdf.png")?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&style.background)?;
+
+    let points = ccdf_points(degree);
+    let max_deg = points.last().map_or(1.0, |p| p.0).max(2.0);
+    let min_p = points.iter().map(|p| p.1).fold(1.0, f64::min);
 
     let mut chart = ChartBuilder::on(&root)
-        .caption("Betweenness Centrality Distribution", ("sans-serif", 30))
+        .caption("Degree Distribution (CCDF, log-log)", style.caption())
         .margin(20)
         .x_label_area_size(40)
-        .y_label_area_size(40)
-        .build_cartesian_2d(0..bins, 0..*counts.iter().max().unwrap_or(&1))?;
+        .y_label_area_size(60)
+        .build_cartesian_2d((1.0..max_deg).log_scale(), (min_p..1.0).log_scale())?;
 
     chart
         .configure_mesh()
-        .x_desc("Betweenness Bin")
-        .y_desc("Count")
+        .x_desc("Degree k")
+        .y_desc("P(Degree >= k)")
         .draw()?;
 
-    chart.draw_series(
-        Histogram::vertical(&chart)
-            .style(RED.filled())
-            .data((0..counts.len()).map(|i| (i, counts[i]))),
-    )?;
+    chart.draw_series(points.iter().map(|&(d, p)| Circle::new((d, p), 2, style.color(DEGREE).filled())))?;
 
+    root.present()?;
     Ok(())
 }
 
-// Plots a scatterplot of closeness vs degree.
-// Saves output as `closeness_vs_degree.png`.
-pub fn plot_closeness_vs_degree(
+// Plots a histogram of betweenness centrality.
+// Saves output as `betweenness_histogram.png`.
+pub fn plot_betweenness_histogram(
+    between: &HashMap<usize, f64>,
+    options: &HistogramOptions,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let values: Vec<f64> = between.values().copied().collect();
+    plot_histogram("betweenness_histogram.png", "Betweenness Centrality Distribution", "Betweenness", &values, config.style.color(BETWEENNESS), options, config)
+}
+
+// Draws the closeness vs degree scatterplot onto `area`.
+fn draw_closeness_vs_degree(
+    area: &Area,
+    style: &PlotStyle,
     degree: &HashMap<usize, usize>,
     closeness: &HashMap<usize, f64>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let root = BitMapBackend::new("closeness_vs_degree.png", (800, 600)).into_drawing_area();
-    root.fill(&WHITE)?;
-
     let mut points: Vec<(usize, f64)> = Vec::new();
     for (&node, &deg) in degree {
         if let Some(&close) = closeness.get(&node) {
@@ -104,8 +347,8 @@ pub fn plot_closeness_vs_degree(
     let max_deg = points.iter().map(|x| x.0).max().unwrap_or(10);
     let max_closeness = points.iter().map(|x| x.1).fold(0.0, f64::max);
 
-    let mut chart = ChartBuilder::on(&root)
-        .caption("Closeness vs Degree", ("sans-serif", 30))
+    let mut chart = ChartBuilder::on(area)
+        .caption("Closeness vs Degree", style.caption())
         .margin(20)
         .x_label_area_size(40)
         .y_label_area_size(40)
@@ -117,25 +360,296 @@ pub fn plot_closeness_vs_degree(
         .y_desc("Closeness Centrality")
         .draw()?;
 
-    chart.draw_series(points.iter().map(|(d, c)| Circle::new((*d, *c), 3, GREEN.filled())))?;
+    chart.draw_series(points.iter().map(|(d, c)| Circle::new((*d, *c), 3, style.color(CLOSENESS).filled())))?;
 
     Ok(())
 }
 
-// Plots nodes colored by cluster assignment in closeness-degree space.
-// Saves output as `clusters.png`.
-pub fn plot_clusters(
+// Plots a scatterplot of closeness vs degree.
+// Saves output as `closeness_vs_degree.png`.
+pub fn plot_closeness_vs_degree(
+    degree: &HashMap<usize, usize>,
+    closeness: &HashMap<usize, f64>,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let path = config.path("closeness_vs_degree.png")?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&style.background)?;
+    draw_closeness_vs_degree(&root, style, degree, closeness)?;
+    root.present()?;
+    Ok(())
+}
+
+// The thresholds brokers are picked by, from (closeness, betweenness, degree) points: the
+// median closeness and the betweenness at the 90th percentile. `points` must not be empty.
+pub(crate) fn broker_thresholds(points: &[(f64, f64, usize)]) -> (f64, f64) {
+    let mut sorted_close: Vec<f64> = points.iter().map(|p| p.0).collect();
+    let mut sorted_between: Vec<f64> = points.iter().map(|p| p.1).collect();
+    sorted_close.sort_by(f64::total_cmp);
+    sorted_between.sort_by(f64::total_cmp);
+    (sorted_close[sorted_close.len() / 2], sorted_between[sorted_between.len() * 9 / 10])
+}
+
+// Whether a (closeness, betweenness, degree) point is a broker: in the top 10% by
+// betweenness but below the median closeness.
+pub(crate) fn is_broker(point: &(f64, f64, usize), median_close: f64, high_between: f64) -> bool {
+    point.1 >= high_between && point.0 < median_close
+}
+
+// Plots betweenness against closeness with the point size growing with degree. Brokers,
+// nodes in the top 10% by betweenness but below the median closeness, are drawn in red.
+// Saves output as `betweenness_vs_closeness.png`, unless no node has both scores.
+pub fn plot_betweenness_vs_closeness(
+    degree: &HashMap<usize, usize>,
+    closeness: &HashMap<usize, f64>,
+    between: &HashMap<usize, f64>,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let points: Vec<(f64, f64, usize)> = closeness
+        .iter()
+        .filter_map(|(node, &close)| between.get(node).map(|&b| (close, b, *degree.get(node).unwrap_or(&0))))
+        .collect();
+    if points.is_empty() {
+        return Ok(());
+    }
+    let path = config.path("betweenness_vs_closeness.png")?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&style.background)?;
+
+    let (median_close, high_between) = broker_thresholds(&points);
+    let min_close = points.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let max_close = points.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max).max(min_close + f64::EPSILON);
+    let max_between = points.iter().map(|p| p.1).fold(0.0, f64::max).max(f64::MIN_POSITIVE);
+    let max_deg = points.iter().map(|p| p.2).max().unwrap_or(1).max(1) as f64;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Betweenness vs Closeness (size = degree)", style.caption())
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(min_close * 0.95..max_close * 1.05, 0.0..max_between * 1.05)?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Closeness Centrality")
+        .y_desc("Betweenness Centrality")
+        .draw()?;
+
+    let broker = |p: &(f64, f64, usize)| is_broker(p, median_close, high_between);
+    let radius = |d: usize| 2 + (8.0 * (d as f64 / max_deg).sqrt()) as u32;
+    chart
+        .draw_series(
+            points
+                .iter()
+                .filter(|p| !broker(p))
+                .map(|&(c, b, d)| Circle::new((c, b), radius(d), style.color(DEGREE).mix(0.4).filled())),
+        )?
+        .label("Other nodes")
+        .legend(|(x, y)| Circle::new((x, y), 4, style.color(DEGREE).mix(0.4).filled()));
+    chart
+        .draw_series(
+            points
+                .iter()
+                .filter(|p| broker(p))
+                .map(|&(c, b, d)| Circle::new((c, b), radius(d), style.color(BETWEENNESS).filled())),
+        )?
+        .label("Brokers")
+        .legend(|(x, y)| Circle::new((x, y), 4, style.color(BETWEENNESS).filled()));
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperLeft)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    root.present()?;
+    Ok(())
+}
+
+// The largest degree, closeness and betweenness among the features, each at least the
+// smallest positive float so all-zero features still give a non-empty axis.
+pub(crate) fn feature_maxima(features: &HashMap<usize, Features>) -> Features {
+    let max = |value: fn(&Features) -> f64| features.values().map(value).fold(0.0, f64::max).max(f64::MIN_POSITIVE);
+    (max(|x| x.0), max(|x| x.1), max(|x| x.2))
+}
+
+// Plots nodes colored by cluster assignment in the full (degree, closeness, betweenness)
+// feature space, so clusters separated only by betweenness are visible too.
+// Saves output as `clusters_3d.png`.
+pub fn plot_clusters_3d(
     features: &HashMap<usize, (f64, f64, f64)>,
     assignments: &HashMap<usize, usize>,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let path = config.path("clusters_3d.png")?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&style.background)?;
+
+    let (max_deg, max_closeness, max_between) = feature_maxima(features);
+
+    // Betweenness is drawn upward, degree and closeness span the floor
+    let mut chart = ChartBuilder::on(&root)
+        .caption("K-Means Clusters: Degree, Closeness, Betweenness", style.caption())
+        .margin(20)
+        .build_cartesian_3d(0.0..max_deg, 0.0..max_between, 0.0..max_closeness)?;
+    chart.with_projection(|mut projection| {
+        projection.yaw = 0.6;
+        projection.pitch = 0.35;
+        projection.scale = 0.8;
+        projection.into_matrix()
+    });
+    chart
+        .configure_axes()
+        .light_grid_style(BLACK.mix(0.1))
+        .max_light_lines(3)
+        .draw()?;
+
+    for (&node, &(deg, close, between)) in features {
+        if let Some(&cluster_id) = assignments.get(&node) {
+            let color = style.color(cluster_id);
+            chart.draw_series(std::iter::once(Circle::new((deg, between, close), 3, color.filled())))?;
+        }
+    }
+
+    // The chart has no axis titles, so the axis order goes in a legend-style note
+    root.draw(&Text::new(
+        "x: degree, y (up): betweenness, z: closeness",
+        (20, config.height as i32 - 30),
+        style.label(),
+    ))?;
+
+    root.present()?;
+    Ok(())
+}
+
+// Plots each cluster's centroid as a group of bars (degree, closeness, betweenness), so the
+// clusters can be read off as e.g. "high betweenness, low degree brokers". Features are
+// expected to be normalized to [0, 1] as they are for k-means.
+// Saves output as `cluster_profiles.png`.
+pub fn plot_cluster_profiles(
+    features: &HashMap<usize, (f64, f64, f64)>,
+    assignments: &HashMap<usize, usize>,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let centroids = cluster_centroids(features, assignments);
+    let k = centroids.len();
+    if k == 0 {
+        return Ok(());
+    }
+    let path = config.path("cluster_profiles.png")?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&style.background)?;
+
+    let max_value = centroids
+        .iter()
+        .map(|((d, c, b), _)| d.max(*c).max(*b))
+        .fold(0.0, f64::max)
+        .max(f64::MIN_POSITIVE);
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("K-Means Cluster Profiles (centroids)", style.caption())
+        .margin(20)
+        .x_label_area_size(20)
+        .y_label_area_size(50)
+        .build_cartesian_2d(0.0..k as f64, 0.0..max_value * 1.4)?;
+
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_label_formatter(&|_| String::new())
+        .y_desc("Mean Normalized Value")
+        .draw()?;
+
+    // Each cluster takes one unit of the x axis with three bars side by side
+    let measures = [("Degree", style.color(DEGREE)), ("Closeness", style.color(CLOSENESS)), ("Betweenness", style.color(BETWEENNESS))];
+    for (m, &(name, color)) in measures.iter().enumerate() {
+        chart
+            .draw_series(centroids.iter().enumerate().map(|(c, &((d, cl, b), _))| {
+                let value = [d, cl, b][m];
+                let left = c as f64 + 0.1 + 0.27 * m as f64;
+                Rectangle::new([(left, 0.0), (left + 0.25, value)], color.filled())
+            }))?
+            .label(name)
+            .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled()));
+    }
+    chart.draw_series(centroids.iter().enumerate().map(|(c, &(_, count))| {
+        Text::new(
+            format!("Cluster {} ({} nodes)", c, count),
+            (c as f64 + 0.5, max_value * 1.08),
+            style.label().into_font().color(&BLACK).pos(Pos::new(HPos::Center, VPos::Center)),
+        )
+    }))?;
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperRight)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    root.present()?;
+    Ok(())
+}
+
+// Animates k-means convergence: one frame per iteration showing the nodes colored by their
+// current cluster in closeness-degree space, with the centroids drawn as crosses. The first
+// two dimensions of the clustered vectors are taken to be degree and closeness.
+// Saves output as `kmeans.gif`.
+pub fn plot_kmeans_animation(
+    features: &HashMap<usize, (f64, f64, f64)>,
+    steps: &[KMeansStep],
+    frame_delay_ms: u32,
+    config: &PlotConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let root = BitMapBackend::new("clusters.png", (800, 600)).into_drawing_area();
-    root.fill(&WHITE)?;
+    let style = &config.style;
+    let path = config.path("kmeans.gif")?;
+    let root = BitMapBackend::gif(&path, config.size(), frame_delay_ms)?.into_drawing_area();
+
+    let (max_deg, max_closeness, _) = feature_maxima(features);
+
+    for (iteration, step) in steps.iter().enumerate() {
+        root.fill(&style.background)?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption(format!("K-Means Iteration {}", iteration + 1), style.caption())
+            .margin(20)
+            .x_label_area_size(40)
+            .y_label_area_size(40)
+            .build_cartesian_2d(0.0..max_deg, 0.0..max_closeness)?;
+        chart
+            .configure_mesh()
+            .x_desc("Degree")
+            .y_desc("Closeness Centrality")
+            .draw()?;
+
+        chart.draw_series(step.assignments.iter().filter_map(|(node, &cluster_id)| {
+            let &(deg, close, _) = features.get(node)?;
+            Some(Circle::new((deg, close), 3, style.color(cluster_id).mix(0.6).filled()))
+        }))?;
+        chart.draw_series(step.centroids.iter().enumerate().filter(|(_, c)| c.len() >= 2).map(|(i, c)| {
+            Cross::new((c[0], c[1]), 8, style.color(i).stroke_width(3))
+        }))?;
+
+        root.present()?;
+    }
 
+    Ok(())
+}
+
+// Draws nodes colored by cluster in closeness-degree space onto `area`.
+fn draw_clusters(
+    area: &Area,
+    style: &PlotStyle,
+    features: &HashMap<usize, (f64, f64, f64)>,
+    assignments: &HashMap<usize, usize>,
+) -> Result<(), Box<dyn std::error::Error>> {
     let max_deg = features.values().map(|x| x.0).fold(0.0, f64::max);
     let max_closeness = features.values().map(|x| x.1).fold(0.0, f64::max);
 
-    let mut chart = ChartBuilder::on(&root)
-        .caption("K-Means Clusters: Closeness vs Degree", ("sans-serif", 30))
+    let mut chart = ChartBuilder::on(area)
+        .caption("K-Means Clusters: Closeness vs Degree", style.caption())
         .margin(20)
         .x_label_area_size(40)
         .y_label_area_size(40)
@@ -147,14 +661,1030 @@ pub fn plot_clusters(
         .y_desc("Closeness Centrality")
         .draw()?;
 
-    let colors = [RED, BLUE, GREEN, BLACK, CYAN];
 
     for (&node, &(deg, close, _)) in features.iter() {
         if let Some(&cluster_id) = assignments.get(&node) {
-            let color = colors[cluster_id % colors.len()];
+            let color = style.color(cluster_id);
             chart.draw_series(std::iter::once(Circle::new((deg, close), 3, color.filled())))?;
         }
     }
 
     Ok(())
-}// Final commit: updated plot.rs
+}
+
+// Plots nodes colored by cluster assignment in closeness-degree space.
+// Saves output as `clusters.png`.
+pub fn plot_clusters(
+    features: &HashMap<usize, (f64, f64, f64)>,
+    assignments: &HashMap<usize, usize>,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let path = config.path("clusters.png")?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&style.background)?;
+    draw_clusters(&root, style, features, assignments)?;
+    root.present()?;
+    Ok(())
+}
+
+// Plots the first two coordinates of a node embedding, with clustered nodes colored by
+// cluster and all other nodes drawn as small grey points.
+// Saves output as `spectral_embedding.png`.
+pub fn plot_embedding_2d(
+    coords: &HashMap<usize, Vec<f64>>,
+    assignments: &HashMap<usize, usize>,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let path = config.path("spectral_embedding.png")?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&style.background)?;
+
+    let points: Vec<(usize, f64, f64)> = coords
+        .iter()
+        .filter(|(_, c)| c.len() >= 2)
+        .map(|(&node, c)| (node, c[0], c[1]))
+        .collect();
+    if points.is_empty() {
+        root.present()?;
+        return Ok(());
+    }
+    let min_x = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max_x = points.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    let min_y = points.iter().map(|p| p.2).fold(f64::INFINITY, f64::min);
+    let max_y = points.iter().map(|p| p.2).fold(f64::NEG_INFINITY, f64::max);
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Laplacian Eigenmap", style.caption())
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(40)
+        .build_cartesian_2d(min_x..max_x, min_y..max_y)?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Eigenvector 1")
+        .y_desc("Eigenvector 2")
+        .draw()?;
+
+
+    chart.draw_series(
+        points
+            .iter()
+            .filter(|p| !assignments.contains_key(&p.0))
+            .map(|&(_, x, y)| Circle::new((x, y), 1, RGBColor(180, 180, 180).filled())),
+    )?;
+    for &(node, x, y) in &points {
+        if let Some(&cluster_id) = assignments.get(&node) {
+            let color = style.color(cluster_id);
+            chart.draw_series(std::iter::once(Circle::new((x, y), 3, color.filled())))?;
+        }
+    }
+
+    root.present()?;
+    Ok(())
+}
+// Plots robustness curves: giant component fraction (top) and average path length (bottom)
+// against the number of removed nodes, one line per removal strategy.
+// Saves output as `robustness.png`, one and a half times the configured height.
+pub fn plot_robustness(
+    curves: &[(String, Vec<RobustnessPoint>)],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let path = config.path("robustness.png")?;
+    let root = BitMapBackend::new(&path, (config.width, config.height * 3 / 2)).into_drawing_area();
+    root.fill(&style.background)?;
+    let (upper, lower) = root.split_vertically(config.height * 3 / 4);
+
+    let max_removed = curves.iter().flat_map(|(_, c)| c.iter().map(|p| p.removed)).max().unwrap_or(1).max(1);
+    let max_path = curves
+        .iter()
+        .flat_map(|(_, c)| c.iter().map(|p| p.avg_path_length))
+        .fold(0.0, f64::max)
+        .max(1.0);
+
+    let mut giant_chart = ChartBuilder::on(&upper)
+        .caption("Robustness: Giant Component", style.caption())
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d(0..max_removed, 0.0..1.0)?;
+    giant_chart
+        .configure_mesh()
+        .x_desc("Nodes Removed")
+        .y_desc("Giant Component Fraction")
+        .draw()?;
+
+    let mut path_chart = ChartBuilder::on(&lower)
+        .caption("Robustness: Average Path Length", style.caption())
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d(0..max_removed, 0.0..max_path * 1.1)?;
+    path_chart
+        .configure_mesh()
+        .x_desc("Nodes Removed")
+        .y_desc("Average Path Length")
+        .draw()?;
+
+    for (i, (name, curve)) in curves.iter().enumerate() {
+        let color = style.color(i);
+        giant_chart
+            .draw_series(LineSeries::new(curve.iter().map(|p| (p.removed, p.giant_fraction)), color.stroke_width(2)))?
+            .label(name.as_str())
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+        path_chart.draw_series(LineSeries::new(curve.iter().map(|p| (p.removed, p.avg_path_length)), color.stroke_width(2)))?;
+    }
+    giant_chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    root.present()?;
+    Ok(())
+}
+// Plots Lorenz curves of centrality measures against the line of perfect equality.
+// Saves output as `lorenz_curve.png`.
+pub fn plot_lorenz_curves(
+    curves: &[(String, Vec<(f64, f64)>)],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let path = config.path("lorenz_curve.png")?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&style.background)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Lorenz Curves of Centrality", style.caption())
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d(0.0..1.0, 0.0..1.0)?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Fraction of Nodes (lowest first)")
+        .y_desc("Fraction of Total Centrality")
+        .draw()?;
+
+    chart
+        .draw_series(LineSeries::new(vec![(0.0, 0.0), (1.0, 1.0)], BLACK.mix(0.5)))?
+        .label("Equality")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLACK.mix(0.5)));
+
+    for (i, (name, curve)) in curves.iter().enumerate() {
+        let color = style.color(i);
+        chart
+            .draw_series(LineSeries::new(curve.iter().copied(), color.stroke_width(2)))?
+            .label(name.as_str())
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperLeft)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    root.present()?;
+    Ok(())
+}
+// Plots the degree histogram, betweenness histogram, closeness vs degree scatterplot and
+// cluster plot as the four panels of one image, each panel at the configured size.
+// Saves output as `dashboard.png`.
+pub fn plot_dashboard(
+    degree: &HashMap<usize, usize>,
+    between: &HashMap<usize, f64>,
+    closeness: &HashMap<usize, f64>,
+    features: &HashMap<usize, (f64, f64, f64)>,
+    assignments: &HashMap<usize, usize>,
+    options: &HistogramOptions,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let path = config.path("dashboard.png")?;
+    let root = BitMapBackend::new(&path, (config.width * 2, config.height * 2)).into_drawing_area();
+    root.fill(&style.background)?;
+    let panels = root.split_evenly((2, 2));
+
+    let degree_values: Vec<f64> = degree.values().map(|&d| d as f64).collect();
+    let between_values: Vec<f64> = between.values().copied().collect();
+    draw_histogram(&panels[0], style, "Degree Centrality Distribution", "Degree", &degree_values, style.color(DEGREE), options)?;
+    draw_histogram(&panels[1], style, "Betweenness Centrality Distribution", "Betweenness", &between_values, style.color(BETWEENNESS), options)?;
+    draw_closeness_vs_degree(&panels[2], style, degree, closeness)?;
+    draw_clusters(&panels[3], style, features, assignments)?;
+
+    root.present()?;
+    Ok(())
+}
+// Plots the network with nodes at their layout positions, colored by community. Edges inside
+// a community take its color, while edges between communities are drawn faintly in grey,
+// so dense clusters and the links bridging them stand out.
+// Saves output as `communities.png`.
+pub fn plot_communities(
+    graph: &Graph,
+    positions: &HashMap<usize, (f64, f64)>,
+    assignments: &HashMap<usize, usize>,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let path = config.path("communities.png")?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&style.background)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Communities in the Network", style.caption())
+        .margin(20)
+        .build_cartesian_2d(-0.02..1.02, -0.02..1.02)?;
+
+    let color_of = |node: usize| assignments.get(&node).map(|&c| style.color(c));
+
+    // Each laid-out edge once, between communities first so they stay underneath
+    let mut inter = Vec::new();
+    let mut intra = Vec::new();
+    for (&node, &p) in positions {
+        let Some(v) = graph.index_of(node) else { continue };
+        for &w in graph.neighbors(v) {
+            let other = graph.node_id(w);
+            if node < other {
+                if let Some(&q) = positions.get(&other) {
+                    match (assignments.get(&node), assignments.get(&other)) {
+                        (Some(a), Some(b)) if a == b => intra.push((p, q, node)),
+                        _ => inter.push((p, q)),
+                    }
+                }
+            }
+        }
+    }
+    chart.draw_series(inter.iter().map(|&(p, q)| PathElement::new(vec![p, q], RGBColor(150, 150, 150).mix(0.15))))?;
+    chart.draw_series(
+        intra
+            .iter()
+            .map(|&(p, q, node)| PathElement::new(vec![p, q], color_of(node).unwrap_or(BLACK).mix(0.3))),
+    )?;
+
+    chart.draw_series(
+        positions
+            .iter()
+            .map(|(&node, &p)| Circle::new(p, 3, color_of(node).unwrap_or(RGBColor(180, 180, 180)).filled())),
+    )?;
+
+    root.present()?;
+    Ok(())
+}
+
+// Animates the evolution of the network over time: one frame per window, with the nodes
+// kept at fixed layout positions so frames are comparable. Nodes active in the window are
+// sized by their degree in it and colored by their community there, inactive ones are
+// drawn as faint dots, and the emails exchanged in the window are drawn as edges.
+// Saves output as `temporal.gif`.
+pub fn plot_temporal_animation(
+    temporal: &TemporalGraph,
+    windows: &[WindowMetrics],
+    positions: &HashMap<usize, (f64, f64)>,
+    frame_delay_ms: u32,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let path = config.path("temporal.gif")?;
+    let root = BitMapBackend::gif(&path, config.size(), frame_delay_ms)?.into_drawing_area();
+
+    // One size scale for every frame, so growth in activity shows as growing nodes
+    let max_deg = windows
+        .iter()
+        .flat_map(|w| positions.keys().filter_map(|node| w.degree.get(node)))
+        .copied()
+        .max()
+        .unwrap_or(1)
+        .max(1) as f64;
+
+    for window in windows {
+        root.fill(&style.background)?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption(
+                format!("Communication from {} to {}", format_date(window.start), format_date(window.end)),
+                style.small_caption(),
+            )
+            .margin(20)
+            .build_cartesian_2d(-0.02..1.02, -0.02..1.02)?;
+
+        let mut pairs = temporal.slice(window.start, window.end);
+        pairs.retain(|&(u, v)| u != v && positions.contains_key(&u) && positions.contains_key(&v));
+        pairs.sort_unstable_by_key(|&(u, v)| (u.min(v), u.max(v)));
+        pairs.dedup_by_key(|&mut (u, v)| (u.min(v), u.max(v)));
+        chart.draw_series(
+            pairs.iter().map(|&(u, v)| PathElement::new(vec![positions[&u], positions[&v]], RGBColor(150, 150, 150).mix(0.3))),
+        )?;
+
+        chart.draw_series(positions.iter().filter(|(node, _)| !window.degree.contains_key(node)).map(|(_, &p)| {
+            Circle::new(p, 1, RGBColor(200, 200, 200).filled())
+        }))?;
+        chart.draw_series(positions.iter().filter_map(|(node, &p)| {
+            let deg = *window.degree.get(node)? as f64;
+            let color = window.community.get(node).map_or(BLACK, |&c| style.color(c));
+            Some(Circle::new(p, 2 + (8.0 * (deg / max_deg).sqrt()).round() as i32, color.mix(0.8).filled()))
+        }))?;
+
+        root.present()?;
+    }
+
+    Ok(())
+}
+// Plots a coarsened summary graph in full: every super-node is a circle whose area grows with
+// the number of nodes merged into it, colored by its community number, and every edge between
+// super-nodes is drawn darker and thicker the more weight it carries. The 10 largest
+// super-nodes are labelled with their size.
+// Saves output as `summary.png`.
+pub fn plot_summary_graph(
+    coarse: &CoarseGraph,
+    positions: &HashMap<usize, (f64, f64)>,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let path = config.path("summary.png")?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&style.background)?;
+
+    let graph = &coarse.graph;
+    let mut chart = ChartBuilder::on(&root)
+        .caption(format!("Summary Graph ({} Super-nodes)", graph.node_count()), style.caption())
+        .margin(20)
+        .build_cartesian_2d(-0.05..1.05, -0.05..1.05)?;
+
+    let mut links = Vec::new();
+    for v in 0..graph.node_count() {
+        for (w, weight) in graph.weighted_neighbors(v).filter(|&(w, _)| w > v) {
+            if let (Some(&p), Some(&q)) = (positions.get(&graph.node_id(v)), positions.get(&graph.node_id(w))) {
+                links.push((p, q, weight));
+            }
+        }
+    }
+    // Heaviest edges last, so they are drawn on top
+    links.sort_by(|a, b| a.2.total_cmp(&b.2));
+    let max_weight = links.last().map_or(1.0, |l| l.2);
+    chart.draw_series(links.iter().map(|&(p, q, weight)| {
+        let share = (weight / max_weight).sqrt();
+        PathElement::new(vec![p, q], RGBColor(90, 90, 90).mix(0.1 + 0.6 * share).stroke_width(1 + (4.0 * share).round() as u32))
+    }))?;
+
+    let size = |node: &usize| coarse.members.get(node).map_or(1, Vec::len);
+    let max_size = positions.keys().map(size).max().unwrap_or(1).max(1) as f64;
+    chart.draw_series(positions.iter().map(|(node, &p)| {
+        let radius = 2 + (18.0 * (size(node) as f64 / max_size).sqrt()).round() as i32;
+        Circle::new(p, radius, style.color(*node).mix(0.8).filled())
+    }))?;
+    let mut largest: Vec<(&usize, &(f64, f64))> = positions.iter().collect();
+    largest.sort_by_key(|&(node, _)| (std::cmp::Reverse(size(node)), *node));
+    chart.draw_series(largest.iter().take(10).map(|&(node, &p)| {
+        Text::new(format!("{}", size(node)), p, style.label().into_font().color(&BLACK).pos(Pos::new(HPos::Center, VPos::Center)))
+    }))?;
+
+    root.present()?;
+    Ok(())
+}
+// Draws sizes as bars in descending order on a log-scale axis onto `area`.
+fn draw_size_bars(
+    area: &Area,
+    style: &PlotStyle,
+    title: &str,
+    x_desc: &str,
+    sizes: &[usize],
+    color: RGBColor,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let max_size = sizes.iter().copied().max().unwrap_or(1).max(1);
+    let mut chart = ChartBuilder::on(area)
+        .caption(title, style.small_caption())
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d((0..sizes.len().saturating_sub(1)).into_segmented(), (1..max_size + 1).log_scale())?;
+
+    chart
+        .configure_mesh()
+        .x_desc(x_desc)
+        .y_desc("Nodes")
+        .disable_x_mesh()
+        .draw()?;
+
+    chart.draw_series(
+        Histogram::vertical(&chart)
+            .style(color.filled())
+            .margin(2)
+            .baseline(1)
+            .data(sizes.iter().enumerate().map(|(i, &size)| (i, size))),
+    )?;
+    Ok(())
+}
+
+// Bar heights of the cluster size chart, largest first: the sizes of the largest
+// `max_components` connected components, and the sizes of all k-means clusters.
+pub(crate) fn size_bars(clusters: &[HashSet<usize>], assignments: &HashMap<usize, usize>, max_components: usize) -> (Vec<usize>, Vec<usize>) {
+    let mut component_sizes: Vec<usize> = clusters.iter().map(|c| c.len()).collect();
+    component_sizes.sort_unstable_by(|a, b| b.cmp(a));
+    component_sizes.truncate(max_components);
+
+    let mut kmeans_sizes: HashMap<usize, usize> = HashMap::new();
+    for &cluster_id in assignments.values() {
+        *kmeans_sizes.entry(cluster_id).or_insert(0) += 1;
+    }
+    let mut kmeans_sizes: Vec<usize> = kmeans_sizes.into_values().collect();
+    kmeans_sizes.sort_unstable_by(|a, b| b.cmp(a));
+    (component_sizes, kmeans_sizes)
+}
+
+// Plots the sizes of the connected components (the largest `max_components`) and of the
+// k-means clusters side by side, as bar charts with a log-scale size axis.
+// Saves output as `cluster_sizes.png`.
+pub fn plot_cluster_sizes(
+    clusters: &[HashSet<usize>],
+    assignments: &HashMap<usize, usize>,
+    max_components: usize,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let path = config.path("cluster_sizes.png")?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&style.background)?;
+    let (left, right) = root.split_horizontally(config.width / 2);
+    let (component_sizes, kmeans_sizes) = size_bars(clusters, assignments, max_components);
+
+    let title = format!("Connected Components ({} of {})", component_sizes.len(), clusters.len());
+    draw_size_bars(&left, style, &title, "Component (by size)", &component_sizes, style.color(DEGREE))?;
+    draw_size_bars(&right, style, "K-Means Clusters", "Cluster (by size)", &kmeans_sizes, style.color(BETWEENNESS))?;
+
+    root.present()?;
+    Ok(())
+}
+// Labels and values of the top-N bar chart, highest first: the `n` highest-scoring nodes,
+// labeled with their email addresses or "Node <id>" when unmapped.
+pub(crate) fn top_bars<V: CentralityValue>(centrality: &HashMap<usize, V>, email_map: &HashMap<usize, (String, String)>, n: usize) -> Vec<(String, f64)> {
+    let values: HashMap<usize, f64> = centrality.iter().map(|(&node, &v)| (node, v.as_f64())).collect();
+    top_k(&values, n)
+        .into_iter()
+        .map(|(node, value)| (email_map.get(&node).map_or_else(|| format!("Node {}", node), |(email, _)| email.clone()), value))
+        .collect()
+}
+
+// Plots the `n` highest-scoring nodes of a centrality as horizontal bars, labeled with
+// their email addresses (or node IDs when unmapped), highest at the top.
+// Saves output as `top_<name>.png`, e.g. `top_betweenness.png`.
+pub fn plot_top_centrality<V: CentralityValue>(
+    centrality: &HashMap<usize, V>,
+    email_map: &HashMap<usize, (String, String)>,
+    name: &str,
+    n: usize,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let bars = top_bars(centrality, email_map, n);
+    if bars.is_empty() {
+        return Ok(());
+    }
+
+    let path = config.path(&format!("top_{}.png", name.to_lowercase().replace(' ', "_")))?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&style.background)?;
+
+    let max_value = bars[0].1.max(f64::MIN_POSITIVE);
+    let rows = bars.len();
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(format!("Top {} Nodes by {}", rows, name), style.caption())
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(220)
+        .build_cartesian_2d(0.0..max_value * 1.05, (0..rows - 1).into_segmented())?;
+
+    // Row 0 is drawn at the bottom, so the highest-ranked node takes the last row
+    let label = |v: &SegmentValue<usize>| match v {
+        SegmentValue::CenterOf(row) | SegmentValue::Exact(row) if *row < rows => bars[rows - 1 - row].0.clone(),
+        _ => String::new(),
+    };
+    chart
+        .configure_mesh()
+        .x_desc(name)
+        .y_labels(rows)
+        .y_label_formatter(&label)
+        .disable_y_mesh()
+        .draw()?;
+
+    chart.draw_series(
+        Histogram::horizontal(&chart)
+            .style(style.color(DEGREE).filled())
+            .margin(3)
+            .data(bars.iter().enumerate().map(|(rank, (_, value))| (rows - 1 - rank, *value))),
+    )?;
+
+    root.present()?;
+    Ok(())
+}
+// One column of values per measure over the nodes that have all of them, in ascending node order.
+pub(crate) fn common_columns(measures: &[(&str, HashMap<usize, f64>)]) -> Vec<Vec<f64>> {
+    let Some((_, first)) = measures.first() else {
+        return Vec::new();
+    };
+    let mut nodes: Vec<usize> = first.keys().copied().filter(|n| measures.iter().all(|(_, m)| m.contains_key(n))).collect();
+    nodes.sort_unstable();
+    measures.iter().map(|(_, m)| nodes.iter().map(|n| m[n]).collect()).collect()
+}
+
+// The axis range spanning `values`, or 0..1 when they are empty or all equal.
+pub(crate) fn axis_range(values: &[f64]) -> std::ops::Range<f64> {
+    let lo = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let hi = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if lo < hi { lo..hi } else { 0.0..1.0 }
+}
+
+// Plots every pair of centrality measures against each other in a grid, over the nodes that
+// have all of them. Row i, column j plots measure i against measure j, and the diagonal
+// shows a histogram of each measure.
+// Saves output as `scatter_matrix.png`, square with the configured width.
+pub fn plot_scatter_matrix(
+    measures: &[(&str, HashMap<usize, f64>)],
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let k = measures.len();
+    if k == 0 {
+        return Ok(());
+    }
+    let path = config.path("scatter_matrix.png")?;
+    let root = BitMapBackend::new(&path, (config.width, config.width)).into_drawing_area();
+    root.fill(&style.background)?;
+
+    let columns = common_columns(measures);
+
+    let panels = root.split_evenly((k, k));
+    for (cell, panel) in panels.iter().enumerate() {
+        let (i, j) = (cell / k, cell % k);
+        if i == j {
+            let options = HistogramOptions { bins: 20, ..HistogramOptions::default() };
+            draw_histogram(panel, style, measures[i].0, measures[i].0, &columns[i], style.color(DEGREE), &options)?;
+            continue;
+        }
+        let mut chart = ChartBuilder::on(panel)
+            .caption(format!("{} vs {}", measures[i].0, measures[j].0), style.small_caption())
+            .margin(10)
+            .x_label_area_size(25)
+            .y_label_area_size(40)
+            .build_cartesian_2d(axis_range(&columns[j]), axis_range(&columns[i]))?;
+        chart
+            .configure_mesh()
+            .x_labels(4)
+            .y_labels(4)
+            .label_style((style.font.as_str(), style.label_size * 5 / 8))
+            .disable_mesh()
+            .draw()?;
+        chart.draw_series(columns[j].iter().zip(&columns[i]).map(|(&x, &y)| Circle::new((x, y), 2, style.color(CLOSENESS).mix(0.5).filled())))?;
+    }
+
+    root.present()?;
+    Ok(())
+}
+// Plots a correlation matrix as a heatmap, from blue (-1) through white (0) to red (+1),
+// with each cell labeled by its coefficient.
+// Saves output as `correlation_heatmap.png`.
+pub fn plot_correlation_heatmap(
+    names: &[&str],
+    matrix: &[Vec<f64>],
+    method: CorrelationMethod,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let k = names.len();
+    if k == 0 {
+        return Ok(());
+    }
+    let path = config.path("correlation_heatmap.png")?;
+    let root = BitMapBackend::new(&path, config.size()).into_drawing_area();
+    root.fill(&style.background)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(format!("{} Correlation of Centrality Measures", method), style.caption())
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(100)
+        .build_cartesian_2d((0..k - 1).into_segmented(), (0..k - 1).into_segmented())?;
+
+    // Row 0 is drawn at the top, so y values are flipped
+    let label = |v: &SegmentValue<usize>, flip: bool| match v {
+        SegmentValue::CenterOf(i) if *i < k => names[if flip { k - 1 - i } else { *i }].to_string(),
+        _ => String::new(),
+    };
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .x_labels(k)
+        .y_labels(k)
+        .x_label_formatter(&|v| label(v, false))
+        .y_label_formatter(&|v| label(v, true))
+        .draw()?;
+
+    let color = |r: f64| {
+        let t = r.clamp(-1.0, 1.0);
+        let fade = |c: u8| (255.0 - (255.0 - c as f64) * t.abs()) as u8;
+        if t >= 0.0 { RGBColor(fade(214), fade(39), fade(40)) } else { RGBColor(fade(31), fade(119), fade(180)) }
+    };
+    for (i, row) in matrix.iter().enumerate().take(k) {
+        let y = k - 1 - i;
+        for (j, &r) in row.iter().enumerate().take(k) {
+            chart.draw_series(std::iter::once(Rectangle::new(
+                [(SegmentValue::Exact(j), SegmentValue::Exact(y)), (SegmentValue::Exact(j + 1), SegmentValue::Exact(y + 1))],
+                color(r).filled(),
+            )))?;
+            chart.draw_series(std::iter::once(Text::new(
+                format!("{:.2}", r),
+                (SegmentValue::CenterOf(j), SegmentValue::CenterOf(y)),
+                style.label().into_font().color(&BLACK).pos(Pos::new(HPos::Center, VPos::Center)),
+            )))?;
+        }
+    }
+
+    root.present()?;
+    Ok(())
+}
+// Rows of the adjacency matrix as (cluster, degree, node), ordered by cluster, then by
+// descending degree, then by node ID; clustered nodes missing from the graph are left out.
+pub(crate) fn adjacency_order(graph: &Graph, assignments: &HashMap<usize, usize>) -> Vec<(usize, usize, usize)> {
+    let mut order: Vec<(usize, usize, usize)> = assignments
+        .iter()
+        .filter_map(|(&node, &cluster)| graph.index_of(node).map(|v| (cluster, graph.degree(v), node)))
+        .collect();
+    order.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+    order
+}
+
+// Plots the adjacency matrix of the clustered nodes as a heatmap, with nodes ordered by
+// cluster (and by degree within a cluster) so dense blocks on the diagonal show clusters
+// that mostly email each other. Grey lines mark the cluster boundaries.
+// Saves output as `adjacency_matrix.png`, square with the configured width.
+pub fn plot_adjacency_matrix(
+    graph: &Graph,
+    assignments: &HashMap<usize, usize>,
+    config: &PlotConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let style = &config.style;
+    let order = adjacency_order(graph, assignments);
+    let n = order.len();
+    if n == 0 {
+        return Ok(());
+    }
+    let position: HashMap<usize, usize> = order.iter().enumerate().map(|(i, &(_, _, node))| (node, i)).collect();
+
+    let path = config.path("adjacency_matrix.png")?;
+    let root = BitMapBackend::new(&path, (config.width, config.width)).into_drawing_area();
+    root.fill(&style.background)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(format!("Adjacency Matrix of {} Clustered Nodes", n), style.caption())
+        .margin(20)
+        .x_label_area_size(30)
+        .y_label_area_size(50)
+        .build_cartesian_2d(0..n, (0..n).with_key_points(vec![]))?;
+    chart.configure_mesh().disable_mesh().y_labels(0).draw()?;
+
+    // Row 0 at the top, so y is flipped
+    let mut cells = Vec::new();
+    for &(cluster, _, node) in &order {
+        let i = position[&node];
+        let v = graph.index_of(node).unwrap();
+        for w in graph.neighbors(v) {
+            if let Some(&j) = position.get(&graph.node_id(*w)) {
+                let same = assignments.get(&graph.node_id(*w)) == Some(&cluster);
+                let color = if same { style.color(cluster) } else { RGBColor(90, 90, 90) };
+                cells.push(Rectangle::new([(j, n - 1 - i), (j + 1, n - i)], color.filled()));
+            }
+        }
+    }
+    chart.draw_series(cells)?;
+
+    // Cluster boundaries
+    let mut boundaries = Vec::new();
+    for i in 1..n {
+        if order[i].0 != order[i - 1].0 {
+            boundaries.push(PathElement::new(vec![(i, 0), (i, n)], BLACK.mix(0.3)));
+            boundaries.push(PathElement::new(vec![(0, n - i), (n, n - i)], BLACK.mix(0.3)));
+        }
+    }
+    chart.draw_series(boundaries)?;
+
+    root.present()?;
+    Ok(())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temporal::{monthly_windows, parse_time, TemporalEdge};
+
+    // Draws one plot into the given configuration.
+    type Render<'a> = Box<dyn Fn(&PlotConfig) -> Result<(), Box<dyn std::error::Error>> + 'a>;
+    // A named render case: the image file it writes and its expected size, if any.
+    type RenderCase<'a> = (&'a str, &'a str, Render<'a>, Option<(u32, u32)>);
+
+    // A small plot configuration writing into its own temporary directory.
+    fn test_config(name: &str) -> PlotConfig {
+        let output_dir = std::env::temp_dir().join(format!("project_plot_{}_test", name));
+        PlotConfig { output_dir, width: 320, height: 240, ..PlotConfig::default() }
+    }
+
+    // Width and height of a PNG or GIF image, from its header.
+    fn image_size(path: &std::path::Path) -> (u32, u32) {
+        let bytes = std::fs::read(path).unwrap();
+        if bytes.starts_with(b"GIF") {
+            let field = |at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]) as u32;
+            return (field(6), field(8));
+        }
+        let field = |at: usize| u32::from_be_bytes(bytes[at..at + 4].try_into().unwrap());
+        (field(16), field(20))
+    }
+
+    // Tests the CCDF points: one per distinct degree with the fraction of nodes at or above
+    // it, starting at 1, while nodes without edges are left out.
+    #[test]
+    fn test_ccdf_points() {
+        assert!(ccdf_points(&HashMap::new()).is_empty());
+        assert!(ccdf_points(&HashMap::from([(1, 0)])).is_empty());
+        assert_eq!(ccdf_points(&HashMap::from([(1, 3), (2, 3)])), vec![(3.0, 1.0)]);
+        let degree = HashMap::from([(1, 0), (2, 1), (3, 5), (4, 1), (5, 2)]);
+        assert_eq!(ccdf_points(&degree), vec![(1.0, 1.0), (2.0, 0.5), (5.0, 0.25)]);
+    }
+
+    // Tests binning on edge cases: no values, a bin count of 0, the maximum landing in the last
+    // bin, and zeros left out of log bins (falling back to 1..10 when nothing is positive).
+    #[test]
+    fn test_histogram_options() {
+        let linear = HistogramOptions { bins: 5, ..HistogramOptions::default() };
+        let log = HistogramOptions { bins: 4, log_bins: true, log_y: true };
+
+        let (edges, counts) = bin_values(&[], &linear);
+        assert_eq!((edges, counts), (vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0], vec![0; 5]));
+        let (edges, counts) = bin_values(&[3.0, 7.0], &HistogramOptions { bins: 0, ..linear });
+        assert_eq!((edges.len(), counts), (2, vec![2]));
+
+        let values: Vec<f64> = (0..=10).map(f64::from).collect();
+        let (edges, counts) = bin_values(&values, &linear);
+        assert_eq!(edges.last(), Some(&10.0));
+        assert_eq!(counts, vec![2, 2, 2, 2, 3]);
+
+        let (edges, counts) = bin_values(&[0.0, 0.0], &log);
+        assert_eq!((edges[0], edges[4], counts), (1.0, 10.0, vec![0; 4]));
+        let (edges, counts) = bin_values(&[0.0, 1.0, 10.0, 100.0, 1000.0], &log);
+        assert!((edges[0] - 1.0).abs() < 1e-12 && (edges[4] - 1000.0).abs() < 1e-9);
+        assert_eq!(counts, vec![1, 1, 1, 1]);
+    }
+
+    // Tests that plot paths get the prefix and that missing output directories are created,
+    // nested ones included, while an output "directory" that is a file is an error rather than
+    // a panic, for the path and for a plot written there, as is an image path taken by a directory.
+    #[test]
+    fn test_plot_config() {
+        let base = test_config("config").output_dir;
+        let _ = std::fs::remove_dir_all(&base);
+        let config = PlotConfig { output_dir: base.join("a").join("b"), prefix: "enron_".to_string(), ..test_config("config") };
+        assert_eq!(config.path("x.png").unwrap(), base.join("a").join("b").join("enron_x.png"));
+        assert!(base.join("a").join("b").is_dir());
+
+        let blocked = PlotConfig { output_dir: base.join("file"), ..config.clone() };
+        std::fs::write(&blocked.output_dir, "not a directory").unwrap();
+        assert!(blocked.path("x.png").is_err());
+        assert!(plot_degree_ccdf(&HashMap::from([(1, 2)]), &blocked).is_err());
+        // A directory in the way of the image itself only fails when the image is saved
+        for name in ["enron_lorenz_curve.png", "enron_clusters.png"] {
+            std::fs::create_dir_all(config.output_dir.join(name)).unwrap();
+        }
+        assert!(plot_lorenz_curves(&[], &config).is_err());
+        assert!(plot_clusters(&HashMap::new(), &HashMap::new(), &config).is_err());
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    // Tests the cluster size bars: component sizes largest first and cut to `max_components`,
+    // and k-means cluster sizes counted from the assignments, largest first.
+    #[test]
+    fn test_size_bars() {
+        assert_eq!(size_bars(&[], &HashMap::new(), 10), (vec![], vec![]));
+        let mut components: Vec<HashSet<usize>> = (0..3).map(|v| HashSet::from([v])).collect();
+        components.push((10..30).collect());
+        components.push((30..35).collect());
+        let assignments: HashMap<usize, usize> = (10..30).map(|v| (v, if v < 14 { 0 } else { 1 + v % 2 })).collect();
+        assert_eq!(size_bars(&components, &assignments, 10), (vec![20, 5, 1, 1, 1], vec![8, 8, 4]));
+        assert_eq!(size_bars(&components, &assignments, 2).0, vec![20, 5]);
+        assert!(size_bars(&components, &assignments, 0).0.is_empty());
+    }
+
+    // Tests the top-N bars: highest first with ties broken by node ID, labeled with the email
+    // address when known and the node ID otherwise, and cut to the nodes there are.
+    #[test]
+    fn test_top_bars() {
+        assert!(top_bars(&HashMap::<usize, f64>::new(), &HashMap::new(), 10).is_empty());
+        let email_map = HashMap::from([(7, ("a@enron.com".to_string(), "inbox".to_string()))]);
+        let degree = HashMap::from([(7, 3usize), (2, 5), (9, 3), (4, 1)]);
+        let bars = top_bars(&degree, &email_map, 3);
+        let expected = [("Node 2", 5.0), ("a@enron.com", 3.0), ("Node 9", 3.0)];
+        assert_eq!(bars, expected.map(|(label, value)| (label.to_string(), value)));
+        assert_eq!(top_bars(&degree, &email_map, 20).len(), 4);
+        assert!(top_bars(&degree, &email_map, 0).is_empty());
+    }
+
+    // Tests the broker thresholds: the median closeness and the 90th percentile of
+    // betweenness, with brokers above that betweenness but below the median closeness.
+    #[test]
+    fn test_broker_thresholds() {
+        // Node i has closeness i / 10 and betweenness i, except that node 1 has the most
+        let points: Vec<(f64, f64, usize)> = (0..10).map(|i| (i as f64 / 10.0, if i == 1 { 100.0 } else { i as f64 }, 1)).collect();
+        let (median_close, high_between) = broker_thresholds(&points);
+        assert_eq!((median_close, high_between), (0.5, 100.0));
+        let brokers: Vec<usize> = (0..10).filter(|&i| is_broker(&points[i], median_close, high_between)).collect();
+        assert_eq!(brokers, vec![1]);
+
+        // Equal scores everywhere make no node a broker
+        let equal = vec![(1.0, 0.0, 0); 6];
+        let (median_close, high_between) = broker_thresholds(&equal);
+        assert!(!is_broker(&equal[0], median_close, high_between));
+        assert_eq!(broker_thresholds(&[(0.5, 2.0, 3)]), (0.5, 2.0));
+    }
+
+    // Tests the scatter matrix columns, which keep only the nodes having every measure in
+    // ascending node order, and the axis ranges, which fall back to 0..1 for constant or no values.
+    #[test]
+    fn test_scatter_matrix_columns() {
+        assert!(common_columns(&[]).is_empty());
+        let disjoint = [("Degree", HashMap::from([(1, 2.0)])), ("Closeness", HashMap::from([(2, 0.5)]))];
+        assert_eq!(common_columns(&disjoint), vec![Vec::<f64>::new(); 2]);
+        let measures = [
+            ("Degree", HashMap::from([(3, 1.0), (1, 4.0), (2, 2.0)])),
+            ("Betweenness", HashMap::from([(1, 0.0), (3, 0.0), (5, 9.0)])),
+        ];
+        assert_eq!(common_columns(&measures), vec![vec![4.0, 1.0], vec![0.0, 0.0]]);
+
+        assert_eq!(axis_range(&[4.0, 1.0]), 1.0..4.0);
+        assert_eq!(axis_range(&[0.0, 0.0]), 0.0..1.0);
+        assert_eq!(axis_range(&[]), 0.0..1.0);
+    }
+
+    // Tests the 3D axis maxima, which stay positive for all-zero features and no features.
+    #[test]
+    fn test_feature_maxima() {
+        let tiny = f64::MIN_POSITIVE;
+        assert_eq!(feature_maxima(&HashMap::new()), (tiny, tiny, tiny));
+        let features = HashMap::from([(0, (0.2, 0.0, 0.7)), (1, (0.9, 0.0, 0.1))]);
+        assert_eq!(feature_maxima(&features), (0.9, tiny, 0.7));
+    }
+
+    // Tests the adjacency matrix order: by cluster, then by descending degree, then by node
+    // ID, with clustered nodes missing from the graph left out.
+    #[test]
+    fn test_adjacency_order() {
+        let graph = Graph::from_edges(&[(1, 2), (1, 3), (1, 4), (5, 6), (5, 7), (8, 9)]);
+        assert!(adjacency_order(&graph, &HashMap::from([(99, 0)])).is_empty());
+        let assignments = HashMap::from([(1, 1), (2, 1), (5, 0), (6, 0), (8, 1), (99, 0)]);
+        let order = adjacency_order(&graph, &assignments);
+        assert_eq!(order, vec![(0, 2, 5), (0, 1, 6), (1, 3, 1), (1, 1, 2), (1, 1, 8)]);
+    }
+
+    // Tests that palette colors wrap around, that the colorblind palette gives eight clusters
+    // eight different colors, that an empty palette falls back to black instead of panicking,
+    // and that caption sizes never reach 0.
+    #[test]
+    fn test_plot_style() {
+        let style = PlotStyle::default();
+        assert_eq!(style, PlotStyle::colorblind());
+        let colors: HashSet<(u8, u8, u8)> = (0..8).map(|i| style.color(i).rgb()).collect();
+        assert_eq!(colors.len(), 8);
+        assert_eq!(style.color(8), style.color(0));
+        assert_eq!(PlotStyle::classic().color(5), RED);
+
+        let empty = PlotStyle { palette: Vec::new(), caption_size: 1, ..PlotStyle::colorblind() };
+        assert_eq!(empty.color(3), BLACK);
+        assert_eq!(empty.small_caption().1, 1);
+        assert_eq!(empty.caption().1, 1);
+    }
+
+    // Tests that every plot renders its edge cases without errors: each case writes the named
+    // image at the expected size, or nothing at all when there is no data to draw.
+    #[test]
+    fn test_plot_renders() {
+        let options = HistogramOptions::default();
+        let log = HistogramOptions { bins: 4, log_bins: true, log_y: true };
+        let zeros: HashMap<usize, Features> = (0..4).map(|v| (v, (0.0, 0.0, 0.0))).collect();
+        let features: HashMap<usize, Features> = (0..4).map(|v| (v, (v as f64, 0.5, 0.0))).collect();
+        let isolated: Vec<HashSet<usize>> = (0..5).map(|v| HashSet::from([v])).collect();
+        let email_map = HashMap::from([(7, ("a@enron.com".to_string(), "inbox".to_string()))]);
+        let graph = Graph::from_edges(&[(1, 2), (3, 4), (5, 6)]);
+
+        // Emails in January and April only, so the February and March windows have no active
+        // nodes; a self-loop and node 9, without a layout position, are skipped
+        let dated = [(1, 2, "2001-01-05"), (2, 2, "2001-01-06"), (2, 3, "2001-04-10"), (3, 9, "2001-04-11")];
+        let temporal = TemporalGraph::new(dated.iter().map(|&(u, v, t)| TemporalEdge { u, v, time: parse_time(t).unwrap() }).collect());
+        let windows = monthly_windows(&temporal, 1, 10);
+        assert_eq!(windows.len(), 4);
+        assert!(windows[1].degree.is_empty() && windows[2].degree.is_empty());
+        let positions = HashMap::from([(1, (0.0, 0.0)), (2, (0.5, 1.0)), (3, (1.0, 0.0))]);
+
+        let small = Some((320, 240));
+        let square = Some((320, 320));
+        let cases: Vec<RenderCase> = vec![
+            ("ccdf of no nodes", "degree_ccdf.png", Box::new(|c| plot_degree_ccdf(&HashMap::new(), c)), small),
+            ("ccdf of one degree", "degree_ccdf.png", Box::new(|c| plot_degree_ccdf(&HashMap::from([(1, 3), (2, 3)]), c)), small),
+            ("ccdf with isolated nodes", "degree_ccdf.png", Box::new(|c| plot_degree_ccdf(&HashMap::from([(1, 0), (2, 1), (3, 5)]), c)), small),
+            ("empty degree histogram", "degree_histogram.png", Box::new(|c| plot_degree_histogram(&HashMap::new(), &options, c)), small),
+            ("empty log histogram", "degree_histogram.png", Box::new(|c| plot_degree_histogram(&HashMap::new(), &log, c)), small),
+            ("zero log histogram", "betweenness_histogram.png", Box::new(|c| plot_betweenness_histogram(&HashMap::from([(1, 0.0)]), &log, c)), small),
+            (
+                "empty dashboard",
+                "dashboard.png",
+                Box::new(|c| plot_dashboard(&HashMap::new(), &HashMap::new(), &HashMap::new(), &HashMap::new(), &HashMap::new(), &options, c)),
+                Some((640, 480)),
+            ),
+            (
+                "dashboard with partial closeness",
+                "dashboard.png",
+                Box::new(|c| {
+                    let degree = HashMap::from([(1, 0), (2, 0), (3, 4)]);
+                    let between = HashMap::from([(1, 0.0), (2, 0.0), (3, 0.0)]);
+                    let closeness = HashMap::from([(3, 0.5)]);
+                    plot_dashboard(&degree, &between, &closeness, &zeros, &HashMap::from([(1, 0), (2, 1)]), &options, c)
+                }),
+                Some((640, 480)),
+            ),
+            ("no cluster sizes", "cluster_sizes.png", Box::new(|c| plot_cluster_sizes(&[], &HashMap::new(), 10, c)), small),
+            ("isolated cluster sizes", "cluster_sizes.png", Box::new(|c| plot_cluster_sizes(&isolated, &HashMap::from([(0, 0)]), 10, c)), small),
+            ("no components shown", "cluster_sizes.png", Box::new(|c| plot_cluster_sizes(&isolated, &HashMap::new(), 0, c)), small),
+            ("empty top-N", "top_betweenness.png", Box::new(|c| plot_top_centrality(&HashMap::<usize, f64>::new(), &HashMap::new(), "Betweenness", 10, c)), None),
+            ("top-0", "top_betweenness.png", Box::new(|c| plot_top_centrality(&HashMap::from([(1, 2.0)]), &HashMap::new(), "Betweenness", 0, c)), None),
+            ("all-zero top-N", "top_betweenness.png", Box::new(|c| plot_top_centrality(&HashMap::from([(0, 0.0), (1, 0.0)]), &HashMap::new(), "Betweenness", 10, c)), small),
+            ("single top-N", "top_in_degree.png", Box::new(|c| plot_top_centrality(&HashMap::from([(7, 3usize)]), &email_map, "In Degree", 20, c)), small),
+            (
+                "broker scatter without common nodes",
+                "betweenness_vs_closeness.png",
+                Box::new(|c| plot_betweenness_vs_closeness(&HashMap::new(), &HashMap::from([(1, 0.5)]), &HashMap::from([(2, 1.0)]), c)),
+                None,
+            ),
+            (
+                "single-node broker scatter",
+                "betweenness_vs_closeness.png",
+                Box::new(|c| plot_betweenness_vs_closeness(&HashMap::from([(1, 2)]), &HashMap::from([(1, 0.5)]), &HashMap::from([(1, 0.0)]), c)),
+                small,
+            ),
+            (
+                "equal broker scatter",
+                "betweenness_vs_closeness.png",
+                Box::new(|c| {
+                    let equal: HashMap<usize, f64> = (0..6).map(|v| (v, 1.0)).collect();
+                    let none: HashMap<usize, f64> = (0..6).map(|v| (v, 0.0)).collect();
+                    plot_betweenness_vs_closeness(&HashMap::new(), &equal, &none, c)
+                }),
+                small,
+            ),
+            ("no scatter matrix", "scatter_matrix.png", Box::new(|c| plot_scatter_matrix(&[], c)), None),
+            (
+                "disjoint scatter matrix",
+                "scatter_matrix.png",
+                Box::new(|c| plot_scatter_matrix(&[("Degree", HashMap::from([(1, 2.0)])), ("Closeness", HashMap::from([(2, 0.5)]))], c)),
+                square,
+            ),
+            (
+                "constant scatter matrix",
+                "scatter_matrix.png",
+                Box::new(|c| {
+                    let degree: HashMap<usize, f64> = (0..10).map(|v| (v, v as f64)).collect();
+                    let between: HashMap<usize, f64> = (0..10).map(|v| (v, 0.0)).collect();
+                    plot_scatter_matrix(&[("Degree", degree), ("Betweenness", between)], c)
+                }),
+                square,
+            ),
+            ("empty 3D clusters", "clusters_3d.png", Box::new(|c| plot_clusters_3d(&HashMap::new(), &HashMap::new(), c)), small),
+            ("zero 3D clusters", "clusters_3d.png", Box::new(|c| plot_clusters_3d(&zeros, &HashMap::from([(0, 0), (1, 9)]), c)), small),
+            ("unassigned 3D clusters", "clusters_3d.png", Box::new(|c| plot_clusters_3d(&features, &HashMap::new(), c)), small),
+            ("no cluster profiles", "cluster_profiles.png", Box::new(|c| plot_cluster_profiles(&HashMap::new(), &HashMap::new(), c)), None),
+            ("zero cluster profiles", "cluster_profiles.png", Box::new(|c| plot_cluster_profiles(&zeros, &HashMap::from([(0, 0), (1, 3)]), c)), small),
+            ("no clustered adjacency", "adjacency_matrix.png", Box::new(|c| plot_adjacency_matrix(&graph, &HashMap::new(), c)), None),
+            ("unknown clustered adjacency", "adjacency_matrix.png", Box::new(|c| plot_adjacency_matrix(&graph, &HashMap::from([(99, 0)]), c)), None),
+            ("disconnected adjacency", "adjacency_matrix.png", Box::new(|c| plot_adjacency_matrix(&graph, &HashMap::from([(1, 0), (3, 1), (5, 1)]), c)), square),
+            ("single-cluster adjacency", "adjacency_matrix.png", Box::new(|c| plot_adjacency_matrix(&graph, &HashMap::from([(1, 0), (2, 0)]), c)), square),
+            (
+                "clusters with an empty palette",
+                "clusters.png",
+                Box::new(|c| {
+                    let config = PlotConfig { style: PlotStyle { palette: Vec::new(), caption_size: 1, ..PlotStyle::colorblind() }, ..c.clone() };
+                    plot_clusters(&features, &HashMap::from([(0, 0), (1, 1), (2, 2)]), &config)
+                }),
+                small,
+            ),
+            ("no temporal windows", "temporal.gif", Box::new(|c| plot_temporal_animation(&TemporalGraph::new(Vec::new()), &[], &HashMap::new(), 100, c)), small),
+            ("sparse temporal windows", "temporal.gif", Box::new(|c| plot_temporal_animation(&temporal, &windows, &positions, 100, c)), small),
+        ];
+
+        for (i, (case, file, render, size)) in cases.iter().enumerate() {
+            let config = test_config(&format!("render_{}", i));
+            let _ = std::fs::remove_dir_all(&config.output_dir);
+            render(&config).unwrap_or_else(|e| panic!("{}: {}", case, e));
+            let path = config.output_dir.join(file);
+            assert_eq!(path.exists().then(|| image_size(&path)), *size, "{}", case);
+            let _ = std::fs::remove_dir_all(&config.output_dir);
+        }
+    }
+}
+// Final commit: updated plot.rs
@@ -0,0 +1,152 @@
+// Includes a pseudonymization pass that replaces every address and folder of the mapping by a
+// stable pseudonym derived from an HMAC-SHA-256 keyed with a salt, and every node ID by a keyed
+// permutation of it, so tables, plots and exports can be shared without exposing who is who:
+// neither the addresses nor a join with the original mapping CSV lead back to a person. The
+// same salt always gives the same pseudonyms, and addresses of one domain share a domain
+// pseudonym so that domain-level patterns survive.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::HashMap;
+
+// Replaces addresses, folders and node IDs by salted-hash pseudonyms.
+#[derive(Debug, Clone)]
+pub struct Pseudonymizer {
+    salt: String,
+}
+
+impl Pseudonymizer {
+    pub fn new(salt: &str) -> Pseudonymizer {
+        Pseudonymizer { salt: salt.to_string() }
+    }
+
+    // HMAC-SHA-256 of the parts, keyed with the salt.
+    fn digest(&self, parts: &[&[u8]]) -> [u8; 32] {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.salt.as_bytes()).expect("HMAC takes keys of any length");
+        for part in parts {
+            mac.update(part);
+            mac.update(b"\0");
+        }
+        mac.finalize().into_bytes().into()
+    }
+
+    // First `len` hex digits of HMAC(salt, kind, value), with the value lowercased so that
+    // differently capitalized copies of an address get the same pseudonym.
+    fn hash(&self, kind: &str, value: &str, len: usize) -> String {
+        let digest = self.digest(&[kind.as_bytes(), value.trim().to_lowercase().as_bytes()]);
+        digest.iter().map(|b| format!("{:02x}", b)).collect::<String>()[..len].to_string()
+    }
+
+    // Pseudonym of a node ID: a four-round Feistel network keyed with the salt permutes the low
+    // 32 bits, so distinct nodes keep distinct IDs and the graph is unchanged, but the ID no
+    // longer matches the original mapping. Bits above the low 32 (never set in SNAP IDs) are kept.
+    pub fn node_id(&self, id: usize) -> usize {
+        let (mut left, mut right) = ((id >> 16) as u16, id as u16);
+        for round in 0..4u8 {
+            let f = self.digest(&[b"node", &[round], &right.to_be_bytes()]);
+            (left, right) = (right, left ^ u16::from_be_bytes([f[0], f[1]]));
+        }
+        (id & !0xffff_ffff) | ((left as usize) << 16) | right as usize
+    }
+
+    // Pseudonyms of many node IDs, computing each distinct ID once.
+    // Its inputs are - `nodes`: node IDs, possibly repeated
+    // Its outputs are - `HashMap<usize, usize>`: node ID -> pseudonymous node ID
+    pub fn node_ids(&self, nodes: impl IntoIterator<Item = usize>) -> HashMap<usize, usize> {
+        let mut ids = HashMap::new();
+        for node in nodes {
+            ids.entry(node).or_insert_with(|| self.node_id(node));
+        }
+        ids
+    }
+
+    // Weighted edges with both endpoints replaced by their pseudonymous IDs.
+    pub fn relabel_edges(&self, edges: &[(usize, usize, f64)]) -> Vec<(usize, usize, f64)> {
+        let ids = self.node_ids(edges.iter().flat_map(|&(u, v, _)| [u, v]));
+        edges.iter().map(|&(u, v, w)| (ids[&u], ids[&v], w)).collect()
+    }
+
+    // A map keyed by pseudonymous node IDs, with the values kept (such as the real addresses
+    // the filters match against).
+    pub fn relabel_keys<V: Clone>(&self, map: &HashMap<usize, V>) -> HashMap<usize, V> {
+        map.iter().map(|(&node, value)| (self.node_id(node), value.clone())).collect()
+    }
+
+    // Pseudonym of an address, e.g. `person-3f1a9c02d4e7@org-8b21e0.invalid`. Strings without
+    // an @ get a person pseudonym only.
+    pub fn email(&self, email: &str) -> String {
+        match email.rsplit_once('@') {
+            Some((_, domain)) => format!("person-{}@org-{}.invalid", self.hash("email", email, 12), self.hash("domain", domain, 6)),
+            None => format!("person-{}", self.hash("email", email, 12)),
+        }
+    }
+
+    // Pseudonym of a mailbox folder, e.g. `folder-5c0e71`; an empty folder stays empty.
+    pub fn folder(&self, folder: &str) -> String {
+        if folder.is_empty() {
+            String::new()
+        } else {
+            format!("folder-{}", self.hash("folder", folder, 6))
+        }
+    }
+
+    // Pseudonymizes a whole mapping, node IDs included.
+    // Its inputs are - `map`: node ID -> (email, folder)
+    // Its outputs are - `HashMap<usize, (String, String)>`: pseudonymous node ID -> (pseudonym,
+    // folder pseudonym)
+    pub fn apply(&self, map: &HashMap<usize, (String, String)>) -> HashMap<usize, (String, String)> {
+        map.iter().map(|(&node, (email, folder))| (self.node_id(node), (self.email(email), self.folder(folder)))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Graph;
+    use crate::pipeline::AnalysisPipeline;
+
+    // Tests that pseudonyms are stable for a salt, ignore case, share the domain part within a
+    // domain and change with the salt, that node IDs are permuted without collisions, and that
+    // the metrics CSV of a pseudonymized analysis contains no original node ID, address or folder.
+    #[test]
+    fn test_pseudonymizer() {
+        let p = Pseudonymizer::new("secret");
+        let lay = p.email("Kenneth.Lay@enron.com");
+        assert_eq!(lay, p.email("kenneth.lay@ENRON.com"));
+        assert!(lay.starts_with("person-") && !lay.contains("lay"));
+        let domain = |e: &str| e.rsplit_once('@').unwrap().1.to_string();
+        assert_eq!(domain(&lay), domain(&p.email("jeff@enron.com")));
+        assert_ne!(domain(&lay), domain(&p.email("jeff@aol.com")));
+        assert_ne!(lay, Pseudonymizer::new("other").email("kenneth.lay@enron.com"));
+        assert_eq!((p.folder(""), p.folder("lay-k").len()), (String::new(), 13));
+
+        let ids = p.node_ids(0..70_000);
+        let distinct: std::collections::HashSet<&usize> = ids.values().collect();
+        assert_eq!(distinct.len(), 70_000);
+        assert_eq!(p.node_id(42), ids[&42]);
+        assert_ne!(p.node_id(42), Pseudonymizer::new("other").node_id(42));
+
+        // A ring of 30 people with chords, each with an address and a folder
+        let original: Vec<usize> = (0..30).map(|i| 1000 + 7 * i).collect();
+        let edges: Vec<(usize, usize, f64)> =
+            (0..30).flat_map(|i| [(original[i], original[(i + 1) % 30], 1.0), (original[i], original[(i * 7 + 3) % 30], 1.0)]).collect();
+        let map: HashMap<usize, (String, String)> =
+            original.iter().map(|&id| (id, (format!("user{}@enron.com", id), format!("folder-of-{}", id)))).collect();
+        let edges = p.relabel_edges(&edges);
+        let map = p.apply(&map);
+        let graph = Graph::from_weighted_edges(&edges);
+        let result = AnalysisPipeline::new().with_kmeans(2).with_pagerank().top_nodes(30).run(&graph, &map).unwrap();
+        let csv = std::env::temp_dir().join("project_pseudonym_test.csv");
+        result.write_csv(&csv, &map).unwrap();
+        let text = std::fs::read_to_string(&csv).unwrap();
+        let _ = std::fs::remove_file(&csv);
+        assert_eq!(text.lines().count(), 31);
+        for row in text.lines().skip(1) {
+            let fields: Vec<&str> = row.split(',').collect();
+            let node: usize = fields[0].parse().unwrap();
+            assert!(!original.contains(&node), "{}", row);
+            assert!(fields[1].starts_with("person-") && fields[2].starts_with("folder-") && !fields[2].contains("of"), "{}", row);
+        }
+        assert!(!text.contains("enron.com") && !text.contains("user"));
+    }
+}
@@ -0,0 +1,229 @@
+// Includes queries for exploring the surroundings of one person: everyone within N hops
+// of a node, with their distance and degree, as a table or as JSON, and the chains of
+// people connecting two employees.
+
+use crate::distance::UNREACHED;
+use crate::graph::{Graph, ShortestPathDag};
+use crate::mapping::EmailIndex;
+use std::collections::{HashMap, VecDeque};
+
+// A node reached from the query node, with its distance and its degree in the whole graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HopNeighbor {
+    pub node: usize,
+    pub distance: usize,
+    pub degree: usize,
+}
+
+// Finds every node within `hops` hops of a node, excluding the node itself.
+// Its inputs are - `graph`: the graph - `node`: node ID to start from - `hops`: maximum distance
+// Its outputs are - `Option<Vec<HopNeighbor>>`: neighbors sorted by distance, then node ID
+// (None if the node is not in the graph)
+pub fn n_hop_neighbors(graph: &Graph, node: usize, hops: usize) -> Option<Vec<HopNeighbor>> {
+    let source = graph.index_of(node)?;
+    let mut neighbors: Vec<HopNeighbor> = graph
+        .within_hops(source, hops)
+        .into_iter()
+        .skip(1)
+        .map(|(v, distance)| HopNeighbor { node: graph.node_id(v), distance, degree: graph.degree(v) })
+        .collect();
+    neighbors.sort_by_key(|n| (n.distance, n.node));
+    Some(neighbors)
+}
+
+// Finds one shortest path between two nodes by BFS with parent tracking, stopping as soon
+// as the target is reached. Among equal-length paths, the one through the lowest-index
+// neighbors is returned.
+// Its inputs are - `graph`: the graph - `from`, `to`: node IDs
+// Its outputs are - `Option<Vec<usize>>`: node IDs from `from` to `to`, or None if either node is
+// missing or they are not connected
+pub fn shortest_path_between(graph: &Graph, from: usize, to: usize) -> Option<Vec<usize>> {
+    let (source, target) = (graph.index_of(from)?, graph.index_of(to)?);
+    let mut parent = vec![usize::MAX; graph.node_count()];
+    parent[source] = source;
+    let mut queue = VecDeque::from([source]);
+    while let Some(curr) = queue.pop_front() {
+        if curr == target {
+            break;
+        }
+        for &nbr in graph.neighbors(curr) {
+            if parent[nbr] == usize::MAX {
+                parent[nbr] = curr;
+                queue.push_back(nbr);
+            }
+        }
+    }
+    if parent[target] == usize::MAX {
+        return None;
+    }
+    let mut path = vec![graph.node_id(target)];
+    let mut curr = target;
+    while curr != source {
+        curr = parent[curr];
+        path.push(graph.node_id(curr));
+    }
+    path.reverse();
+    Some(path)
+}
+
+// Finds how two employees are connected: one shortest chain of correspondents between them.
+// Its inputs are - `graph`: the graph - `emails`: the email index
+// - `from_email`, `to_email`: addresses (or node IDs) of the two ends
+// Its outputs are - `Option<Vec<usize>>`: node IDs along the path, or None if an address is
+// unknown or the two are not connected
+pub fn shortest_path(graph: &Graph, emails: &EmailIndex, from_email: &str, to_email: &str) -> Option<Vec<usize>> {
+    let from = emails.resolve(from_email)?;
+    let to = emails.resolve(to_email)?;
+    shortest_path_between(graph, from, to)
+}
+
+// All shortest paths between two nodes: how many there are, and up to a limit of them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShortestPaths {
+    pub length: usize,
+    pub count: f64,
+    // Node IDs from source to target, sorted lexicographically
+    pub paths: Vec<Vec<usize>>,
+}
+
+// Counts and enumerates the shortest paths between two nodes from the same path counts
+// (sigma) and predecessor lists that Brandes' betweenness uses. Paths are listed by walking
+// the predecessors back from the target, stopping after `limit` paths, since their number
+// can grow exponentially with the length.
+// Its inputs are - `graph`: the graph - `from`, `to`: node IDs - `limit`: most paths to list
+// Its outputs are - `Option<ShortestPaths>`: length, total count and listed paths, or None if
+// either node is missing or they are not connected
+pub fn all_shortest_paths(graph: &Graph, from: usize, to: usize, limit: usize) -> Option<ShortestPaths> {
+    let (source, target) = (graph.index_of(from)?, graph.index_of(to)?);
+    let mut dag = ShortestPathDag::new(graph.node_count());
+    dag.search(graph, source);
+    let length = dag.dist[target];
+    if length == UNREACHED {
+        return None;
+    }
+
+    // Depth-first walk over predecessors; `partial` holds the path from the target backwards
+    let mut paths = Vec::new();
+    let mut stack = vec![vec![target]];
+    while let Some(partial) = stack.pop() {
+        if paths.len() == limit {
+            break;
+        }
+        let last = *partial.last().unwrap();
+        if last == source {
+            paths.push(partial.iter().rev().map(|&v| graph.node_id(v)).collect::<Vec<usize>>());
+            continue;
+        }
+        let mut pred: Vec<usize> = dag.predecessors(graph, last).collect();
+        pred.reverse();
+        for p in pred {
+            let mut next = partial.clone();
+            next.push(p);
+            stack.push(next);
+        }
+    }
+    paths.sort();
+    Some(ShortestPaths { length, count: dag.sigma[target], paths })
+}
+
+// Formats the neighbors as a table with their email address and folder.
+// Its inputs are - `neighbors`: result of `n_hop_neighbors` - `email_map`: node ID -> (email, folder)
+// Its outputs are - `Vec<String>`: header line followed by one line per neighbor
+pub fn neighbors_table(neighbors: &[HopNeighbor], email_map: &HashMap<usize, (String, String)>) -> Vec<String> {
+    let mut lines = vec![format!("{:>8} {:>7} {:>7}  {:<40} {}", "Distance", "Node", "Degree", "Email", "Folder")];
+    for n in neighbors {
+        let (email, folder) = email_map.get(&n.node).map_or(("", ""), |(e, f)| (e.as_str(), f.as_str()));
+        lines.push(format!("{:>8} {:>7} {:>7}  {:<40} {}", n.distance, n.node, n.degree, email, folder));
+    }
+    lines
+}
+
+// Formats the neighbors as a JSON array of objects, with null for unmapped addresses.
+// Its inputs are - `neighbors`: result of `n_hop_neighbors` - `email_map`: node ID -> (email, folder)
+// Its outputs are - `String`: the JSON document
+pub fn neighbors_json(neighbors: &[HopNeighbor], email_map: &HashMap<usize, (String, String)>) -> String {
+    let items: Vec<String> = neighbors
+        .iter()
+        .map(|n| {
+            let (email, folder) = match email_map.get(&n.node) {
+                Some((e, f)) => (json_string(e), json_string(f)),
+                None => ("null".to_string(), "null".to_string()),
+            };
+            format!(
+                "  {{\"node\": {}, \"distance\": {}, \"degree\": {}, \"email\": {}, \"folder\": {}}}",
+                n.node, n.distance, n.degree, email, folder
+            )
+        })
+        .collect();
+    if items.is_empty() { "[]".to_string() } else { format!("[\n{}\n]", items.join(",\n")) }
+}
+
+// Quotes a string for JSON, escaping quotes, backslashes and control characters.
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests the 2-hop neighbors of the center of a star with one longer arm, and their JSON form.
+    #[test]
+    fn test_n_hop_neighbors() {
+        let graph = Graph::from_edges(&[(1, 2), (1, 3), (3, 4), (4, 5)]);
+        let neighbors = n_hop_neighbors(&graph, 1, 2).unwrap();
+        let found: Vec<(usize, usize, usize)> = neighbors.iter().map(|n| (n.node, n.distance, n.degree)).collect();
+        assert_eq!(found, vec![(2, 1, 1), (3, 1, 2), (4, 2, 2)]);
+        assert!(n_hop_neighbors(&graph, 9, 2).is_none());
+
+        let email_map = HashMap::from([(2, ("a\"b@enron.com".to_string(), "inbox".to_string()))]);
+        let json = neighbors_json(&neighbors[..2], &email_map);
+        assert_eq!(
+            json,
+            "[\n  {\"node\": 2, \"distance\": 1, \"degree\": 1, \"email\": \"a\\\"b@enron.com\", \"folder\": \"inbox\"},\n  \
+             {\"node\": 3, \"distance\": 1, \"degree\": 2, \"email\": null, \"folder\": null}\n]"
+        );
+    }
+
+    // Tests shortest paths on a square with a tail: 1-2-3-4-1 plus 4-5, and an isolated edge 8-9.
+    #[test]
+    fn test_shortest_path() {
+        let graph = Graph::from_edges(&[(1, 2), (2, 3), (3, 4), (4, 1), (4, 5), (8, 9)]);
+        assert_eq!(shortest_path_between(&graph, 2, 5), Some(vec![2, 1, 4, 5]));
+        assert_eq!(shortest_path_between(&graph, 3, 3), Some(vec![3]));
+        assert_eq!(shortest_path_between(&graph, 1, 9), None);
+
+        let emails = EmailIndex::new(HashMap::from([(2, ("a@enron.com".to_string(), "inbox".to_string()))]));
+        assert_eq!(shortest_path(&graph, &emails, "a@enron.com", "5"), Some(vec![2, 1, 4, 5]));
+        assert_eq!(shortest_path(&graph, &emails, "b@enron.com", "5"), None);
+    }
+
+    // Tests that both routes around the square 1-2-3-4-1 are found between opposite corners,
+    // and that the limit caps the listed paths but not the count. Nodes in another component,
+    // missing from the graph, or a limit of 0 give no paths.
+    #[test]
+    fn test_all_shortest_paths() {
+        let graph = Graph::from_edges(&[(1, 2), (2, 3), (3, 4), (4, 1), (3, 5)]);
+        let all = all_shortest_paths(&graph, 1, 5, 10).unwrap();
+        assert_eq!((all.length, all.count), (3, 2.0));
+        assert_eq!(all.paths, vec![vec![1, 2, 3, 5], vec![1, 4, 3, 5]]);
+        assert_eq!(all_shortest_paths(&graph, 1, 5, 1).unwrap().paths.len(), 1);
+        assert_eq!(all_shortest_paths(&graph, 1, 1, 10).unwrap().paths, vec![vec![1]]);
+
+        let split = Graph::from_edges(&[(1, 2), (2, 3), (7, 8)]);
+        assert!(all_shortest_paths(&split, 1, 8, 10).is_none() && all_shortest_paths(&split, 1, 99, 10).is_none());
+        assert_eq!(all_shortest_paths(&split, 8, 7, 10).unwrap().paths, vec![vec![8, 7]]);
+        let none = all_shortest_paths(&split, 1, 3, 0).unwrap();
+        assert_eq!((none.count, none.paths.len()), (1.0, 0));
+    }
+}
@@ -0,0 +1,157 @@
+// Includes functions to measure how the network falls apart when nodes are removed,
+// either targeting the most central employees first or removing them at random.
+// After every batch of removals the giant component size and its average path length are recorded.
+
+use crate::distance::sampled_path_length;
+use crate::graph::Graph;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::fmt;
+
+// Order in which nodes are removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemovalStrategy {
+    // Highest degree first
+    Degree,
+    // Highest betweenness first; nodes without a score follow by degree
+    Betweenness,
+    // Uniformly random order
+    Random,
+}
+
+impl fmt::Display for RemovalStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            RemovalStrategy::Degree => "Degree",
+            RemovalStrategy::Betweenness => "Betweenness",
+            RemovalStrategy::Random => "Random",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// State of the network after some removals.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RobustnessPoint {
+    pub removed: usize,
+    // Giant component size as a fraction of the original node count
+    pub giant_fraction: f64,
+    // Average shortest path length inside the giant component (estimated from sampled sources)
+    pub avg_path_length: f64,
+}
+
+// Returns the node IDs in removal order for a strategy.
+// Its inputs are - `graph`: the graph - `strategy`: removal order - `betweenness`: node ID -> betweenness,
+// used by `RemovalStrategy::Betweenness` - `seed`: RNG seed for `RemovalStrategy::Random`
+// Its outputs are - `Vec<usize>`: every node ID, first to be removed first
+pub fn removal_order(graph: &Graph, strategy: RemovalStrategy, betweenness: &HashMap<usize, f64>, seed: u64) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..graph.node_count()).collect();
+    match strategy {
+        RemovalStrategy::Degree => order.sort_by_key(|&v| (std::cmp::Reverse(graph.degree(v)), graph.node_id(v))),
+        RemovalStrategy::Betweenness => {
+            let score = |v: usize| betweenness.get(&graph.node_id(v)).copied().unwrap_or(-1.0);
+            order.sort_by(|&a, &b| {
+                score(b)
+                    .partial_cmp(&score(a))
+                    .unwrap()
+                    .then(graph.degree(b).cmp(&graph.degree(a)))
+                    .then(graph.node_id(a).cmp(&graph.node_id(b)))
+            });
+        }
+        RemovalStrategy::Random => order.shuffle(&mut StdRng::seed_from_u64(seed)),
+    }
+    order.into_iter().map(|v| graph.node_id(v)).collect()
+}
+
+// Removes nodes in the given order and measures the network every `step` removals,
+// up to `max_removed` nodes. Removal is simulated by rebuilding the graph without the removed nodes.
+// Its inputs are - `graph`: the graph - `order`: node IDs in removal order - `step`: removals between measurements
+// - `max_removed`: last removal count to measure - `path_samples`: BFS sources for the path length - `seed`: RNG seed
+// Its outputs are - `Vec<RobustnessPoint>`: one point per measurement, starting with nothing removed
+pub fn robustness_curve(
+    graph: &Graph,
+    order: &[usize],
+    step: usize,
+    max_removed: usize,
+    path_samples: usize,
+    seed: u64,
+) -> Vec<RobustnessPoint> {
+    let total = graph.node_count();
+    let mut removed = vec![false; total];
+    let mut points = Vec::new();
+    let max_removed = max_removed.min(order.len());
+    let step = step.max(1);
+
+    let mut count = 0;
+    loop {
+        // Rebuild the remaining graph and measure its giant component
+        let mut remaining = Vec::new();
+        for u in 0..total {
+            if removed[u] {
+                continue;
+            }
+            for &v in graph.neighbors(u) {
+                if u < v && !removed[v] {
+                    remaining.push((graph.node_id(u), graph.node_id(v)));
+                }
+            }
+        }
+        let rest = Graph::from_edges(&remaining);
+        let giant = rest.components().into_iter().max_by_key(|c| c.len()).unwrap_or_default();
+        // Remaining nodes without edges are not in `rest`, but still form components of size 1
+        let giant_size = if giant.is_empty() && count < total { 1 } else { giant.len() };
+        points.push(RobustnessPoint {
+            removed: count,
+            giant_fraction: giant_size as f64 / total.max(1) as f64,
+            avg_path_length: sampled_path_length(&rest, &giant, path_samples, seed.wrapping_add(count as u64)),
+        });
+
+        if count >= max_removed {
+            break;
+        }
+        let next = (count + step).min(max_removed);
+        for &id in &order[count..next] {
+            if let Some(v) = graph.index_of(id) {
+                removed[v] = true;
+            }
+        }
+        count = next;
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that removing the hub of two joined stars splits the giant component,
+    // while removing a leaf barely changes it.
+    #[test]
+    fn test_robustness_curve() {
+        let mut edges = vec![(1, 2)];
+        for leaf in 10..15 {
+            edges.push((1, leaf));
+        }
+        for leaf in 20..23 {
+            edges.push((2, leaf));
+        }
+        let graph = Graph::from_edges(&edges);
+
+        let order = removal_order(&graph, RemovalStrategy::Degree, &HashMap::new(), 0);
+        assert_eq!(&order[..2], &[1, 2]);
+        let curve = robustness_curve(&graph, &order, 1, 2, 100, 0);
+        assert_eq!(curve.len(), 3);
+        assert_eq!(curve[0].giant_fraction, 1.0);
+        assert!(curve[0].avg_path_length > 1.0);
+        // Without node 1 only the star around node 2 (4 nodes) stays connected
+        assert_eq!(curve[1].giant_fraction, 0.4);
+        assert_eq!(curve[1].avg_path_length, 1.5);
+        // Only isolated leaves remain
+        assert_eq!(curve[2].giant_fraction, 0.1);
+
+        let betweenness: HashMap<usize, f64> = vec![(2, 10.0), (1, 5.0)].into_iter().collect();
+        assert_eq!(&removal_order(&graph, RemovalStrategy::Betweenness, &betweenness, 0)[..2], &[2, 1]);
+    }
+}
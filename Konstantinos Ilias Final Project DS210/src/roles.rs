@@ -0,0 +1,214 @@
+// Includes functions for RolX-style role discovery: recursive structural features are extracted
+// for every node, factorized with non-negative matrix factorization (NMF), and each node gets
+// the role with the largest weight. Roles are then named (hub, bridge, peripheral) from their members.
+
+use crate::graph::Graph;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+
+// Result of role discovery.
+#[derive(Debug, Clone)]
+pub struct Roles {
+    // Node ID -> index of its dominant role
+    pub assignments: HashMap<usize, usize>,
+    // Node ID -> non-negative weight of every role
+    pub weights: HashMap<usize, Vec<f64>>,
+    // Human-readable name of each role
+    pub labels: Vec<String>,
+}
+
+// Extracts ReFeX-style recursive features. The base features are the degree, the number of
+// edges inside the node's egonet and the number of edges leaving it; every iteration appends
+// the mean and the sum of each existing feature over the node's neighbors.
+// Its inputs are - `graph`: the graph - `iterations`: number of aggregation rounds
+// Its outputs are - `Vec<Vec<f64>>`: feature vector per compact node index
+pub fn recursive_features(graph: &Graph, iterations: usize) -> Vec<Vec<f64>> {
+    let n = graph.node_count();
+    let mut features: Vec<Vec<f64>> = (0..n)
+        .map(|v| {
+            let nbrs = graph.neighbors(v);
+            // Edges between neighbors, found by intersecting ascending neighbor lists
+            let mut inner = 0;
+            for &u in nbrs {
+                inner += graph.neighbors(u).iter().filter(|w| nbrs.binary_search(w).is_ok()).count();
+            }
+            let internal = nbrs.len() + inner / 2;
+            let ego_degrees: usize = nbrs.len() + nbrs.iter().map(|&u| graph.degree(u)).sum::<usize>();
+            vec![nbrs.len() as f64, internal as f64, (ego_degrees - 2 * internal) as f64]
+        })
+        .collect();
+
+    for _ in 0..iterations {
+        let next: Vec<Vec<f64>> = (0..n)
+            .map(|v| {
+                let nbrs = graph.neighbors(v);
+                let mut row = features[v].clone();
+                for (d, _) in features[v].iter().enumerate() {
+                    let sum: f64 = nbrs.iter().map(|&u| features[u][d]).sum();
+                    row.push(if nbrs.is_empty() { 0.0 } else { sum / nbrs.len() as f64 });
+                    row.push(sum);
+                }
+                row
+            })
+            .collect();
+        features = next;
+    }
+    features
+}
+
+// Factorizes the non-negative matrix `v` (rows x cols) into W (rows x rank) and H (rank x cols)
+// with Lee–Seung multiplicative updates minimizing the squared error.
+fn nmf(v: &[Vec<f64>], rank: usize, iters: usize, rng: &mut StdRng) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let rows = v.len();
+    let cols = v.first().map_or(0, |r| r.len());
+    let mut w: Vec<Vec<f64>> = (0..rows).map(|_| (0..rank).map(|_| rng.gen_range(0.1..1.0)).collect()).collect();
+    let mut h: Vec<Vec<f64>> = (0..rank).map(|_| (0..cols).map(|_| rng.gen_range(0.1..1.0)).collect()).collect();
+    let eps = 1e-9;
+
+    for _ in 0..iters {
+        // H <- H * (W^T V) / (W^T W H)
+        let mut wtv = vec![vec![0.0; cols]; rank];
+        let mut wtw = vec![vec![0.0; rank]; rank];
+        for i in 0..rows {
+            for a in 0..rank {
+                for j in 0..cols {
+                    wtv[a][j] += w[i][a] * v[i][j];
+                }
+                for b in 0..rank {
+                    wtw[a][b] += w[i][a] * w[i][b];
+                }
+            }
+        }
+        for a in 0..rank {
+            for j in 0..cols {
+                let denom: f64 = (0..rank).map(|b| wtw[a][b] * h[b][j]).sum();
+                h[a][j] *= wtv[a][j] / (denom + eps);
+            }
+        }
+
+        // W <- W * (V H^T) / (W H H^T)
+        let mut hht = vec![vec![0.0; rank]; rank];
+        for a in 0..rank {
+            for b in 0..rank {
+                hht[a][b] = (0..cols).map(|j| h[a][j] * h[b][j]).sum();
+            }
+        }
+        for i in 0..rows {
+            let vht: Vec<f64> = (0..rank).map(|a| (0..cols).map(|j| v[i][j] * h[a][j]).sum()).collect();
+            let old = w[i].clone();
+            for a in 0..rank {
+                let denom: f64 = (0..rank).map(|b| old[b] * hht[b][a]).sum();
+                w[i][a] *= vht[a] / (denom + eps);
+            }
+        }
+    }
+    (w, h)
+}
+
+// Discovers structural roles for every node: recursive features are log-scaled and normalized
+// to [0, 1], factorized into `roles` non-negative roles, and each node takes its strongest role.
+// Roles are named by their members' base features: the highest mean degree is "hub", the lowest
+// is "peripheral", the highest ratio of egonet-leaving to internal edges among the rest is "bridge",
+// and any remaining roles are "member".
+// Its inputs are - `graph`: the graph - `roles`: number of roles
+// - `iterations`: recursive feature rounds - `seed`: RNG seed for the factorization
+// Its outputs are - `Roles`: role assignment, weights and labels
+pub fn discover_roles(graph: &Graph, roles: usize, iterations: usize, seed: u64) -> Roles {
+    let n = graph.node_count();
+    let raw = recursive_features(graph, iterations);
+    let dims = raw.first().map_or(0, |f| f.len());
+
+    // Log-scale and normalize each column so no single feature dominates the factorization
+    let mut matrix: Vec<Vec<f64>> = raw.iter().map(|row| row.iter().map(|x| x.ln_1p()).collect()).collect();
+    for d in 0..dims {
+        let max = matrix.iter().map(|row| row[d]).fold(0.0, f64::max);
+        if max > 0.0 {
+            for row in matrix.iter_mut() {
+                row[d] /= max;
+            }
+        }
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (w, _) = nmf(&matrix, roles, 200, &mut rng);
+    let dominant: Vec<usize> = w
+        .iter()
+        .map(|row| (0..roles).max_by(|&a, &b| row[a].partial_cmp(&row[b]).unwrap()).unwrap_or(0))
+        .collect();
+
+    // Mean degree and egonet out/in ratio of each role's members
+    let mut degree_sum = vec![0.0; roles];
+    let mut ratio_sum = vec![0.0; roles];
+    let mut count = vec![0usize; roles];
+    for v in 0..n {
+        let r = dominant[v];
+        degree_sum[r] += raw[v][0];
+        ratio_sum[r] += raw[v][2] / raw[v][1].max(1.0);
+        count[r] += 1;
+    }
+    let mean = |sums: &[f64], r: usize| if count[r] > 0 { sums[r] / count[r] as f64 } else { 0.0 };
+
+    let mut labels = vec![String::from("member"); roles];
+    let mut unnamed: Vec<usize> = (0..roles).filter(|&r| count[r] > 0).collect();
+    let by = |unnamed: &[usize], sums: &[f64], highest: bool| {
+        let cmp = |a: &&usize, b: &&usize| mean(sums, **a).partial_cmp(&mean(sums, **b)).unwrap();
+        if highest { unnamed.iter().max_by(cmp).copied() } else { unnamed.iter().min_by(cmp).copied() }
+    };
+    for (name, sums, highest) in [("hub", &degree_sum, true), ("peripheral", &degree_sum, false), ("bridge", &ratio_sum, true)] {
+        if let Some(r) = by(&unnamed, sums, highest) {
+            labels[r] = name.to_string();
+            unnamed.retain(|&x| x != r);
+        }
+    }
+
+    Roles {
+        assignments: (0..n).map(|v| (graph.node_id(v), dominant[v])).collect(),
+        weights: (0..n).map(|v| (graph.node_id(v), w[v].clone())).collect(),
+        labels,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that base recursive features count egonet edges correctly.
+    // Node 2 sits in triangle 1-2-3 and also links to 4, which links on to 5.
+    #[test]
+    fn test_recursive_features() {
+        let edges = vec![(1, 2), (2, 3), (3, 1), (2, 4), (4, 5)];
+        let graph = Graph::from_edges(&edges);
+        let features = recursive_features(&graph, 1);
+        let v = graph.index_of(2).unwrap();
+
+        assert_eq!(features[v].len(), 9);
+        // Degree 3, egonet edges 1-2, 2-3, 3-1, 2-4, and one edge 4-5 leaving the egonet
+        assert_eq!(&features[v][..3], &[3.0, 4.0, 1.0]);
+        // Mean and sum of neighbor degrees (2, 2, 2)
+        assert_eq!(&features[v][3..5], &[2.0, 6.0]);
+    }
+
+    // Tests that star centers are labeled as hubs and their leaves as peripheral nodes.
+    // Two stars with 8 leaves each are joined through a path between their centers.
+    #[test]
+    fn test_discover_roles() {
+        let mut edges = Vec::new();
+        for leaf in 10..18 {
+            edges.push((1, leaf));
+        }
+        for leaf in 20..28 {
+            edges.push((2, leaf));
+        }
+        edges.push((1, 3));
+        edges.push((3, 2));
+        let graph = Graph::from_edges(&edges);
+        let roles = discover_roles(&graph, 3, 2, 7);
+
+        let label = |id: usize| roles.labels[roles.assignments[&id]].as_str();
+        assert_eq!(label(1), "hub");
+        assert_eq!(label(2), "hub");
+        assert_eq!(label(10), "peripheral");
+        assert_eq!(roles.assignments[&10], roles.assignments[&25]);
+    }
+}
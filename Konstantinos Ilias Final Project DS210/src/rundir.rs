@@ -0,0 +1,191 @@
+// Includes the run directory: the plots, CSV files and reports of one run of the analysis are
+// written into a directory of their own, named after the time the run started (UTC, e.g.
+// `runs/2024-05-01_13-45-07`) or given a name, together with a manifest of the parameters of
+// the run and the files it wrote. Runs on different datasets or settings can then be compared
+// side by side instead of each overwriting the images of the last one in the working directory.
+
+use crate::temporal::civil_from_days;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Name of the manifest in every run directory.
+pub const MANIFEST: &str = "manifest.txt";
+
+// Flags whose values are secrets and are left out of the recorded command line.
+pub const SECRET_FLAGS: [&str; 1] = ["--salt"];
+
+// Directory the run directories are created in, unless another is given.
+pub fn default_runs_dir() -> PathBuf {
+    PathBuf::from("runs")
+}
+
+// Formats Unix seconds as a UTC date and time, e.g. `2024-05-01 13:45:07`.
+pub fn format_timestamp(secs: i64) -> String {
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let time = secs.rem_euclid(86_400);
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, time / 3600, time / 60 % 60, time % 60)
+}
+
+// The command line of a run as it is recorded in the manifest, with the values of the
+// `SECRET_FLAGS` (such as the pseudonym salt) replaced by `<redacted>`.
+pub fn command_line(args: impl IntoIterator<Item = String>) -> String {
+    let mut words = Vec::new();
+    let mut secret = false;
+    for arg in args {
+        if secret {
+            words.push("<redacted>".to_string());
+            secret = false;
+        } else if let Some(flag) = SECRET_FLAGS.iter().find(|flag| arg.starts_with(&format!("{}=", flag))) {
+            words.push(format!("{}=<redacted>", flag));
+        } else {
+            secret = SECRET_FLAGS.contains(&arg.as_str());
+            words.push(arg);
+        }
+    }
+    words.join(" ")
+}
+
+fn unix_secs(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64)
+}
+
+// The output directory of one run and the parameters recorded for its manifest. The directory
+// is removed again if the run ends without writing anything to it.
+#[derive(Debug)]
+pub struct RunDirectory {
+    path: PathBuf,
+    started: SystemTime,
+    // (name, value) in the order they were recorded
+    parameters: Vec<(String, String)>,
+}
+
+impl RunDirectory {
+    // Creates a run directory in `base`, called `name`, or after the current time if there is
+    // no name. A named directory that exists is reused; a timestamped one is claimed with
+    // `create_dir`, which fails if another run created it first, and then gets a suffix (`-2`,
+    // `-3`, ...), so two runs started in the same second never mix their files.
+    // Its outputs are - `RunDirectory`: the created directory, or the error of creating it
+    pub fn new(base: &Path, name: Option<&str>) -> std::io::Result<RunDirectory> {
+        let started = SystemTime::now();
+        std::fs::create_dir_all(base)?;
+        let path = match name {
+            Some(name) => {
+                let path = base.join(name);
+                std::fs::create_dir_all(&path)?;
+                path
+            }
+            None => {
+                let stamp = format_timestamp(unix_secs(started)).replace(' ', "_").replace(':', "-");
+                let mut attempt = 1;
+                loop {
+                    let path = if attempt == 1 { base.join(&stamp) } else { base.join(format!("{}-{}", stamp, attempt)) };
+                    match std::fs::create_dir(&path) {
+                        Ok(()) => break path,
+                        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => attempt += 1,
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+        };
+        Ok(RunDirectory { path, started, parameters: Vec::new() })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Path of the output file `name` in the run directory. An absolute `name` is kept as it is.
+    pub fn file(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.path.join(name)
+    }
+
+    // Records a parameter of the run for the manifest; a parameter recorded again is replaced.
+    pub fn record(&mut self, name: &str, value: impl std::fmt::Display) {
+        let value = value.to_string();
+        match self.parameters.iter_mut().find(|(n, _)| n == name) {
+            Some(entry) => entry.1 = value,
+            None => self.parameters.push((name.to_string(), value)),
+        }
+    }
+
+    // Writes the manifest: when the run started and finished, the parameters recorded and every
+    // file in the directory with its size. It is written last, so it lists all the outputs.
+    // Its outputs are - `PathBuf`: path of the manifest, or the error of writing it
+    pub fn write_manifest(&self) -> std::io::Result<PathBuf> {
+        let path = self.file(MANIFEST);
+        let mut files: Vec<(String, u64)> = Vec::new();
+        for entry in std::fs::read_dir(&self.path)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name != MANIFEST && !name.ends_with(".part") && entry.file_type()?.is_file() {
+                files.push((name, entry.metadata()?.len()));
+            }
+        }
+        files.sort();
+
+        let partial = path.with_extension("part");
+        let mut out = BufWriter::new(File::create(&partial)?);
+        writeln!(out, "# Parameters and outputs of one run of the analysis")?;
+        writeln!(out, "started = {} UTC", format_timestamp(unix_secs(self.started)))?;
+        writeln!(out, "finished = {} UTC", format_timestamp(unix_secs(SystemTime::now())))?;
+        writeln!(out, "seconds = {:.1}", self.started.elapsed().map_or(0.0, |d| d.as_secs_f64()))?;
+        for (name, value) in &self.parameters {
+            writeln!(out, "{} = {}", name, value)?;
+        }
+        writeln!(out, "\n[files]")?;
+        for (name, bytes) in &files {
+            writeln!(out, "{} = {} bytes", name, bytes)?;
+        }
+        out.flush()?;
+        drop(out);
+        std::fs::rename(&partial, &path)?;
+        Ok(path)
+    }
+}
+
+impl Drop for RunDirectory {
+    // Removes the directory if nothing was written to it; `remove_dir` leaves a non-empty one.
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests timestamp formatting and the redaction of secrets, that timestamped directories
+    // created in the same second are distinct, that an unused directory is removed, and that the
+    // manifest lists the parameters and files of the run.
+    #[test]
+    fn test_run_directory() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_timestamp(1_714_571_107), "2024-05-01 13:45:07");
+        let args = ["project", "--pseudonymize", "--salt", "hunter2", "--k", "3", "--salt=hunter2"].map(String::from);
+        assert_eq!(command_line(args), "project --pseudonymize --salt <redacted> --k 3 --salt=<redacted>");
+
+        let base = std::env::temp_dir().join("project_run_directory_test");
+        let _ = std::fs::remove_dir_all(&base);
+        let runs: Vec<RunDirectory> = (0..3).map(|_| RunDirectory::new(&base, None).unwrap()).collect();
+        assert!(runs.iter().all(|run| run.path().is_dir()));
+        assert!(runs[0].path() != runs[1].path() && runs[1].path() != runs[2].path() && runs[0].path() != runs[2].path());
+        let unused = runs[2].path().to_path_buf();
+        std::fs::write(runs[0].file("degree.png"), b"png").unwrap();
+        let kept = runs[0].path().to_path_buf();
+        drop(runs);
+        assert!(kept.is_dir() && !unused.exists());
+
+        let mut named = RunDirectory::new(&base, Some("enron")).unwrap();
+        assert_eq!(named.path(), base.join("enron"));
+        named.record("k", 5);
+        named.record("scope", "top-n");
+        named.record("k", 3);
+        std::fs::write(named.file("metrics.csv"), "node\n1\n").unwrap();
+        let manifest = std::fs::read_to_string(named.write_manifest().unwrap()).unwrap();
+        assert!(manifest.contains("k = 3\nscope = top-n\n"), "{}", manifest);
+        assert!(manifest.ends_with("[files]\nmetrics.csv = 7 bytes\n"), "{}", manifest);
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,185 @@
+// Includes graph sampling: random-node, random-edge, snowball and forest-fire samples that are
+// much smaller than the graph but keep some of its structure, so that expensive exact metrics
+// can be tried on a sample before running them on the whole network. Node-based samples are
+// induced subgraphs; a random-edge sample keeps only the sampled edges.
+
+use crate::graph::Graph;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashSet, VecDeque};
+
+// How the sample is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamplingMethod {
+    // Nodes chosen uniformly at random; keeps the degree mix but breaks up neighborhoods
+    RandomNode,
+    // Edges chosen uniformly at random with their endpoints; favors high-degree nodes
+    RandomEdge,
+    // Breadth-first waves from random seeds; keeps neighborhoods but over-samples hubs
+    Snowball,
+    // Leskovec and Faloutsos' forest fire: each burning node sets fire to a geometric number of
+    // its neighbors; keeps both degree and clustering patterns well
+    ForestFire,
+}
+
+impl std::fmt::Display for SamplingMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SamplingMethod::RandomNode => write!(f, "random node"),
+            SamplingMethod::RandomEdge => write!(f, "random edge"),
+            SamplingMethod::Snowball => write!(f, "snowball"),
+            SamplingMethod::ForestFire => write!(f, "forest fire"),
+        }
+    }
+}
+
+// Parameters of a sample.
+#[derive(Debug, Clone, Copy)]
+pub struct SamplingConfig {
+    pub method: SamplingMethod,
+    // Number of nodes in the sample (the whole graph if it is smaller)
+    pub size: usize,
+    pub seed: u64,
+    // Snowball: neighbors followed per node (0 for all of them)
+    pub snowball_neighbors: usize,
+    // Forest fire: forward burning probability p, so a node burns p / (1 - p) neighbors on average
+    pub burn_probability: f64,
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        SamplingConfig { method: SamplingMethod::ForestFire, size: 1000, seed: 42, snowball_neighbors: 0, burn_probability: 0.7 }
+    }
+}
+
+// A random node not sampled yet, if there is one left.
+fn unsampled_node(graph: &Graph, sampled: &HashSet<usize>, rng: &mut StdRng) -> Option<usize> {
+    if sampled.len() * 2 < graph.node_count() {
+        // Mostly unsampled: rejection sampling is fast
+        loop {
+            let v = rng.gen_range(0..graph.node_count());
+            if !sampled.contains(&v) {
+                return Some(v);
+            }
+        }
+    }
+    let rest: Vec<usize> = (0..graph.node_count()).filter(|v| !sampled.contains(v)).collect();
+    rest.choose(rng).copied()
+}
+
+// Grows a sample from random seeds, adding from each node the unsampled neighbors that `spread`
+// leaves in the list, until it has `size` nodes; a new seed is drawn whenever the wave dies out.
+fn grow(graph: &Graph, size: usize, rng: &mut StdRng, mut spread: impl FnMut(&mut Vec<usize>, &mut StdRng)) -> Vec<usize> {
+    let mut sampled = HashSet::new();
+    let mut order = Vec::new();
+    let mut queue = VecDeque::new();
+    while order.len() < size {
+        let v = match queue.pop_front() {
+            Some(v) => v,
+            None => {
+                let Some(seed) = unsampled_node(graph, &sampled, rng) else {
+                    break;
+                };
+                sampled.insert(seed);
+                order.push(seed);
+                seed
+            }
+        };
+        let mut next: Vec<usize> = graph.neighbors(v).iter().copied().filter(|w| !sampled.contains(w)).collect();
+        spread(&mut next, rng);
+        for w in next {
+            if order.len() == size {
+                break;
+            }
+            sampled.insert(w);
+            order.push(w);
+            queue.push_back(w);
+        }
+    }
+    order
+}
+
+// Draws a sample of a graph.
+// Its inputs are - `graph`: the graph - `config`: method, sample size, seed and method parameters
+// Its outputs are - `Graph`: the sampled subgraph, with the original node IDs (and weights)
+pub fn sample(graph: &Graph, config: &SamplingConfig) -> Graph {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let size = config.size.min(graph.node_count());
+    let mut nodes: Vec<usize> = match config.method {
+        SamplingMethod::RandomNode => rand::seq::index::sample(&mut rng, graph.node_count(), size).into_vec(),
+        SamplingMethod::RandomEdge => {
+            let mut edges = graph.edges();
+            edges.shuffle(&mut rng);
+            let mut sampled = HashSet::new();
+            let mut kept = HashSet::new();
+            for (u, v) in edges {
+                if sampled.len() == size {
+                    break;
+                }
+                let new = usize::from(!sampled.contains(&u)) + usize::from(!sampled.contains(&v));
+                if sampled.len() + new <= size {
+                    sampled.extend([u, v]);
+                    kept.insert((u.min(v), u.max(v)));
+                }
+            }
+            // Induce on the sampled nodes, then drop the edges that were not drawn
+            let mut nodes: Vec<usize> = sampled.iter().map(|&id| graph.index_of(id).expect("sampled node")).collect();
+            nodes.sort_unstable();
+            let mut sub = graph.subgraph(&nodes);
+            for (u, v) in sub.edges() {
+                if !kept.contains(&(u.min(v), u.max(v))) {
+                    sub.remove_edge(u, v);
+                }
+            }
+            return sub;
+        }
+        SamplingMethod::Snowball => grow(graph, size, &mut rng, |next, rng| {
+            if config.snowball_neighbors > 0 && next.len() > config.snowball_neighbors {
+                next.shuffle(rng);
+                next.truncate(config.snowball_neighbors);
+            }
+        }),
+        SamplingMethod::ForestFire => grow(graph, size, &mut rng, |next, rng| {
+            // Geometric number of neighbors to burn: keep going while a coin with bias p comes up
+            let mut burn = 0;
+            while rng.gen_bool(config.burn_probability.clamp(0.0, 0.99)) {
+                burn += 1;
+            }
+            next.shuffle(rng);
+            next.truncate(burn);
+        }),
+    };
+    nodes.sort_unstable();
+    graph.subgraph(&nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that every method returns a subgraph of the requested size whose edges are edges
+    // of the graph, that samples are reproducible from the seed, and that a snowball sample of
+    // a connected graph started from one seed stays connected.
+    #[test]
+    fn test_sample() {
+        let mut edges = Vec::new();
+        for v in 0..200usize {
+            edges.push((v, (v + 1) % 200));
+            edges.push((v, (v * 7 + 3) % 200));
+        }
+        let graph = Graph::from_edges(&edges);
+        let all: HashSet<(usize, usize)> = graph.edges().into_iter().map(|(u, v)| (u.min(v), u.max(v))).collect();
+        for method in [SamplingMethod::RandomNode, SamplingMethod::RandomEdge, SamplingMethod::Snowball, SamplingMethod::ForestFire] {
+            let config = SamplingConfig { method, size: 50, seed: 7, ..SamplingConfig::default() };
+            let sampled = sample(&graph, &config);
+            assert_eq!(sampled.node_count(), 50, "{}", method);
+            assert!(sampled.edges().iter().all(|&(u, v)| all.contains(&(u.min(v), u.max(v)))), "{}", method);
+            assert_eq!(sampled.edges(), sample(&graph, &config).edges(), "{}", method);
+        }
+        let snowball = sample(&graph, &SamplingConfig { method: SamplingMethod::Snowball, size: 50, ..SamplingConfig::default() });
+        assert_eq!(snowball.components().len(), 1);
+        let whole = sample(&graph, &SamplingConfig { size: 1000, ..SamplingConfig::default() });
+        assert_eq!(whole.edge_count(), graph.edge_count());
+    }
+}
@@ -0,0 +1,82 @@
+// Includes functions for the small-world coefficients sigma and omega, which compare the
+// network's clustering and path length with random and ring-lattice reference graphs.
+// Sigma above 1 and omega near 0 indicate a small world; omega near -1 is lattice-like
+// and near 1 random-like.
+
+use crate::distance::sampled_path_length;
+use crate::generate::watts_strogatz;
+use crate::graph::Graph;
+use crate::nullmodel::{null_graphs, NullModel};
+
+// Clustering and path lengths of the network and its references, with both coefficients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmallWorld {
+    pub clustering: f64,
+    pub path_length: f64,
+    pub random_clustering: f64,
+    pub random_path_length: f64,
+    pub lattice_clustering: f64,
+    // (C / C_random) / (L / L_random)
+    pub sigma: f64,
+    // L_random / L - C / C_lattice
+    pub omega: f64,
+}
+
+// Clustering (transitivity) and giant-component path length of one graph.
+fn clustering_and_path(graph: &Graph, path_samples: usize, seed: u64) -> (f64, f64) {
+    let giant = graph.components().into_iter().max_by_key(|c| c.len()).unwrap_or_default();
+    (graph.transitivity(), sampled_path_length(graph, &giant, path_samples, seed))
+}
+
+// Computes sigma and omega. The random references are degree-preserving randomizations and
+// the lattice reference is a ring lattice with the same number of nodes and about the same
+// mean degree. Path lengths are estimated from sampled BFS sources in each giant component.
+// Its inputs are - `graph`: the graph - `references`: number of random references
+// - `path_samples`: BFS sources per path length estimate - `seed`: RNG seed
+// Its outputs are - `SmallWorld`: the measured values and both coefficients
+pub fn small_world(graph: &Graph, references: usize, path_samples: usize, seed: u64) -> SmallWorld {
+    let (clustering, path_length) = clustering_and_path(graph, path_samples, seed);
+
+    let randoms = null_graphs(graph, NullModel::SameDegrees, references, seed);
+    let (mut random_clustering, mut random_path_length) = (0.0, 0.0);
+    for (i, random) in randoms.iter().enumerate() {
+        let (c, l) = clustering_and_path(random, path_samples, seed.wrapping_add(i as u64 + 1));
+        random_clustering += c / randoms.len() as f64;
+        random_path_length += l / randoms.len() as f64;
+    }
+
+    let n = graph.node_count();
+    let k = 2 * (graph.edge_count() as f64 / n.max(1) as f64).round() as usize;
+    let lattice_clustering = Graph::from_edges(&watts_strogatz(n, k, 0.0, seed)).transitivity();
+
+    let ratio = |a: f64, b: f64| if b > 0.0 { a / b } else { 0.0 };
+    SmallWorld {
+        clustering,
+        path_length,
+        random_clustering,
+        random_path_length,
+        lattice_clustering,
+        sigma: ratio(ratio(clustering, random_clustering), ratio(path_length, random_path_length)),
+        omega: ratio(random_path_length, path_length) - ratio(clustering, lattice_clustering),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that a lightly rewired ring lattice is recognized as a small world: it keeps most
+    // of the lattice's clustering but has short, random-like paths.
+    #[test]
+    fn test_small_world() {
+        let graph = Graph::from_edges(&watts_strogatz(400, 8, 0.1, 5));
+        let result = small_world(&graph, 3, 400, 5);
+
+        assert!(result.clustering > 5.0 * result.random_clustering);
+        assert!(result.path_length < 2.0 * result.random_path_length);
+        assert!(result.sigma > 2.0);
+        assert!(result.omega.abs() < 0.5);
+        // A ring lattice where each node sees 4 neighbors per side has clustering 3 (k - 2) / (4 (k - 1)) = 9/14
+        assert!((result.lattice_clustering - 9.0 / 14.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,377 @@
+// Includes the `GraphSource` trait, which hides where a graph comes from behind two calls (its
+// weighted edges and its node attributes), with implementations for SNAP edge lists, CSV edge
+// lists, GraphML files and maildir trees. The analysis only ever sees (u, v, weight) edges and
+// a node ID -> (email, folder) map, so any source can feed it.
+
+use crate::datasets::{read_edge_list, EdgeListFormat};
+use crate::graph::{drop_invalid_weights, load_email_mapping};
+use crate::maildir::{ingest_maildir, HeaderWeights, MaildirGraph};
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+// Node ID -> (email or label, folder or group), as from `load_email_mapping`.
+pub type NodeAttributes = HashMap<usize, (String, String)>;
+
+// A dataset the analysis can be run on.
+pub trait GraphSource {
+    // Short description for the log, e.g. "SNAP edge list email-Enron.txt".
+    fn describe(&self) -> String;
+
+    // Weighted edges of the graph, (u, v, weight) by node ID.
+    fn load_edges(&self) -> std::io::Result<Vec<(usize, usize, f64)>>;
+
+    // Node attributes; nodes without any are labelled by ID.
+    fn load_attributes(&self) -> std::io::Result<NodeAttributes>;
+
+    // Whether an edge (u, v) means u -> v.
+    fn directed(&self) -> bool;
+
+    // Statistics worth reporting once the source has been loaded, if any.
+    fn summary(&self) -> Option<String> {
+        None
+    }
+}
+
+// Reads an optional mapping CSV, failing with an error instead of a panic if it is missing.
+fn read_mapping(path: Option<&str>) -> std::io::Result<NodeAttributes> {
+    match path {
+        Some(path) => {
+            File::open(path)?;
+            Ok(load_email_mapping(path))
+        }
+        None => Ok(NodeAttributes::new()),
+    }
+}
+
+// A SNAP-style edge list: whitespace-separated node IDs, '#' comments, optional weights.
+#[derive(Debug, Clone)]
+pub struct SnapSource {
+    pub path: String,
+    pub format: EdgeListFormat,
+    // Mapping CSV (node_id,email,employee_folder) that matches the node IDs, if any
+    pub mapping: Option<String>,
+}
+
+impl GraphSource for SnapSource {
+    fn describe(&self) -> String {
+        format!("SNAP edge list {}", self.path)
+    }
+
+    fn load_edges(&self) -> std::io::Result<Vec<(usize, usize, f64)>> {
+        read_edge_list(Path::new(&self.path), &self.format)
+    }
+
+    fn load_attributes(&self) -> std::io::Result<NodeAttributes> {
+        read_mapping(self.mapping.as_deref())
+    }
+
+    fn directed(&self) -> bool {
+        self.format.directed
+    }
+}
+
+// A CSV edge list with one `source,target[,weight]` row per edge. A header row, or any row
+// whose first two fields are not node IDs, is skipped; so is a row whose weight is not a finite
+// positive number, with a warning.
+#[derive(Debug, Clone)]
+pub struct CsvSource {
+    pub path: String,
+    pub directed: bool,
+    pub mapping: Option<String>,
+}
+
+impl GraphSource for CsvSource {
+    fn describe(&self) -> String {
+        format!("CSV edge list {}", self.path)
+    }
+
+    fn load_edges(&self) -> std::io::Result<Vec<(usize, usize, f64)>> {
+        let mut edges = Vec::new();
+        for line in BufReader::new(File::open(&self.path)?).lines() {
+            let line = line?;
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let weight = fields.get(2).filter(|w| !w.is_empty()).map_or(Some(1.0), |w| w.parse().ok());
+            if let (Some(Ok(u)), Some(Ok(v)), Some(w)) = (fields.first().map(|x| x.parse()), fields.get(1).map(|x| x.parse()), weight) {
+                edges.push((u, v, w));
+            }
+        }
+        Ok(drop_invalid_weights(edges, &self.path))
+    }
+
+    fn load_attributes(&self) -> std::io::Result<NodeAttributes> {
+        read_mapping(self.mapping.as_deref())
+    }
+
+    fn directed(&self) -> bool {
+        self.directed
+    }
+}
+
+// Replaces the five predefined XML entities.
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&")
+}
+
+// Name and attributes of an XML tag such as `edge source="a" target="b"`.
+fn parse_tag(tag: &str) -> (&str, HashMap<&str, String>) {
+    let tag = tag.trim_end_matches('/').trim();
+    let (name, mut rest) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+    let mut attributes = HashMap::new();
+    while let Some((key, after)) = rest.split_once('=') {
+        let after = after.trim_start();
+        let Some(quote) = after.chars().next().filter(|&q| q == '"' || q == '\'') else {
+            break;
+        };
+        let Some(end) = after[1..].find(quote) else {
+            break;
+        };
+        attributes.insert(key.trim(), unescape(&after[1..end + 1]));
+        rest = &after[end + 2..];
+    }
+    (name, attributes)
+}
+
+// Edges and node data of a GraphML document, with node IDs as written in the file.
+#[derive(Debug, Default)]
+struct GraphMlDocument {
+    directed: bool,
+    edges: Vec<(String, String, f64)>,
+    // Node ID -> attribute name -> value
+    nodes: HashMap<String, HashMap<String, String>>,
+}
+
+// Parses the parts of GraphML the analysis uses: the edge default, node and edge elements, and
+// their <data> values, named through the <key> declarations.
+fn parse_graphml(text: &str) -> GraphMlDocument {
+    let mut document = GraphMlDocument::default();
+    let mut keys: HashMap<String, String> = HashMap::new();
+    let (mut node, mut edge): (Option<String>, Option<usize>) = (None, None);
+    for chunk in text.split('<').skip(1) {
+        let Some((tag, content)) = chunk.split_once('>') else {
+            continue;
+        };
+        if tag.starts_with(['?', '!']) {
+            continue;
+        }
+        let closes = tag.ends_with('/');
+        let (name, attributes) = parse_tag(tag);
+        match name {
+            "key" => {
+                if let Some(id) = attributes.get("id") {
+                    let name = attributes.get("attr.name").unwrap_or(id);
+                    keys.insert(id.clone(), name.clone());
+                }
+            }
+            "graph" => document.directed = attributes.get("edgedefault").is_some_and(|d| d == "directed"),
+            "node" => {
+                node = attributes.get("id").cloned();
+                if let Some(id) = &node {
+                    document.nodes.entry(id.clone()).or_default();
+                }
+                if closes {
+                    node = None;
+                }
+            }
+            "edge" => {
+                if let (Some(source), Some(target)) = (attributes.get("source"), attributes.get("target")) {
+                    document.edges.push((source.clone(), target.clone(), 1.0));
+                    edge = (!closes).then_some(document.edges.len() - 1);
+                }
+            }
+            "data" if !closes => {
+                let Some(key) = attributes.get("key") else {
+                    continue;
+                };
+                let key = keys.get(key).unwrap_or(key).to_lowercase();
+                let value = unescape(content.trim());
+                if let Some(e) = edge {
+                    if key == "weight" {
+                        if let Ok(w) = value.parse() {
+                            document.edges[e].2 = w;
+                        }
+                    }
+                } else if let Some(id) = &node {
+                    document.nodes.entry(id.clone()).or_default().insert(key, value);
+                }
+            }
+            "/node" => node = None,
+            "/edge" => edge = None,
+            _ => {}
+        }
+    }
+    document
+}
+
+// A GraphML file. Integer node IDs are kept; other IDs (such as "n12") are numbered in order of
+// first appearance after the largest integer ID. A node's "email", "label" or "name" data
+// becomes its address and its "folder" or "group" data its folder.
+#[derive(Debug)]
+pub struct GraphMlSource {
+    pub path: String,
+    document: OnceCell<GraphMlDocument>,
+}
+
+impl GraphMlSource {
+    pub fn new(path: &str) -> GraphMlSource {
+        GraphMlSource { path: path.to_string(), document: OnceCell::new() }
+    }
+
+    // The parsed file, read on first use.
+    fn document(&self) -> std::io::Result<&GraphMlDocument> {
+        if self.document.get().is_none() {
+            let text = std::fs::read_to_string(&self.path)?;
+            let _ = self.document.set(parse_graphml(&text));
+        }
+        Ok(self.document.get().expect("parsed above"))
+    }
+
+    // Node ID of every node named in the file.
+    fn node_ids(&self) -> std::io::Result<HashMap<String, usize>> {
+        let document = self.document()?;
+        // Names in order of first appearance: edge endpoints, then nodes only declared
+        let mut declared: Vec<&String> = document.nodes.keys().collect();
+        declared.sort();
+        let names: Vec<&String> = document.edges.iter().flat_map(|(u, v, _)| [u, v]).chain(declared).collect();
+        let mut ids: HashMap<String, usize> = names.iter().filter_map(|name| Some(((*name).clone(), name.parse().ok()?))).collect();
+        let mut next = ids.values().max().map_or(0, |&id| id + 1);
+        for name in names {
+            if !ids.contains_key(name) {
+                ids.insert(name.clone(), next);
+                next += 1;
+            }
+        }
+        Ok(ids)
+    }
+}
+
+impl GraphSource for GraphMlSource {
+    fn describe(&self) -> String {
+        format!("GraphML file {}", self.path)
+    }
+
+    fn load_edges(&self) -> std::io::Result<Vec<(usize, usize, f64)>> {
+        let ids = self.node_ids()?;
+        let edges = self.document()?.edges.iter().map(|(u, v, w)| (ids[u], ids[v], *w)).collect();
+        Ok(drop_invalid_weights(edges, &self.path))
+    }
+
+    fn load_attributes(&self) -> std::io::Result<NodeAttributes> {
+        let ids = self.node_ids()?;
+        let mut attributes = NodeAttributes::new();
+        for (name, data) in &self.document()?.nodes {
+            let first = |keys: &[&str]| keys.iter().find_map(|k| data.get(*k)).cloned();
+            if let Some(label) = first(&["email", "label", "name"]) {
+                attributes.insert(ids[name], (label, first(&["folder", "group"]).unwrap_or_default()));
+            }
+        }
+        Ok(attributes)
+    }
+
+    fn directed(&self) -> bool {
+        self.document().is_ok_and(|d| d.directed)
+    }
+}
+
+// A maildir tree, read once by `ingest_maildir`. Addresses of the mapping keep their node IDs
+// and new addresses join the attributes with their mailbox folder.
+#[derive(Debug)]
+pub struct MaildirSource {
+    pub root: String,
+    pub mapping: Option<String>,
+    pub weights: HeaderWeights,
+    mail: OnceCell<MaildirGraph>,
+}
+
+impl MaildirSource {
+    pub fn new(root: &str, mapping: Option<&str>, weights: HeaderWeights) -> MaildirSource {
+        MaildirSource { root: root.to_string(), mapping: mapping.map(str::to_string), weights, mail: OnceCell::new() }
+    }
+
+    // The ingested mailboxes, read on first use.
+    pub fn mail(&self) -> std::io::Result<&MaildirGraph> {
+        if self.mail.get().is_none() {
+            let known = read_mapping(self.mapping.as_deref())?.into_iter().map(|(id, (email, _))| (email.to_lowercase(), id)).collect();
+            let _ = self.mail.set(ingest_maildir(Path::new(&self.root), &known)?);
+        }
+        Ok(self.mail.get().expect("ingested above"))
+    }
+}
+
+impl GraphSource for MaildirSource {
+    fn describe(&self) -> String {
+        format!("maildir {}", self.root)
+    }
+
+    fn load_edges(&self) -> std::io::Result<Vec<(usize, usize, f64)>> {
+        Ok(self.mail()?.weighted_edges(&self.weights))
+    }
+
+    fn load_attributes(&self) -> std::io::Result<NodeAttributes> {
+        let mail = self.mail()?;
+        let mut attributes = read_mapping(self.mapping.as_deref())?;
+        for (address, &id) in &mail.nodes {
+            attributes.entry(id).or_insert_with(|| (address.clone(), mail.folders.get(&id).cloned().unwrap_or_default()));
+        }
+        Ok(attributes)
+    }
+
+    fn directed(&self) -> bool {
+        true
+    }
+
+    fn summary(&self) -> Option<String> {
+        let mail = self.mail.get()?;
+        let totals = mail.totals();
+        Some(format!(
+            "{} messages ({} files skipped), {} directed pairs; recipients in To {}, Cc {}, Bcc {}",
+            mail.messages, mail.skipped, mail.pairs.len(), totals.to, totals.cc, totals.bcc
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that a CSV edge list and a GraphML file describing the same weighted graph load to
+    // the same edges, and that GraphML node data and non-integer IDs are handled.
+    #[test]
+    fn test_sources() {
+        let dir = std::env::temp_dir();
+        let csv = dir.join("project_source_test.csv");
+        std::fs::write(&csv, "source,target,weight\n0,1,2\n1,2,\nbad,row\n2,3,0\n3,4,-1.5\n").unwrap();
+        let csv = CsvSource { path: csv.to_string_lossy().into_owned(), directed: false, mapping: None };
+        assert_eq!(csv.load_edges().unwrap(), vec![(0, 1, 2.0), (1, 2, 1.0)]);
+        assert!(csv.load_attributes().unwrap().is_empty());
+
+        let graphml = dir.join("project_source_test.graphml");
+        std::fs::write(
+            &graphml,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="email" attr.type="string"/>
+  <key id="d1" for="edge" attr.name="weight" attr.type="double"/>
+  <graph id="G" edgedefault="directed">
+    <node id="0"><data key="d0">lay&amp;co@enron.com</data></node>
+    <node id="1"/>
+    <node id="n5"><data key="d0">x@enron.com</data></node>
+    <edge source="0" target="1"><data key="d1">2</data></edge>
+    <edge source="1" target="n5"/>
+  </graph>
+</graphml>"#,
+        )
+        .unwrap();
+        let source = GraphMlSource::new(&graphml.to_string_lossy());
+        assert!(source.directed());
+        assert_eq!(source.load_edges().unwrap(), vec![(0, 1, 2.0), (1, 2, 1.0)]);
+        let attributes = source.load_attributes().unwrap();
+        assert_eq!(attributes[&0].0, "lay&co@enron.com");
+        assert_eq!(attributes[&2].0, "x@enron.com");
+        assert!(!attributes.contains_key(&1));
+        std::fs::remove_file(&graphml).unwrap();
+        std::fs::remove_file(dir.join("project_source_test.csv")).unwrap();
+    }
+}
@@ -0,0 +1,232 @@
+// Includes a dense symmetric eigen-decomposition (Householder tridiagonalization followed by
+// the implicit QL algorithm, as in EISPACK's tred2/tql2) and subgraph centrality (Estrada):
+// the number of closed walks starting and ending at a node, walks of length k weighted by
+// 1 / k!, which is the diagonal of exp(A). It is meant for small graphs such as the top-N
+// subgraph, since the decomposition takes O(n^3) time and O(n^2) memory.
+
+use crate::graph::Graph;
+use std::collections::HashMap;
+
+// Eigenvalues and orthonormal eigenvectors of a symmetric matrix.
+#[derive(Debug, Clone)]
+pub struct Eigen {
+    // Eigenvalues in ascending order
+    pub values: Vec<f64>,
+    // `vectors[j]` is the unit eigenvector of `values[j]`
+    pub vectors: Vec<Vec<f64>>,
+}
+
+// Reduces a symmetric matrix to tridiagonal form by Householder reflections, returning the
+// diagonal, the subdiagonal (in e[1..]) and the accumulated orthogonal transformation.
+fn tridiagonalize(mut v: Vec<Vec<f64>>) -> (Vec<f64>, Vec<f64>, Vec<Vec<f64>>) {
+    let n = v.len();
+    let mut d: Vec<f64> = v[n - 1].clone();
+    let mut e = vec![0.0; n];
+    for i in (1..n).rev() {
+        let scale: f64 = d[..i].iter().map(|x| x.abs()).sum();
+        let mut h = 0.0;
+        if scale == 0.0 {
+            e[i] = d[i - 1];
+            for j in 0..i {
+                d[j] = v[i - 1][j];
+                v[i][j] = 0.0;
+                v[j][i] = 0.0;
+            }
+        } else {
+            for x in d[..i].iter_mut() {
+                *x /= scale;
+                h += *x * *x;
+            }
+            let f = d[i - 1];
+            let g = if f > 0.0 { -h.sqrt() } else { h.sqrt() };
+            e[i] = scale * g;
+            h -= f * g;
+            d[i - 1] = f - g;
+            e[..i].iter_mut().for_each(|x| *x = 0.0);
+            for j in 0..i {
+                let f = d[j];
+                v[j][i] = f;
+                let mut g = e[j] + v[j][j] * f;
+                for k in j + 1..i {
+                    g += v[k][j] * d[k];
+                    e[k] += v[k][j] * f;
+                }
+                e[j] = g;
+            }
+            let mut f = 0.0;
+            for j in 0..i {
+                e[j] /= h;
+                f += e[j] * d[j];
+            }
+            let hh = f / (h + h);
+            for j in 0..i {
+                e[j] -= hh * d[j];
+            }
+            for j in 0..i {
+                let (f, g) = (d[j], e[j]);
+                for k in j..i {
+                    v[k][j] -= f * e[k] + g * d[k];
+                }
+                d[j] = v[i - 1][j];
+                v[i][j] = 0.0;
+            }
+        }
+        d[i] = h;
+    }
+
+    // Accumulate the transformations
+    for i in 0..n - 1 {
+        v[n - 1][i] = v[i][i];
+        v[i][i] = 1.0;
+        let h = d[i + 1];
+        if h != 0.0 {
+            for k in 0..=i {
+                d[k] = v[k][i + 1] / h;
+            }
+            for j in 0..=i {
+                let g: f64 = (0..=i).map(|k| v[k][i + 1] * v[k][j]).sum();
+                for k in 0..=i {
+                    v[k][j] -= g * d[k];
+                }
+            }
+        }
+        for row in v[..=i].iter_mut() {
+            row[i + 1] = 0.0;
+        }
+    }
+    for j in 0..n {
+        d[j] = v[n - 1][j];
+        v[n - 1][j] = 0.0;
+    }
+    v[n - 1][n - 1] = 1.0;
+    e[0] = 0.0;
+    (d, e, v)
+}
+
+// Computes all eigenvalues and eigenvectors of a symmetric matrix.
+// Its inputs are - `matrix`: symmetric n x n matrix, as rows
+// Its outputs are - `Eigen`: eigenvalues in ascending order with their unit eigenvectors
+pub fn symmetric_eigen(matrix: Vec<Vec<f64>>) -> Eigen {
+    let n = matrix.len();
+    if n == 0 {
+        return Eigen { values: Vec::new(), vectors: Vec::new() };
+    }
+    let (mut d, mut e, v) = tridiagonalize(matrix);
+    // Eigenvectors as rows, so each QL rotation updates two contiguous rows
+    let mut z: Vec<Vec<f64>> = (0..n).map(|j| (0..n).map(|k| v[k][j]).collect()).collect();
+
+    // Implicit QL iterations on the tridiagonal matrix
+    for i in 1..n {
+        e[i - 1] = e[i];
+    }
+    e[n - 1] = 0.0;
+    let (mut f, mut tst1) = (0.0f64, 0.0f64);
+    for l in 0..n {
+        tst1 = tst1.max(d[l].abs() + e[l].abs());
+        let mut m = l;
+        while m < n - 1 && e[m].abs() > f64::EPSILON * tst1 {
+            m += 1;
+        }
+        if m > l {
+            loop {
+                let g = d[l];
+                let p = (d[l + 1] - g) / (2.0 * e[l]);
+                let r = if p < 0.0 { -p.hypot(1.0) } else { p.hypot(1.0) };
+                d[l] = e[l] / (p + r);
+                d[l + 1] = e[l] * (p + r);
+                let dl1 = d[l + 1];
+                let h = g - d[l];
+                for x in d[l + 2..].iter_mut() {
+                    *x -= h;
+                }
+                f += h;
+
+                let mut p = d[m];
+                let (mut c, mut c2, mut c3) = (1.0, 1.0, 1.0);
+                let el1 = e[l + 1];
+                let (mut s, mut s2) = (0.0, 0.0);
+                for i in (l..m).rev() {
+                    c3 = c2;
+                    c2 = c;
+                    s2 = s;
+                    let g = c * e[i];
+                    let h = c * p;
+                    let r = p.hypot(e[i]);
+                    e[i + 1] = s * r;
+                    s = e[i] / r;
+                    c = p / r;
+                    p = c * d[i] - s * g;
+                    d[i + 1] = h + s * (c * g + s * d[i]);
+                    let (low, high) = z.split_at_mut(i + 1);
+                    for (a, b) in low[i].iter_mut().zip(high[0].iter_mut()) {
+                        let h = *b;
+                        *b = s * *a + c * h;
+                        *a = c * *a - s * h;
+                    }
+                }
+                p = -s * s2 * c3 * el1 * e[l] / dl1;
+                e[l] = s * p;
+                d[l] = c * p;
+                if e[l].abs() <= f64::EPSILON * tst1 {
+                    break;
+                }
+            }
+        }
+        d[l] += f;
+        e[l] = 0.0;
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| d[a].total_cmp(&d[b]));
+    Eigen { values: order.iter().map(|&j| d[j]).collect(), vectors: order.iter().map(|&j| std::mem::take(&mut z[j])).collect() }
+}
+
+// Computes the subgraph centrality of every node, exp(A)[v][v] = sum over eigenpairs of
+// exp(lambda_j) * u_j(v)^2, from the eigen-decomposition of the adjacency matrix. Hubs of
+// dense, clustered regions score highest, since they close the most short walks.
+// Its inputs are - `graph`: a small graph (e.g. the top-N subgraph)
+// Its outputs are - `HashMap<usize, f64>`: node ID -> subgraph centrality
+pub fn subgraph_centrality(graph: &Graph) -> HashMap<usize, f64> {
+    let n = graph.node_count();
+    let mut adjacency = vec![vec![0.0; n]; n];
+    for (v, row) in adjacency.iter_mut().enumerate() {
+        for (w, weight) in graph.weighted_neighbors(v) {
+            row[w] = weight;
+        }
+    }
+    let eigen = symmetric_eigen(adjacency);
+    (0..n)
+        .map(|v| {
+            let closed_walks = eigen.values.iter().zip(&eigen.vectors).map(|(&lambda, u)| lambda.exp() * u[v] * u[v]).sum();
+            (graph.node_id(v), closed_walks)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests the decomposition on a small dense symmetric matrix (A u = lambda u, unit vectors)
+    // and subgraph centrality on the path 1-2-3, whose eigenvalues are -sqrt(2), 0 and sqrt(2):
+    // the middle node scores cosh(sqrt 2) and the ends (cosh(sqrt 2) + 1) / 2.
+    #[test]
+    fn test_subgraph_centrality() {
+        let matrix = vec![vec![4.0, 1.0, -2.0, 2.0], vec![1.0, 2.0, 0.0, 1.0], vec![-2.0, 0.0, 3.0, -2.0], vec![2.0, 1.0, -2.0, -1.0]];
+        let eigen = symmetric_eigen(matrix.clone());
+        assert!(eigen.values.windows(2).all(|w| w[0] <= w[1]));
+        for (lambda, u) in eigen.values.iter().zip(&eigen.vectors) {
+            assert!((u.iter().map(|x| x * x).sum::<f64>() - 1.0).abs() < 1e-9);
+            for (row, &x) in matrix.iter().zip(u) {
+                let au: f64 = row.iter().zip(u).map(|(a, b)| a * b).sum();
+                assert!((au - lambda * x).abs() < 1e-9);
+            }
+        }
+
+        let path = Graph::from_edges(&[(1, 2), (2, 3)]);
+        let scores = subgraph_centrality(&path);
+        let cosh = 2f64.sqrt().cosh();
+        assert!((scores[&2] - cosh).abs() < 1e-9);
+        assert!((scores[&1] - (cosh + 1.0) / 2.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,147 @@
+// Includes descriptive statistics of a metric over the nodes: mean, median, standard
+// deviation, extremes and percentiles, so a whole distribution can be summarized in one
+// line instead of only its top 10, and the top-k / bottom-k rankings used in the reports.
+
+use crate::inequality::CentralityValue;
+use std::collections::HashMap;
+
+// Summary of the values of a metric map
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    // (percentile in [0, 100], value) in the order they were requested
+    pub percentiles: Vec<(f64, f64)>,
+}
+
+// Computes the p-th percentile of values sorted ascending, interpolating linearly between
+// the two closest ranks (the same convention as numpy's default).
+// Its inputs are - `sorted`: non-empty values sorted ascending - `p`: percentile in [0, 100]
+// Its outputs are - `f64`: the percentile
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f64;
+    let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+}
+
+// Summarizes the values of a metric map. The standard deviation is the population one.
+// Non-finite values (NaN, infinities) are left out, so `count` is the number of finite values.
+// Its inputs are - `metric`: node ID -> value - `percentiles`: percentiles to report, in [0, 100]
+// Its outputs are - `Option<Summary>`: None if no value is finite
+pub fn describe<V: CentralityValue>(metric: &HashMap<usize, V>, percentiles: &[f64]) -> Option<Summary> {
+    if metric.is_empty() {
+        return None;
+    }
+    let mut values: Vec<f64> = metric.values().map(|v| v.as_f64()).filter(|x| x.is_finite()).collect();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    Some(Summary {
+        count: values.len(),
+        mean,
+        median: percentile(&values, 50.0),
+        std_dev: variance.sqrt(),
+        min: values[0],
+        max: values[values.len() - 1],
+        percentiles: percentiles.iter().map(|&p| (p, percentile(&values, p))).collect(),
+    })
+}
+
+// Orders the entries of a metric map and keeps the first k, breaking ties by smaller node ID
+// so the rankings do not depend on hash order. Values are compared by `f64::total_cmp`, a total
+// order, with NaN last whichever way the ranking goes.
+fn ranked<T: CentralityValue>(map: &HashMap<usize, T>, k: usize, descending: bool) -> Vec<(usize, T)> {
+    let key = |value: T| {
+        let x = value.as_f64();
+        (x.is_nan(), if descending { -x } else { x })
+    };
+    let compare = |a: &(usize, T), b: &(usize, T)| {
+        let ((a_nan, a_value), (b_nan, b_value)) = (key(a.1), key(b.1));
+        a_nan.cmp(&b_nan).then(a_value.total_cmp(&b_value)).then(a.0.cmp(&b.0))
+    };
+    let mut entries: Vec<(usize, T)> = map.iter().map(|(&node, &value)| (node, value)).collect();
+    if k == 0 {
+        return Vec::new();
+    }
+    if k < entries.len() {
+        entries.select_nth_unstable_by(k - 1, compare);
+        entries.truncate(k);
+    }
+    entries.sort_by(compare);
+    entries
+}
+
+// Finds the k nodes with the largest values.
+// Its inputs are - `map`: node ID -> value - `k`: number of nodes to keep
+// Its outputs are - `Vec<(usize, T)>`: (node ID, value), largest first
+pub fn top_k<T: CentralityValue>(map: &HashMap<usize, T>, k: usize) -> Vec<(usize, T)> {
+    ranked(map, k, true)
+}
+
+// Finds the k nodes with the smallest values.
+// Its inputs are - `map`: node ID -> value - `k`: number of nodes to keep
+// Its outputs are - `Vec<(usize, T)>`: (node ID, value), smallest first
+pub fn bottom_k<T: CentralityValue>(map: &HashMap<usize, T>, k: usize) -> Vec<(usize, T)> {
+    ranked(map, k, false)
+}
+
+// Ranks every node by a metric, 1 being the largest value, with ties broken as in `top_k`.
+// Its inputs are - `map`: node ID -> value
+// Its outputs are - `HashMap<usize, usize>`: node ID -> rank
+pub fn ranks<T: CentralityValue>(map: &HashMap<usize, T>) -> HashMap<usize, usize> {
+    ranked(map, map.len(), true).into_iter().enumerate().map(|(i, (node, _))| (node, i + 1)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests the summary of 1..=5 (mean 3, population variance 2) and percentile interpolation,
+    // and that non-finite values are left out of a summary.
+    #[test]
+    fn test_describe() {
+        let metric: HashMap<usize, usize> = (1..=5).map(|i| (i, i)).collect();
+        let summary = describe(&metric, &[25.0, 90.0]).unwrap();
+        assert_eq!(summary.count, 5);
+        assert_eq!((summary.mean, summary.median, summary.min, summary.max), (3.0, 3.0, 1.0, 5.0));
+        assert!((summary.std_dev - 2f64.sqrt()).abs() < 1e-12);
+        assert_eq!(summary.percentiles[0], (25.0, 2.0));
+        assert!((summary.percentiles[1].1 - 4.6).abs() < 1e-12);
+        assert!(describe(&HashMap::<usize, f64>::new(), &[50.0]).is_none());
+
+        let with_nan = HashMap::from([(1, 2.0), (2, f64::NAN), (3, 4.0), (4, f64::INFINITY)]);
+        let summary = describe(&with_nan, &[50.0]).unwrap();
+        assert_eq!((summary.count, summary.mean, summary.min, summary.max), (2, 3.0, 2.0, 4.0));
+        assert!(describe(&HashMap::from([(1, f64::NAN)]), &[50.0]).is_none());
+    }
+
+    // Tests that rankings are sorted, truncated to k, and break ties by node ID, with NaN last.
+    #[test]
+    fn test_top_and_bottom_k() {
+        let metric: HashMap<usize, f64> = vec![(5, 0.2), (1, 0.9), (3, 0.2), (2, 0.1), (4, 0.9)].into_iter().collect();
+        assert_eq!(top_k(&metric, 3), vec![(1, 0.9), (4, 0.9), (3, 0.2)]);
+        assert_eq!(bottom_k(&metric, 2), vec![(2, 0.1), (3, 0.2)]);
+        assert_eq!(top_k(&metric, 10).len(), 5);
+        assert!(bottom_k(&metric, 0).is_empty());
+        assert_eq!((ranks(&metric)[&4], ranks(&metric)[&2]), (2, 5));
+
+        // NaN ranks last both ways, behind infinities, and ties among NaN still go by node ID
+        let mut with_nan: HashMap<usize, f64> = (0..50).map(|i| (i, if i % 3 == 0 { f64::NAN } else { i as f64 })).collect();
+        with_nan.insert(100, f64::INFINITY);
+        with_nan.insert(101, f64::NEG_INFINITY);
+        let nodes = |ranking: Vec<(usize, f64)>| ranking.into_iter().map(|(node, _)| node).collect::<Vec<_>>();
+        assert_eq!(nodes(top_k(&with_nan, 3)), vec![100, 49, 47]);
+        assert_eq!(nodes(bottom_k(&with_nan, 3)), vec![101, 1, 2]);
+        let all = nodes(top_k(&with_nan, with_nan.len()));
+        assert_eq!(all[all.len() - 17..], (0..50).step_by(3).collect::<Vec<_>>()[..]);
+        assert_eq!(nodes(bottom_k(&with_nan, 52))[35..], all[35..]);
+    }
+}
@@ -0,0 +1,319 @@
+// Includes an online analysis of an edge stream, for edge lists too large to hold in memory or
+// still being written. Edges are consumed one at a time and only compact summaries are kept:
+// a Bloom filter of the edges seen (to skip duplicates such as the reverse direction), exact
+// per-node degrees in a log-binned degree histogram, a union-find for the component count, and
+// a fixed-size reservoir of edges from which triangles are estimated (TRIÈST-impr, De Stefani
+// et al.). Memory grows with the number of nodes, not with the number of edges.
+
+use crate::unionfind::UnionFind;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+use std::time::Duration;
+
+// Smallest triangle-counting reservoir: the estimate scales by M (M - 1), which must not be 0.
+pub const MIN_RESERVOIR: usize = 2;
+
+// Settings of the streaming statistics.
+#[derive(Debug, Clone)]
+pub struct StreamConfig {
+    // Number of edges kept in the triangle-counting reservoir, at least `MIN_RESERVOIR`
+    pub reservoir: usize,
+    // Size of the duplicate-edge Bloom filter in bits; a false positive drops a new edge
+    pub bloom_bits: usize,
+    pub seed: u64,
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        StreamConfig { reservoir: 100_000, bloom_bits: 1 << 24, seed: 42 }
+    }
+}
+
+// Hash functions of the Bloom filter
+const BLOOM_HASHES: u64 = 4;
+
+// Mixes a 64-bit value (SplitMix64 finalizer), used to hash edges.
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+// Set of undirected edges with no false negatives and a small false positive rate.
+struct BloomFilter {
+    bits: Vec<u64>,
+}
+
+impl BloomFilter {
+    fn new(bits: usize) -> BloomFilter {
+        BloomFilter { bits: vec![0; bits.div_ceil(64).max(1)] }
+    }
+
+    // Inserts an edge, returning false if it (probably) was already present.
+    fn insert(&mut self, u: usize, v: usize) -> bool {
+        let (a, b) = (u.min(v) as u64, u.max(v) as u64);
+        let h1 = mix(a.wrapping_mul(0x9e3779b97f4a7c15) ^ b);
+        let h2 = mix(h1 ^ b.rotate_left(32)) | 1;
+        let m = self.bits.len() as u64 * 64;
+        let mut new = false;
+        for i in 0..BLOOM_HASHES {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % m;
+            let (word, mask) = ((bit / 64) as usize, 1u64 << (bit % 64));
+            new |= self.bits[word] & mask == 0;
+            self.bits[word] |= mask;
+        }
+        new
+    }
+}
+
+// Snapshot of the running statistics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamSummary {
+    // Lines read, including duplicates and self loops
+    pub edges_read: usize,
+    // Distinct edges kept
+    pub edges: usize,
+    pub nodes: usize,
+    pub components: usize,
+    pub largest_component: usize,
+    pub max_degree: usize,
+    // Estimated number of triangles (exact while every edge fits in the reservoir)
+    pub triangles: f64,
+    // `degree_bins[i]` counts the nodes with degree in [2^i, 2^(i + 1))
+    pub degree_bins: Vec<usize>,
+}
+
+// Running statistics of an undirected edge stream.
+pub struct StreamStats {
+    config: StreamConfig,
+    rng: StdRng,
+    seen: BloomFilter,
+    index: HashMap<usize, usize>,
+    degree: Vec<usize>,
+    degree_bins: Vec<usize>,
+    components: UnionFind,
+    largest_component: usize,
+    edges_read: usize,
+    edges: usize,
+    // Reservoir of edges and the adjacency of the sampled graph
+    sample: Vec<(usize, usize)>,
+    sample_adj: HashMap<usize, HashSet<usize>>,
+    triangles: f64,
+}
+
+// Histogram bin of a positive degree.
+fn degree_bin(degree: usize) -> usize {
+    degree.ilog2() as usize
+}
+
+impl StreamStats {
+    // Starts empty statistics; a reservoir smaller than `MIN_RESERVOIR` is raised to it.
+    pub fn new(mut config: StreamConfig) -> StreamStats {
+        config.reservoir = config.reservoir.max(MIN_RESERVOIR);
+        StreamStats {
+            rng: StdRng::seed_from_u64(config.seed),
+            seen: BloomFilter::new(config.bloom_bits),
+            config,
+            index: HashMap::new(),
+            degree: Vec::new(),
+            degree_bins: Vec::new(),
+            components: UnionFind::new(0),
+            largest_component: 0,
+            edges_read: 0,
+            edges: 0,
+            sample: Vec::new(),
+            sample_adj: HashMap::new(),
+            triangles: 0.0,
+        }
+    }
+
+    // Compact index of a node ID, adding the node if it is new.
+    fn intern(&mut self, id: usize) -> usize {
+        let next = self.index.len();
+        let i = *self.index.entry(id).or_insert(next);
+        if i == next {
+            self.degree.push(0);
+            self.components.push();
+        }
+        i
+    }
+
+    // Consumes one edge, updating every statistic in O(1) expected time plus the triangle
+    // count, which intersects the endpoints' neighborhoods in the reservoir.
+    // Its inputs are - `u`, `v`: node IDs of the endpoints
+    // Its outputs are - `bool`: false if the edge was a self loop or (probably) a duplicate
+    pub fn add_edge(&mut self, u: usize, v: usize) -> bool {
+        self.edges_read += 1;
+        if u == v || !self.seen.insert(u, v) {
+            return false;
+        }
+        self.edges += 1;
+        let (a, b) = (self.intern(u), self.intern(v));
+        for x in [a, b] {
+            if self.degree[x] > 0 {
+                self.degree_bins[degree_bin(self.degree[x])] -= 1;
+            }
+            self.degree[x] += 1;
+            let bin = degree_bin(self.degree[x]);
+            if bin >= self.degree_bins.len() {
+                self.degree_bins.resize(bin + 1, 0);
+            }
+            self.degree_bins[bin] += 1;
+        }
+        self.components.union(a, b);
+        self.largest_component = self.largest_component.max(self.components.set_size(a));
+        self.count_triangles(u, v);
+        true
+    }
+
+    // TRIÈST-impr: every sampled wedge closed by the new edge counts as a triangle, weighted
+    // by the inverse probability that both of its other edges are in the reservoir; then the
+    // edge enters the reservoir with probability M / t, evicting a random edge.
+    fn count_triangles(&mut self, u: usize, v: usize) {
+        let (t, m) = (self.edges as f64, self.config.reservoir as f64);
+        let weight = ((t - 1.0) * (t - 2.0) / (m * (m - 1.0))).max(1.0);
+        if let (Some(nu), Some(nv)) = (self.sample_adj.get(&u), self.sample_adj.get(&v)) {
+            let (small, large) = if nu.len() <= nv.len() { (nu, nv) } else { (nv, nu) };
+            self.triangles += weight * small.iter().filter(|w| large.contains(w)).count() as f64;
+        }
+
+        if self.sample.len() < self.config.reservoir {
+            self.sample.push((u, v));
+        } else if self.rng.gen_bool(m / t) {
+            let slot = self.rng.gen_range(0..self.sample.len());
+            let (x, y) = std::mem::replace(&mut self.sample[slot], (u, v));
+            for (p, q) in [(x, y), (y, x)] {
+                if let Some(nbrs) = self.sample_adj.get_mut(&p) {
+                    nbrs.remove(&q);
+                    if nbrs.is_empty() {
+                        self.sample_adj.remove(&p);
+                    }
+                }
+            }
+        } else {
+            return;
+        }
+        self.sample_adj.entry(u).or_default().insert(v);
+        self.sample_adj.entry(v).or_default().insert(u);
+    }
+
+    // Whether two node IDs have been connected by the edges seen so far, in O(α(n)) amortized time.
+    pub fn same_component(&mut self, u: usize, v: usize) -> bool {
+        match (self.index.get(&u), self.index.get(&v)) {
+            (Some(&a), Some(&b)) => self.components.same_set(a, b),
+            _ => u == v,
+        }
+    }
+
+    // Number of nodes in the component containing a node ID, if the node has been seen.
+    pub fn component_size(&mut self, id: usize) -> Option<usize> {
+        let &v = self.index.get(&id)?;
+        Some(self.components.set_size(v))
+    }
+
+    // Consumes every edge of a reader in the edge-list format (tab or space separated node
+    // IDs; lines starting with '#' and malformed lines are skipped), calling `report` after
+    // every `report_every` lines read.
+    // Its inputs are - `reader`: the edge list - `report_every`: lines between reports (0 for none)
+    // - `report`: called with the current summary
+    pub fn consume<R: BufRead>(&mut self, reader: R, report_every: usize, mut report: impl FnMut(&StreamSummary)) {
+        for line in reader.lines().map_while(Result::ok) {
+            if let Some((u, v)) = parse_edge(&line) {
+                self.add_edge(u, v);
+                if report_every > 0 && self.edges_read.is_multiple_of(report_every) {
+                    report(&self.summary());
+                }
+            }
+        }
+    }
+
+    // Current values of the running statistics.
+    pub fn summary(&self) -> StreamSummary {
+        StreamSummary {
+            edges_read: self.edges_read,
+            edges: self.edges,
+            nodes: self.index.len(),
+            components: self.components.set_count(),
+            largest_component: self.largest_component,
+            max_degree: self.degree.iter().copied().max().unwrap_or(0),
+            triangles: self.triangles,
+            degree_bins: self.degree_bins.clone(),
+        }
+    }
+}
+
+// Parses one edge-list line, or None for comments and malformed lines.
+fn parse_edge(line: &str) -> Option<(usize, usize)> {
+    if line.starts_with('#') {
+        return None;
+    }
+    let mut fields = line.split_whitespace();
+    Some((fields.next()?.parse().ok()?, fields.next()?.parse().ok()?))
+}
+
+// Reader of a file that is still being appended to: at the end of the file it waits for more
+// data instead of reporting the end, like `tail -f`, so a partial last line is completed later.
+pub struct FollowReader<R: BufRead> {
+    reader: R,
+    poll: Duration,
+}
+
+impl<R: BufRead> FollowReader<R> {
+    pub fn new(reader: R, poll: Duration) -> FollowReader<R> {
+        FollowReader { reader, poll }
+    }
+}
+
+impl<R: BufRead> std::io::Read for FollowReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            let n = self.reader.read(buf)?;
+            if n > 0 || buf.is_empty() {
+                return Ok(n);
+            }
+            std::thread::sleep(self.poll);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests the streaming statistics on two triangles joined by an edge, with each edge listed
+    // in both directions: duplicates are skipped, the triangle count is exact while the
+    // reservoir holds every edge, and the degree histogram and components are correct; the
+    // estimate of a small reservoir is close, and that of a too small one finite.
+    #[test]
+    fn test_stream_stats() {
+        let text = "# comment\n1\t2\n2\t1\n2 3\n3\t1\n1\t3\n3\t4\n4\t5\n5\t6\n6\t4\n7\t8\n8\t8\n";
+        let mut stats = StreamStats::new(StreamConfig::default());
+        let mut reports = 0;
+        stats.consume(text.as_bytes(), 5, |_| reports += 1);
+        let summary = stats.summary();
+        assert_eq!(reports, 2);
+        assert_eq!((summary.edges_read, summary.edges, summary.nodes), (11, 8, 8));
+        assert_eq!((summary.components, summary.largest_component, summary.max_degree), (2, 6, 3));
+        assert_eq!(summary.triangles, 2.0);
+        // Degrees 2, 2, 3, 3, 2, 2, 1, 1
+        assert_eq!(summary.degree_bins, vec![2, 6]);
+        assert!(stats.same_component(1, 6) && !stats.same_component(6, 7));
+        assert_eq!((stats.component_size(8), stats.component_size(9)), (Some(2), None));
+
+        // With a tiny reservoir the estimate stays near the truth on a larger graph
+        let mut stats = StreamStats::new(StreamConfig { reservoir: 400, ..StreamConfig::default() });
+        let clique: Vec<String> = (0..40).flat_map(|i| (i + 1..40).map(move |j| format!("{} {}\n", i, j))).collect();
+        stats.consume(clique.concat().as_bytes(), 0, |_| {});
+        let exact = 40.0 * 39.0 * 38.0 / 6.0;
+        assert!((stats.summary().triangles / exact - 1.0).abs() < 0.25);
+
+        // A reservoir too small to hold a wedge is raised to the minimum instead of giving NaN
+        for reservoir in [0, 1] {
+            let mut stats = StreamStats::new(StreamConfig { reservoir, ..StreamConfig::default() });
+            stats.consume(clique.concat().as_bytes(), 0, |_| {});
+            assert!(stats.summary().triangles.is_finite());
+        }
+    }
+}
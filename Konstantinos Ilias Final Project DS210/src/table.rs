@@ -0,0 +1,183 @@
+// Includes the tables printed to the console: rankings and other listings are laid out in
+// aligned columns under a header, with long cells such as email addresses cut short and, on a
+// terminal, a bold header. The plain style prints the same rows tab-separated with nothing cut
+// or colored, so the output can be read by other programs.
+
+use std::collections::HashMap;
+use std::io::IsTerminal;
+
+// Alignment of a column: text to the left, numbers to the right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+// How tables are printed.
+#[derive(Debug, Clone, Copy)]
+pub struct TableStyle {
+    // Tab-separated rows under a tab-separated header, nothing truncated or colored
+    pub plain: bool,
+    // Bold header and dimmed rule, with ANSI escape codes
+    pub color: bool,
+    // Longest cell in characters; longer ones are cut short with '…'
+    pub max_width: usize,
+}
+
+impl Default for TableStyle {
+    fn default() -> Self {
+        TableStyle { plain: false, color: false, max_width: 32 }
+    }
+}
+
+impl TableStyle {
+    // The plain style, or aligned columns colored only when stdout is a terminal and
+    // `$NO_COLOR` is not set.
+    pub fn for_stdout(plain: bool) -> TableStyle {
+        let color = !plain && std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        TableStyle { plain, color, ..TableStyle::default() }
+    }
+}
+
+// Cuts `text` to at most `max` characters, ending it with '…' if anything was cut.
+pub fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+// A table of text cells under a header.
+#[derive(Debug, Clone)]
+pub struct Table {
+    columns: Vec<(String, Align)>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    // An empty table with the given column headers and alignments.
+    pub fn new(columns: &[(&str, Align)]) -> Table {
+        Table { columns: columns.iter().map(|&(header, align)| (header.to_string(), align)).collect(), rows: Vec::new() }
+    }
+
+    // Adds a row; missing cells are left empty and extra cells are dropped.
+    pub fn row(&mut self, mut cells: Vec<String>) {
+        cells.resize(self.columns.len(), String::new());
+        self.rows.push(cells);
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    // Renders the table, one line per row after the header (and the rule under it).
+    // Its inputs are - `style`: plain or aligned, colored, and the widest cell
+    // Its outputs are - `String`: the lines of the table, each ending in a newline
+    pub fn render(&self, style: &TableStyle) -> String {
+        if style.plain {
+            let line = |cells: Vec<&str>| cells.iter().map(|c| c.replace(['\t', '\n'], " ")).collect::<Vec<_>>().join("\t") + "\n";
+            let mut out = line(self.columns.iter().map(|(header, _)| header.as_str()).collect());
+            for row in &self.rows {
+                out += &line(row.iter().map(String::as_str).collect());
+            }
+            return out;
+        }
+
+        let header: Vec<String> = self.columns.iter().map(|(header, _)| truncate(header, style.max_width)).collect();
+        let rows: Vec<Vec<String>> = self.rows.iter().map(|row| row.iter().map(|c| truncate(c, style.max_width)).collect()).collect();
+        let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let line = |cells: &[String]| {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .zip(&self.columns)
+                .map(|((cell, &width), (_, align))| match align {
+                    Align::Left => format!("{:<width$}", cell),
+                    Align::Right => format!("{:>width$}", cell),
+                })
+                .collect();
+            padded.join("  ").trim_end().to_string()
+        };
+        let rule = widths.iter().map(|&w| "─".repeat(w)).collect::<Vec<_>>().join("  ");
+        let mut out = if style.color {
+            format!("\x1b[1m{}\x1b[0m\n\x1b[2m{}\x1b[0m\n", line(&header), rule)
+        } else {
+            format!("{}\n{}\n", line(&header), rule)
+        };
+        for row in &rows {
+            out += &line(row);
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn print(&self, style: &TableStyle) {
+        print!("{}", self.render(style));
+    }
+}
+
+// A ranking of nodes as a table of rank, node, email and folder followed by the value columns.
+// Nodes without an email are left out, keeping the ranks of the others.
+// Its inputs are - `ranking`: (node ID, value) from the highest rank down - `email_map`: node
+// ID -> (email, folder) - `columns`: headers of the value columns - `values`: the cells of a value
+// Its outputs are - `Table`: the ranking
+pub fn ranking<T>(
+    ranking: &[(usize, T)],
+    email_map: &HashMap<usize, (String, String)>,
+    columns: &[&str],
+    values: impl Fn(&T) -> Vec<String>,
+) -> Table {
+    let mut headers = vec![("#", Align::Right), ("Node", Align::Right), ("Email", Align::Left), ("Folder", Align::Left)];
+    headers.extend(columns.iter().map(|&column| (column, Align::Right)));
+    let mut table = Table::new(&headers);
+    for (i, (node, value)) in ranking.iter().enumerate() {
+        if let Some((email, folder)) = email_map.get(node) {
+            let mut cells = vec![(i + 1).to_string(), node.to_string(), email.clone(), folder.clone()];
+            cells.extend(values(value));
+            table.row(cells);
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that columns are aligned with long cells cut short, that nodes without an email are
+    // left out of a ranking, and that the plain style prints every cell in full, tab-separated.
+    #[test]
+    fn test_table() {
+        let email_map = HashMap::from([
+            (7, ("kenneth.lay@enron.com".to_string(), "lay-k".to_string())),
+            (12, ("jeff@enron.com".to_string(), "dasovich-j".to_string())),
+        ]);
+        let table = ranking(&[(12, 1383), (3, 900), (7, 95)], &email_map, &["Degree"], |d| vec![d.to_string()]);
+        assert_eq!(table.len(), 2);
+
+        let style = TableStyle { max_width: 12, ..TableStyle::default() };
+        let rendered = table.render(&style);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "#  Node  Email         Folder      Degree");
+        assert_eq!(lines[1], "─  ────  ────────────  ──────────  ──────");
+        assert_eq!(lines[2], "1    12  jeff@enron.…  dasovich-j    1383");
+        assert_eq!(lines[3], "3     7  kenneth.lay…  lay-k           95");
+
+        let plain = table.render(&TableStyle { plain: true, ..style });
+        assert_eq!(plain, "#\tNode\tEmail\tFolder\tDegree\n1\t12\tjeff@enron.com\tdasovich-j\t1383\n3\t7\tkenneth.lay@enron.com\tlay-k\t95\n");
+        let colored = table.render(&TableStyle { color: true, ..style });
+        assert!(colored.starts_with("\x1b[1m#  Node"));
+        assert_eq!(truncate("abc", 3), "abc");
+    }
+}
@@ -0,0 +1,313 @@
+// Includes support for timestamped edge lists, such as the dated Enron corpus where every
+// email is one (sender, receiver, time) line: a loader, date parsing without external crates,
+// and `TemporalGraph`, which keeps the edges sorted by time so any time range can be cut out
+// as an ordinary `Graph` and every existing metric run on that slice. Sliding monthly windows
+// track how degree, betweenness and community membership of each person change over time.
+
+use crate::cluster::label_propagation;
+use crate::graph::{brandes_betweenness, BetweennessNormalization, Graph, NodeOrder};
+use crate::stats::top_k;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+// One timestamped edge; `time` is in seconds since 1970-01-01 UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemporalEdge {
+    pub u: usize,
+    pub v: usize,
+    pub time: i64,
+}
+
+// Days since 1970-01-01 of a proleptic Gregorian date (Howard Hinnant's days_from_civil).
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+// Date (year, month, day) of a number of days since 1970-01-01, the inverse of `days_from_civil`.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (if month <= 2 { yoe + era * 400 + 1 } else { yoe + era * 400 }, month, day)
+}
+
+// Parses a timestamp given either as Unix seconds or as a UTC date `YYYY-MM-DD`, optionally
+// followed by a time `HH:MM[:SS]` after a space or `T`.
+// Its inputs are - `text`: the timestamp
+// Its outputs are - `Option<i64>`: seconds since 1970-01-01 UTC, or None if it does not parse
+pub fn parse_time(text: &str) -> Option<i64> {
+    let text = text.trim();
+    if let Ok(seconds) = text.parse() {
+        return Some(seconds);
+    }
+    let (date, clock) = match text.find([' ', 'T']) {
+        Some(i) => (&text[..i], Some(&text[i + 1..])),
+        None => (text, None),
+    };
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut seconds = days_from_civil(year, month, day) * SECONDS_PER_DAY;
+    if let Some(clock) = clock {
+        let fields: Vec<i64> = clock.split(':').map(|f| f.parse().ok()).collect::<Option<_>>()?;
+        let scale = [3600, 60, 1];
+        if fields.is_empty() || fields.len() > 3 {
+            return None;
+        }
+        seconds += fields.iter().zip(scale).map(|(f, s)| f * s).sum::<i64>();
+    }
+    Some(seconds)
+}
+
+// Formats seconds since 1970-01-01 UTC as a `YYYY-MM-DD` date.
+pub fn format_date(time: i64) -> String {
+    let (year, month, day) = civil_from_days(time.div_euclid(SECONDS_PER_DAY));
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Reads a timestamped edge list: tab-separated `u v time` lines (see `parse_time` for the
+// time formats). Lines starting with '#' and lines that do not parse are skipped.
+// Its inputs are - `path`: path to the edge list file
+// Its outputs are - `Vec<TemporalEdge>`: the edges in file order
+pub fn read_temporal_file(path: &str) -> Vec<TemporalEdge> {
+    let file = File::open(path).expect("Could not open file");
+    let mut result = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.expect("Error reading");
+        if line.starts_with('#') {
+            continue;
+        }
+        let v: Vec<&str> = line.trim().split('\t').collect();
+        if v.len() == 3 {
+            if let (Ok(u), Ok(w), Some(time)) = (v[0].parse(), v[1].parse(), parse_time(v[2])) {
+                result.push(TemporalEdge { u, v: w, time });
+            }
+        }
+    }
+    result
+}
+
+// Timestamped edges kept sorted by time, so a time range is found by binary search.
+#[derive(Debug, Clone)]
+pub struct TemporalGraph {
+    edges: Vec<TemporalEdge>,
+}
+
+impl TemporalGraph {
+    // Builds the temporal graph from edges in any order.
+    pub fn new(mut edges: Vec<TemporalEdge>) -> TemporalGraph {
+        edges.sort_by_key(|e| e.time);
+        TemporalGraph { edges }
+    }
+
+    // Loads a timestamped edge list, as in `read_temporal_file`.
+    pub fn from_file(path: &str) -> TemporalGraph {
+        TemporalGraph::new(read_temporal_file(path))
+    }
+
+    // All edges, sorted by time.
+    pub fn edges(&self) -> &[TemporalEdge] {
+        &self.edges
+    }
+
+    // First and last timestamps, or None if there are no edges.
+    pub fn time_range(&self) -> Option<(i64, i64)> {
+        Some((self.edges.first()?.time, self.edges.last()?.time))
+    }
+
+    // Edges with start <= time < end.
+    // Its inputs are - `start`, `end`: bounds in seconds since 1970-01-01 UTC
+    // Its outputs are - `&[TemporalEdge]`: the edges of the range, sorted by time
+    pub fn between(&self, start: i64, end: i64) -> &[TemporalEdge] {
+        let from = self.edges.partition_point(|e| e.time < start);
+        let to = self.edges.partition_point(|e| e.time < end).max(from);
+        &self.edges[from..to]
+    }
+
+    // The edges of a time range as a plain edge list, for `compute_degree` and the like.
+    pub fn slice(&self, start: i64, end: i64) -> Vec<(usize, usize)> {
+        self.between(start, end).iter().map(|e| (e.u, e.v)).collect()
+    }
+
+    // The edges of a time range with the number of emails between each pair as its weight.
+    // Its inputs are - `start`, `end`: bounds in seconds since 1970-01-01 UTC
+    // Its outputs are - `Vec<(usize, usize, f64)>`: one (u, v, count) per unordered pair, u < v
+    pub fn weighted_slice(&self, start: i64, end: i64) -> Vec<(usize, usize, f64)> {
+        let mut counts: HashMap<(usize, usize), f64> = HashMap::new();
+        for e in self.between(start, end).iter().filter(|e| e.u != e.v) {
+            *counts.entry((e.u.min(e.v), e.u.max(e.v))).or_insert(0.0) += 1.0;
+        }
+        let mut edges: Vec<(usize, usize, f64)> = counts.into_iter().map(|((u, v), w)| (u, v, w)).collect();
+        edges.sort_by_key(|&(u, v, _)| (u, v));
+        edges
+    }
+
+    // The graph of a time range, on which every existing metric can be run.
+    pub fn graph_between(&self, start: i64, end: i64) -> Graph {
+        Graph::from_edges(&self.slice(start, end))
+    }
+}
+
+// Start of the month containing a time, in seconds since 1970-01-01 UTC.
+pub fn month_start(time: i64) -> i64 {
+    let (year, month, _) = civil_from_days(time.div_euclid(SECONDS_PER_DAY));
+    days_from_civil(year, month, 1) * SECONDS_PER_DAY
+}
+
+// Start of the month `months` calendar months after the month containing `time`.
+pub fn add_months(time: i64, months: i64) -> i64 {
+    let (year, month, _) = civil_from_days(time.div_euclid(SECONDS_PER_DAY));
+    let index = year * 12 + month as i64 - 1 + months;
+    days_from_civil(index.div_euclid(12), (index.rem_euclid(12) + 1) as u32, 1) * SECONDS_PER_DAY
+}
+
+// Metrics of every node active in one time window [start, end).
+#[derive(Debug, Clone)]
+pub struct WindowMetrics {
+    pub start: i64,
+    pub end: i64,
+    pub edges: usize,
+    pub degree: HashMap<usize, usize>,
+    // Betweenness from the `sources` highest-degree nodes of the window, normalized by pairs
+    // so windows of different sizes are comparable
+    pub betweenness: HashMap<usize, f64>,
+    // Label-propagation community, 0 being the largest of the window
+    pub community: HashMap<usize, usize>,
+}
+
+// Computes degree, betweenness and community membership in sliding windows of
+// `window_months` calendar months, advancing one month at a time from the first email to the
+// last. Pairs are weighted by their number of emails in the window, so betweenness follows
+// the busiest channels. Windows are processed in parallel.
+// Its inputs are - `temporal`: the timestamped edges - `window_months`: window length in months
+// - `sources`: betweenness sources per window (highest degree first)
+// Its outputs are - `Vec<WindowMetrics>`: one entry per window, in time order
+pub fn monthly_windows(temporal: &TemporalGraph, window_months: i64, sources: usize) -> Vec<WindowMetrics> {
+    let Some((first, last)) = temporal.time_range() else {
+        return Vec::new();
+    };
+    let window_months = window_months.max(1);
+    let mut starts = vec![month_start(first)];
+    while add_months(*starts.last().unwrap(), window_months) <= last {
+        starts.push(add_months(*starts.last().unwrap(), 1));
+    }
+    starts
+        .par_iter()
+        .map(|&start| {
+            let end = add_months(start, window_months);
+            let weighted = temporal.weighted_slice(start, end);
+            let graph = Graph::from_weighted_edges(&weighted).reordered(NodeOrder::Bfs);
+            let degree: HashMap<usize, usize> = (0..graph.node_count()).map(|v| (graph.node_id(v), graph.degree(v))).collect();
+            let top: HashSet<usize> = top_k(&degree, sources).into_iter().map(|(node, _)| node).collect();
+            WindowMetrics {
+                start,
+                end,
+                edges: graph.edge_count(),
+                betweenness: brandes_betweenness(&graph, &top, BetweennessNormalization::Pairs),
+                community: label_propagation(&graph, 100, 42),
+                degree,
+            }
+        })
+        .collect()
+}
+
+// Writes the window metrics as a per-node time series in long format, one row per node and
+// window it was active in: window,node,degree,betweenness,community.
+// Its inputs are - `path`: output CSV path - `windows`: result of `monthly_windows`
+// Its outputs are - `std::io::Result<()>`: Ok when the file was written
+pub fn write_time_series_csv(path: impl AsRef<std::path::Path>, windows: &[WindowMetrics]) -> std::io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "window,node,degree,betweenness,community")?;
+    for window in windows {
+        let mut nodes: Vec<usize> = window.degree.keys().copied().collect();
+        nodes.sort_unstable();
+        for node in nodes {
+            writeln!(
+                out,
+                "{},{},{},{:.6},{}",
+                format_date(window.start),
+                node,
+                window.degree[&node],
+                window.betweenness.get(&node).copied().unwrap_or(0.0),
+                window.community.get(&node).copied().unwrap_or(0)
+            )?;
+        }
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests date parsing and formatting, and the time slices of a small dated edge list.
+    #[test]
+    fn test_temporal_graph() {
+        assert_eq!(parse_time("1970-01-02"), Some(86_400));
+        assert_eq!(parse_time("2001-12-02T10:30"), Some(1_007_289_000));
+        assert_eq!(parse_time("2001-12-02 10:30:15"), Some(1_007_289_015));
+        assert_eq!(parse_time("42"), Some(42));
+        assert_eq!(parse_time("2001-13-01"), None);
+        assert_eq!(format_date(1_007_289_000), "2001-12-02");
+        assert_eq!(civil_from_days(days_from_civil(2000, 2, 29)), (2000, 2, 29));
+
+        let path = std::env::temp_dir().join("project_temporal_edges.txt");
+        std::fs::write(&path, "# u v time\n1\t2\t2001-03-01\n2\t3\t2001-01-15\n2\t1\t2001-03-20\n3\t4\tnever\n").unwrap();
+        let temporal = TemporalGraph::from_file(path.to_str().unwrap());
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(temporal.edges().len(), 3);
+        assert_eq!(temporal.time_range().map(|(a, _)| format_date(a)), Some("2001-01-15".to_string()));
+
+        let (march, april) = (parse_time("2001-03-01").unwrap(), parse_time("2001-04-01").unwrap());
+        assert_eq!(temporal.slice(march, april), vec![(1, 2), (2, 1)]);
+        assert_eq!(temporal.weighted_slice(march, april), vec![(1, 2, 2.0)]);
+        assert_eq!(temporal.graph_between(march, april).node_count(), 2);
+        assert!(temporal.between(april, march).is_empty());
+    }
+
+    // Tests month arithmetic and two-month windows over emails in January to March 2001:
+    // the windows start in January and February, and the CSV has one row per active node.
+    #[test]
+    fn test_monthly_windows() {
+        let jan = parse_time("2001-01-20").unwrap();
+        assert_eq!(format_date(month_start(jan)), "2001-01-01");
+        assert_eq!(format_date(add_months(jan, 13)), "2002-02-01");
+        assert_eq!(format_date(add_months(jan, -1)), "2000-12-01");
+
+        let dated = [(1, 2, "2001-01-05"), (2, 3, "2001-01-25"), (3, 4, "2001-02-10"), (4, 5, "2001-03-03")];
+        let temporal = TemporalGraph::new(dated.iter().map(|&(u, v, t)| TemporalEdge { u, v, time: parse_time(t).unwrap() }).collect());
+        let windows = monthly_windows(&temporal, 2, 10);
+        let starts: Vec<String> = windows.iter().map(|w| format_date(w.start)).collect();
+        assert_eq!(starts, vec!["2001-01-01", "2001-02-01"]);
+        // January-February is the path 1-2-3-4, whose middle nodes carry 2 of the 3 outer pairs
+        assert_eq!(windows[0].edges, 3);
+        assert!((windows[0].betweenness[&2] - 2.0 / 3.0).abs() < 1e-12);
+        assert_eq!(windows[1].degree.len(), 3);
+
+        let path = std::env::temp_dir().join("project_time_series.csv");
+        write_time_series_csv(path.to_str().unwrap(), &windows).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(csv.lines().count(), 1 + 4 + 3);
+        assert!(csv.lines().nth(1).unwrap().starts_with("2001-01-01,1,1,0.000000,"));
+    }
+}
@@ -0,0 +1,89 @@
+// Includes text versions of the plots for terminals without an image viewer: unicode
+// sparklines for distributions and horizontal bar charts built from block characters.
+
+use crate::plot::{bin_values, HistogramOptions};
+
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+// Draws values as a one-line sparkline scaled to the largest value. Zeros are drawn as
+// spaces, so empty bins stay visible as gaps.
+// Its inputs are - `values`: non-negative values, one character each
+// Its outputs are - `String`: the sparkline
+pub fn sparkline(values: &[f64]) -> String {
+    let max = values.iter().copied().fold(0.0, f64::max);
+    values
+        .iter()
+        .map(|&v| {
+            if v <= 0.0 || max <= 0.0 {
+                ' '
+            } else {
+                let level = ((v / max) * (LEVELS.len() - 1) as f64).round() as usize;
+                LEVELS[level.min(LEVELS.len() - 1)]
+            }
+        })
+        .collect()
+}
+
+// Bins the values like the histogram plots and draws the counts as a sparkline, labeled
+// with the range of the values.
+// Its inputs are - `values`: the sample - `options`: bins and binning (`log_y` scales the counts)
+// Its outputs are - `String`: "min ▁▂█▅▂ max"
+pub fn histogram_sparkline(values: &[f64], options: &HistogramOptions) -> String {
+    let (edges, counts) = bin_values(values, options);
+    let heights: Vec<f64> = counts
+        .iter()
+        .map(|&c| if options.log_y { (c as f64).ln_1p() } else { c as f64 })
+        .collect();
+    format!(
+        "{:.3} {} {:.3}",
+        edges.first().copied().unwrap_or(0.0),
+        sparkline(&heights),
+        edges.last().copied().unwrap_or(0.0)
+    )
+}
+
+// Draws a horizontal bar chart, one line per row, with bars scaled so the largest value
+// spans `width` characters (to an eighth of a character).
+// Its inputs are - `rows`: (label, non-negative value) - `width`: length of the longest bar
+// Its outputs are - `Vec<String>`: one line per row, "label │████▌ value"
+pub fn bar_chart(rows: &[(String, f64)], width: usize) -> Vec<String> {
+    let max = rows.iter().map(|r| r.1).fold(0.0, f64::max);
+    let label_width = rows.iter().map(|r| r.0.chars().count()).max().unwrap_or(0);
+    rows.iter()
+        .map(|(label, value)| {
+            let eighths = if max > 0.0 { (value.max(0.0) / max * (width * 8) as f64).round() as usize } else { 0 };
+            let mut bar = "█".repeat(eighths / 8);
+            if eighths % 8 > 0 {
+                bar.push(PARTIAL_BLOCKS[eighths % 8]);
+            }
+            format!("{:<label_width$} │{} {}", label, bar, format_value(*value))
+        })
+        .collect()
+}
+
+fn format_value(value: f64) -> String {
+    if value == value.round() && value.abs() < 1e12 {
+        format!("{:.0}", value)
+    } else if value.abs() >= 0.01 {
+        format!("{:.3}", value)
+    } else {
+        format!("{:.2e}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that sparklines scale to the maximum and that bars use eighth blocks.
+    #[test]
+    fn test_sparkline_and_bar_chart() {
+        assert_eq!(sparkline(&[0.0, 1.0, 7.0]), " ▂█");
+        let lines = bar_chart(&[("a".to_string(), 4.0), ("bb".to_string(), 1.0)], 2);
+        assert_eq!(lines[0], "a  │██ 4");
+        assert_eq!(lines[1], "bb │▌ 1");
+        let options = HistogramOptions { bins: 3, ..HistogramOptions::default() };
+        assert_eq!(histogram_sparkline(&[0.0, 0.0, 3.0], &options), "0.000 █ ▅ 3.000");
+    }
+}
@@ -0,0 +1,113 @@
+// Includes a union-find (disjoint-set forest) over compact node indices, kept alongside a graph
+// that only gains edges so that component queries stay cheap at every point of the insertion:
+// with union by size and path halving, `same_set` and `set_size` take O(α(n)) amortized time,
+// where α is the inverse Ackermann function (at most 4 for any realistic n).
+
+use crate::graph::Graph;
+
+// Disjoint sets of the indices 0..len, with the size of each set and the number of sets.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    // Creates `n` singleton sets.
+    pub fn new(n: usize) -> UnionFind {
+        UnionFind { parent: (0..n).collect(), size: vec![1; n], sets: n }
+    }
+
+    // Builds the sets of the connected components of a graph, over its compact indices.
+    pub fn from_graph(graph: &Graph) -> UnionFind {
+        let mut sets = UnionFind::new(graph.node_count());
+        for v in 0..graph.node_count() {
+            for &w in graph.neighbors(v).iter().filter(|&&w| w > v) {
+                sets.union(v, w);
+            }
+        }
+        sets
+    }
+
+    // Number of indices covered.
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    // Adds a new singleton set for the next index, returning that index.
+    pub fn push(&mut self) -> usize {
+        let i = self.parent.len();
+        self.parent.push(i);
+        self.size.push(1);
+        self.sets += 1;
+        i
+    }
+
+    // Representative of the set containing `v`, halving the path to it on the way.
+    pub fn find(&mut self, mut v: usize) -> usize {
+        while self.parent[v] != v {
+            self.parent[v] = self.parent[self.parent[v]];
+            v = self.parent[v];
+        }
+        v
+    }
+
+    // Merges the sets of `a` and `b`, returning false if they were already one set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.sets -= 1;
+        true
+    }
+
+    // Whether `a` and `b` are in the same set.
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    // Size of the set containing `v`.
+    pub fn set_size(&mut self, v: usize) -> usize {
+        let root = self.find(v);
+        self.size[root]
+    }
+
+    // Number of disjoint sets.
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that the sets built from a graph are its components, and that they stay correct
+    // while edges are added one at a time.
+    #[test]
+    fn test_union_find() {
+        let graph = Graph::from_edges(&[(1, 2), (2, 3), (4, 5), (6, 6)]);
+        let mut sets = UnionFind::from_graph(&graph);
+        let id = |v: usize| graph.index_of(v).unwrap();
+        assert_eq!((sets.len(), sets.set_count()), (5, 2));
+        assert!(sets.same_set(id(1), id(3)) && !sets.same_set(id(1), id(4)));
+        assert_eq!((sets.set_size(id(3)), sets.set_size(id(5))), (3, 2));
+
+        let extra = sets.push();
+        assert_eq!((extra, sets.set_count()), (5, 3));
+        assert!(sets.union(id(3), id(4)) && !sets.union(id(1), id(5)));
+        assert!(sets.union(extra, id(5)));
+        assert_eq!((sets.set_count(), sets.set_size(id(1))), (1, 6));
+    }
+}
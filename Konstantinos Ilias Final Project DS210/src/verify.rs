@@ -0,0 +1,180 @@
+// Includes a reference-verification mode: degrees, connected components and shortest-path
+// distances of the hand-written algorithms are recomputed with petgraph on sampled subgraphs
+// of the network and compared, so that a change that silently breaks one of them shows up as
+// a mismatch instead of as slightly different rankings. Built only with the `verify` feature,
+// so petgraph is not a dependency of the normal program.
+
+use crate::compressed::CompressedGraph;
+use crate::distance::{bfs_from, default_delta, delta_stepping, UNREACHED};
+use crate::graph::{Adjacency, Graph};
+use crate::sampling::{sample, SamplingConfig, SamplingMethod};
+use petgraph::algo::{connected_components, dijkstra, kosaraju_scc};
+use petgraph::graph::{NodeIndex, UnGraph};
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rand::SeedableRng;
+
+// Parameters of a verification run.
+#[derive(Debug, Clone, Copy)]
+pub struct VerifyConfig {
+    // Number of subgraphs checked; they alternate between forest-fire and random-node samples,
+    // so both connected and fragmented graphs are covered
+    pub samples: usize,
+    // Nodes per sampled subgraph
+    pub sample_size: usize,
+    // Shortest-path sources per subgraph
+    pub sources: usize,
+    pub seed: u64,
+}
+
+impl Default for VerifyConfig {
+    fn default() -> Self {
+        VerifyConfig { samples: 20, sample_size: 500, sources: 10, seed: 42 }
+    }
+}
+
+// Outcome of one kind of comparison.
+#[derive(Debug, Clone, Default)]
+pub struct Check {
+    pub name: &'static str,
+    // Values compared
+    pub checked: usize,
+    // A description of every value that differed
+    pub failures: Vec<String>,
+}
+
+impl Check {
+    fn new(name: &'static str) -> Check {
+        Check { name, ..Check::default() }
+    }
+
+    fn compare<T: PartialEq + std::fmt::Debug>(&mut self, what: impl FnOnce() -> String, ours: T, reference: T) {
+        self.checked += 1;
+        if ours != reference {
+            self.failures.push(format!("{}: ours {:?}, petgraph {:?}", what(), ours, reference));
+        }
+    }
+}
+
+// The same subgraph as a petgraph graph, node `NodeIndex(v)` standing for compact index `v`
+// and edge weights kept as path lengths (1 / weight).
+fn reference_graph(graph: &Graph) -> UnGraph<(), f64> {
+    let mut reference = UnGraph::with_capacity(graph.node_count(), graph.edge_count());
+    for _ in 0..graph.node_count() {
+        reference.add_node(());
+    }
+    for v in 0..graph.node_count() {
+        for (w, weight) in graph.weighted_neighbors(v).filter(|&(w, _)| w > v) {
+            reference.add_edge(NodeIndex::new(v), NodeIndex::new(w), 1.0 / weight);
+        }
+    }
+    reference
+}
+
+// Compares one subgraph, adding to the checks.
+fn verify_subgraph(graph: &Graph, sources: &[usize], label: &str, checks: &mut [Check; 5]) {
+    let reference = reference_graph(graph);
+    let [degree, components, hops, compressed_hops, weighted] = checks;
+
+    for v in 0..graph.node_count() {
+        degree.compare(|| format!("{} degree of node {}", label, graph.node_id(v)), graph.degree(v), reference.edges(NodeIndex::new(v)).count());
+    }
+
+    let mut ours: Vec<Vec<usize>> = graph.components();
+    let mut theirs: Vec<Vec<usize>> = kosaraju_scc(&reference).into_iter().map(|c| c.into_iter().map(|n| n.index()).collect()).collect();
+    for list in ours.iter_mut().chain(theirs.iter_mut()) {
+        list.sort_unstable();
+    }
+    ours.sort_unstable();
+    theirs.sort_unstable();
+    components.compare(|| format!("{} component count", label), ours.len(), connected_components(&reference));
+    components.compare(|| format!("{} component membership", label), ours, theirs);
+
+    let packed = CompressedGraph::from_graph(graph);
+    let mut dist = vec![UNREACHED; graph.node_count()];
+    let mut packed_dist = vec![UNREACHED; graph.node_count()];
+    let mut order = Vec::new();
+    let delta = default_delta(graph);
+    for &s in sources {
+        let source = NodeIndex::new(s);
+        let hop_reference = dijkstra(&reference, source, None, |_| 1usize);
+        dist.fill(UNREACHED);
+        bfs_from(graph, s, &mut dist, &mut order);
+        // The compressed graph numbers nodes by ascending ID; map its distances back
+        let packed_source = packed.index_of(graph.node_id(s)).expect("same nodes");
+        packed_dist.fill(UNREACHED);
+        bfs_from(&packed, packed_source, &mut packed_dist, &mut order);
+        let weighted_dist = if graph.is_weighted() { Some(delta_stepping(graph, s, delta)) } else { None };
+        let weighted_reference = weighted_dist.as_ref().map(|_| dijkstra(&reference, source, None, |e| *e.weight()));
+
+        for v in 0..graph.node_count() {
+            let node = NodeIndex::new(v);
+            let expected = hop_reference.get(&node).copied();
+            let found = Some(dist[v]).filter(|&d| d != UNREACHED);
+            let pair = || format!("{} distance {} -> {}", label, graph.node_id(s), graph.node_id(v));
+            hops.compare(pair, found, expected);
+            let packed_found = Some(packed_dist[packed.index_of(graph.node_id(v)).expect("same nodes")]).filter(|&d| d != UNREACHED);
+            compressed_hops.compare(pair, packed_found, expected);
+            if let (Some(ours), Some(theirs)) = (&weighted_dist, &weighted_reference) {
+                let expected = theirs.get(&node).copied().unwrap_or(f64::INFINITY);
+                // Paths summed in a different order may differ in the last bits
+                let close = ours[v] == expected || (ours[v] - expected).abs() <= 1e-9 * expected.max(1.0);
+                weighted.compare(pair, if close { expected } else { ours[v] }, expected);
+            }
+        }
+    }
+}
+
+// Cross-checks degrees, components and shortest-path distances against petgraph on sampled
+// subgraphs of a graph.
+// Its inputs are - `graph`: the graph - `config`: number and size of the samples, sources, seed
+// Its outputs are - `Vec<Check>`: one check each for degrees, components, hop distances (of
+// `Graph` and `CompressedGraph`) and weighted distances, with the mismatches found
+pub fn verify(graph: &Graph, config: &VerifyConfig) -> Vec<Check> {
+    let mut checks = [
+        Check::new("degree"),
+        Check::new("connected components"),
+        Check::new("BFS distances"),
+        Check::new("BFS distances (compressed)"),
+        Check::new("weighted distances"),
+    ];
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    for i in 0..config.samples {
+        let method = if i % 2 == 0 { SamplingMethod::ForestFire } else { SamplingMethod::RandomNode };
+        let sampling = SamplingConfig { method, size: config.sample_size, seed: config.seed + i as u64, ..SamplingConfig::default() };
+        let subgraph = sample(graph, &sampling);
+        let count = config.sources.min(subgraph.node_count());
+        let sources = index::sample(&mut rng, subgraph.node_count(), count).into_vec();
+        verify_subgraph(&subgraph, &sources, &format!("sample {} ({})", i + 1, method), &mut checks);
+    }
+    checks.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests that a graph with several components and weighted edges passes every check, and
+    // that a wrong value is reported as a failure.
+    #[test]
+    fn test_verify() {
+        let mut edges = Vec::new();
+        for v in 0..300usize {
+            edges.push((v, (v * 7 + 1) % 300, 1.0 + (v % 4) as f64));
+            if v % 50 != 0 {
+                edges.push((1000 + v, 1000 + v + 1, 2.0));
+            }
+        }
+        let graph = Graph::from_weighted_edges(&edges);
+        let checks = verify(&graph, &VerifyConfig { samples: 4, sample_size: 120, sources: 5, seed: 7 });
+        assert_eq!(checks.len(), 5);
+        for check in &checks {
+            assert!(check.checked > 0, "{}", check.name);
+            assert!(check.failures.is_empty(), "{}: {:?}", check.name, &check.failures[..1]);
+        }
+
+        let mut check = Check::new("test");
+        check.compare(|| "value".to_string(), 1, 2);
+        assert_eq!(check.failures, vec!["value: ours 1, petgraph 2".to_string()]);
+    }
+}